@@ -0,0 +1,85 @@
+// Allocation + throughput benchmark for `!ticker@arr` parsing.
+// Run with: cargo bench --bench ticker_parse
+
+#[path = "../src/ticker.rs"]
+#[allow(dead_code)]
+mod ticker;
+
+use serde::Deserialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// The previous owned representation, kept here as the baseline.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct OwnedTickerEvent {
+    s: String,
+    c: String,
+    v: String,
+    #[serde(rename = "E")]
+    event_time: i64,
+}
+
+fn payload(symbols: usize) -> String {
+    let events: Vec<String> = (0..symbols)
+        .map(|i| {
+            format!(
+                r#"{{"e":"24hrTicker","E":1700000000000,"s":"SYM{i}USDT","p":"0.0100","P":"1.234","w":"1.2345","c":"1.2500","Q":"10","o":"1.2400","h":"1.3000","l":"1.2000","v":"123456.7","q":"154321.9","O":1699913600000,"C":1700000000000,"F":1,"L":1000,"n":1000}}"#
+            )
+        })
+        .collect();
+    format!("[{}]", events.join(","))
+}
+
+fn measure(name: &str, iterations: usize, mut f: impl FnMut() -> f64) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let started = Instant::now();
+    let mut sink = 0.0;
+    for _ in 0..iterations {
+        sink += f();
+    }
+    let elapsed = started.elapsed();
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<10} {:>8.1} allocs/msg {:>10.1} us/msg (checksum {:.0})",
+        name,
+        allocs as f64 / iterations as f64,
+        elapsed.as_secs_f64() * 1e6 / iterations as f64,
+        sink
+    );
+}
+
+fn main() {
+    let text = payload(300);
+    let iterations = 2_000;
+
+    measure("owned", iterations, || {
+        let events: Vec<OwnedTickerEvent> = serde_json::from_str(&text).unwrap();
+        events.iter().map(|e| e.c.parse::<f64>().unwrap_or(0.0) + e.v.parse::<f64>().unwrap_or(0.0)).sum()
+    });
+
+    measure("borrowed", iterations, || {
+        let mut sum = 0.0;
+        ticker::for_each_ticker(&text, |e| sum += e.price().unwrap_or(0.0) + e.volume_total().unwrap_or(0.0)).unwrap();
+        sum
+    });
+}
@@ -0,0 +1,451 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{timeout, Duration};
+use tracing::{debug, info, warn, Instrument};
+use crate::history::HistoryManager;
+use crate::leader::Leadership;
+use crate::lifecycle::{SignalState, StateTransition};
+use crate::metrics::{symbol_counters, SymbolCounters, METRICS};
+use crate::model::{ActiveSignal, CandleBuilder, MarketData, SymbolState, Tick};
+use crate::scanner::{signal_span, Invalidation, ReasonCode, Signal, SignalType, SignalUpdate, WsMessage};
+use crate::shard::{forward_invalidation, forward_signal, SHARD};
+use crate::store::SharedState;
+use crate::update_batcher::PendingUpdates;
+use crate::warmup::{self, WarmupMode, WARMUP};
+use crate::price_alert::PriceAlerts;
+use crate::watchlist::Watchlist;
+
+// Everything a symbol actor needs to do its job. Cheap to clone (all Arcs).
+#[derive(Clone)]
+pub struct ActorContext {
+    pub store: SharedState,
+    pub tx: broadcast::Sender<WsMessage>,
+    pub pending_updates: PendingUpdates,
+    pub history: Arc<HistoryManager>,
+    pub leadership: Leadership,
+    pub watchlist: Arc<Watchlist>,
+    pub price_alerts: Arc<PriceAlerts>,
+}
+
+fn queue_capacity() -> usize {
+    std::env::var("ACTOR_QUEUE_CAPACITY").ok().and_then(|v| v.parse().ok()).unwrap_or(64)
+}
+
+// Adverse move (percent of entry) that invalidates a live signal
+pub fn invalidation_pct() -> f64 {
+    std::env::var("SIGNAL_INVALIDATION_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or(2.0)
+}
+
+// Delay before a published signal's order book is checked again; 0 disables re-verification
+fn reverify_after_secs() -> u64 {
+    std::env::var("SIGNAL_REVERIFY_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(300)
+}
+
+// Actors with no ticks for this long shut themselves down; the router respawns on demand.
+const ACTOR_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Routes ticks to one lightweight task per symbol.
+///
+/// Each actor exclusively owns the processing for its symbol (minute bucketing,
+/// signal checks, update throttling), so a burst on one symbol never waits behind
+/// another symbol's lock. Queues are bounded: when an actor falls behind, new ticks
+/// for that symbol are dropped (a later ticker snapshot supersedes them anyway).
+pub struct Router {
+    ctx: ActorContext,
+    actors: HashMap<String, mpsc::Sender<Tick>>,
+    capacity: usize,
+}
+
+impl Router {
+    pub fn new(ctx: ActorContext) -> Self {
+        Self { ctx, actors: HashMap::new(), capacity: queue_capacity() }
+    }
+
+    pub fn route(&mut self, symbol: &str, tick: Tick) {
+        if let Some(sender) = self.actors.get(symbol) {
+            match sender.try_send(tick) {
+                Ok(()) => return,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    METRICS.actor_dropped_ticks.fetch_add(1, Ordering::Relaxed);
+                    debug!(symbol, "Actor queue full, dropping tick");
+                    return;
+                }
+                // Actor exited (idle), respawn below
+                Err(mpsc::error::TrySendError::Closed(_)) => {}
+            }
+        }
+
+        let symbol = symbol.to_string();
+        // First sight of the symbol (or back from idle): fill a short window from REST
+        crate::backfill::request(&symbol);
+        let sender = spawn_actor(symbol.clone(), self.ctx.clone(), self.capacity);
+        if sender.try_send(tick).is_err() {
+            warn!(symbol = %symbol, "Freshly spawned actor rejected its first tick");
+        }
+        self.actors.insert(symbol, sender);
+        // Drop the senders of actors that shut down idle, or every symbol ever seen stays here
+        self.actors.retain(|_, sender| !sender.is_closed());
+        METRICS.actors.store(self.actors.len() as u64, Ordering::Relaxed);
+    }
+}
+
+fn spawn_actor(symbol: String, ctx: ActorContext, capacity: usize) -> mpsc::Sender<Tick> {
+    let (sender, rx) = mpsc::channel(capacity);
+    tokio::spawn(run_actor(symbol, ctx, rx));
+    sender
+}
+
+async fn run_actor(symbol: String, ctx: ActorContext, mut rx: mpsc::Receiver<Tick>) {
+    // Reused for every tick instead of allocating a fresh MarketData (and symbol String) each time
+    let mut scratch = MarketData {
+        symbol: symbol.clone(),
+        open: 0.0,
+        high: 0.0,
+        low: 0.0,
+        close: 0.0,
+        volume: 0.0,
+        quote_volume: 0.0,
+        trades: 0,
+        taker_buy_volume: None,
+        taker_buy_quote_volume: None,
+        timestamp: 0,
+    };
+    let counters = symbol_counters(&symbol);
+
+    loop {
+        let tick = match timeout(ACTOR_IDLE_TIMEOUT, rx.recv()).await {
+            Ok(Some(tick)) => tick,
+            Ok(None) => break, // Router dropped us
+            Err(_) => {
+                debug!(symbol = %symbol, "Actor idle, shutting down");
+                break;
+            }
+        };
+        counters.events.fetch_add(1, Ordering::Relaxed);
+        counters.last_event_ms.store(tick.event_time, Ordering::Relaxed);
+        process_tick(&ctx, &symbol, tick, &mut scratch, &counters);
+    }
+}
+
+fn process_tick(ctx: &ActorContext, symbol: &str, tick: Tick, market_data: &mut MarketData, counters: &SymbolCounters) {
+    let event_time = tick.event_time;
+
+    // Round to minute
+    let current_minute = event_time / 60000;
+
+    // Get or Insert the in-progress candle (lookup by &str first so the hot path doesn't allocate a key)
+    let (mut builder, created) = match ctx.store.volume_cache.get_mut(symbol) {
+        Some(entry) => (entry, false),
+        None => (ctx.store.volume_cache.entry(symbol.to_string()).or_insert(CandleBuilder::new(current_minute, &tick)), true),
+    };
+
+    if builder.minute < current_minute {
+        // New minute started!
+        // 1. Finalize the previous candle and push to History
+        let mut candle = builder.finish(symbol);
+        let mut state_entry = ctx.store.entry(symbol.to_string()).or_insert_with(|| SymbolState::new(symbol.to_string()));
+        state_entry.order_flow.fill(&mut candle);
+        state_entry.add_data(candle);
+
+        // 2. Start the candle for the new minute with this tick
+        *builder = builder.next(current_minute, &tick);
+        METRICS.pipeline.candles_finalized.fetch_add(1, Ordering::Relaxed);
+        counters.candles_finalized.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+
+    // Same minute: extend the candle so far. A candle just started from this tick already has
+    // it, and adding its taker-buy volume again would count it twice.
+    if !created {
+        builder.update(&tick);
+    }
+
+    // Check Signaler immediately! (Real-time)
+
+    // 1. Prepare Market Data
+    builder.fill(market_data, event_time);
+    drop(builder);
+
+    // 2. Check Signals
+    let mut signals_found = Vec::new();
+    let mut invalidation = None;
+    let mut avg_volume = 0.0;
+    if let Some(mut state_entry) = ctx.store.get_mut(symbol) {
+        avg_volume = state_entry.get_average_volume();
+        // No taker split from the feed: take it from the streamed trades (see `order_flow`)
+        if market_data.taker_buy_volume.is_none() {
+            crate::order_flow::watch_if_ramping(symbol, market_data.volume, avg_volume, event_time);
+            state_entry.order_flow.fill(market_data);
+        }
+        // 24h context is cheap to keep current while we hold the entry anyway
+        state_entry.day.update(&tick);
+        // Warming up: windows shorter than WARMUP_MIN_CANDLES give garbage averages
+        let warm = warmup::is_warm(state_entry.window.len());
+        let watched = ctx.watchlist.is_watched(symbol);
+        let mut detected = if !ctx.store.control.scans(symbol) {
+            Vec::new() // Paused or blacklisted from the admin API
+        } else if warm || WARMUP.mode == WarmupMode::Flag {
+            METRICS.pipeline.signals_evaluated.fetch_add(1, Ordering::Relaxed);
+            counters.signals_evaluated.fetch_add(1, Ordering::Relaxed);
+            ctx.store.strategies.evaluate(&state_entry, market_data, watched)
+        } else {
+            Vec::new()
+        };
+        // A fresh listing has no history to warm up on; it fires once, on its own terms
+        if ctx.store.control.scans(symbol) {
+            if let Some(listed_at) = crate::listing::claim(symbol, event_time) {
+                detected.push(crate::scanner::new_listing_signal(&state_entry, market_data, listed_at));
+            }
+        }
+
+        if !detected.is_empty() {
+            for mut signal in detected {
+                METRICS.pipeline.signals_detected.fetch_add(1, Ordering::Relaxed);
+                counters.signals_detected.fetch_add(1, Ordering::Relaxed);
+                if !warm && !matches!(signal.signal_type, SignalType::NewListing) {
+                    signal.low_confidence = true;
+                    signal.add_reason(ReasonCode::WarmingUp {
+                        candles: state_entry.window.len(),
+                        required: WARMUP.min_candles,
+                    });
+                }
+                crate::bias::annotate(&mut signal);
+                signal.market_context = crate::sentiment::current();
+                info!(parent: &signal.span(), strategy = %signal.strategy_name, signal_type = ?signal.signal_type, reason = %signal.reason, "Signal detected");
+                signals_found.push(signal);
+            }
+        } else {
+            // Queue a "Live Update" if active signal exists within 60 mins, or always for a
+            // watched symbol. The batcher sends the latest one per symbol on its own cadence.
+            // (A signal rejected by verification leaves the cooldown but no active signal.)
+            let live = state_entry.last_signal_time.is_some_and(|t| event_time - t < 60 * 60 * 1000)
+                && state_entry.active_signal.is_some();
+            if live {
+                if let Some(active) = state_entry.active_signal.as_mut().filter(|a| !a.invalidated) {
+                    let adverse = active.adverse_move(market_data.close);
+                    if adverse * 100.0 > invalidation_pct() {
+                        active.invalidated = true;
+                        let invalidated = Invalidation {
+                            signal_id: active.id.clone(),
+                            symbol: symbol.to_string(),
+                            reason: format!("Price moved {:.2}% against the signal", adverse * 100.0),
+                            timestamp: event_time,
+                            stopped_out: true,
+                        };
+                        invalidation = Some((invalidated, active.trace_id.clone()));
+                    }
+                }
+            }
+            if live || watched {
+                ctx.pending_updates.insert(symbol.to_string(), SignalUpdate {
+                    signal_id: state_entry.active_signal.as_ref().filter(|_| live).map(|a| a.id.clone()),
+                    symbol: symbol.to_string(),
+                    price: market_data.close,
+                    open: market_data.open,
+                    high: market_data.high,
+                    low: market_data.low,
+                    volume: market_data.volume,
+                    quote_volume: market_data.quote_volume,
+                    trades: market_data.trades,
+                    taker_buy_volume: market_data.taker_buy_volume,
+                    timestamp: market_data.timestamp,
+                    indicators: Some(state_entry.indicators()),
+                    imbalance: state_entry.depth.summary(event_time),
+                });
+            }
+        }
+    }
+
+    crate::correlation::observe(symbol, market_data, avg_volume);
+
+    // 3. Process Signals (Outside lock)
+    for mut signal in signals_found {
+        crate::correlation::annotate(&mut signal);
+        crate::divergence::annotate(ctx, &mut signal, market_data, avg_volume);
+        // Suppressed market-wide signals keep the cooldown, but never go live
+        let suppressed = signal.market_wide && crate::correlation::suppress();
+        // Update Last Signal Time (the last strategy's signal is the one watched for invalidation)
+        if let Some(mut state_mut) = ctx.store.get_mut(symbol) {
+            state_mut.record_signal(&signal.strategy_name, market_data.timestamp);
+            if !suppressed {
+                state_mut.active_signal = Some(ActiveSignal {
+                    id: signal.id.clone(),
+                    trace_id: signal.trace_id.clone(),
+                    signal_type: signal.signal_type.clone(),
+                    entry_price: signal.price,
+                    invalidated: false,
+                    score: signal.score.map(|s| s.total),
+                });
+            }
+        }
+        if suppressed {
+            METRICS.pipeline.signals_suppressed.fetch_add(1, Ordering::Relaxed);
+            info!(parent: &signal.span(), reason = %signal.reason, "Market-wide signal suppressed");
+            continue;
+        }
+
+        // Verification, publishing and re-verification all log under the signal's trace id
+        let ctx = ctx.clone();
+        let span = signal.span();
+        tokio::spawn(async move {
+            if crate::verifier::verify_signal(&mut signal).await {
+                if let Some(mut state) = ctx.store.get_mut(&signal.symbol) {
+                    if let Some(active) = state.active_signal.as_mut().filter(|a| a.id == signal.id) {
+                        active.score = signal.score.map(|s| s.total);
+                    }
+                }
+                publish_signal(&ctx, signal.clone()).await;
+                reverify_later(&ctx, signal).await;
+            } else {
+                reject_signal(&ctx, signal).await;
+            }
+        }.instrument(span));
+    }
+
+    // User watch rules, independent of the scanner's own criteria
+    if ctx.leadership.is_leader() {
+        for alert in ctx.watchlist.check(symbol, market_data, avg_volume) {
+            info!(symbol, rule_id = %alert.rule_id, message = %alert.message, "Watch rule fired");
+            let _ = ctx.tx.send(WsMessage::WatchAlert(alert));
+        }
+        for alert in ctx.price_alerts.check(symbol, market_data) {
+            info!(symbol, alert_id = %alert.id, kind = ?alert.kind, level = alert.level, price = market_data.close, "Price alert fired");
+            let _ = ctx.tx.send(WsMessage::AlertTriggered(alert));
+        }
+    }
+
+    if let Some((invalidation, trace_id)) = invalidation {
+        let ctx = ctx.clone();
+        let span = signal_span(&trace_id, &invalidation.signal_id, symbol);
+        tokio::spawn(async move { invalidate_signal(&ctx, invalidation).await }.instrument(span));
+    }
+}
+
+// A signal strict verification dropped: it keeps its cooldown (re-verifying it on every tick
+// would hammer the REST API) but isn't live, so it gets no updates or invalidation.
+async fn reject_signal(ctx: &ActorContext, signal: Signal) {
+    METRICS.pipeline.signals_rejected.fetch_add(1, Ordering::Relaxed);
+    info!(symbol = %signal.symbol, signal_id = %signal.id, confidence = ?signal.confidence, "Signal rejected by verification");
+    if let Some(mut state) = ctx.store.get_mut(&signal.symbol) {
+        if state.active_signal.as_ref().is_some_and(|a| a.id == signal.id) {
+            state.active_signal = None;
+        }
+    }
+    if crate::verifier::record_rejected() && ctx.leadership.is_leader() {
+        ctx.history.add_rejected(signal).await;
+    }
+}
+
+// Checks the order book once more a while after publishing; a book that has turned
+// against the signal invalidates it.
+async fn reverify_later(ctx: &ActorContext, signal: Signal) {
+    let delay = reverify_after_secs();
+    if delay == 0 {
+        return;
+    }
+    tokio::time::sleep(Duration::from_secs(delay)).await;
+    let now = chrono::Utc::now().timestamp_millis();
+    let tracked = ctx.store.get(&signal.symbol).and_then(|state| state.depth.summary(now));
+    if let Some(reason) = crate::verifier::reverify(&signal, tracked).await {
+        let invalidation = Invalidation {
+            signal_id: signal.id.clone(),
+            symbol: signal.symbol.clone(),
+            reason,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            stopped_out: false,
+        };
+        invalidate_signal(ctx, invalidation).await;
+    }
+}
+
+/// Records a verified signal in history, then broadcasts it to clients.
+///
+/// History gets a direct call rather than a broadcast subscription: the broadcast channel
+/// drops messages for lagging receivers, and every emitted signal must be persisted.
+/// Standby instances (see `leader`) detect signals too but never emit them.
+///
+/// Shard workers (see `shard`) still record locally, where the prices for outcome tracking
+/// are, but hand the signal to the aggregator instead of broadcasting it themselves.
+pub async fn publish_signal(ctx: &ActorContext, signal: Signal) {
+    if !ctx.leadership.is_leader() {
+        debug!(symbol = %signal.symbol, signal_id = %signal.id, "Standby instance, not emitting signal");
+        return;
+    }
+    match &SHARD.aggregator_url {
+        Some(url) => {
+            record_signal(&ctx.history, &signal).await;
+            METRICS.pipeline.signals_emitted.fetch_add(1, Ordering::Relaxed);
+            forward_signal(url, &signal).await;
+        }
+        None => emit_signal(&ctx.history, &ctx.tx, signal).await,
+    }
+}
+
+/// Records and broadcasts a signal, and hands it to the other instances of a cluster. Also
+/// used by the aggregator for forwarded signals.
+pub async fn emit_signal(history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>, signal: Signal) {
+    crate::cluster::publish(WsMessage::Signal(Box::new(signal.clone())));
+    apply_signal(history, tx, signal).await;
+}
+
+/// Records and broadcasts a signal, locally only. Used for signals relayed from the cluster.
+pub async fn apply_signal(history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>, signal: Signal) {
+    let transitions = record_signal(history, &signal).await;
+    METRICS.pipeline.signals_emitted.fetch_add(1, Ordering::Relaxed);
+    info!(strategy = %signal.strategy_name, confidence = ?signal.confidence, "Signal published");
+    let _ = tx.send(WsMessage::Signal(Box::new(signal)));
+    for transition in transitions {
+        let _ = tx.send(WsMessage::SignalState(transition));
+    }
+}
+
+/// Marks a signal invalidated in history and tells clients, following the same leader and
+/// shard rules as `publish_signal`.
+pub async fn invalidate_signal(ctx: &ActorContext, invalidation: Invalidation) {
+    if !ctx.leadership.is_leader() {
+        return;
+    }
+    match &SHARD.aggregator_url {
+        Some(url) => {
+            if ctx.history.invalidate(&invalidation).await {
+                ctx.history.apply_transition(ending(&invalidation)).await;
+                forward_invalidation(url, &invalidation).await;
+            }
+        }
+        None => emit_invalidation(&ctx.history, &ctx.tx, invalidation).await,
+    }
+}
+
+/// Records and broadcasts an invalidation, once per signal, and hands it to the rest of the
+/// cluster. Also used by the aggregator.
+pub async fn emit_invalidation(history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>, invalidation: Invalidation) {
+    if apply_invalidation(history, tx, invalidation.clone()).await {
+        crate::cluster::publish(WsMessage::Invalidate(invalidation));
+    }
+}
+
+/// Records and broadcasts an invalidation locally; false if the signal already had one.
+pub async fn apply_invalidation(history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>, invalidation: Invalidation) -> bool {
+    if !history.invalidate(&invalidation).await {
+        return false;
+    }
+    info!(symbol = %invalidation.symbol, signal_id = %invalidation.signal_id, reason = %invalidation.reason, "Signal invalidated");
+    METRICS.pipeline.signals_invalidated.fetch_add(1, Ordering::Relaxed);
+    if let Some(transition) = history.apply_transition(ending(&invalidation)).await {
+        history.announce(tx, transition).await;
+    }
+    let _ = tx.send(WsMessage::Invalidate(invalidation));
+    true
+}
+
+// The lifecycle end an invalidation stands for; refused by history if the signal already ended
+fn ending(invalidation: &Invalidation) -> StateTransition {
+    let to = if invalidation.stopped_out { SignalState::StoppedOut } else { SignalState::Invalidated };
+    StateTransition::new(&invalidation.signal_id, &invalidation.symbol, to, Some(invalidation.reason.clone()), invalidation.timestamp)
+}
+
+async fn record_signal(history: &Arc<HistoryManager>, signal: &Signal) -> Vec<StateTransition> {
+    history.add_signal(signal.clone()).await
+}
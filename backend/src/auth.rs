@@ -0,0 +1,130 @@
+use crate::config::{AuthConfig, Role};
+use crate::metrics::METRICS;
+use crate::secrets::Secret;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::SystemTime;
+use tracing::{error, info};
+
+// Who may use `/ws`. Once tokens are configured (`config::AuthConfig`), a client presents one
+// as `?token=` or, if it would rather keep it out of the URL, as its first message
+// (`wire::AuthRequest`) within `AUTH_TIMEOUT_SECS`. Anything else gets the socket closed with
+// `POLICY_VIOLATION`. The tokens are loaded once and again whenever the config file or the
+// `WS_TOKENS` secret changes, so edits apply to new clients.
+
+/// WebSocket close code for a missing or rejected token.
+pub const POLICY_VIOLATION: u16 = 1008;
+
+pub fn timeout_secs() -> u64 {
+    std::env::var("AUTH_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(5)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("token required")]
+    Missing,
+    #[error("invalid token")]
+    Invalid,
+    #[error("admin token required")]
+    Forbidden,
+}
+
+/// An authenticated client: the name of its token and what it may do.
+#[derive(Debug, Clone)]
+pub struct Access {
+    pub name: String,
+    pub role: Role,
+    pub open: bool, // No tokens configured, so no one actually authenticated
+}
+
+impl Access {
+    /// The admin API changes the running scanner, so it takes a real admin token even while
+    /// the rest is open.
+    pub fn can_administer(&self) -> bool {
+        self.role == Role::Admin && !self.open
+    }
+}
+
+// What the loaded tokens came from; a difference in any of them means loading again
+#[derive(PartialEq)]
+struct Source {
+    path: String,
+    modified: Option<SystemTime>,
+    ws_tokens: Option<Secret>,
+}
+
+impl Source {
+    fn current() -> Self {
+        let path = crate::config::config_path();
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        Self { path, modified, ws_tokens: crate::secrets::get("WS_TOKENS") }
+    }
+}
+
+// The auth config as last loaded and where from; the config is None if it was invalid
+type Loaded = (Source, Option<Arc<AuthConfig>>);
+static LOADED: LazyLock<RwLock<Option<Loaded>>> = LazyLock::new(|| RwLock::new(None));
+
+fn config() -> Option<Arc<AuthConfig>> {
+    let source = Source::current();
+    if let Some((loaded_from, config)) = LOADED.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if *loaded_from == source {
+            return config.clone();
+        }
+    }
+    let config = match AuthConfig::load(&source.path) {
+        Ok(config) => {
+            info!(path = %source.path, tokens = config.tokens.len(), "Auth config loaded");
+            Some(Arc::new(config))
+        }
+        Err(e) => {
+            METRICS.record_error("config");
+            error!(path = %source.path, error = %e, "Invalid auth config, rejecting WebSocket clients");
+            None
+        }
+    };
+    *LOADED.write().unwrap_or_else(|e| e.into_inner()) = Some((source, config.clone()));
+    config
+}
+
+/// Checks a presented token. With no tokens configured everyone gets `Admin`, as before auth
+/// existed; a broken auth config lets no one in rather than everyone.
+pub fn authorize(token: Option<&str>) -> Result<Access, AuthError> {
+    let config = config().ok_or(AuthError::Invalid)?;
+    if config.tokens.is_empty() {
+        return Ok(Access { name: "open".to_string(), role: Role::Admin, open: true });
+    }
+    let token = token.ok_or(AuthError::Missing)?;
+    config
+        .tokens
+        .iter()
+        .find(|t| t.token.matches(token))
+        .map(|t| Access { name: t.name.clone(), role: t.role, open: false })
+        .ok_or(AuthError::Invalid)
+}
+
+// The token of an `Authorization: Bearer <token>` header
+fn bearer(header: Option<&str>) -> Option<&str> {
+    header.map(|h| h.strip_prefix("Bearer ").unwrap_or(h).trim())
+}
+
+/// For the REST admin API: an `Authorization: Bearer <token>` header carrying an admin token.
+pub fn admin(header: Option<&str>) -> Result<Access, AuthError> {
+    let access = authorize(bearer(header))?;
+    if access.can_administer() {
+        Ok(access)
+    } else {
+        Err(AuthError::Forbidden)
+    }
+}
+
+/// For the REST routes that do what WebSocket commands do (watch rules, alerts, feedback): an
+/// `Authorization: Bearer <token>` header with an `Admin` token, which read-only WebSocket
+/// clients don't have. Open to anyone while no tokens are configured, like the commands.
+pub fn commands(header: Option<&str>) -> Result<Access, AuthError> {
+    let access = authorize(bearer(header))?;
+    if access.role == Role::Admin {
+        Ok(access)
+    } else {
+        Err(AuthError::Forbidden)
+    }
+}
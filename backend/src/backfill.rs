@@ -0,0 +1,126 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::exchange::{fetch_json, Exchange};
+use crate::metrics::METRICS;
+use crate::model::{MarketData, SymbolState, WINDOW_CANDLES};
+use crate::store::SharedState;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+// A cold window takes an hour of live minutes before its averages mean anything. Instead, the
+// first time an actor starts for a Binance futures symbol (every symbol at boot, then new ones)
+// its last `BACKFILL_CANDLES` finished 1m klines (default 60, 0 = off) are fetched from
+// `/fapi/v1/klines` and put in front of whatever the window has, unless a restored snapshot
+// already filled it. More than 60 only feeds the z-score baseline; the window keeps the last
+// hour. At most `BACKFILL_CONCURRENCY` requests (default 4) run at once, through `rest`'s
+// weight budget, and they wait while less than `MIN_HEADROOM` of it is left so the verifier's
+// lookups never queue behind a boot-time backfill.
+
+const QUEUE_CAPACITY: usize = 4096;
+const MIN_HEADROOM: f64 = 0.5;
+const HEADROOM_POLL: Duration = Duration::from_secs(1);
+const MAX_CANDLES: usize = 1500; // Binance's cap per request
+
+fn candles() -> usize {
+    std::env::var("BACKFILL_CANDLES").ok().and_then(|v| v.parse().ok()).unwrap_or(WINDOW_CANDLES).min(MAX_CANDLES)
+}
+
+fn concurrency() -> usize {
+    std::env::var("BACKFILL_CONCURRENCY").ok().and_then(|v| v.parse().ok()).unwrap_or(4).max(1)
+}
+
+// Symbols waiting for their backfill. Unset when it's off (and in a replay).
+static QUEUE: OnceLock<mpsc::Sender<String>> = OnceLock::new();
+
+/// Queues `symbol` for a backfill. A no-op for symbols there are no futures klines for, and
+/// when backfill doesn't run.
+pub fn request(symbol: &str) {
+    if crate::exchange::split(symbol).0 != Exchange::Binance || symbol.contains(':') {
+        return;
+    }
+    if let Some(queue) = QUEUE.get() {
+        if queue.try_send(symbol.to_string()).is_err() {
+            METRICS.record_error("backfill");
+            debug!(symbol, "Backfill queue full, skipping");
+        }
+    }
+}
+
+// A klines row: [open time, open, high, low, close, volume, close time, quote volume, trades,
+// taker buy volume, taker buy quote volume, ...]
+fn candle(symbol: &str, row: &[serde_json::Value]) -> Option<MarketData> {
+    let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+    Some(MarketData {
+        symbol: symbol.to_string(),
+        open: number(1)?,
+        high: number(2)?,
+        low: number(3)?,
+        close: number(4)?,
+        volume: number(5)?,
+        quote_volume: number(7)?,
+        trades: row.get(8)?.as_u64()?,
+        taker_buy_volume: number(9),
+        taker_buy_quote_volume: number(10),
+        timestamp: row.first()?.as_i64()?,
+    })
+}
+
+async fn backfill(store: &SharedState, symbol: &str, candles: usize) {
+    if store.get(symbol).is_some_and(|state| state.window.len() >= candles.min(WINDOW_CANDLES)) {
+        return;
+    }
+    // One more than asked for: the last row is the minute still trading
+    let url = format!("{}/fapi/v1/klines?symbol={}&interval=1m&limit={}", FUTURES_REST_URL, symbol, candles + 1);
+    while crate::rest::headroom(&url) < MIN_HEADROOM {
+        sleep(HEADROOM_POLL).await;
+    }
+    let rows: Vec<Vec<serde_json::Value>> = match fetch_json(&crate::rest::client(), &url).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            METRICS.record_error("backfill");
+            warn!(symbol, error = %e, "Failed to fetch klines for backfill");
+            return;
+        }
+    };
+    let Some(mut history) = rows.iter().map(|row| candle(symbol, row)).collect::<Option<Vec<_>>>() else {
+        METRICS.record_error("backfill");
+        warn!(symbol, "Malformed kline in backfill");
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    history.retain(|c| c.timestamp + 60_000 <= now);
+
+    let mut state = store.entry(symbol.to_string()).or_insert_with(|| SymbolState::new(symbol.to_string()));
+    let added = state.prepend(history);
+    if added > 0 {
+        METRICS.symbols_backfilled.fetch_add(1, Ordering::Relaxed);
+        debug!(symbol, candles = added, window = state.window.len(), "Window backfilled");
+    }
+}
+
+/// Works through the backfill queue, `BACKFILL_CONCURRENCY` symbols at a time.
+pub async fn backfill_task(store: SharedState) {
+    let candles = candles();
+    if candles == 0 {
+        return std::future::pending().await;
+    }
+    let (queue, mut pending) = mpsc::channel(QUEUE_CAPACITY);
+    if QUEUE.set(queue).is_err() {
+        warn!("Backfill already running");
+        return std::future::pending().await;
+    }
+    info!(candles, concurrency = concurrency(), "Backfilling windows from REST klines");
+    let permits = Arc::new(Semaphore::new(concurrency()));
+    while let Some(symbol) = pending.recv().await {
+        let Ok(permit) = permits.clone().acquire_owned().await else {
+            break;
+        };
+        let store = store.clone();
+        tokio::spawn(async move {
+            backfill(&store, &symbol, candles).await;
+            drop(permit);
+        });
+    }
+}
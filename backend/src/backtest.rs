@@ -0,0 +1,329 @@
+// Replays historical 1m klines through the live scanner (`SymbolState` + `strategy::Registry`)
+// to see how a set of thresholds would have done before running them live.
+//
+// Differences from live: each minute is checked once, as a finished candle, where the live
+// actor checks the candle so far on every tick; and there is no order book verification,
+// market bias or market-wide alert, so every detection counts as published.
+use crate::binance_client::FUTURES_REST_URL;
+use crate::strategy::Registry;
+use crate::lifecycle::EXPIRY_MS;
+use crate::model::{MarketData, SymbolState};
+use crate::scanner::SignalType;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const KLINES_PER_REQUEST: i64 = 1500; // Binance's cap
+const MINUTE_MS: i64 = 60_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BacktestError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("malformed kline in response")]
+    Malformed,
+    #[error("csv io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path} line {line}: {reason}")]
+    Csv { path: String, line: usize, reason: String },
+}
+
+/// Where the klines come from: downloaded per symbol, or Binance data dumps on disk
+/// (`BTCUSDT-1m-2024-05.csv`, symbol taken from the file name), optionally cut to [from, to).
+#[derive(Debug, Clone)]
+pub enum Source {
+    Binance { symbols: Vec<String>, from: i64, to: i64 },
+    Csv { paths: Vec<String>, from: Option<i64>, to: Option<i64> },
+}
+
+/// How one replayed signal ended.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trade {
+    pub symbol: String,
+    pub strategy: String,
+    pub signal_type: SignalType,
+    pub timestamp: i64,
+    pub entry: f64,
+    pub max_gain_pct: f64, // Best move in the signal's favour before it ended
+    pub return_pct: f64,   // At the stop, or at the close `EXPIRY_MS` after the signal
+    pub success: bool,     // Best move past OUTCOME_TAKE_PROFIT_PCT before the stop
+    pub stopped_out: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub signals: usize,
+    pub win_rate: f64,
+    pub avg_return_pct: f64,
+    pub avg_max_gain_pct: f64,
+    pub stopped_out: usize,
+}
+
+impl Summary {
+    fn of<'a>(trades: impl Iterator<Item = &'a Trade>) -> Self {
+        let mut summary = Summary::default();
+        let (mut wins, mut returns, mut gains) = (0, 0.0, 0.0);
+        for trade in trades {
+            summary.signals += 1;
+            wins += trade.success as usize;
+            summary.stopped_out += trade.stopped_out as usize;
+            returns += trade.return_pct;
+            gains += trade.max_gain_pct;
+        }
+        if summary.signals > 0 {
+            let n = summary.signals as f64;
+            summary.win_rate = wins as f64 / n * 100.0;
+            summary.avg_return_pct = returns / n;
+            summary.avg_max_gain_pct = gains / n;
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestReport {
+    pub from: i64,
+    pub to: i64,
+    pub candles: usize,
+    pub total: Summary,
+    // Peak-to-trough of the cumulative return, trading every signal in time order with equal size
+    pub max_drawdown_pct: f64,
+    pub by_symbol: BTreeMap<String, Summary>,
+    pub trades: Vec<Trade>,
+}
+
+impl BacktestReport {
+    pub fn print(&self) {
+        let date = |ms: i64| chrono::DateTime::from_timestamp_millis(ms).map_or(String::new(), |d| d.format("%Y-%m-%d %H:%M").to_string());
+        println!("Backtest {} .. {} UTC, {} candles", date(self.from), date(self.to), self.candles);
+        println!("{:<14} {:>7} {:>8} {:>10} {:>10} {:>8}", "symbol", "signals", "win %", "avg ret %", "avg max %", "stopped");
+        let row = |name: &str, s: &Summary| {
+            println!(
+                "{:<14} {:>7} {:>8.1} {:>10.2} {:>10.2} {:>8}",
+                name, s.signals, s.win_rate, s.avg_return_pct, s.avg_max_gain_pct, s.stopped_out
+            )
+        };
+        for (symbol, summary) in &self.by_symbol {
+            row(symbol, summary);
+        }
+        row("TOTAL", &self.total);
+        println!("Max drawdown: {:.2}%", self.max_drawdown_pct);
+    }
+}
+
+fn parse_row(row: &[serde_json::Value]) -> Option<(i64, [f64; 6], u64, f64)> {
+    let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+    Some((
+        row.first()?.as_i64()?,
+        [number(1)?, number(2)?, number(3)?, number(4)?, number(5)?, number(7)?],
+        row.get(8)?.as_u64()?,
+        number(9)?,
+    ))
+}
+
+fn candle(symbol: &str, open_time: i64, [open, high, low, close, volume, quote_volume]: [f64; 6], trades: u64, taker_buy: f64) -> MarketData {
+    MarketData {
+        symbol: symbol.to_string(),
+        open,
+        high,
+        low,
+        close,
+        volume,
+        quote_volume,
+        trades,
+        taker_buy_volume: Some(taker_buy),
+        taker_buy_quote_volume: None,
+        timestamp: open_time,
+    }
+}
+
+/// 1m klines for `symbol` in `[from, to)`, paging through Binance 1500 at a time.
+pub async fn download(client: &reqwest::Client, symbol: &str, from: i64, to: i64) -> Result<Vec<MarketData>, BacktestError> {
+    let mut candles = Vec::new();
+    let mut start = from;
+    while start < to {
+        let url = format!(
+            "{}/fapi/v1/klines?symbol={}&interval=1m&startTime={}&endTime={}&limit={}",
+            FUTURES_REST_URL, symbol, start, to - 1, KLINES_PER_REQUEST
+        );
+        let resp = client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(BacktestError::Status(resp.status()));
+        }
+        let rows: Vec<Vec<serde_json::Value>> = resp.json().await?;
+        let page = rows
+            .iter()
+            .map(|row| parse_row(row).map(|(t, ohlcv, trades, taker)| candle(symbol, t, ohlcv, trades, taker)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(BacktestError::Malformed)?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        start = last.timestamp + MINUTE_MS;
+        candles.extend(page);
+        // Klines weigh 10 per request; stay far below the 2400/min budget the live feed shares
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    Ok(candles)
+}
+
+/// A Binance data dump: `open_time,open,high,low,close,volume,close_time,quote_volume,count,
+/// taker_buy_volume,...`, with or without a header line.
+pub fn read_csv(path: &str) -> Result<(String, Vec<MarketData>), BacktestError> {
+    let name = std::path::Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+    let symbol = name.split(['-', '.']).next().unwrap_or(name).to_uppercase();
+    let data = std::fs::read_to_string(path)?;
+    let mut candles = Vec::new();
+    for (i, line) in data.lines().enumerate() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.first().is_none_or(|f| f.parse::<i64>().is_err()) {
+            continue; // Header or blank line
+        }
+        let error = |reason: &str| BacktestError::Csv { path: path.to_string(), line: i + 1, reason: reason.to_string() };
+        if fields.len() < 10 {
+            return Err(error("expected at least 10 columns"));
+        }
+        let number = |i: usize| fields[i].parse::<f64>().map_err(|_| error("not a number"));
+        let mut open_time: i64 = fields[0].parse().map_err(|_| error("bad open time"))?;
+        if open_time > 10_000_000_000_000 {
+            open_time /= 1000; // Newer dumps are in microseconds
+        }
+        let ohlcv = [number(1)?, number(2)?, number(3)?, number(4)?, number(5)?, number(7)?];
+        let trades = fields[8].parse().map_err(|_| error("bad trade count"))?;
+        candles.push(candle(&symbol, open_time, ohlcv, trades, number(9)?));
+    }
+    Ok((symbol, candles))
+}
+
+// Walks the minutes after the signal: stopped out once price moves `stop` against it (checked
+// before the target within a minute, to stay conservative), otherwise held for `EXPIRY_MS`.
+fn simulate(signal_type: &SignalType, entry: f64, timestamp: i64, after: &[MarketData], stop: f64) -> (f64, f64, bool, bool) {
+    let favourable = |price: f64| match signal_type {
+        SignalType::Long | SignalType::NewListing | SignalType::Breakout => (price - entry) / entry,
+        SignalType::Short => (entry - price) / entry,
+    };
+    let target = crate::history::take_profit_pct() / 100.0; // Same success bar as outcome tracking
+    let mut max_gain: f64 = 0.0;
+    let mut last_close = entry;
+    for candle in after.iter().take_while(|c| c.timestamp < timestamp + EXPIRY_MS) {
+        let (best, worst) = match signal_type {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => (candle.high, candle.low),
+            SignalType::Short => (candle.low, candle.high),
+        };
+        if favourable(worst) < -stop {
+            return (max_gain, -stop, max_gain > target, true);
+        }
+        max_gain = max_gain.max(favourable(best));
+        last_close = candle.close;
+    }
+    (max_gain, favourable(last_close), max_gain > target, false)
+}
+
+/// Replays one symbol's candles (oldest first) and returns the trades its signals made.
+pub fn replay(symbol: &str, candles: &[MarketData], strategies: &Registry, stop_pct: f64) -> Vec<Trade> {
+    let mut state = SymbolState::new(symbol.to_string());
+    let mut trades = Vec::new();
+    for (i, candle) in candles.iter().enumerate() {
+        // A gap in the data (delisting, outage) makes the window's average meaningless
+        if state.window.back().is_some_and(|last| candle.timestamp - last.timestamp > 5 * MINUTE_MS) {
+            state = SymbolState {
+                last_signal_time: state.last_signal_time,
+                strategy_signals: std::mem::take(&mut state.strategy_signals),
+                ..SymbolState::new(symbol.to_string())
+            };
+        }
+        if crate::warmup::is_warm(state.window.len()) {
+            for signal in strategies.evaluate(&state, candle, false) {
+                state.record_signal(&signal.strategy_name, candle.timestamp);
+                let (max_gain, ret, success, stopped_out) =
+                    simulate(&signal.signal_type, signal.price, candle.timestamp, &candles[i + 1..], stop_pct / 100.0);
+                trades.push(Trade {
+                    symbol: symbol.to_string(),
+                    strategy: signal.strategy_name,
+                    signal_type: signal.signal_type,
+                    timestamp: candle.timestamp,
+                    entry: signal.price,
+                    max_gain_pct: max_gain * 100.0,
+                    return_pct: ret * 100.0,
+                    success,
+                    stopped_out,
+                });
+            }
+        }
+        state.add_data(candle.clone());
+    }
+    trades
+}
+
+fn max_drawdown(trades: &[Trade]) -> f64 {
+    let (mut equity, mut peak, mut drawdown) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for trade in trades {
+        equity += trade.return_pct;
+        peak = peak.max(equity);
+        drawdown = drawdown.max(peak - equity);
+    }
+    drawdown
+}
+
+/// Loads every symbol's klines, replays them with `strategies` and summarizes the result.
+/// The stop is `SIGNAL_INVALIDATION_PCT`, as live.
+pub async fn run(source: Source, strategies: &Registry) -> Result<BacktestReport, BacktestError> {
+    let mut series = Vec::new();
+    match source {
+        Source::Binance { symbols, from, to } => {
+            let client = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+            for symbol in symbols {
+                let candles = download(&client, &symbol, from, to).await?;
+                eprintln!("{}: {} candles", symbol, candles.len());
+                series.push((symbol, candles));
+            }
+        }
+        Source::Csv { paths, from, to } => {
+            for path in paths {
+                let (symbol, mut candles) = read_csv(&path)?;
+                candles.retain(|c| from.is_none_or(|f| c.timestamp >= f) && to.is_none_or(|t| c.timestamp < t));
+                eprintln!("{}: {} candles from {}", symbol, candles.len(), path);
+                series.push((symbol, candles));
+            }
+        }
+    }
+    // Several dumps of the same symbol (one per month) are one continuous series
+    let mut by_symbol: BTreeMap<String, Vec<MarketData>> = BTreeMap::new();
+    for (symbol, candles) in series {
+        by_symbol.entry(symbol).or_default().extend(candles);
+    }
+
+    let stop_pct = crate::actor::invalidation_pct();
+    let mut trades = Vec::new();
+    let (mut from, mut to, mut count) = (i64::MAX, i64::MIN, 0);
+    for (symbol, candles) in &mut by_symbol {
+        candles.sort_by_key(|c| c.timestamp);
+        candles.dedup_by_key(|c| c.timestamp);
+        if let (Some(first), Some(last)) = (candles.first(), candles.last()) {
+            from = from.min(first.timestamp);
+            to = to.max(last.timestamp + MINUTE_MS);
+        }
+        count += candles.len();
+        trades.extend(replay(symbol, candles, strategies, stop_pct));
+    }
+    trades.sort_by_key(|t| t.timestamp);
+
+    let summaries = by_symbol
+        .keys()
+        .map(|symbol| (symbol.clone(), Summary::of(trades.iter().filter(|t| &t.symbol == symbol))))
+        .collect();
+    Ok(BacktestReport {
+        from: if count > 0 { from } else { 0 },
+        to: if count > 0 { to } else { 0 },
+        candles: count,
+        total: Summary::of(trades.iter()),
+        max_drawdown_pct: max_drawdown(&trades),
+        by_symbol: summaries,
+        trades,
+    })
+}
@@ -0,0 +1,144 @@
+use crate::leaderboard::mover;
+use crate::scanner::{ReasonCode, Signal, SignalType, WsMessage};
+use crate::shard::SHARD;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+const LEADERS: [&str; 2] = ["BTCUSDT", "ETHUSDT"];
+// A 15m move of this size in BTC/ETH counts as a full-strength trend
+const FULL_TREND_PCT: f64 = 1.0;
+// Minutes of candles the aggregate CVD covers
+const CVD_MINUTES: usize = 15;
+// Signals against a bias this strong get flagged
+const AGAINST_BIAS_SCORE: f64 = 60.0;
+
+fn interval_secs() -> u64 {
+    std::env::var("BIAS_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum BiasLabel {
+    StrongBear,
+    Bear,
+    Neutral,
+    Bull,
+    StrongBull,
+}
+
+impl BiasLabel {
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= AGAINST_BIAS_SCORE => BiasLabel::StrongBull,
+            s if s >= 20.0 => BiasLabel::Bull,
+            s if s <= -AGAINST_BIAS_SCORE => BiasLabel::StrongBear,
+            s if s <= -20.0 => BiasLabel::Bear,
+            _ => BiasLabel::Neutral,
+        }
+    }
+}
+
+/// Which way the whole tape leans, -100 (everything dumping) to +100 (everything ripping).
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketBias {
+    pub score: f64,
+    pub label: BiasLabel,
+    pub leaders_change_15m_pct: Option<f64>, // Average of BTC and ETH
+    pub breadth: Option<f64>,                // (green - red) / (green + red) over 15m, -1..1
+    pub cvd_ratio: Option<f64>,              // Net taker buying / traded value over 15m, -1..1
+    pub timestamp: i64,
+}
+
+static CURRENT: LazyLock<RwLock<Option<MarketBias>>> = LazyLock::new(Default::default);
+
+pub fn current() -> Option<MarketBias> {
+    CURRENT.read().ok().and_then(|b| b.clone())
+}
+
+/// Stamps the prevailing bias on a new signal and flags one fired against a strong bias.
+pub fn annotate(signal: &mut Signal) {
+    let Some(bias) = current() else {
+        return;
+    };
+    signal.market_bias = Some(bias.score);
+    let against = match signal.signal_type {
+        SignalType::Long | SignalType::NewListing | SignalType::Breakout => bias.score <= -AGAINST_BIAS_SCORE,
+        SignalType::Short => bias.score >= AGAINST_BIAS_SCORE,
+    };
+    if against {
+        signal.add_reason(ReasonCode::AgainstBias { score: bias.score });
+    }
+}
+
+pub fn compute(store: &SharedState) -> Option<MarketBias> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let leader_changes: Vec<f64> = LEADERS
+        .iter()
+        .filter_map(|s| store.get(*s).and_then(|state| mover(&state, now)?.change_15m_pct))
+        .collect();
+    let leaders_change_15m_pct =
+        (!leader_changes.is_empty()).then(|| leader_changes.iter().sum::<f64>() / leader_changes.len() as f64);
+
+    let (mut green, mut red) = (0usize, 0usize);
+    let (mut net_buy_value, mut traded_value) = (0.0, 0.0);
+    for entry in store.iter() {
+        let state = entry.value();
+        match mover(state, now).and_then(|m| m.change_15m_pct) {
+            Some(change) if change > 0.0 => green += 1,
+            Some(change) if change < 0.0 => red += 1,
+            _ => {}
+        }
+        for candle in state.window.iter().rev().take(CVD_MINUTES) {
+            // Only the streams that report taker volume contribute
+            if let Some(buy) = candle.taker_buy_volume {
+                net_buy_value += (2.0 * buy - candle.volume) * candle.close;
+                traded_value += candle.volume * candle.close;
+            }
+        }
+    }
+    let breadth = (green + red > 0).then(|| (green as f64 - red as f64) / (green + red) as f64);
+    let cvd_ratio = (traded_value > 0.0).then(|| net_buy_value / traded_value);
+
+    // Weighted average of whatever components are available
+    let components = [
+        (0.4, leaders_change_15m_pct.map(|c| (c / FULL_TREND_PCT).clamp(-1.0, 1.0))),
+        (0.3, breadth),
+        (0.3, cvd_ratio),
+    ];
+    let weight: f64 = components.iter().filter(|(_, v)| v.is_some()).map(|(w, _)| w).sum();
+    if weight == 0.0 {
+        return None;
+    }
+    let score = components.iter().filter_map(|(w, v)| v.map(|v| w * v)).sum::<f64>() / weight * 100.0;
+    Some(MarketBias {
+        score,
+        label: BiasLabel::from_score(score),
+        leaders_change_15m_pct,
+        breadth,
+        cvd_ratio,
+        timestamp: now,
+    })
+}
+
+/// Recomputes the bias every `BIAS_INTERVAL_SECS` and broadcasts it.
+///
+/// Shard workers compute it over their own symbols (for `annotate`) but don't broadcast.
+pub async fn bias_task(store: SharedState, tx: broadcast::Sender<WsMessage>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs().max(1)));
+    loop {
+        interval.tick().await;
+        let Some(bias) = compute(&store) else {
+            continue;
+        };
+        if let Ok(mut current) = CURRENT.write() {
+            *current = Some(bias.clone());
+        }
+        if SHARD.aggregator_url.is_none() {
+            let _ = tx.send(WsMessage::MarketBias(bias));
+        }
+    }
+}
@@ -1,184 +1,493 @@
-use futures_util::{StreamExt, SinkExt};
+use futures_util::{SinkExt, StreamExt};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use serde::Deserialize;
 use url::Url;
-use crate::model::{MarketData, SymbolState};
+use crate::actor::{ActorContext, Router};
+use crate::config::SymbolFilter;
+use crate::exchange::{fetch_json, ClientError, Exchange, ExchangeClient, KlineNumbers, KlineTotals, OrderBook, PriceBar};
+use crate::ingest::{self, FrameHandler, Partitioned};
+use crate::model::Tick;
+use crate::ticker::for_each_ticker;
+use crate::metrics::METRICS;
+use crate::recorder;
+use crate::replay::Feed;
+use crate::shard::SHARD;
+use crate::shutdown::Shutdown;
 use crate::store::SharedState;
-use crate::scanner::{check_for_signals, WsMessage};
-use log::{info, error};
-// using crate::verifier inside function
-
-// Kline Event from !kline_1m without @arr effectively?
-// Actually for All Market Mini Tickers it is !miniTicker@arr.
-// For Klines, generic stream is <symbol>@kline_<interval>.
-// There is NO !kline@arr for all symbols.
-// Wait, is there?
-// Documentation says: "Aggregate Trade Streams", "Mark Price", "Ticker".
-// No "!kline@arr".
-// WE MUST USE !miniticker or !ticker.
-//
-// Re-read user request: "Binance WebSocket se !ticker@arr ya !kline_1m@arr ka data fetch karna hai."
-// The user MIGHT be mistaken about `!kline_1m@arr` existing.
-// Let's assume `!ticker@arr` is the intended one if `!kline` doesn't exist for all.
-//
-// BUT `!bookTicker` exists.
-//
-// Let's go back to `!ticker@arr` (24h rolling).
-// And use the "Volume Delta" approach.
-// 
-// To make it robust:
-// Store `last_volume` and `last_timestamp`.
-// `delta_vol = current_vol - last_vol`.
-// If `delta_vol < 0`, `delta_vol = current_vol` (new day).
-// Push `delta_vol` to a minute accumulator.
-//
-// Actually, I'll stick to the "Snapshot every minute" approach for the Sliding Window (History).
-// But for the "Current Spike", I can compare `Current 24h Vol` to `Volume 1 min ago`?
-// No, that requires history.
-//
-// Let's try to find if there's a way to get all klines.
-// If not, I will implement the "Snapshot 24h Volume" every minute.
-// This means the "Current Candle" is built by `current_24h - 24h_at_start_of_minute`.
+use std::sync::atomic::Ordering;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+// Two feeds, picked with `FEED_MODE`:
 //
-// Let's implement `binance_client.rs` to handle `!ticker@arr` and track the start-of-minute volume.
+// - `kline` (default): `<symbol>@kline_1m` for every trading perpetual, over as many combined
+//   stream connections as Binance's 200-streams-per-connection limit needs. Klines carry the
+//   exchange's own OHLC and taker-buy volume, so signal direction comes from real order flow.
+//   There is no all-market kline stream, hence one stream per symbol.
+// - `ticker`: the single `!ticker@arr` firehose (24h rolling totals, ~1/s). Minute volume is
+//   reconstructed from deltas of the 24h totals (see `CandleBuilder`); no taker-buy volume, but
+//   it needs no exchangeInfo and carries the 24h context.
+// Event parsing lives in `ticker.rs` (borrowed, allocation-free).
+
+pub const FUTURES_WS_URL: &str = "wss://fstream.binance.com/ws/!ticker@arr";
+pub const FUTURES_STREAM_URL: &str = "wss://fstream.binance.com/stream";
+pub const FUTURES_REST_URL: &str = "https://fapi.binance.com";
+
+// Binance caps a combined stream connection at 200 streams
+pub const MAX_STREAMS_PER_CONNECTION: usize = 200;
+
+// A half-open connection never errors, it just goes quiet. Pings make the server answer at
+// least that often, so a connection silent for IDLE_TIMEOUT is dead and gets reconnected.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedMode {
+    Kline,
+    Ticker,
+}
+
+pub fn feed_mode() -> FeedMode {
+    match std::env::var("FEED_MODE") {
+        Ok(v) if v.eq_ignore_ascii_case("ticker") => FeedMode::Ticker,
+        _ => FeedMode::Kline,
+    }
+}
 
 #[derive(Debug, Deserialize)]
-struct TickerEvent {
-    s: String, // Symbol
-    c: String, // Close price
-    v: String, // Total traded base asset volume
-    E: i64,    // Event time
+#[serde(rename_all = "camelCase")]
+struct OpenInterest {
+    open_interest: String,
 }
 
-// We need a map to store "Volume at start of current minute" for each symbol.
-// And "Last updated minute timestamp".
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayTicker {
+    symbol: String,
+    quote_volume: String,
+}
+
+pub static CLIENT: LazyLock<BinanceClient> = LazyLock::new(|| BinanceClient { http: crate::rest::client() });
+
+pub struct BinanceClient {
+    http: reqwest::Client,
+}
 
-pub async fn binance_ws_task(store: SharedState, tx: tokio::sync::broadcast::Sender<WsMessage>) {
-    let url = Url::parse("wss://fstream.binance.com/ws/!ticker@arr").unwrap();
-    info!("Connecting to Binance WebSocket: {}", url);
+impl ExchangeClient for BinanceClient {
+    const EXCHANGE: Exchange = Exchange::Binance;
 
-    let (ws_stream, _) = connect_async(url).await.expect("Failed to connect");
+    // From the exchangeInfo cache `exchange_info_task` keeps fresh
+    async fn symbols(&self) -> Result<Vec<String>, ClientError> {
+        Ok(crate::exchange_info::perpetuals())
+    }
+
+    // Errors bubble up to the supervisor, which restarts (reconnects) with backoff.
+    // Per-message problems (bad JSON, bad numbers) are counted and skipped instead.
+    async fn stream_ticks(&self, ctx: ActorContext, shutdown: Shutdown) -> Result<(), ClientError> {
+        match feed_mode() {
+            FeedMode::Kline => kline_streams(ctx, shutdown).await,
+            FeedMode::Ticker => ticker_stream(FUTURES_WS_URL, Self::EXCHANGE, ctx, shutdown).await,
+        }
+    }
+
+    async fn depth(&self, symbol: &str, limit: usize) -> Result<OrderBook, ClientError> {
+        let url = format!("{}/fapi/v1/depth?symbol={}&limit={}", FUTURES_REST_URL, symbol, limit);
+        fetch_json(&self.http, &url).await
+    }
+
+    async fn open_interest(&self, symbol: &str) -> Result<f64, ClientError> {
+        let url = format!("{}/fapi/v1/openInterest?symbol={}", FUTURES_REST_URL, symbol);
+        let oi: OpenInterest = fetch_json(&self.http, &url).await?;
+        oi.open_interest.parse().map_err(|e: std::num::ParseFloatError| ClientError::Malformed(e.to_string()))
+    }
+
+    async fn minute_bars(&self, symbol: &str, from: i64, to: i64) -> Result<Vec<PriceBar>, ClientError> {
+        let url = format!(
+            "{}/fapi/v1/klines?symbol={}&interval=1m&startTime={}&endTime={}&limit={}",
+            FUTURES_REST_URL, symbol, from, to - 1, crate::exchange::bar_limit(from, to)
+        );
+        let rows: Vec<Vec<serde_json::Value>> = fetch_json(&self.http, &url).await?;
+        price_bars(&rows).ok_or_else(|| ClientError::Malformed(format!("kline for {}", symbol)))
+    }
+}
+
+/// Finished bars from a klines response; futures and spot rows have the same layout. None
+/// when a row is malformed.
+pub(crate) fn price_bars(rows: &[Vec<serde_json::Value>]) -> Option<Vec<PriceBar>> {
+    let bars = rows
+        .iter()
+        .map(|row| {
+            let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+            Some(PriceBar { open_time: row.first()?.as_i64()?, high: number(2)?, low: number(3)?, close: number(4)? })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(crate::exchange::finished(bars))
+}
+
+/// One `!ticker@arr` connection, futures' or spot's (`exchange` tags the symbols).
+pub(crate) async fn ticker_stream(url: &str, exchange: Exchange, ctx: ActorContext, shutdown: Shutdown) -> Result<(), ClientError> {
+    let url = Url::parse(url)?;
+    info!(%url, exchange = exchange.name(), "Connecting to Binance WebSocket");
+
+    let (ws_stream, _) = connect_async(url).await?;
     info!("Connected to Binance WebSocket");
 
-    let (_, mut read) = ws_stream.split();
-    
-    // We need a local map to track volume at the start of the minute to calculate "current minute volume".
-    // Map<Symbol, (StartOfMinuteVolume, MinuteTimestamp)>
-    let mut volume_cache: dashmap::DashMap<String, (f64, i64)> = dashmap::DashMap::new();
-    let mut last_update_broadcast: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let (mut write, mut read) = ws_stream.split();
+
+    // This loop only reads; the frame is parsed by a worker and processed by the symbol actors.
+    // Every frame carries all symbols, so one worker keeps them in order.
+    let feed = Partitioned::spawn(1, "", || TickerFeed::new(ctx.clone(), exchange));
+    let recorded = match exchange {
+        Exchange::BinanceSpot => Feed::SpotTicker,
+        _ => Feed::Ticker,
+    };
 
-    while let Some(msg) = read.next().await {
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    let mut last_frame = Instant::now();
+
+    loop {
+        let msg = tokio::select! {
+            _ = shutdown.wait() => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            _ = ping.tick() => {
+                write.send(Message::Ping(Vec::new())).await?;
+                continue;
+            }
+            _ = sleep_until(last_frame + IDLE_TIMEOUT) => return Err(idle("WS idle, reconnecting")),
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+        };
+        last_frame = Instant::now();
         match msg {
             Ok(Message::Text(text)) => {
-                if let Ok(events) = serde_json::from_str::<Vec<TickerEvent>>(&text) {
-                    for event in events {
-                        let symbol = event.s;
-                        let price = event.c.parse::<f64>().unwrap_or(0.0);
-                        let volume_total = event.v.parse::<f64>().unwrap_or(0.0);
-                        let event_time = event.E;
-                        
-                        // Round to minute
-                        let current_minute = event_time / 60000;
-                        
-                        // Get or Insert cache
-                        let mut cache_entry = volume_cache.entry(symbol.clone()).or_insert((volume_total, current_minute));
-                        
-                        if cache_entry.1 < current_minute {
-                            // New minute started!
-                            // 1. Finalize the previous candle and push to History
-                            let prev_vol_total = cache_entry.0;
-                            let prev_minute_vol = if volume_total >= prev_vol_total {
-                                volume_total - prev_vol_total
-                            } else {
-                                volume_total // Reset happened
-                            };
-                            
-                            let mut state_entry = store.entry(symbol.clone()).or_insert_with(|| SymbolState { 
-                                symbol: symbol.clone(), 
-                                window: std::collections::VecDeque::new(),
-                                last_signal_time: None,
-                            });
-                            
-                            // Push to window
-                            state_entry.add_data(MarketData {
-                                symbol: symbol.clone(),
-                                price,
-                                volume: prev_minute_vol,
-                                timestamp: event_time,
-                            });
-                            
-                            // 2. Reset cache for new minute
-                            cache_entry.0 = volume_total;
-                            cache_entry.1 = current_minute;
-                        } else {
-                            // Same minute. 
-                            // Calculate "Current Minute Volume" so far.
-                            let start_of_min_vol = cache_entry.0;
-                            let current_min_vol = if volume_total >= start_of_min_vol {
-                                volume_total - start_of_min_vol
-                            } else {
-                                volume_total
-                            };
-                            
-                            let start_of_min_vol = cache_entry.0;
-                            // Check Signaler immediately! (Real-time)
-                            
-                            // 1. Prepare Market Data
-                            let market_data = MarketData {
-                                symbol: symbol.clone(),
-                                price,
-                                volume: current_min_vol,
-                                timestamp: event_time,
-                            };
-
-                            // 2. Check Signals
-                            let mut signal_found = None;
-                            if let Some(state_entry) = store.get(&symbol) {
-                                if let Some(signal) = check_for_signals(&state_entry, &market_data, 0.0) {
-                                     signal_found = Some(signal);
-                                } else {
-                                    // Check for "Live Update" if active signal exists within 60 mins
-                                    if let Some(last_time) = state_entry.last_signal_time {
-                                        if event_time - last_time < 60 * 60 * 1000 {
-                                            // THROTTLE: Only update every 2000ms
-                                            let last_broadcast = last_update_broadcast.get(&symbol).cloned().unwrap_or(0);
-                                            if event_time - last_broadcast > 2000 {
-                                                // Broadcast Update
-                                                let update = crate::scanner::SignalUpdate {
-                                                    symbol: symbol.clone(),
-                                                    price: market_data.price,
-                                                    volume: market_data.volume,
-                                                    timestamp: market_data.timestamp,
-                                                };
-                                                if let Ok(_) = tx.send(crate::scanner::WsMessage::Update(update)) {
-                                                    last_update_broadcast.insert(symbol.clone(), event_time);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            // 3. Process Signal (Outside lock)
-                            if let Some(mut signal) = signal_found {
-                                // Update Last Signal Time
-                                if let Some(mut state_mut) = store.get_mut(&symbol) {
-                                     state_mut.last_signal_time = Some(market_data.timestamp);
-                                }
-                                
-                                let tx = tx.clone();
-                                tokio::spawn(async move {
-                                    if crate::verifier::verify_signal(&mut signal).await {
-                                        let _ = tx.send(crate::scanner::WsMessage::Signal(signal));
-                                    }
-                                });
-                            }
-                        }
-                    }
+                recorder::record(recorded, &text);
+                feed.send(text);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                METRICS.record_error("binance_client");
+                error!(error = ?e, "WS Error");
+                return Err(e.into());
+            }
+        }
+    }
+    Err(ClientError::Closed)
+}
+
+// No frame, not even a pong, for IDLE_TIMEOUT: counted, and returned to the supervisor so its
+// restart reconnects and resubscribes
+fn idle(message: &str) -> ClientError {
+    METRICS.record_error("binance_client");
+    warn!(idle_secs = IDLE_TIMEOUT.as_secs(), "{}", message);
+    ClientError::Idle(IDLE_TIMEOUT.as_secs())
+}
+
+/// Turns `!ticker@arr` frames into ticks for the symbol actors. Shared by the live streams and
+/// `replay`; `exchange` is Binance (futures) or Binance spot.
+pub(crate) struct TickerFeed {
+    exchange: Exchange,
+    store: SharedState,
+    router: Router,
+    filter: SymbolFilter,
+    listed: HashMap<String, bool>, // Include/exclude/quote/trading verdicts, by native symbol
+    generation: u64,               // `universe::generation()` the verdicts were made under
+}
+
+impl TickerFeed {
+    pub(crate) fn new(ctx: ActorContext, exchange: Exchange) -> Self {
+        Self {
+            exchange,
+            store: ctx.store.clone(),
+            router: Router::new(ctx),
+            filter: SymbolFilter::init(),
+            listed: HashMap::new(),
+            generation: crate::universe::generation(),
+        }
+    }
+}
+
+impl FrameHandler for TickerFeed {
+    fn handle(&mut self, text: &str) {
+        METRICS.last_feed_message_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        METRICS.pipeline.messages_received.fetch_add(1, Ordering::Relaxed);
+        // Contracts started or stopped trading: judge every symbol again
+        let generation = crate::universe::generation();
+        if generation != self.generation {
+            self.listed.clear();
+            self.generation = generation;
+        }
+        let parsed = for_each_ticker(text, |event| {
+            let allowed = match self.listed.get(event.symbol) {
+                Some(allowed) => *allowed,
+                None => {
+                    // Spot pairs missing from the futures exchangeInfo fall back to the name's suffix
+                    let quote = crate::exchange_info::get(event.symbol).map(|m| m.quote_asset);
+                    let allowed = self.filter.allows(event.symbol, quote.as_deref())
+                        && crate::universe::tradable(&self.exchange.tag(event.symbol));
+                    self.listed.insert(event.symbol.to_string(), allowed);
+                    allowed
                 }
+            };
+            if !allowed {
+                return;
+            }
+            // Futures symbols are keyed bare, so the hot path only allocates for spot
+            let key = match self.exchange {
+                Exchange::Binance => Cow::Borrowed(event.symbol),
+                exchange => Cow::Owned(exchange.tag(event.symbol)),
+            };
+            // Another shard's symbol: skip before touching the numbers
+            if !SHARD.owns(&key) {
+                METRICS.shard_skipped.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            // A malformed number must not become a 0.0 price in the window
+            match (event.price(), event.volume_total(), event.quote_volume_total()) {
+                // The 24h volume gate only decides whether a symbol gets state; one
+                // already scanned keeps its ticks when the day turns quiet
+                (Ok(_), Ok(_), Ok(quote_volume_total))
+                    if !self.filter.allows_volume(quote_volume_total) && !self.store.contains_key(key.as_ref()) => {}
+                (Ok(price), Ok(volume_total), Ok(quote_volume_total)) => self.router.route(&key, Tick {
+                    price,
+                    volume_total,
+                    quote_volume_total,
+                    trades_total: event.trades,
+                    taker_buy_volume: None,
+                    taker_buy_quote_volume: None,
+                    range: None,
+                    day_high: event.day_high(),
+                    day_low: event.day_low(),
+                    day_change_pct: event.day_change_percent(),
+                    event_time: event.event_time,
+                }),
+                _ => {
+                    METRICS.record_error("ticker");
+                    debug!(symbol = event.symbol, "Skipping ticker event with malformed numbers");
+                }
+            }
+        });
+        match parsed {
+            Ok(count) => {
+                METRICS.pipeline.events_parsed.fetch_add(count as u64, Ordering::Relaxed);
+            }
+            Err(e) => {
+                METRICS.pipeline.parse_errors.fetch_add(1, Ordering::Relaxed);
+                METRICS.record_error("ticker");
+                debug!(error = %e, "Failed to parse ticker message");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CombinedKline<'a> {
+    #[serde(borrow)]
+    data: KlineEvent<'a>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KlineEvent<'a> {
+    #[serde(rename = "E")]
+    event_time: i64,
+    #[serde(rename = "s", borrow)]
+    symbol: &'a str,
+    #[serde(rename = "k", borrow)]
+    kline: Kline<'a>,
+}
+
+// Values are for the kline so far (they reset every minute), as strings like the ticker's
+#[derive(Debug, Deserialize)]
+struct Kline<'a> {
+    #[serde(rename = "t")]
+    start: i64,
+    #[serde(rename = "T")]
+    end: i64,
+    #[serde(rename = "o", borrow)]
+    open: &'a str,
+    #[serde(rename = "h", borrow)]
+    high: &'a str,
+    #[serde(rename = "l", borrow)]
+    low: &'a str,
+    #[serde(rename = "c", borrow)]
+    close: &'a str,
+    #[serde(rename = "v", borrow)]
+    volume: &'a str,
+    #[serde(rename = "q", borrow)]
+    quote_volume: &'a str,
+    #[serde(rename = "n")]
+    trades: u64,
+    #[serde(rename = "V", borrow)]
+    taker_buy_volume: &'a str,
+    #[serde(rename = "Q", borrow)]
+    taker_buy_quote_volume: &'a str,
+}
+
+impl Kline<'_> {
+    fn numbers(&self, event_time: i64) -> Result<KlineNumbers, std::num::ParseFloatError> {
+        Ok(KlineNumbers {
+            start: self.start,
+            end: self.end,
+            event_time,
+            open: self.open.parse()?,
+            high: self.high.parse()?,
+            low: self.low.parse()?,
+            close: self.close.parse()?,
+            volume: self.volume.parse()?,
+            quote_volume: self.quote_volume.parse()?,
+            trades: self.trades,
+            taker_buy_volume: Some(self.taker_buy_volume.parse()?),
+            taker_buy_quote_volume: Some(self.taker_buy_quote_volume.parse()?),
+        })
+    }
+}
+
+// Perpetuals this instance scans, waiting for exchangeInfo to load them the first time,
+// narrowed by the symbol filter
+async fn universe(ctx: &ActorContext) -> Result<Vec<String>, ClientError> {
+    let perpetuals = loop {
+        let symbols: Vec<String> = CLIENT.symbols().await.unwrap_or_default().into_iter().filter(|s| SHARD.owns(s)).collect();
+        if !symbols.is_empty() {
+            break symbols;
+        }
+        debug!("Waiting for exchangeInfo before subscribing to klines");
+        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    };
+    let filter = SymbolFilter::init();
+    let mut symbols: Vec<String> = perpetuals
+        .into_iter()
+        .filter(|s| filter.allows(s, crate::exchange_info::get(s).map(|m| m.quote_asset).as_deref()))
+        .collect();
+    if filter.min_day_volume > 0.0 {
+        // Symbols already in the store keep their stream, as in ticker mode
+        let tickers: Vec<DayTicker> = fetch_json(&CLIENT.http, &format!("{}/fapi/v1/ticker/24hr", FUTURES_REST_URL)).await?;
+        let volumes: HashMap<String, f64> =
+            tickers.into_iter().filter_map(|t| Some((t.symbol, t.quote_volume.parse().ok()?))).collect();
+        symbols.retain(|s| {
+            ctx.store.contains_key(s) || volumes.get(s).is_some_and(|v| filter.allows_volume(*v))
+        });
+    }
+    if symbols.is_empty() {
+        warn!(filter = ?filter, "No symbols pass the symbol filter");
+    }
+    Ok(symbols)
+}
+
+// One connection per chunk of the universe. The first to fail takes the others down with it,
+// so the supervisor's restart reconnects as a whole. When contracts start or stop trading
+// (see `universe`) all of them are dropped and the new universe subscribed.
+async fn kline_streams(ctx: ActorContext, shutdown: Shutdown) -> Result<(), ClientError> {
+    loop {
+        let symbols = tokio::select! {
+            symbols = universe(&ctx) => symbols?,
+            _ = shutdown.wait() => return Ok(()),
+        };
+        let generation = crate::universe::generation();
+        let chunks: Vec<Vec<String>> = symbols.chunks(MAX_STREAMS_PER_CONNECTION).map(|c| c.to_vec()).collect();
+        info!(symbols = symbols.len(), connections = chunks.len(), "Subscribing to kline streams");
+        let connections = chunks.into_iter().map(|chunk| kline_connection(ctx.clone(), chunk, shutdown.clone()));
+        tokio::select! {
+            result = futures_util::future::try_join_all(connections) => {
+                result?;
+                if shutdown.is_triggered() {
+                    return Ok(());
+                }
+                return Err(ClientError::Closed);
+            }
+            _ = crate::universe::changed(generation) => info!("Trading contracts changed, resubscribing kline streams"),
+        }
+    }
+}
+
+async fn kline_connection(ctx: ActorContext, symbols: Vec<String>, shutdown: Shutdown) -> Result<(), ClientError> {
+    let streams: Vec<String> = symbols.iter().map(|s| format!("{}@kline_1m", s.to_lowercase())).collect();
+    let url = Url::parse(&format!("{}?streams={}", FUTURES_STREAM_URL, streams.join("/")))?;
+    let (ws_stream, _) = connect_async(url).await?;
+    info!(streams = streams.len(), "Connected to Binance kline streams");
+    let (mut write, mut read) = ws_stream.split();
+
+    // Chunks never overlap and frames are partitioned by stream, so each symbol's actor is only
+    // ever fed by one of this connection's workers
+    let feed = Partitioned::spawn(ingest::workers(), "stream", || KlineFeed::new(ctx.clone()));
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    let mut last_frame = Instant::now();
+
+    loop {
+        let msg = tokio::select! {
+            _ = shutdown.wait() => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            _ = ping.tick() => {
+                write.send(Message::Ping(Vec::new())).await?;
+                continue;
+            }
+            _ = sleep_until(last_frame + IDLE_TIMEOUT) => return Err(idle("Kline stream idle, reconnecting")),
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+        };
+        last_frame = Instant::now();
+        match msg {
+            Ok(Message::Text(text)) => {
+                recorder::record(Feed::Kline, &text);
+                feed.send(text);
             }
             Ok(_) => {}
-            Err(e) => error!("WS Error: {:?}", e),
+            Err(e) => {
+                METRICS.record_error("binance_client");
+                error!(error = ?e, "Kline stream error");
+                return Err(e.into());
+            }
         }
     }
+    Err(ClientError::Closed)
+}
+
+/// Turns combined-stream `<symbol>@kline_1m` frames into ticks for the symbol actors. Shared by
+/// the live streams and `replay`.
+pub(crate) struct KlineFeed {
+    router: Router,
+    totals: HashMap<String, KlineTotals>,
+}
+
+impl KlineFeed {
+    pub(crate) fn new(ctx: ActorContext) -> Self {
+        Self { router: Router::new(ctx), totals: HashMap::new() }
+    }
+}
+
+impl FrameHandler for KlineFeed {
+    fn handle(&mut self, text: &str) {
+        METRICS.last_feed_message_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        METRICS.pipeline.messages_received.fetch_add(1, Ordering::Relaxed);
+        let event = match serde_json::from_str::<CombinedKline>(text) {
+            Ok(combined) => combined.data,
+            Err(e) => {
+                METRICS.pipeline.parse_errors.fetch_add(1, Ordering::Relaxed);
+                METRICS.record_error("kline");
+                debug!(error = %e, "Failed to parse kline message");
+                return;
+            }
+        };
+        let Ok(numbers) = event.kline.numbers(event.event_time) else {
+            METRICS.record_error("kline");
+            debug!(symbol = event.symbol, "Skipping kline event with malformed numbers");
+            return;
+        };
+        METRICS.pipeline.events_parsed.fetch_add(1, Ordering::Relaxed);
+        let tick = match self.totals.get_mut(event.symbol) {
+            Some(t) => t.tick(&numbers),
+            None => self.totals.entry(event.symbol.to_string()).or_default().tick(&numbers),
+        };
+        self.router.route(event.symbol, tick);
+    }
 }
@@ -0,0 +1,64 @@
+use crate::actor::ActorContext;
+use crate::binance_client::{price_bars, ticker_stream};
+use crate::exchange::{fetch_json, ClientError, Exchange, ExchangeClient, OrderBook, PriceBar};
+use crate::shutdown::Shutdown;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// Binance spot, next to the futures feed (`EXCHANGES=binance,binance_spot`). Spot is watched
+// through its own `!ticker@arr` firehose, whatever `FEED_MODE` is: one connection, no
+// exchangeInfo needed, minute volume from the 24h totals as in futures ticker mode. Symbols are
+// keyed "SPOT:BTCUSDT". Spot has no open interest; `divergence` compares it with futures.
+
+pub const SPOT_WS_URL: &str = "wss://stream.binance.com:9443/ws/!ticker@arr";
+pub const SPOT_REST_URL: &str = "https://api.binance.com";
+
+pub static CLIENT: LazyLock<SpotClient> = LazyLock::new(|| SpotClient { http: crate::rest::client() });
+
+pub struct SpotClient {
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SpotSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotSymbol {
+    symbol: String,
+}
+
+impl ExchangeClient for SpotClient {
+    const EXCHANGE: Exchange = Exchange::BinanceSpot;
+
+    async fn symbols(&self) -> Result<Vec<String>, ClientError> {
+        let url = format!("{}/api/v3/exchangeInfo?symbolStatus=TRADING", SPOT_REST_URL);
+        let info: ExchangeInfo = fetch_json(&self.http, &url).await?;
+        let mut symbols: Vec<String> = info.symbols.into_iter().map(|s| s.symbol).collect();
+        symbols.sort();
+        Ok(symbols)
+    }
+
+    async fn stream_ticks(&self, ctx: ActorContext, shutdown: Shutdown) -> Result<(), ClientError> {
+        ticker_stream(SPOT_WS_URL, Self::EXCHANGE, ctx, shutdown).await
+    }
+
+    async fn depth(&self, symbol: &str, limit: usize) -> Result<OrderBook, ClientError> {
+        let url = format!("{}/api/v3/depth?symbol={}&limit={}", SPOT_REST_URL, symbol, limit);
+        fetch_json(&self.http, &url).await
+    }
+
+    async fn open_interest(&self, _symbol: &str) -> Result<f64, ClientError> {
+        Err(ClientError::Unsupported { exchange: Self::EXCHANGE.name(), what: "open interest" })
+    }
+
+    async fn minute_bars(&self, symbol: &str, from: i64, to: i64) -> Result<Vec<PriceBar>, ClientError> {
+        let url = format!(
+            "{}/api/v3/klines?symbol={}&interval=1m&startTime={}&endTime={}&limit={}",
+            SPOT_REST_URL, symbol, from, to - 1, crate::exchange::bar_limit(from, to)
+        );
+        let rows: Vec<Vec<serde_json::Value>> = fetch_json(&self.http, &url).await?;
+        price_bars(&rows).ok_or_else(|| ClientError::Malformed(format!("kline for {}", symbol)))
+    }
+}
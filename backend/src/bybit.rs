@@ -0,0 +1,297 @@
+use crate::actor::{ActorContext, Router};
+use crate::ingest::{self, FrameHandler, Partitioned};
+use crate::exchange::{fetch_json, ClientError, Exchange, ExchangeClient, KlineNumbers, KlineTotals, OrderBook, PriceBar};
+use crate::metrics::METRICS;
+use crate::shard::SHARD;
+use crate::shutdown::Shutdown;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, error, info};
+use url::Url;
+
+// Bybit USDT/USDC linear perpetuals (v5 API). Same shape as the Binance kline feed: one
+// `kline.1.<SYMBOL>` topic per contract, chunked over connections. Bybit klines carry no
+// taker-buy volume or trade count, so its signals take direction from price alone.
+
+pub const LINEAR_WS_URL: &str = "wss://stream.bybit.com/v5/public/linear";
+pub const REST_URL: &str = "https://api.bybit.com";
+
+// Topics per connection, and per subscribe request (Bybit rejects longer arg lists)
+const MAX_TOPICS_PER_CONNECTION: usize = 200;
+const TOPICS_PER_SUBSCRIBE: usize = 10;
+// Bybit drops connections that stay quiet for 30s, ping well inside that
+const PING_INTERVAL_SECS: u64 = 20;
+
+pub static CLIENT: LazyLock<BybitClient> = LazyLock::new(|| BybitClient { http: crate::rest::client() });
+
+pub struct BybitClient {
+    http: reqwest::Client,
+}
+
+// Every v5 REST response: retCode 0 on success, the payload in `result`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Response<T> {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Instruments {
+    list: Vec<Instrument>,
+    #[serde(default)]
+    next_page_cursor: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Instrument {
+    symbol: String,
+    contract_type: String, // LinearPerpetual, LinearFutures
+    status: String,        // Trading, PreLaunch, Settling, ...
+}
+
+#[derive(Debug, Deserialize)]
+struct Book {
+    b: Vec<[String; 2]>,
+    a: Vec<[String; 2]>,
+}
+
+// `[start, open, high, low, close, volume, turnover]`, newest first
+#[derive(Debug, Deserialize)]
+struct Klines {
+    list: Vec<[String; 7]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenInterests {
+    list: Vec<OpenInterest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenInterest {
+    open_interest: String,
+}
+
+impl BybitClient {
+    async fn get<T: serde::de::DeserializeOwned>(&self, path_and_query: &str) -> Result<T, ClientError> {
+        let resp: Response<T> = fetch_json(&self.http, &format!("{}{}", REST_URL, path_and_query)).await?;
+        match resp.result {
+            Some(result) if resp.ret_code == 0 => Ok(result),
+            _ => Err(ClientError::Api { exchange: Self::EXCHANGE.name(), code: resp.ret_code, msg: resp.ret_msg }),
+        }
+    }
+}
+
+impl ExchangeClient for BybitClient {
+    const EXCHANGE: Exchange = Exchange::Bybit;
+
+    async fn symbols(&self) -> Result<Vec<String>, ClientError> {
+        let mut symbols = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let page: Instruments =
+                self.get(&format!("/v5/market/instruments-info?category=linear&limit=1000&cursor={}", cursor)).await?;
+            symbols.extend(
+                page.list
+                    .into_iter()
+                    .filter(|i| i.contract_type == "LinearPerpetual" && i.status == "Trading")
+                    .map(|i| i.symbol),
+            );
+            if page.next_page_cursor.is_empty() {
+                break;
+            }
+            cursor = page.next_page_cursor;
+        }
+        symbols.sort();
+        Ok(symbols)
+    }
+
+    // One connection per chunk; the first to fail takes the others down, as for Binance
+    async fn stream_ticks(&self, ctx: ActorContext, shutdown: Shutdown) -> Result<(), ClientError> {
+        let symbols: Vec<String> =
+            self.symbols().await?.into_iter().filter(|s| SHARD.owns(&Self::EXCHANGE.tag(s))).collect();
+        let chunks: Vec<Vec<String>> = symbols.chunks(MAX_TOPICS_PER_CONNECTION).map(|c| c.to_vec()).collect();
+        info!(symbols = symbols.len(), connections = chunks.len(), "Subscribing to Bybit kline streams");
+        let connections = chunks.into_iter().map(|chunk| kline_connection(ctx.clone(), chunk, shutdown.clone()));
+        futures_util::future::try_join_all(connections).await?;
+        if shutdown.is_triggered() {
+            return Ok(());
+        }
+        Err(ClientError::Closed)
+    }
+
+    async fn depth(&self, symbol: &str, limit: usize) -> Result<OrderBook, ClientError> {
+        let book: Book = self.get(&format!("/v5/market/orderbook?category=linear&symbol={}&limit={}", symbol, limit)).await?;
+        Ok(OrderBook { bids: book.b, asks: book.a })
+    }
+
+    async fn open_interest(&self, symbol: &str) -> Result<f64, ClientError> {
+        let oi: OpenInterests = self
+            .get(&format!("/v5/market/open-interest?category=linear&symbol={}&intervalTime=5min&limit=1", symbol))
+            .await?;
+        let latest = oi.list.first().ok_or_else(|| ClientError::Malformed(format!("no open interest for {}", symbol)))?;
+        latest.open_interest.parse().map_err(|e: std::num::ParseFloatError| ClientError::Malformed(e.to_string()))
+    }
+
+    async fn minute_bars(&self, symbol: &str, from: i64, to: i64) -> Result<Vec<PriceBar>, ClientError> {
+        let klines: Klines = self
+            .get(&format!(
+                "/v5/market/kline?category=linear&symbol={}&interval=1&start={}&end={}&limit={}",
+                symbol,
+                from,
+                to - 1,
+                crate::exchange::bar_limit(from, to)
+            ))
+            .await?;
+        let bars = klines
+            .list
+            .iter()
+            .rev()
+            .map(|k| {
+                Some(PriceBar { open_time: k[0].parse().ok()?, high: k[2].parse().ok()?, low: k[3].parse().ok()?, close: k[4].parse().ok()? })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| ClientError::Malformed(format!("kline for {}", symbol)))?;
+        Ok(crate::exchange::finished(bars))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KlineMessage<'a> {
+    #[serde(borrow)]
+    topic: &'a str, // kline.1.<SYMBOL>
+    #[serde(borrow)]
+    data: Vec<Kline<'a>>,
+}
+
+// Values are for the kline so far, as strings
+#[derive(Debug, Deserialize)]
+struct Kline<'a> {
+    start: i64,
+    end: i64,
+    #[serde(borrow)]
+    open: &'a str,
+    #[serde(borrow)]
+    high: &'a str,
+    #[serde(borrow)]
+    low: &'a str,
+    #[serde(borrow)]
+    close: &'a str,
+    #[serde(borrow)]
+    volume: &'a str,
+    #[serde(borrow)]
+    turnover: &'a str, // Quote volume
+    timestamp: i64,
+}
+
+impl Kline<'_> {
+    fn numbers(&self) -> Result<KlineNumbers, std::num::ParseFloatError> {
+        Ok(KlineNumbers {
+            start: self.start,
+            end: self.end,
+            event_time: self.timestamp,
+            open: self.open.parse()?,
+            high: self.high.parse()?,
+            low: self.low.parse()?,
+            close: self.close.parse()?,
+            volume: self.volume.parse()?,
+            quote_volume: self.turnover.parse()?,
+            trades: 0,
+            taker_buy_volume: None,
+            taker_buy_quote_volume: None,
+        })
+    }
+}
+
+async fn kline_connection(ctx: ActorContext, symbols: Vec<String>, shutdown: Shutdown) -> Result<(), ClientError> {
+    let (ws_stream, _) = connect_async(Url::parse(LINEAR_WS_URL)?).await?;
+    let (mut write, mut read) = ws_stream.split();
+    for chunk in symbols.chunks(TOPICS_PER_SUBSCRIBE) {
+        let args: Vec<String> = chunk.iter().map(|s| format!("kline.1.{}", s)).collect();
+        write.send(Message::Text(serde_json::json!({ "op": "subscribe", "args": args }).to_string())).await?;
+    }
+    info!(streams = symbols.len(), "Connected to Bybit kline streams");
+
+    // Chunks never overlap and frames are partitioned by topic, so each symbol's actor is only
+    // ever fed by one of this connection's workers
+    let feed = Partitioned::spawn(ingest::workers(), "topic", || KlineFeed::new(ctx.clone()));
+    let mut ping = tokio::time::interval(tokio::time::Duration::from_secs(PING_INTERVAL_SECS));
+
+    loop {
+        let msg = tokio::select! {
+            _ = shutdown.wait() => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            _ = ping.tick() => {
+                write.send(Message::Text(r#"{"op":"ping"}"#.to_string())).await?;
+                continue;
+            }
+            msg = read.next() => msg,
+        };
+        match msg {
+            // Subscription acks and pongs carry no topic
+            Some(Ok(Message::Text(text))) if text.contains("\"topic\"") => feed.send(text),
+            Some(Ok(_)) => {}
+            Some(Err(e)) => {
+                METRICS.record_error("bybit");
+                error!(error = ?e, "Bybit kline stream error");
+                return Err(e.into());
+            }
+            None => return Err(ClientError::Closed),
+        }
+    }
+}
+
+// Turns `kline.1.<SYMBOL>` frames into ticks for the symbol actors
+struct KlineFeed {
+    router: Router,
+    totals: HashMap<String, (String, KlineTotals)>, // native -> (tagged, totals)
+}
+
+impl KlineFeed {
+    fn new(ctx: ActorContext) -> Self {
+        Self { router: Router::new(ctx), totals: HashMap::new() }
+    }
+}
+
+impl FrameHandler for KlineFeed {
+    fn handle(&mut self, text: &str) {
+        METRICS.last_feed_message_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        METRICS.pipeline.messages_received.fetch_add(1, Ordering::Relaxed);
+        let message = match serde_json::from_str::<KlineMessage>(text) {
+            Ok(message) => message,
+            Err(e) => {
+                METRICS.pipeline.parse_errors.fetch_add(1, Ordering::Relaxed);
+                METRICS.record_error("bybit");
+                debug!(error = %e, "Failed to parse Bybit kline message");
+                return;
+            }
+        };
+        let Some(symbol) = message.topic.strip_prefix("kline.1.") else {
+            return;
+        };
+        for kline in &message.data {
+            let Ok(numbers) = kline.numbers() else {
+                METRICS.record_error("bybit");
+                debug!(symbol, "Skipping Bybit kline with malformed numbers");
+                continue;
+            };
+            METRICS.pipeline.events_parsed.fetch_add(1, Ordering::Relaxed);
+            let (tagged, symbol_totals) = self
+                .totals
+                .entry(symbol.to_string())
+                .or_insert_with(|| (BybitClient::EXCHANGE.tag(symbol), KlineTotals::default()));
+            let tick = symbol_totals.tick(&numbers);
+            self.router.route(tagged, tick);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::exchange::{fetch_json, ClientError};
+use crate::metrics::METRICS;
+use crate::scanner::WsMessage;
+use crate::shard::SHARD;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+pub const SPOT_REST_URL: &str = "https://api.binance.com";
+
+// Binance settles funding every 8 hours on most contracts
+const FUNDINGS_PER_YEAR: f64 = 3.0 * 365.0;
+
+fn scan_secs() -> u64 {
+    std::env::var("CARRY_SCAN_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(300)
+}
+
+fn min_yield_pct() -> f64 {
+    std::env::var("CARRY_MIN_YIELD_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or(20.0)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CarryError {
+    #[error(transparent)]
+    Request(#[from] ClientError),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PremiumIndex {
+    symbol: String,
+    mark_price: String,
+    last_funding_rate: String,
+    next_funding_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotPrice {
+    symbol: String,
+    price: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum Market {
+    Spot,
+    Perp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CarryLeg {
+    pub market: Market,
+    pub side: Side,
+}
+
+/// A funding carry: hold spot against the perp and collect funding. Not a momentum signal.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CarryOpportunity {
+    pub symbol: String,
+    pub funding_rate: f64,         // Last settled rate, per funding interval
+    pub annualized_yield_pct: f64, // Funding rate times fundings per year, not compounded
+    pub basis_pct: f64,            // Perp mark vs spot, positive when the perp trades rich
+    pub mark_price: f64,
+    pub spot_price: f64,
+    pub next_funding_time: i64,
+    pub legs: Vec<CarryLeg>,
+    pub timestamp: i64,
+}
+
+/// Latest scan, for `GET /api/carry`.
+#[derive(Debug, Clone, Default, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CarryScan {
+    pub opportunities: Vec<CarryOpportunity>, // Highest yield first
+    pub timestamp: i64,
+}
+
+static LATEST: LazyLock<RwLock<Arc<CarryScan>>> = LazyLock::new(Default::default);
+
+pub fn latest() -> Arc<CarryScan> {
+    LATEST.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn opportunity(index: &PremiumIndex, spot_price: f64, now: i64) -> Option<CarryOpportunity> {
+    let funding_rate: f64 = index.last_funding_rate.parse().ok()?;
+    let mark_price: f64 = index.mark_price.parse().ok()?;
+    if spot_price <= 0.0 || mark_price <= 0.0 {
+        return None;
+    }
+    let annualized_yield_pct = funding_rate * FUNDINGS_PER_YEAR * 100.0;
+    if annualized_yield_pct.abs() < min_yield_pct() {
+        return None;
+    }
+    // Positive funding: longs pay shorts, so own spot and short the perp. Negative: the reverse
+    // (the spot leg then needs a margin borrow).
+    let legs = if funding_rate > 0.0 {
+        vec![CarryLeg { market: Market::Spot, side: Side::Buy }, CarryLeg { market: Market::Perp, side: Side::Sell }]
+    } else {
+        vec![CarryLeg { market: Market::Spot, side: Side::Sell }, CarryLeg { market: Market::Perp, side: Side::Buy }]
+    };
+    Some(CarryOpportunity {
+        symbol: index.symbol.clone(),
+        funding_rate,
+        annualized_yield_pct: annualized_yield_pct.abs(),
+        basis_pct: (mark_price - spot_price) / spot_price * 100.0,
+        mark_price,
+        spot_price,
+        next_funding_time: index.next_funding_time,
+        legs,
+        timestamp: now,
+    })
+}
+
+async fn scan(client: &reqwest::Client, store: &SharedState) -> Result<CarryScan, CarryError> {
+    let indexes: Vec<PremiumIndex> = fetch_json(client, &format!("{}/fapi/v1/premiumIndex", FUTURES_REST_URL)).await?;
+    let spot: Vec<SpotPrice> = fetch_json(client, &format!("{}/api/v3/ticker/price", SPOT_REST_URL)).await?;
+    let spot: HashMap<String, f64> = spot
+        .into_iter()
+        .filter_map(|p| Some((p.symbol, p.price.parse().ok()?)))
+        .collect();
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut opportunities: Vec<CarryOpportunity> = indexes
+        .iter()
+        // Only what this instance tracks; the aggregator (no symbols of its own) scans everything
+        .filter(|i| store.is_empty() || store.contains_key(&i.symbol))
+        // Perps without a same-named spot pair (e.g. 1000PEPEUSDT) have no spot leg
+        .filter_map(|i| opportunity(i, *spot.get(&i.symbol)?, now))
+        .collect();
+    opportunities.sort_by(|a, b| b.annualized_yield_pct.total_cmp(&a.annualized_yield_pct));
+    Ok(CarryScan { opportunities, timestamp: now })
+}
+
+/// Scans funding and basis every `CARRY_SCAN_SECS` and announces each opportunity once per
+/// funding interval as a `Carry` message.
+///
+/// Shard workers skip it: funding is market-wide, the aggregator covers it.
+pub async fn carry_task(store: SharedState, tx: broadcast::Sender<WsMessage>) {
+    let client = crate::rest::client();
+    // Symbol -> funding time already announced
+    let mut announced: HashMap<String, i64> = HashMap::new();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(scan_secs().max(1)));
+    loop {
+        interval.tick().await;
+        if SHARD.aggregator_url.is_some() {
+            continue;
+        }
+        let result = match scan(&client, &store).await {
+            Ok(result) => result,
+            Err(e) => {
+                METRICS.record_error("carry");
+                warn!(error = %e, "Carry scan failed");
+                continue;
+            }
+        };
+        announced.retain(|symbol, _| result.opportunities.iter().any(|o| &o.symbol == symbol));
+        for opportunity in &result.opportunities {
+            if announced.get(&opportunity.symbol) == Some(&opportunity.next_funding_time) {
+                continue;
+            }
+            announced.insert(opportunity.symbol.clone(), opportunity.next_funding_time);
+            info!(
+                symbol = %opportunity.symbol,
+                funding_rate = opportunity.funding_rate,
+                annualized_yield_pct = opportunity.annualized_yield_pct,
+                basis_pct = opportunity.basis_pct,
+                "Carry opportunity"
+            );
+            let _ = tx.send(WsMessage::Carry(opportunity.clone()));
+        }
+        if let Ok(mut latest) = LATEST.write() {
+            *latest = Arc::new(result);
+        }
+    }
+}
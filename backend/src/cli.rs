@@ -0,0 +1,355 @@
+use crate::history::{HistoryManager, HistoryQuery, SignalRecord, Stats};
+use std::collections::HashMap;
+use std::io::Write;
+
+// The binary's command line: `teeb_trade_backend [COMMAND] [OPTIONS]`, `run` when no command is
+// given. The older flag forms (`--check`, `--backtest ...`, `--export-state ...`) still work and
+// map onto the same commands, so existing units and scripts keep running. Parsed by hand: the
+// grammar is a handful of `--name value` options per command.
+
+pub const USAGE: &str = "\
+usage: teeb_trade_backend [COMMAND] [OPTIONS]
+
+commands:
+  run [--replay <recording>]              start the scanner (the default)
+  backtest <SYMBOL,...|dump.csv,...> [--from T] [--to T] [--days N] [--report out.json]
+                                          replay klines through the scanner
+  stats [--json]                          win rates from the history database
+  export [--format csv|json] [--out FILE] [--symbol S] [--strategy S] [--from T] [--to T]
+                                          write recorded signals and outcomes
+  validate-config                         check the deployment and exit (also --check)
+  export-types [dir]                      regenerate the frontend's TypeScript types
+  seal-secrets <plain.json>               encrypt secrets into SECRETS_STORE_PATH
+  export-state <archive.json>             archive history, snapshot and positions
+  restore-state <archive.json>            restore such an archive
+  help                                    this text
+
+T is a date (2024-05-01), an RFC 3339 time or Unix milliseconds.";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("unknown command '{0}'")]
+    UnknownCommand(String),
+    #[error("unknown option '{option}' for {command}")]
+    UnknownOption { command: &'static str, option: String },
+    #[error("{0} needs a value")]
+    MissingValue(String),
+    #[error("invalid {option} '{value}'")]
+    Invalid { option: &'static str, value: String },
+    #[error("usage: {0}")]
+    Usage(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Run { replay: Option<String> },
+    Backtest { targets: Vec<String>, from: Option<i64>, to: Option<i64>, days: i64, report: Option<String> },
+    Stats { json: bool },
+    Export { format: ExportFormat, out: Option<String>, query: HistoryQuery },
+    ValidateConfig,
+    ExportTypes { dir: Option<String> },
+    SealSecrets { input: Option<String> },
+    ExportState { path: Option<String> },
+    RestoreState { path: Option<String> },
+    Help,
+}
+
+// One command's arguments: positionals in order, then `--name value` / `--name=value` options
+// from `values` and bare `--name` switches from `flags`
+struct Args {
+    positional: Vec<String>,
+    values: HashMap<&'static str, String>,
+    flags: Vec<&'static str>,
+}
+
+impl Args {
+    fn parse(command: &'static str, args: &[String], values: &[&'static str], flags: &[&'static str]) -> Result<Self, CliError> {
+        let mut parsed = Args { positional: Vec::new(), values: HashMap::new(), flags: Vec::new() };
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg.clone());
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            if let Some(flag) = flags.iter().find(|f| **f == name) {
+                parsed.flags.push(flag);
+            } else if let Some(option) = values.iter().find(|v| **v == name) {
+                let value = inline.or_else(|| rest.next().cloned()).ok_or_else(|| CliError::MissingValue(name.to_string()))?;
+                parsed.values.insert(option, value);
+            } else {
+                return Err(CliError::UnknownOption { command, option: name.to_string() });
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.values.get(name).cloned()
+    }
+
+    fn time(&self, name: &'static str) -> Result<Option<i64>, CliError> {
+        self.values.get(name).map(|v| parse_time(name, v)).transpose()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+}
+
+// A date (midnight UTC), an RFC 3339 time or Unix milliseconds
+fn parse_time(option: &'static str, value: &str) -> Result<i64, CliError> {
+    if let Ok(ms) = value.parse::<i64>() {
+        return Ok(ms);
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp_millis());
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc().timestamp_millis())
+        .ok_or_else(|| CliError::Invalid { option, value: value.to_string() })
+}
+
+/// The command in `args` (as from `std::env::args`, program name first).
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let rest = args.get(1..).unwrap_or_default();
+    match rest.first().map(String::as_str) {
+        None => Ok(Command::Run { replay: None }),
+        Some("help" | "-h" | "--help") => Ok(Command::Help),
+        Some(first) if first.starts_with("--") => legacy(rest),
+        Some(name) => command(name, &rest[1..]),
+    }
+}
+
+fn command(name: &str, args: &[String]) -> Result<Command, CliError> {
+    let single = |command: &'static str, usage: &'static str| -> Result<Option<String>, CliError> {
+        let parsed = Args::parse(command, args, &[], &[])?;
+        if parsed.positional.len() > 1 {
+            return Err(CliError::Usage(usage));
+        }
+        Ok(parsed.positional.into_iter().next())
+    };
+    match name {
+        "run" => {
+            let parsed = Args::parse("run", args, &["--replay"], &[])?;
+            if !parsed.positional.is_empty() {
+                return Err(CliError::Usage("run [--replay <recording.ndjson[.gz] | recording dir>]"));
+            }
+            Ok(Command::Run { replay: parsed.value("--replay") })
+        }
+        "backtest" => parse_backtest(args),
+        "stats" => {
+            let parsed = Args::parse("stats", args, &[], &["--json"])?;
+            Ok(Command::Stats { json: parsed.flag("--json") })
+        }
+        "export" => parse_export(args),
+        "validate-config" => {
+            Args::parse("validate-config", args, &[], &[])?;
+            Ok(Command::ValidateConfig)
+        }
+        "export-types" => Ok(Command::ExportTypes { dir: single("export-types", "export-types [dir]")? }),
+        "seal-secrets" => Ok(Command::SealSecrets { input: single("seal-secrets", "seal-secrets <plain.json>")? }),
+        "export-state" => Ok(Command::ExportState { path: single("export-state", "export-state <archive.json>")? }),
+        "restore-state" => Ok(Command::RestoreState { path: single("restore-state", "restore-state <archive.json>")? }),
+        other => Err(CliError::UnknownCommand(other.to_string())),
+    }
+}
+
+fn parse_backtest(args: &[String]) -> Result<Command, CliError> {
+    let parsed = Args::parse("backtest", args, &["--from", "--to", "--days", "--report"], &[])?;
+    let [targets] = parsed.positional.as_slice() else {
+        return Err(CliError::Usage("backtest <SYMBOL,...|dump.csv,...> [--from T] [--to T] [--days N] [--report out.json]"));
+    };
+    let days = match parsed.value("--days") {
+        Some(days) => days.parse().ok().filter(|d| *d > 0).ok_or(CliError::Invalid { option: "--days", value: days })?,
+        None => 7,
+    };
+    let (from, to) = (parsed.time("--from")?, parsed.time("--to")?);
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err(CliError::Invalid { option: "--from", value: "not before --to".to_string() });
+        }
+    }
+    Ok(Command::Backtest {
+        targets: targets.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+        from,
+        to,
+        days,
+        report: parsed.value("--report"),
+    })
+}
+
+fn parse_export(args: &[String]) -> Result<Command, CliError> {
+    let parsed = Args::parse("export", args, &["--format", "--out", "--symbol", "--strategy", "--from", "--to"], &[])?;
+    if !parsed.positional.is_empty() {
+        return Err(CliError::Usage("export [--format csv|json] [--out FILE] [--symbol S] [--strategy S] [--from T] [--to T]"));
+    }
+    let format = match parsed.value("--format").as_deref() {
+        None | Some("csv") => ExportFormat::Csv,
+        Some("json") => ExportFormat::Json,
+        Some(other) => return Err(CliError::Invalid { option: "--format", value: other.to_string() }),
+    };
+    let query = HistoryQuery {
+        symbol: parsed.value("--symbol").map(|s| s.trim().to_uppercase()),
+        from: parsed.time("--from")?,
+        to: parsed.time("--to")?,
+        strategy: parsed.value("--strategy"),
+        ..Default::default()
+    };
+    Ok(Command::Export { format, out: parsed.value("--out"), query })
+}
+
+// The flag forms from before subcommands, matched anywhere in the arguments as they were
+fn legacy(args: &[String]) -> Result<Command, CliError> {
+    let after = |flag: &str| args.iter().position(|a| a == flag).map(|pos| args.get(pos + 1).cloned());
+    if args.iter().any(|a| a == "--check") {
+        return Ok(Command::ValidateConfig);
+    }
+    if let Some(input) = after("--seal-secrets") {
+        return Ok(Command::SealSecrets { input });
+    }
+    if let Some(path) = after("--export-state") {
+        return Ok(Command::ExportState { path });
+    }
+    if let Some(path) = after("--restore-state") {
+        return Ok(Command::RestoreState { path });
+    }
+    if let Some(dir) = after("--export-types") {
+        return Ok(Command::ExportTypes { dir });
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--backtest") {
+        return parse_backtest(&args[pos + 1..]);
+    }
+    if args.first().is_some_and(|a| a == "--replay") {
+        return command("run", args);
+    }
+    Err(CliError::UnknownOption { command: "run", option: args[0].clone() })
+}
+
+/// `stats`: the same numbers as `/api/stats`, from the history database.
+pub async fn stats(json: bool) -> i32 {
+    let history = HistoryManager::new(&crate::history::history_db_path(), &crate::history::history_path());
+    let stats = history.get_stats().await;
+    if json {
+        match serde_json::to_string_pretty(&crate::wire::versioned(stats)) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize stats: {}", e);
+                return 1;
+            }
+        }
+    } else {
+        print_stats(&stats);
+    }
+    0
+}
+
+fn print_stats(stats: &Stats) {
+    println!("{} signals, {:.1}% won, top gainer {}", stats.total_signals, stats.win_rate, stats.top_gainer);
+    if let Some(rate) = stats.feedback.taken_win_rate {
+        println!("{} taken ({:.1}% won), {} skipped", stats.feedback.taken, rate, stats.feedback.skipped);
+    }
+    let header = |title: &str| println!("\n{:<16} {:>7} {:>8} {:>10}", title, "signals", "win %", "avg max %");
+    let row = |key: &str, total: usize, win_rate: f64, avg_gain: f64| println!("{:<16} {:>7} {:>8.1} {:>10.2}", key, total, win_rate, avg_gain);
+    header("strategy");
+    for s in &stats.by_strategy {
+        row(&s.strategy, s.total_signals, s.win_rate, s.avg_gain);
+    }
+    for (title, buckets) in [("type", &stats.by_signal_type), ("symbol", &stats.by_symbol), ("week", &stats.by_week), ("day", &stats.by_day)] {
+        if buckets.is_empty() {
+            continue;
+        }
+        header(title);
+        for b in buckets {
+            row(&b.key, b.total_signals, b.win_rate, b.avg_gain);
+        }
+    }
+}
+
+/// `export`: every record matching `query`, newest first, to `out` or stdout.
+pub async fn export(format: ExportFormat, out: Option<&str>, mut query: HistoryQuery) -> i32 {
+    let history = HistoryManager::new(&crate::history::history_db_path(), &crate::history::history_path());
+    let mut records = Vec::new();
+    query.limit = Some(1000);
+    loop {
+        query.offset = Some(records.len());
+        let page = history.query(query.clone()).await;
+        let done = page.records.is_empty() || records.len() + page.records.len() >= page.total;
+        records.extend(page.records);
+        if done {
+            break;
+        }
+    }
+    let sink: Box<dyn Write> = match out {
+        Some(path) => match std::fs::File::create(path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                eprintln!("Failed to create {}: {}", path, e);
+                return 1;
+            }
+        },
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut sink = std::io::BufWriter::new(sink);
+    let written = match format {
+        ExportFormat::Csv => write_csv(&mut sink, &records),
+        ExportFormat::Json => serde_json::to_writer_pretty(&mut sink, &records).map_err(std::io::Error::from),
+    }
+    .and_then(|()| sink.flush());
+    if let Err(e) = written {
+        eprintln!("Failed to write export: {}", e);
+        return 1;
+    }
+    if let Some(path) = out {
+        eprintln!("Exported {} signals to {}", records.len(), path);
+    }
+    0
+}
+
+const CSV_HEADER: &str = "id,symbol,timestamp,time,signal_type,strategy,price,confidence,success,hit_tp,hit_sl,\
+max_gain_pct,max_drawdown_pct,time_to_target_ms,price_15m,price_30m,price_60m,state";
+
+fn write_csv(sink: &mut impl Write, records: &[SignalRecord]) -> std::io::Result<()> {
+    // Symbols, ids and strategy names never contain commas or quotes, but stay safe
+    let field = |s: &str| if s.contains([',', '"', '\n']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() };
+    let price = |p: Option<f64>| p.map_or(String::new(), |p| p.to_string());
+    writeln!(sink, "{}", CSV_HEADER)?;
+    for record in records {
+        let (signal, outcome) = (&record.signal, &record.outcome);
+        let time = chrono::DateTime::from_timestamp_millis(signal.timestamp).map_or(String::new(), |t| t.to_rfc3339());
+        writeln!(
+            sink,
+            "{},{},{},{},{:?},{},{},{},{},{},{},{:.4},{:.4},{},{},{},{},{}",
+            field(&signal.id),
+            field(&signal.symbol),
+            signal.timestamp,
+            time,
+            signal.signal_type,
+            field(&signal.strategy_name),
+            signal.price,
+            signal.confidence.map_or(String::new(), |c| c.to_string()),
+            outcome.success,
+            outcome.hit_tp,
+            outcome.hit_sl,
+            outcome.max_gain_percent * 100.0,
+            outcome.max_drawdown_percent * 100.0,
+            outcome.time_to_target_ms.map_or(String::new(), |ms| ms.to_string()),
+            price(outcome.price_at_15m),
+            price(outcome.price_at_30m),
+            price(outcome.price_at_60m),
+            record.state().map_or(String::new(), |s| format!("{:?}", s)),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::encoding::Encoding;
+use crate::scanner::WsMessage;
+use crate::wire::versioned;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+use ts_rs::TS;
+
+// Per-client outbound queue between the broadcast channel and the client's socket, so one
+// slow browser never holds up the others (or the broadcast). It holds `CLIENT_QUEUE_CAPACITY`
+// messages (default 512); when full, the oldest superseded-anyway message (live Updates and
+// the periodic Leaderboard/MarketOverview/MarketBias/Positions snapshots) makes room. Signals and everything
+// else are never dropped. A client that lost messages gets a `Lagged` message with the count
+// before its next one. One whose queue stays full for `CLIENT_LAG_DISCONNECT_SECS` (default
+// 30, 0 = never) is disconnected; its reconnect starts from a fresh snapshot.
+
+fn capacity() -> usize {
+    std::env::var("CLIENT_QUEUE_CAPACITY").ok().and_then(|v| v.parse().ok()).unwrap_or(512).max(1)
+}
+
+fn disconnect_after() -> Option<Duration> {
+    let secs = std::env::var("CLIENT_LAG_DISCONNECT_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(30);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Sent to a client ahead of its next message when some were dropped for it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct LagNotice {
+    pub missed: u64, // Messages dropped since the last notice
+}
+
+// Messages a later one of the same kind supersedes, so the client loses little by missing them
+fn droppable(message: &WsMessage) -> bool {
+    match message {
+        WsMessage::Candle(live) => !live.closed,
+        _ => matches!(
+            message,
+            WsMessage::Updates(_)
+                | WsMessage::Leaderboard(_)
+                | WsMessage::MarketOverview(_)
+                | WsMessage::MarketBias(_)
+                | WsMessage::Positions(_)
+                | WsMessage::Portfolio(_)
+        ),
+    }
+}
+
+struct Queued {
+    frame: warp::ws::Message,
+    droppable: bool,
+}
+
+#[derive(Default)]
+struct Inner {
+    frames: VecDeque<Queued>,
+    missed: u64,
+    full_since: Option<Instant>,
+    closed: bool,
+}
+
+pub struct ClientQueue {
+    inner: Mutex<Inner>,
+    notify: Notify,
+    capacity: usize,
+    disconnect_after: Option<Duration>,
+    encoding: Encoding,
+}
+
+impl ClientQueue {
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            inner: Mutex::default(),
+            notify: Notify::new(),
+            capacity: capacity(),
+            disconnect_after: disconnect_after(),
+            encoding,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues `message`, dropping the oldest droppable one when full. Err when serialization
+    /// fails (the message is skipped).
+    pub fn push(&self, message: &WsMessage) -> Result<(), serde_json::Error> {
+        let frame = self.encoding.frame(&versioned(message))?;
+        let mut inner = self.lock();
+        if inner.closed {
+            return Ok(());
+        }
+        if inner.frames.len() >= self.capacity {
+            inner.full_since.get_or_insert_with(Instant::now);
+            let oldest = inner.frames.iter().position(|q| q.droppable);
+            match (oldest, droppable(message)) {
+                (Some(i), _) => {
+                    inner.frames.remove(i);
+                    inner.missed += 1;
+                }
+                // Nothing older to give up: lose this one rather than an older snapshot
+                (None, true) => {
+                    inner.missed += 1;
+                    return Ok(());
+                }
+                // Never dropped; the queue grows until the client catches up or is cut off
+                (None, false) => {}
+            }
+        } else {
+            inner.full_since = None;
+        }
+        inner.frames.push_back(Queued { frame, droppable: droppable(message) });
+        drop(inner);
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// Counts messages the client lost upstream (broadcast lag) for its next `Lagged`.
+    pub fn missed(&self, count: u64) {
+        self.lock().missed += count;
+        self.notify.notify_one();
+    }
+
+    /// True once the queue has been full for longer than the client is allowed to lag.
+    pub fn too_slow(&self) -> bool {
+        let inner = self.lock();
+        match (inner.full_since, self.disconnect_after) {
+            (Some(since), Some(limit)) => since.elapsed() >= limit,
+            _ => false,
+        }
+    }
+
+    /// Replaces whatever is queued with `frame` (a close), which the writer sends last.
+    pub fn close(&self, frame: warp::ws::Message) {
+        let mut inner = self.lock();
+        inner.frames.clear();
+        inner.missed = 0;
+        inner.frames.push_back(Queued { frame, droppable: false });
+        inner.closed = true;
+        drop(inner);
+        self.notify.notify_one();
+    }
+
+    /// The next frame to write: a `Lagged` notice first if messages were dropped. None after
+    /// the close frame.
+    pub async fn next(&self) -> Option<warp::ws::Message> {
+        loop {
+            {
+                let mut inner = self.lock();
+                if inner.missed > 0 && !inner.closed {
+                    let notice = WsMessage::Lagged(LagNotice { missed: std::mem::take(&mut inner.missed) });
+                    if let Ok(frame) = self.encoding.frame(&versioned(notice)) {
+                        return Some(frame);
+                    }
+                }
+                if let Some(queued) = inner.frames.pop_front() {
+                    return Some(queued.frame);
+                }
+                if inner.closed {
+                    return None;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::history::HistoryManager;
+use crate::metrics::METRICS;
+use crate::redis::{Connection, RedisError};
+use crate::scanner::WsMessage;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn, Instrument};
+
+// Redundant instances on separate machines, coordinated through Redis (`CLUSTER_REDIS_URL`, a
+// secret since it may carry the password).
+// The leader lease lives under `<prefix>:leader` (see `leader`), so only one instance emits.
+// Whatever the leader emits (signals and invalidations) is also published on
+// `<prefix>:signals`; the other instances record it in their own history and broadcast it to
+// their clients, so a dashboard on either machine shows every alert exactly once and a
+// standby taking over has the history to track outcomes from. `CLUSTER_KEY_PREFIX` defaults to
+// "teeb".
+
+const OUTBOX_CAPACITY: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+pub fn redis_url() -> Option<String> {
+    crate::secrets::get("CLUSTER_REDIS_URL").map(|url| url.expose().trim().to_string()).filter(|url| !url.is_empty())
+}
+
+fn key_prefix() -> String {
+    std::env::var("CLUSTER_KEY_PREFIX").ok().filter(|v| !v.is_empty()).unwrap_or_else(|| "teeb".to_string())
+}
+
+pub fn leader_key() -> String {
+    format!("{}:leader", key_prefix())
+}
+
+fn channel() -> String {
+    format!("{}:signals", key_prefix())
+}
+
+// What goes over the bus: a message and the instance it came from
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    origin: String,
+    message: WsMessage,
+}
+
+// Messages emitted here, waiting for the bus task to publish them. Unset outside a cluster.
+static OUTBOX: OnceLock<mpsc::Sender<WsMessage>> = OnceLock::new();
+
+/// Hands a message this instance emitted to the other instances. A no-op outside a cluster;
+/// dropped (and counted) if the bus is too far behind.
+pub fn publish(message: WsMessage) {
+    if let Some(outbox) = OUTBOX.get() {
+        if outbox.try_send(message).is_err() {
+            METRICS.record_error("cluster");
+            debug!("Cluster outbox full, dropping message");
+        }
+    }
+}
+
+/// Publishes this instance's signals and relays the other instances'. Parked outside a cluster.
+pub async fn bus_task(history: Arc<HistoryManager>, tx: broadcast::Sender<WsMessage>, instance_id: String) {
+    let Some(url) = redis_url() else {
+        return std::future::pending().await;
+    };
+    let (outbox, mut pending) = mpsc::channel(OUTBOX_CAPACITY);
+    if OUTBOX.set(outbox).is_err() {
+        warn!("Cluster bus already running");
+        return std::future::pending().await;
+    }
+    let channel = channel();
+    info!(channel = %channel, instance = %instance_id, "Cluster bus enabled");
+    loop {
+        if let Err(e) = run(&url, &channel, &instance_id, &history, &tx, &mut pending).await {
+            METRICS.record_error("cluster");
+            warn!(error = %e, "Cluster bus connection lost, reconnecting");
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn run(
+    url: &str,
+    channel: &str,
+    instance_id: &str,
+    history: &Arc<HistoryManager>,
+    tx: &broadcast::Sender<WsMessage>,
+    pending: &mut mpsc::Receiver<WsMessage>,
+) -> Result<(), RedisError> {
+    let mut publisher = Connection::connect(url).await?;
+    let mut subscription = Connection::connect(url).await?.subscribe(channel).await?;
+    // Read on its own task: a reply half-read when `select!` picked the other branch would
+    // leave the connection out of step
+    let (incoming_tx, mut incoming) = mpsc::channel::<Result<String, RedisError>>(OUTBOX_CAPACITY);
+    let reader = tokio::spawn(async move {
+        loop {
+            let payload = subscription.next().await;
+            let failed = payload.is_err();
+            if incoming_tx.send(payload).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+    let result = loop {
+        tokio::select! {
+            Some(message) = pending.recv() => {
+                let envelope = Envelope { origin: instance_id.to_string(), message };
+                match serde_json::to_string(&envelope) {
+                    Ok(json) => {
+                        if let Err(e) = publisher.command(&["PUBLISH", channel, &json]).await {
+                            break Err(e);
+                        }
+                        METRICS.cluster_published.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => warn!(error = %e, "Failed to serialize cluster message"),
+                }
+            }
+            Some(payload) = incoming.recv() => {
+                let payload = match payload {
+                    Ok(payload) => payload,
+                    Err(e) => break Err(e),
+                };
+                match serde_json::from_str::<Envelope>(&payload) {
+                    Ok(envelope) if envelope.origin == instance_id => {}
+                    Ok(envelope) => relay(history, tx, envelope.message).await,
+                    Err(e) => {
+                        METRICS.record_error("cluster");
+                        warn!(error = %e, "Malformed cluster message");
+                    }
+                }
+            }
+        }
+    };
+    reader.abort();
+    result
+}
+
+// Another instance's message: recorded here as if emitted here, without publishing it back.
+// Signals are marked `relayed` so the notifiers, webhooks and executor leave them to the
+// instance that emitted them.
+async fn relay(history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>, message: WsMessage) {
+    METRICS.cluster_relayed.fetch_add(1, Ordering::Relaxed);
+    match message {
+        WsMessage::Signal(mut signal) => {
+            signal.relayed = true;
+            let span = signal.span();
+            crate::actor::apply_signal(history, tx, *signal).instrument(span).await
+        }
+        WsMessage::Invalidate(invalidation) => {
+            crate::actor::apply_invalidation(history, tx, invalidation).await;
+        }
+        other => debug!(kind = other.kind(), "Ignoring cluster message"),
+    }
+}
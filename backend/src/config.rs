@@ -0,0 +1,745 @@
+use crate::metrics::METRICS;
+use crate::model::Interval;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+pub fn config_path() -> String {
+    std::env::var("CONFIG_PATH").unwrap_or_else(|_| "config.toml".to_string())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("config io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("config parse error: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid {0}: must be positive")]
+    Invalid(&'static str),
+    #[error("invalid {0}: must be between {1} and {2}")]
+    OutOfRange(&'static str, usize, usize),
+    #[error("unknown strategy '{0}'")]
+    UnknownStrategy(String),
+    #[error("WS_TOKENS entry {0} is not name:role:token")]
+    TokenEntry(usize),
+    #[error("auth token '{0}' is empty or used twice")]
+    Token(String),
+    #[error("override for {0}: {1}")]
+    Override(String, Box<ConfigError>),
+}
+
+/// What makes a reading a spike: a multiple of its average (`ratio`), or a number of standard
+/// deviations above its baseline (`zscore`), which adapts to coins whose volume is spiky anyway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
+pub enum SpikeTrigger {
+    #[default]
+    #[serde(alias = "ratio")]
+    Ratio,
+    #[serde(alias = "zscore")]
+    ZScore,
+}
+
+impl std::str::FromStr for SpikeTrigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ratio" => Ok(SpikeTrigger::Ratio),
+            "zscore" => Ok(SpikeTrigger::ZScore),
+            other => Err(format!("unknown trigger '{}'", other)),
+        }
+    }
+}
+
+/// Thresholds of the Silent Watcher strategy (`scanner::check_for_signals`).
+///
+/// Read from the `[scanner]` table of `config.toml` (`CONFIG_PATH`), each then overridden by
+/// its `SCANNER_*` env var. Missing keys keep the defaults below.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScannerConfig {
+    pub min_value: f64,            // USDT traded in the current minute, below is dust
+    pub min_avg_value: f64,        // USDT per minute on average, below is too illiquid
+    pub dead_avg_value: f64,       // Below this average a coin counts as dead
+    pub spike_ratio: f64,          // Volume vs average for an active coin
+    pub dead_spike_ratio: f64,     // Volume vs average for a dead coin waking up
+    pub trigger: SpikeTrigger,     // Ratios above, or the z-scores below
+    pub zscore_threshold: f64,     // Standard deviations above baseline for an active coin
+    pub dead_zscore_threshold: f64, // Same for a dead coin waking up
+    pub max_price_change_pct: f64, // The move (wicks included) must stay under this
+    pub max_prior_move_pct: f64,   // Over the last hour, the move already made the signal's way
+    pub cooldown_secs: i64,        // A symbol signals at most once per this
+    // Candle the spike is measured on; the value and ratio thresholds apply to its candles
+    pub timeframe: Interval,
+    // Optional confirmation: price must have moved the signal's way over the last
+    // `trend_candles` candles of this timeframe
+    pub trend_timeframe: Option<Interval>,
+    pub trend_candles: usize,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            min_value: 10_000.0,
+            min_avg_value: 50_000.0,
+            dead_avg_value: 100_000.0,
+            spike_ratio: 3.0,
+            dead_spike_ratio: 5.0,
+            trigger: SpikeTrigger::Ratio,
+            zscore_threshold: 4.0,
+            dead_zscore_threshold: 6.0,
+            max_price_change_pct: 0.8,
+            max_prior_move_pct: 10.0,
+            cooldown_secs: 30 * 60,
+            timeframe: Interval::M1,
+            trend_timeframe: None,
+            trend_candles: 4,
+        }
+    }
+}
+
+/// Thresholds of the liquidation cascade strategy (`scanner::check_liquidation_cascade`), from
+/// the `[liquidation_cascade]` table, each overridden by its `LIQUIDATION_*` env var.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LiquidationConfig {
+    pub min_value: f64,        // USDT liquidated in the minute
+    pub ratio: f64,            // vs the average liquidated per minute over the hour before
+    pub trigger: SpikeTrigger, // `ratio` above, or `zscore_threshold` over the same hour
+    pub zscore_threshold: f64,
+    pub cooldown_secs: i64,    // A symbol signals at most once per this
+}
+
+impl Default for LiquidationConfig {
+    fn default() -> Self {
+        Self { min_value: 250_000.0, ratio: 5.0, trigger: SpikeTrigger::Ratio, zscore_threshold: 4.0, cooldown_secs: 30 * 60 }
+    }
+}
+
+/// Thresholds of the short pressure strategy (`scanner::check_short_pressure`), from the
+/// `[short_pressure]` table, each overridden by its `SHORT_PRESSURE_*` env var.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ShortPressureConfig {
+    pub min_value: f64,      // USDT traded in the minute
+    pub ratio: f64,          // Volume vs the window average
+    pub min_oi_delta: f64,   // Net taker selling share times the 5m OI rise (%), see the strategy
+    pub min_wall_ratio: f64, // Ask vs bid wall the book must show (streamed, and at verification)
+    pub cooldown_secs: i64,  // A symbol signals at most once per this
+}
+
+impl Default for ShortPressureConfig {
+    fn default() -> Self {
+        Self { min_value: 50_000.0, ratio: 3.0, min_oi_delta: 0.1, min_wall_ratio: 1.5, cooldown_secs: 30 * 60 }
+    }
+}
+
+/// Thresholds of the price velocity strategy (`scanner::check_breakout`), from the
+/// `[breakout]` table, each overridden by its `BREAKOUT_*` env var.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BreakoutConfig {
+    pub min_value: f64,      // USDT traded in the minute
+    pub ratio: f64,          // Volume vs the window average
+    pub candles: usize,      // Consecutive minutes of higher closes on growing volume
+    pub min_change_pct: f64, // Rise over those minutes, from the first one's open
+    pub cooldown_secs: i64,  // A symbol signals at most once per this
+}
+
+impl Default for BreakoutConfig {
+    fn default() -> Self {
+        Self { min_value: 100_000.0, ratio: 2.0, candles: 3, min_change_pct: 1.5, cooldown_secs: 30 * 60 }
+    }
+}
+
+/// Silent Watcher thresholds for one symbol, from `[overrides.<SYMBOL>.scanner]`. Keys left
+/// out keep the `[scanner]` value (env and admin API changes included).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScannerOverride {
+    pub min_value: Option<f64>,
+    pub min_avg_value: Option<f64>,
+    pub dead_avg_value: Option<f64>,
+    pub spike_ratio: Option<f64>,
+    pub dead_spike_ratio: Option<f64>,
+    pub trigger: Option<SpikeTrigger>,
+    pub zscore_threshold: Option<f64>,
+    pub dead_zscore_threshold: Option<f64>,
+    pub max_price_change_pct: Option<f64>,
+    pub max_prior_move_pct: Option<f64>,
+    pub cooldown_secs: Option<i64>,
+    pub timeframe: Option<Interval>,
+    pub trend_candles: Option<usize>,
+}
+
+/// Liquidation cascade thresholds for one symbol, from `[overrides.<SYMBOL>.liquidation_cascade]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LiquidationOverride {
+    pub min_value: Option<f64>,
+    pub ratio: Option<f64>,
+    pub trigger: Option<SpikeTrigger>,
+    pub zscore_threshold: Option<f64>,
+    pub cooldown_secs: Option<i64>,
+}
+
+/// Short pressure thresholds for one symbol, from `[overrides.<SYMBOL>.short_pressure]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShortPressureOverride {
+    pub min_value: Option<f64>,
+    pub ratio: Option<f64>,
+    pub min_oi_delta: Option<f64>,
+    pub min_wall_ratio: Option<f64>,
+    pub cooldown_secs: Option<i64>,
+}
+
+/// Breakout thresholds for one symbol, from `[overrides.<SYMBOL>.breakout]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BreakoutOverride {
+    pub min_value: Option<f64>,
+    pub ratio: Option<f64>,
+    pub candles: Option<usize>,
+    pub min_change_pct: Option<f64>,
+    pub cooldown_secs: Option<i64>,
+}
+
+/// One symbol's `[overrides.<SYMBOL>]` table: thresholds (and cooldowns) merged over each
+/// strategy's own, and optionally a narrower list of strategies to run on it. Keyed like the
+/// store, e.g. `BTCUSDT` or `"BYBIT:BTCUSDT"`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SymbolOverride {
+    pub strategies: Option<Vec<String>>, // Only these of the enabled ones; all when left out
+    pub scanner: ScannerOverride,
+    pub liquidation_cascade: LiquidationOverride,
+    pub short_pressure: ShortPressureOverride,
+    pub breakout: BreakoutOverride,
+}
+
+fn set<T: Copy>(value: &mut T, new: Option<T>) {
+    if let Some(new) = new {
+        *value = new;
+    }
+}
+
+impl ScannerOverride {
+    pub fn apply(&self, config: &ScannerConfig) -> ScannerConfig {
+        let mut config = config.clone();
+        set(&mut config.min_value, self.min_value);
+        set(&mut config.min_avg_value, self.min_avg_value);
+        set(&mut config.dead_avg_value, self.dead_avg_value);
+        set(&mut config.spike_ratio, self.spike_ratio);
+        set(&mut config.dead_spike_ratio, self.dead_spike_ratio);
+        set(&mut config.trigger, self.trigger);
+        set(&mut config.zscore_threshold, self.zscore_threshold);
+        set(&mut config.dead_zscore_threshold, self.dead_zscore_threshold);
+        set(&mut config.max_price_change_pct, self.max_price_change_pct);
+        set(&mut config.max_prior_move_pct, self.max_prior_move_pct);
+        set(&mut config.cooldown_secs, self.cooldown_secs);
+        set(&mut config.timeframe, self.timeframe);
+        set(&mut config.trend_candles, self.trend_candles);
+        config
+    }
+}
+
+impl LiquidationOverride {
+    pub fn apply(&self, config: &LiquidationConfig) -> LiquidationConfig {
+        let mut config = config.clone();
+        set(&mut config.min_value, self.min_value);
+        set(&mut config.ratio, self.ratio);
+        set(&mut config.trigger, self.trigger);
+        set(&mut config.zscore_threshold, self.zscore_threshold);
+        set(&mut config.cooldown_secs, self.cooldown_secs);
+        config
+    }
+}
+
+impl ShortPressureOverride {
+    pub fn apply(&self, config: &ShortPressureConfig) -> ShortPressureConfig {
+        let mut config = config.clone();
+        set(&mut config.min_value, self.min_value);
+        set(&mut config.ratio, self.ratio);
+        set(&mut config.min_oi_delta, self.min_oi_delta);
+        set(&mut config.min_wall_ratio, self.min_wall_ratio);
+        set(&mut config.cooldown_secs, self.cooldown_secs);
+        config
+    }
+}
+
+impl BreakoutOverride {
+    pub fn apply(&self, config: &BreakoutConfig) -> BreakoutConfig {
+        let mut config = config.clone();
+        set(&mut config.min_value, self.min_value);
+        set(&mut config.ratio, self.ratio);
+        set(&mut config.candles, self.candles);
+        set(&mut config.min_change_pct, self.min_change_pct);
+        set(&mut config.cooldown_secs, self.cooldown_secs);
+        config
+    }
+}
+
+impl SymbolOverride {
+    pub fn runs(&self, strategy: &str) -> bool {
+        self.strategies.as_ref().is_none_or(|names| names.iter().any(|n| n == strategy))
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let known = [
+            crate::strategy::SILENT_WATCHER,
+            crate::strategy::LIQUIDATION_CASCADE,
+            crate::strategy::SHORT_PRESSURE,
+            crate::strategy::BREAKOUT,
+        ];
+        if let Some(unknown) = self.strategies.iter().flatten().find(|n| !known.contains(&n.as_str())) {
+            return Err(ConfigError::UnknownStrategy(unknown.clone()));
+        }
+        self.scanner.apply(&ScannerConfig::default()).validate()?;
+        self.liquidation_cascade.apply(&LiquidationConfig::default()).validate()?;
+        self.short_pressure.apply(&ShortPressureConfig::default()).validate()?;
+        self.breakout.apply(&BreakoutConfig::default()).validate()
+    }
+}
+
+/// The `[overrides]` tables, keyed by upper-cased symbol. Each must be valid on its own.
+pub fn load_overrides(path: &str) -> Result<std::collections::HashMap<String, SymbolOverride>, ConfigError> {
+    read_file(path)?
+        .overrides
+        .into_iter()
+        .map(|(symbol, o)| {
+            let symbol = symbol.to_uppercase();
+            match o.validate() {
+                Ok(()) => Ok((symbol, o)),
+                Err(e) => Err(ConfigError::Override(symbol, Box::new(e))),
+            }
+        })
+        .collect()
+}
+
+/// Which Binance symbols are scanned at all (`binance_client`), from the `[symbols]` table,
+/// each key overridden by its `SYMBOLS_*` env var (lists comma-separated). Applied before a
+/// symbol gets any state in the store.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SymbolFilter {
+    pub include: Vec<String>,      // Only these, if non-empty
+    pub exclude: Vec<String>,      // Never these, wins over `include`
+    pub quote_assets: Vec<String>, // Allowed quote assets, empty for any
+    pub min_day_volume: f64,       // 24h quote volume a symbol needs to be picked up
+}
+
+impl Default for SymbolFilter {
+    fn default() -> Self {
+        Self { include: Vec::new(), exclude: Vec::new(), quote_assets: vec!["USDT".to_string()], min_day_volume: 0.0 }
+    }
+}
+
+/// Which strategies run (`strategy::Registry`), from the `[strategies]` table or `STRATEGIES`
+/// (comma-separated). Each strategy reads its own table of the same file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StrategiesConfig {
+    pub enabled: Vec<String>,
+}
+
+impl Default for StrategiesConfig {
+    fn default() -> Self {
+        Self { enabled: vec![crate::strategy::SILENT_WATCHER.to_string()] }
+    }
+}
+
+impl StrategiesConfig {
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.strategies;
+        if let Ok(v) = std::env::var("STRATEGIES") {
+            config.enabled = v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        }
+        config.enabled.dedup();
+        Ok(config)
+    }
+}
+
+/// What a `/ws` token lets a client do (`auth`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Read,  // The broadcast, and choosing what of it to get
+    Admin, // Commands as well (feedback)
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "read" => Ok(Role::Read),
+            "admin" => Ok(Role::Admin),
+            other => Err(format!("unknown role '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiToken {
+    pub name: String, // For logs, never the token itself
+    pub token: crate::secrets::Secret,
+    pub role: Role,
+}
+
+/// Who may open `/ws`, from the `[[auth.tokens]]` entries plus the `WS_TOKENS` secret
+/// (`name:role:token`, comma-separated). No tokens at all leaves it open to anyone.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub tokens: Vec<ApiToken>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    scanner: ScannerConfig,
+    #[serde(default)]
+    symbols: SymbolFilter,
+    #[serde(default)]
+    strategies: StrategiesConfig,
+    #[serde(default)]
+    liquidation_cascade: LiquidationConfig,
+    #[serde(default)]
+    short_pressure: ShortPressureConfig,
+    #[serde(default)]
+    breakout: BreakoutConfig,
+    #[serde(default)]
+    auth: AuthConfig,
+    #[serde(default)]
+    overrides: std::collections::HashMap<String, SymbolOverride>,
+}
+
+// Overrides `value` with the env var if set and parseable
+fn env<T: std::str::FromStr>(key: &str, value: &mut T) {
+    if let Some(v) = std::env::var(key).ok().and_then(|v| v.parse().ok()) {
+        *value = v;
+    }
+}
+
+fn read_file(path: &str) -> Result<ConfigFile, ConfigError> {
+    match std::fs::read_to_string(path) {
+        Ok(data) => Ok(toml::from_str::<ConfigFile>(&data)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ConfigFile::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// A spike ratio scaled by `factor`: it's the excess over 1 that shrinks, so it stays a spike
+fn boost_ratio(ratio: f64, factor: f64) -> f64 {
+    1.0 + (ratio - 1.0) * factor
+}
+
+impl ScannerConfig {
+    pub fn cooldown_ms(&self) -> i64 {
+        self.cooldown_secs * 1000
+    }
+
+    /// These thresholds made more sensitive for a watchlisted symbol (see `watchlist::boost`):
+    /// value floors, ratios and z-scores scaled by `factor`, the price check left as it is.
+    pub fn boosted(&self, factor: f64) -> ScannerConfig {
+        ScannerConfig {
+            min_value: self.min_value * factor,
+            min_avg_value: self.min_avg_value * factor,
+            spike_ratio: boost_ratio(self.spike_ratio, factor),
+            dead_spike_ratio: boost_ratio(self.dead_spike_ratio, factor),
+            zscore_threshold: self.zscore_threshold * factor,
+            dead_zscore_threshold: self.dead_zscore_threshold * factor,
+            ..self.clone()
+        }
+    }
+
+    /// These thresholds made stricter for a symbol whose signals do poorly (see `tuner`): ratios
+    /// and z-scores scaled by `factor` (> 1), the value floors left as they are.
+    pub fn tightened(&self, factor: f64) -> ScannerConfig {
+        ScannerConfig {
+            spike_ratio: boost_ratio(self.spike_ratio, factor),
+            dead_spike_ratio: boost_ratio(self.dead_spike_ratio, factor),
+            zscore_threshold: self.zscore_threshold * factor,
+            dead_zscore_threshold: self.dead_zscore_threshold * factor,
+            ..self.clone()
+        }
+    }
+
+    /// File (if present) plus env overrides. A missing file is fine; an unreadable or invalid
+    /// one is an error so `--check` can fail on it.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.scanner;
+        config.apply_env();
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) {
+        env("SCANNER_MIN_VALUE", &mut self.min_value);
+        env("SCANNER_MIN_AVG_VALUE", &mut self.min_avg_value);
+        env("SCANNER_DEAD_AVG_VALUE", &mut self.dead_avg_value);
+        env("SCANNER_SPIKE_RATIO", &mut self.spike_ratio);
+        env("SCANNER_DEAD_SPIKE_RATIO", &mut self.dead_spike_ratio);
+        env("SCANNER_TRIGGER", &mut self.trigger);
+        env("SCANNER_ZSCORE_THRESHOLD", &mut self.zscore_threshold);
+        env("SCANNER_DEAD_ZSCORE_THRESHOLD", &mut self.dead_zscore_threshold);
+        env("SCANNER_MAX_PRICE_CHANGE_PCT", &mut self.max_price_change_pct);
+        env("SCANNER_MAX_PRIOR_MOVE_PCT", &mut self.max_prior_move_pct);
+        env("SCANNER_COOLDOWN_SECS", &mut self.cooldown_secs);
+        env("SCANNER_TIMEFRAME", &mut self.timeframe);
+        env("SCANNER_TREND_CANDLES", &mut self.trend_candles);
+        // `none` turns a trend timeframe from the file off
+        if let Ok(v) = std::env::var("SCANNER_TREND_TIMEFRAME") {
+            if v.eq_ignore_ascii_case("none") {
+                self.trend_timeframe = None;
+            } else if let Ok(interval) = v.parse() {
+                self.trend_timeframe = Some(interval);
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let fields = [
+            ("min_value", self.min_value),
+            ("min_avg_value", self.min_avg_value),
+            ("dead_avg_value", self.dead_avg_value),
+            ("spike_ratio", self.spike_ratio),
+            ("dead_spike_ratio", self.dead_spike_ratio),
+            ("zscore_threshold", self.zscore_threshold),
+            ("dead_zscore_threshold", self.dead_zscore_threshold),
+            ("max_price_change_pct", self.max_price_change_pct),
+            ("max_prior_move_pct", self.max_prior_move_pct),
+        ];
+        match fields.iter().find(|(_, v)| v.is_nan() || *v <= 0.0) {
+            Some((name, _)) => Err(ConfigError::Invalid(name)),
+            None if self.cooldown_secs < 0 => Err(ConfigError::Invalid("cooldown_secs")),
+            None if self.trend_candles == 0 => Err(ConfigError::Invalid("trend_candles")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Loads the scanner config at startup. A broken file is logged and the defaults (still with
+/// env overrides) are used rather than refusing to scan; `--check` reports it as a failure.
+pub fn init() -> ScannerConfig {
+    let path = config_path();
+    match ScannerConfig::load(&path) {
+        Ok(config) => {
+            info!(path = %path, config = ?config, "Scanner config loaded");
+            config
+        }
+        Err(e) => {
+            METRICS.record_error("config");
+            error!(path = %path, error = %e, "Invalid scanner config, using defaults");
+            let mut config = ScannerConfig::default();
+            config.apply_env();
+            config
+        }
+    }
+}
+
+impl LiquidationConfig {
+    pub fn cooldown_ms(&self) -> i64 {
+        self.cooldown_secs * 1000
+    }
+
+    /// Like `ScannerConfig::boosted`.
+    pub fn boosted(&self, factor: f64) -> LiquidationConfig {
+        LiquidationConfig {
+            min_value: self.min_value * factor,
+            ratio: boost_ratio(self.ratio, factor),
+            zscore_threshold: self.zscore_threshold * factor,
+            ..self.clone()
+        }
+    }
+
+    /// File (if present) plus env overrides, like `ScannerConfig::load`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.liquidation_cascade;
+        env("LIQUIDATION_MIN_VALUE", &mut config.min_value);
+        env("LIQUIDATION_RATIO", &mut config.ratio);
+        env("LIQUIDATION_TRIGGER", &mut config.trigger);
+        env("LIQUIDATION_ZSCORE_THRESHOLD", &mut config.zscore_threshold);
+        env("LIQUIDATION_COOLDOWN_SECS", &mut config.cooldown_secs);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let fields = [("min_value", self.min_value), ("ratio", self.ratio), ("zscore_threshold", self.zscore_threshold)];
+        match fields.iter().find(|(_, v)| v.is_nan() || *v <= 0.0) {
+            Some((name, _)) => Err(ConfigError::Invalid(name)),
+            None if self.cooldown_secs < 0 => Err(ConfigError::Invalid("cooldown_secs")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ShortPressureConfig {
+    pub fn cooldown_ms(&self) -> i64 {
+        self.cooldown_secs * 1000
+    }
+
+    /// Like `ScannerConfig::boosted`; the confirmation it needs stays as strict.
+    pub fn boosted(&self, factor: f64) -> ShortPressureConfig {
+        ShortPressureConfig { min_value: self.min_value * factor, ratio: boost_ratio(self.ratio, factor), ..self.clone() }
+    }
+
+    /// File (if present) plus env overrides, like `ScannerConfig::load`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.short_pressure;
+        env("SHORT_PRESSURE_MIN_VALUE", &mut config.min_value);
+        env("SHORT_PRESSURE_RATIO", &mut config.ratio);
+        env("SHORT_PRESSURE_MIN_OI_DELTA", &mut config.min_oi_delta);
+        env("SHORT_PRESSURE_MIN_WALL_RATIO", &mut config.min_wall_ratio);
+        env("SHORT_PRESSURE_COOLDOWN_SECS", &mut config.cooldown_secs);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let fields = [
+            ("min_value", self.min_value),
+            ("ratio", self.ratio),
+            ("min_oi_delta", self.min_oi_delta),
+            ("min_wall_ratio", self.min_wall_ratio),
+        ];
+        match fields.iter().find(|(_, v)| v.is_nan() || *v <= 0.0) {
+            Some((name, _)) => Err(ConfigError::Invalid(name)),
+            None if self.cooldown_secs < 0 => Err(ConfigError::Invalid("cooldown_secs")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl BreakoutConfig {
+    pub fn cooldown_ms(&self) -> i64 {
+        self.cooldown_secs * 1000
+    }
+
+    /// Like `ScannerConfig::boosted`; the run of rising minutes it needs stays as long.
+    pub fn boosted(&self, factor: f64) -> BreakoutConfig {
+        BreakoutConfig {
+            min_value: self.min_value * factor,
+            ratio: boost_ratio(self.ratio, factor),
+            min_change_pct: self.min_change_pct * factor,
+            ..self.clone()
+        }
+    }
+
+    /// File (if present) plus env overrides, like `ScannerConfig::load`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.breakout;
+        env("BREAKOUT_MIN_VALUE", &mut config.min_value);
+        env("BREAKOUT_RATIO", &mut config.ratio);
+        env("BREAKOUT_CANDLES", &mut config.candles);
+        env("BREAKOUT_MIN_CHANGE_PCT", &mut config.min_change_pct);
+        env("BREAKOUT_COOLDOWN_SECS", &mut config.cooldown_secs);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let fields = [("min_value", self.min_value), ("ratio", self.ratio), ("min_change_pct", self.min_change_pct)];
+        match fields.iter().find(|(_, v)| v.is_nan() || *v <= 0.0) {
+            Some((name, _)) => Err(ConfigError::Invalid(name)),
+            // One minute is a spike, not a run
+            None if !(2..=crate::model::WINDOW_CANDLES).contains(&self.candles) => {
+                Err(ConfigError::OutOfRange("candles", 2, crate::model::WINDOW_CANDLES))
+            }
+            None if self.cooldown_secs < 0 => Err(ConfigError::Invalid("cooldown_secs")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl AuthConfig {
+    /// File (if present) plus the `WS_TOKENS` secret. Every token must be non-empty and unique.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut config = read_file(path)?.auth;
+        if let Some(secret) = crate::secrets::get("WS_TOKENS") {
+            for (i, entry) in secret.expose().split(',').map(str::trim).filter(|e| !e.is_empty()).enumerate() {
+                let mut parts = entry.splitn(3, ':');
+                let (Some(name), Some(role), Some(token)) = (parts.next(), parts.next(), parts.next()) else {
+                    return Err(ConfigError::TokenEntry(i + 1));
+                };
+                let role = role.parse().map_err(|_| ConfigError::TokenEntry(i + 1))?;
+                config.tokens.push(ApiToken { name: name.to_string(), token: crate::secrets::Secret::new(token), role });
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(bad) = config.tokens.iter().find(|t| t.token.expose().is_empty() || !seen.insert(t.token.expose())) {
+            return Err(ConfigError::Token(bad.name.clone()));
+        }
+        Ok(config)
+    }
+}
+
+impl SymbolFilter {
+    /// File (if present) plus env overrides, like `ScannerConfig::load`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let mut filter = read_file(path)?.symbols;
+        filter.apply_env();
+        filter.normalize();
+        if filter.min_day_volume.is_nan() || filter.min_day_volume < 0.0 {
+            return Err(ConfigError::Invalid("min_day_volume"));
+        }
+        Ok(filter)
+    }
+
+    /// Loaded at every (re)connect of the feed, so edits apply on the next reconnect.
+    /// A broken file is logged and nothing is filtered beyond the env overrides.
+    pub fn init() -> Self {
+        let path = config_path();
+        Self::load(&path).unwrap_or_else(|e| {
+            METRICS.record_error("config");
+            error!(path = %path, error = %e, "Invalid symbol filter, using defaults");
+            let mut filter = Self::default();
+            filter.apply_env();
+            filter.normalize();
+            filter
+        })
+    }
+
+    fn apply_env(&mut self) {
+        fn list(key: &str, value: &mut Vec<String>) {
+            if let Ok(v) = std::env::var(key) {
+                *value = v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+            }
+        }
+        list("SYMBOLS_INCLUDE", &mut self.include);
+        list("SYMBOLS_EXCLUDE", &mut self.exclude);
+        list("SYMBOLS_QUOTE_ASSETS", &mut self.quote_assets);
+        if let Some(v) = std::env::var("SYMBOLS_MIN_DAY_VOLUME").ok().and_then(|v| v.parse().ok()) {
+            self.min_day_volume = v;
+        }
+    }
+
+    fn normalize(&mut self) {
+        for list in [&mut self.include, &mut self.exclude, &mut self.quote_assets] {
+            list.iter_mut().for_each(|s| *s = s.to_uppercase());
+        }
+    }
+
+    /// Lists and quote asset. `quote_asset` comes from exchangeInfo when loaded; without it
+    /// (ticker mode before the first refresh) the symbol's suffix is checked instead.
+    pub fn allows(&self, symbol: &str, quote_asset: Option<&str>) -> bool {
+        if self.exclude.iter().any(|s| s == symbol) {
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|s| s == symbol) {
+            return false;
+        }
+        self.quote_assets.is_empty()
+            || self.quote_assets.iter().any(|q| match quote_asset {
+                Some(quote) => quote == q,
+                None => symbol.ends_with(q.as_str()),
+            })
+    }
+
+    pub fn allows_volume(&self, day_quote_volume: f64) -> bool {
+        day_quote_volume >= self.min_day_volume
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::{ConfigError, ScannerConfig, SpikeTrigger};
+use crate::model::Interval;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Runtime switches over the scanner for the admin API (`/api/admin/*`, or `Admin` commands
+// from an admin WebSocket client): thresholds, pause, a symbol blacklist and cooldown resets.
+// Changes apply from the next tick and are broadcast as `ConfigChanged`; they last until the
+// process restarts, after which `config.toml` and the env apply again.
+
+/// Pause and blacklist, checked by every symbol actor before running the strategies.
+#[derive(Debug, Default)]
+pub struct Control {
+    paused: AtomicBool,
+    blacklist: RwLock<BTreeSet<String>>,
+}
+
+impl Control {
+    /// Whether the strategies should run on `symbol` at all.
+    pub fn scans(&self, symbol: &str) -> bool {
+        !self.paused.load(Ordering::Relaxed)
+            && !self.blacklist.read().unwrap_or_else(|e| e.into_inner()).contains(symbol)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ControlError {
+    #[error("{0}")]
+    Config(#[from] ConfigError),
+    #[error("the silent_watcher strategy is not enabled")]
+    NoScanner,
+    #[error("no symbol given")]
+    NoSymbol,
+}
+
+/// The Silent Watcher's thresholds, as `[scanner]` in `config.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Thresholds {
+    pub min_value: f64,
+    pub min_avg_value: f64,
+    pub dead_avg_value: f64,
+    pub spike_ratio: f64,
+    pub dead_spike_ratio: f64,
+    pub trigger: SpikeTrigger,
+    pub zscore_threshold: f64,
+    pub dead_zscore_threshold: f64,
+    pub max_price_change_pct: f64,
+    pub max_prior_move_pct: f64,
+    pub cooldown_secs: i64,
+    pub timeframe: Interval,
+    pub trend_timeframe: Option<Interval>,
+    pub trend_candles: usize,
+}
+
+impl From<&ScannerConfig> for Thresholds {
+    fn from(c: &ScannerConfig) -> Self {
+        Self {
+            min_value: c.min_value,
+            min_avg_value: c.min_avg_value,
+            dead_avg_value: c.dead_avg_value,
+            spike_ratio: c.spike_ratio,
+            dead_spike_ratio: c.dead_spike_ratio,
+            trigger: c.trigger,
+            zscore_threshold: c.zscore_threshold,
+            dead_zscore_threshold: c.dead_zscore_threshold,
+            max_price_change_pct: c.max_price_change_pct,
+            max_prior_move_pct: c.max_prior_move_pct,
+            cooldown_secs: c.cooldown_secs,
+            timeframe: c.timeframe,
+            trend_timeframe: c.trend_timeframe,
+            trend_candles: c.trend_candles,
+        }
+    }
+}
+
+/// Thresholds to change; the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThresholdsPatch {
+    pub min_value: Option<f64>,
+    pub min_avg_value: Option<f64>,
+    pub dead_avg_value: Option<f64>,
+    pub spike_ratio: Option<f64>,
+    pub dead_spike_ratio: Option<f64>,
+    pub trigger: Option<SpikeTrigger>,
+    pub zscore_threshold: Option<f64>,
+    pub dead_zscore_threshold: Option<f64>,
+    pub max_price_change_pct: Option<f64>,
+    pub max_prior_move_pct: Option<f64>,
+    pub cooldown_secs: Option<i64>,
+    pub timeframe: Option<Interval>,
+    pub trend_candles: Option<usize>,
+}
+
+impl ThresholdsPatch {
+    fn apply(&self, config: &mut ScannerConfig) {
+        fn set<T: Copy>(value: &mut T, new: Option<T>) {
+            if let Some(new) = new {
+                *value = new;
+            }
+        }
+        set(&mut config.min_value, self.min_value);
+        set(&mut config.min_avg_value, self.min_avg_value);
+        set(&mut config.dead_avg_value, self.dead_avg_value);
+        set(&mut config.spike_ratio, self.spike_ratio);
+        set(&mut config.dead_spike_ratio, self.dead_spike_ratio);
+        set(&mut config.trigger, self.trigger);
+        set(&mut config.zscore_threshold, self.zscore_threshold);
+        set(&mut config.dead_zscore_threshold, self.dead_zscore_threshold);
+        set(&mut config.max_price_change_pct, self.max_price_change_pct);
+        set(&mut config.max_prior_move_pct, self.max_prior_move_pct);
+        set(&mut config.cooldown_secs, self.cooldown_secs);
+        set(&mut config.timeframe, self.timeframe);
+        set(&mut config.trend_candles, self.trend_candles);
+    }
+}
+
+/// A change to the running scanner, e.g. `{"type": "Blacklist", "payload": "BTCUSDT"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(tag = "type", content = "payload")]
+pub enum AdminCommand {
+    SetThresholds(ThresholdsPatch),
+    Pause,
+    Resume,
+    Blacklist(String),
+    Unblacklist(String),
+    ClearCooldown(String), // Every strategy's, so the symbol can signal on its next tick
+}
+
+/// What the admin API has changed from the startup config.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    pub paused: bool,
+    pub blacklist: Vec<String>,
+    pub thresholds: Option<Thresholds>, // None when the Silent Watcher isn't enabled
+}
+
+/// An applied admin command and the config it left, broadcast as `ConfigChanged`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    pub command: AdminCommand,
+    pub changed_by: String, // Name of the admin token
+    pub config: RuntimeConfig,
+    pub timestamp: i64,
+}
+
+pub fn current(store: &SharedState) -> RuntimeConfig {
+    RuntimeConfig {
+        paused: store.control.paused.load(Ordering::Relaxed),
+        blacklist: store.control.blacklist.read().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect(),
+        thresholds: store.strategies.scanner_config().as_ref().map(Thresholds::from),
+    }
+}
+
+fn symbol(symbol: &str) -> Result<String, ControlError> {
+    Some(symbol.trim().to_uppercase()).filter(|s| !s.is_empty()).ok_or(ControlError::NoSymbol)
+}
+
+/// Applies `command` to the running scanner. Broadcasting the change is up to the caller.
+pub fn apply(store: &SharedState, command: AdminCommand, changed_by: &str) -> Result<ConfigChange, ControlError> {
+    let control = &store.control;
+    match &command {
+        AdminCommand::SetThresholds(patch) => {
+            let mut config = store.strategies.scanner_config().ok_or(ControlError::NoScanner)?;
+            patch.apply(&mut config);
+            config.validate()?;
+            store.strategies.set_scanner_config(config);
+        }
+        AdminCommand::Pause => control.paused.store(true, Ordering::Relaxed),
+        AdminCommand::Resume => control.paused.store(false, Ordering::Relaxed),
+        AdminCommand::Blacklist(s) => {
+            control.blacklist.write().unwrap_or_else(|e| e.into_inner()).insert(symbol(s)?);
+        }
+        AdminCommand::Unblacklist(s) => {
+            control.blacklist.write().unwrap_or_else(|e| e.into_inner()).remove(&symbol(s)?);
+        }
+        AdminCommand::ClearCooldown(s) => {
+            if let Some(mut state) = store.get_mut(&symbol(s)?) {
+                state.clear_cooldowns(&store.strategies.names());
+            }
+        }
+    }
+    Ok(ConfigChange {
+        command,
+        changed_by: changed_by.to_string(),
+        config: current(store),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    })
+}
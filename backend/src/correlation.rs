@@ -0,0 +1,107 @@
+use crate::model::MarketData;
+use crate::scanner::{ReasonCode, Signal};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{LazyLock, Mutex};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Groups the signals fired in the same minute, so a move the whole market makes (BTC dumps and
+// forty alts "spike" with it) can be told from a symbol moving on its own. A signal is
+// market-wide when BTCUSDT itself spiked or moved in its minute or the one before, or when
+// `CORRELATION_MIN_SIGNALS` symbols have fired in its minute, counting itself. The first few
+// signals of a minute can't know about the ones after them; the leader check covers those.
+// Counts are per instance, and only the shard that owns BTCUSDT sees the leader.
+
+const LEADER: &str = "BTCUSDT";
+// Minutes of groups and leader candles kept
+const KEEP_MINUTES: i64 = 5;
+const MINUTE_MS: i64 = 60_000;
+
+fn min_signals() -> usize {
+    std::env::var("CORRELATION_MIN_SIGNALS").ok().and_then(|v| v.parse().ok()).unwrap_or(5)
+}
+
+fn leader_volume_ratio() -> f64 {
+    std::env::var("CORRELATION_LEADER_VOLUME_RATIO").ok().and_then(|v| v.parse().ok()).unwrap_or(3.0)
+}
+
+fn leader_move_pct() -> f64 {
+    std::env::var("CORRELATION_LEADER_MOVE_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or(0.5)
+}
+
+/// `CORRELATION_MODE=suppress` drops market-wide signals; the default (`label`) only marks them.
+pub fn suppress() -> bool {
+    std::env::var("CORRELATION_MODE").is_ok_and(|v| v.eq_ignore_ascii_case("suppress"))
+}
+
+/// How a signal relates to the others fired around it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Correlation {
+    pub minute: i64,                      // Group key: the minute every signal in the group fired in
+    pub group_size: usize,                // Symbols that had fired in that minute, this one included
+    pub leader_volume_ratio: Option<f64>, // BTCUSDT's minute volume vs its average (the larger minute)
+    pub leader_change_pct: Option<f64>,   // BTCUSDT's open-to-close move (the larger minute)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LeaderMinute {
+    volume_ratio: f64,
+    change_pct: f64,
+}
+
+// Symbols that fired, by minute start
+static GROUPS: LazyLock<Mutex<BTreeMap<i64, BTreeSet<String>>>> = LazyLock::new(Default::default);
+// The leader's candle so far, by minute start
+static LEADER_MINUTES: LazyLock<Mutex<BTreeMap<i64, LeaderMinute>>> = LazyLock::new(Default::default);
+
+fn prune<T>(map: &mut BTreeMap<i64, T>, minute: i64) {
+    while map.first_key_value().is_some_and(|(m, _)| *m < minute - KEEP_MINUTES * MINUTE_MS) {
+        map.pop_first();
+    }
+}
+
+/// Keeps the leader's current minute, from its actor's ticks. A no-op for every other symbol.
+pub fn observe(symbol: &str, candle: &MarketData, avg_volume: f64) {
+    if symbol != LEADER || candle.open <= 0.0 {
+        return;
+    }
+    let minute = LeaderMinute {
+        volume_ratio: if avg_volume > 0.0 { candle.volume / avg_volume } else { 0.0 },
+        change_pct: (candle.close - candle.open) / candle.open * 100.0,
+    };
+    // The candle's timestamp is the tick's event time; each tick overwrites its minute's entry
+    let start = candle.timestamp / MINUTE_MS * MINUTE_MS;
+    let mut minutes = LEADER_MINUTES.lock().unwrap_or_else(|e| e.into_inner());
+    minutes.insert(start, minute);
+    prune(&mut minutes, start);
+}
+
+/// Adds a new signal to its minute's group and stamps its `correlation` and `market_wide`.
+pub fn annotate(signal: &mut Signal) {
+    let minute = signal.timestamp / MINUTE_MS * MINUTE_MS;
+    let group_size = {
+        let mut groups = GROUPS.lock().unwrap_or_else(|e| e.into_inner());
+        let group = groups.entry(minute).or_default();
+        group.insert(signal.symbol.clone());
+        let size = group.len();
+        prune(&mut groups, minute);
+        size
+    };
+    let leader: Vec<LeaderMinute> = {
+        let minutes = LEADER_MINUTES.lock().unwrap_or_else(|e| e.into_inner());
+        minutes.range(minute - MINUTE_MS..=minute).map(|(_, m)| *m).collect()
+    };
+    let volume_ratio = leader.iter().map(|m| m.volume_ratio).reduce(f64::max);
+    let change_pct = leader.iter().map(|m| m.change_pct).reduce(|a, b| if b.abs() > a.abs() { b } else { a });
+
+    let leader_moving = signal.symbol != LEADER
+        && (volume_ratio.is_some_and(|r| r >= leader_volume_ratio()) || change_pct.is_some_and(|c| c.abs() >= leader_move_pct()));
+    signal.market_wide = leader_moving || group_size >= min_signals();
+    if signal.market_wide {
+        signal.add_reason(ReasonCode::MarketWide { group_size, leader_change_pct: change_pct });
+    }
+    signal.correlation =
+        Some(Correlation { minute, group_size, leader_volume_ratio: volume_ratio, leader_change_pct: change_pct });
+}
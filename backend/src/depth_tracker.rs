@@ -0,0 +1,184 @@
+use crate::binance_client::{FUTURES_STREAM_URL, MAX_STREAMS_PER_CONNECTION};
+use crate::metrics::METRICS;
+use crate::store::SharedState;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, info};
+use ts_rs::TS;
+use url::Url;
+use utoipa::ToSchema;
+
+// Order book imbalance for symbols with a live signal. The verifier's single depth fetch is
+// easy to spoof (a wall placed for a second and pulled), so the top 20 levels are streamed
+// (`<symbol>@depth20@500ms`) and the bid/ask wall ratio is kept over the last
+// `DEPTH_WINDOW_SECS` (default 60). Live updates carry the summary as `imbalance`, so the
+// frontend can show whether a wall persists or vanishes, and re-verification uses the
+// window's average instead of a fresh snapshot. On by default; `DEPTH_TRACKER_ENABLED=0`
+// turns it off. Binance futures only, as the streams are.
+
+// How often the set of symbols with a live signal is re-checked
+const RESCAN: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+// Signals older than this are no longer tracked, same horizon as live updates
+const ACTIVE_MS: i64 = 60 * 60 * 1000;
+// Fewer samples than this in the window (~5s of book) are not worth a summary
+const MIN_SAMPLES: usize = 10;
+
+pub fn enabled() -> bool {
+    !std::env::var("DEPTH_TRACKER_ENABLED").is_ok_and(|v| v == "0" || v.eq_ignore_ascii_case("false"))
+}
+
+fn window_ms() -> i64 {
+    std::env::var("DEPTH_WINDOW_SECS").ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(60) * 1000
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DepthError {
+    #[error("invalid stream url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("websocket error: {0}")]
+    Ws(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("stream closed by server")]
+    Closed,
+}
+
+/// The book's bid vs ask walls (summed top 20 levels) over the tracking window.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BookImbalance {
+    pub ratio: f64,         // Bid wall / ask wall in the latest book
+    pub avg_ratio: f64,     // Mean of the ratios over the window
+    pub min_ratio: f64,
+    pub max_ratio: f64,
+    pub bid_heavy_pct: f64, // Share of the window's books with the bids heavier, 0..100
+    pub samples: usize,
+    pub window_secs: i64,
+}
+
+/// A symbol's recent bid/ask wall ratios, oldest first. Not snapshotted: a book from before a
+/// restart says nothing about the current one.
+#[derive(Debug, Clone, Default)]
+pub struct DepthHistory {
+    samples: VecDeque<(i64, f64)>, // (ms, bid wall / ask wall)
+}
+
+impl DepthHistory {
+    pub fn record(&mut self, time: i64, ratio: f64) {
+        if !ratio.is_finite() || self.samples.back().is_some_and(|(t, _)| *t >= time) {
+            return;
+        }
+        self.samples.push_back((time, ratio));
+        let window = window_ms();
+        while self.samples.front().is_some_and(|(t, _)| *t < time - window) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The window as of `now`; None when tracking stopped or hasn't gathered enough books.
+    pub fn summary(&self, now: i64) -> Option<BookImbalance> {
+        let window = window_ms();
+        let ratios: Vec<f64> = self.samples.iter().filter(|(t, _)| *t >= now - window).map(|(_, r)| *r).collect();
+        if ratios.len() < MIN_SAMPLES {
+            return None;
+        }
+        let count = ratios.len() as f64;
+        Some(BookImbalance {
+            ratio: *ratios.last()?,
+            avg_ratio: ratios.iter().sum::<f64>() / count,
+            min_ratio: ratios.iter().copied().fold(f64::INFINITY, f64::min),
+            max_ratio: ratios.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            bid_heavy_pct: ratios.iter().filter(|r| **r > 1.0).count() as f64 / count * 100.0,
+            samples: ratios.len(),
+            window_secs: window / 1000,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Combined {
+    data: PartialDepth,
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialDepth {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "E")]
+    event_time: i64,
+    #[serde(rename = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    asks: Vec<[String; 2]>,
+}
+
+// Binance futures symbols with a live, still-valid signal
+fn watched(store: &SharedState) -> BTreeSet<String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    store
+        .iter()
+        .filter(|e| crate::exchange::split(e.key()).0 == crate::exchange::Exchange::Binance)
+        .filter(|e| {
+            let state = e.value();
+            state.active_signal.as_ref().is_some_and(|a| !a.invalidated)
+                && state.last_signal_time.is_some_and(|t| now - t < ACTIVE_MS)
+        })
+        .map(|e| e.key().clone())
+        .take(MAX_STREAMS_PER_CONNECTION)
+        .collect()
+}
+
+// Streams partial books for `symbols` until that set changes (Ok) or the connection fails
+async fn stream(store: &SharedState, symbols: &BTreeSet<String>) -> Result<(), DepthError> {
+    let streams: Vec<String> = symbols.iter().map(|s| format!("{}@depth20@500ms", s.to_lowercase())).collect();
+    let url = Url::parse(&format!("{}?streams={}", FUTURES_STREAM_URL, streams.join("/")))?;
+    info!(symbols = symbols.len(), "Connecting to depth streams for book imbalance");
+    let (ws_stream, _) = connect_async(url).await?;
+    let (_, mut read) = ws_stream.split();
+
+    let mut rescan = tokio::time::interval(RESCAN);
+    rescan.tick().await;
+    loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Combined>(&text) {
+                    Ok(Combined { data: book }) => {
+                        let bid_wall = crate::verifier::calculate_wall(book.bids);
+                        let ask_wall = crate::verifier::calculate_wall(book.asks);
+                        if let Some(mut state) = store.get_mut(&book.symbol) {
+                            state.depth.record(book.event_time, bid_wall / ask_wall);
+                        }
+                    }
+                    Err(e) => {
+                        METRICS.record_error("depth_tracker");
+                        debug!(error = %e, "Failed to parse depth message");
+                    }
+                },
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(DepthError::Closed),
+            },
+            _ = rescan.tick() => {
+                if watched(store) != *symbols {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the book imbalance of symbols with a live signal from their depth streams,
+/// reconnecting with the new set whenever a signal starts or ends.
+pub async fn depth_task(store: SharedState) -> Result<(), DepthError> {
+    if !enabled() {
+        std::future::pending::<()>().await;
+    }
+    loop {
+        let symbols = watched(&store);
+        if symbols.is_empty() {
+            tokio::time::sleep(RESCAN).await;
+            continue;
+        }
+        stream(&store, &symbols).await?;
+    }
+}
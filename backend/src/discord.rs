@@ -0,0 +1,175 @@
+// Posts published signals to Discord webhooks as embeds, next to the Telegram notifier.
+// Webhook URLs embed their token, so they are read through the secrets layer.
+use crate::metrics::METRICS;
+use crate::scanner::{ReasonCode, Signal, SignalType, WsMessage};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+// Fallback for both directions, then one per direction so they can go to separate channels
+pub const WEBHOOK_SECRET: &str = "DISCORD_WEBHOOK_URL";
+pub const LONG_WEBHOOK_SECRET: &str = "DISCORD_WEBHOOK_URL_LONG";
+pub const SHORT_WEBHOOK_SECRET: &str = "DISCORD_WEBHOOK_URL_SHORT";
+const LONG_COLOR: u32 = 0x2ECC71;
+const SHORT_COLOR: u32 = 0xE74C3C;
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Webhook for a signal direction: its own if set, else the shared one.
+pub fn webhook_url(signal_type: &SignalType) -> Option<String> {
+    let own = match signal_type {
+        SignalType::Long | SignalType::NewListing | SignalType::Breakout => LONG_WEBHOOK_SECRET,
+        SignalType::Short => SHORT_WEBHOOK_SECRET,
+    };
+    [own, WEBHOOK_SECRET]
+        .iter()
+        .filter_map(|name| crate::secrets::get(name))
+        .map(|url| url.expose().trim().to_string())
+        .find(|url| !url.is_empty())
+}
+
+pub fn enabled() -> bool {
+    webhook_url(&SignalType::Long).is_some() || webhook_url(&SignalType::Short).is_some()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiscordError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("rate limited, retry after {0:.1}s")]
+    RateLimited(f64),
+    #[error("webhook rejected with status {0}")]
+    Status(reqwest::StatusCode),
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookMessage {
+    embeds: Vec<Embed>,
+}
+
+#[derive(Debug, Serialize)]
+struct Embed {
+    title: String,
+    description: String,
+    color: u32,
+    fields: Vec<EmbedField>,
+    timestamp: String, // ISO 8601, shown in the reader's timezone
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedField {
+    name: &'static str,
+    value: String,
+    inline: bool,
+}
+
+#[derive(Deserialize)]
+struct RateLimit {
+    retry_after: f64,
+}
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default()
+});
+
+/// A signal as one embed: colour by direction, volume ratio / OI / order book wall as fields
+/// (each only when the scanner or verifier produced it), the full reason text as description.
+pub fn embed(signal: &Signal) -> WebhookMessage {
+    let (icon, side, color) = match signal.signal_type {
+        SignalType::Long => ("🟢", "LONG", LONG_COLOR),
+        SignalType::Short => ("🔴", "SHORT", SHORT_COLOR),
+        SignalType::NewListing => ("🆕", "NEW LISTING", LONG_COLOR),
+        SignalType::Breakout => ("🚀", "BREAKOUT", LONG_COLOR),
+    };
+    let price = match signal.price_precision {
+        Some(precision) => format!("{:.*}", precision as usize, signal.price),
+        None => signal.price.to_string(),
+    };
+    let field = |name, value| EmbedField { name, value, inline: true };
+    let mut fields = vec![field("Price", price)];
+    for reason in &signal.reasons {
+        match reason {
+            ReasonCode::VolumeSpike { ratio, .. } => fields.push(field("Volume ratio", format!("{:.1}x", ratio))),
+            ReasonCode::OpenInterest { value } => fields.push(field("Open interest", format!("${:.1}M", value / 1_000_000.0))),
+            ReasonCode::BuyWall { ratio } => fields.push(field("Bid wall", format!("x{:.1} vs asks", ratio))),
+            ReasonCode::SellWall { ratio } => fields.push(field("Ask wall", format!("x{:.1} vs bids", ratio))),
+            _ => {}
+        }
+    }
+    if let Some(score) = signal.market_bias {
+        fields.push(field("Market bias", format!("{:+.0}", score)));
+    }
+    let timestamp = chrono::DateTime::from_timestamp_millis(signal.timestamp).unwrap_or_default().to_rfc3339();
+    WebhookMessage {
+        embeds: vec![Embed {
+            title: format!("{} {} {}", icon, side, signal.symbol),
+            description: signal.reason.replace(" | ", "\n"),
+            color,
+            fields,
+            timestamp,
+        }],
+    }
+}
+
+async fn post(url: &str, message: &WebhookMessage) -> Result<(), DiscordError> {
+    // reqwest errors carry the URL, which carries the webhook token
+    let resp = CLIENT.post(url).json(message).send().await.map_err(|e| e.without_url())?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = resp.json::<RateLimit>().await.map(|r| r.retry_after).unwrap_or(5.0);
+        return Err(DiscordError::RateLimited(retry_after));
+    }
+    if !status.is_success() {
+        return Err(DiscordError::Status(status));
+    }
+    Ok(())
+}
+
+// Retried with backoff, or after Discord's `retry_after` on 429; other client errors
+// (a deleted webhook, a malformed embed) won't get better and are dropped.
+async fn deliver(url: &str, message: &WebhookMessage, symbol: &str) -> bool {
+    let mut delay = Duration::from_secs(1);
+    for attempt in 1..=MAX_ATTEMPTS {
+        match post(url, message).await {
+            Ok(()) => return true,
+            Err(e) => {
+                METRICS.record_error("discord");
+                warn!(symbol, attempt, error = %e, "Failed to post Discord webhook");
+                let wait = match e {
+                    DiscordError::RateLimited(secs) => Duration::from_secs_f64(secs.max(0.0)),
+                    DiscordError::Status(status) if status.is_client_error() => return false,
+                    _ => delay,
+                };
+                if attempt < MAX_ATTEMPTS {
+                    sleep(wait).await;
+                }
+                delay *= 4;
+            }
+        }
+    }
+    false
+}
+
+/// Posts every published signal to the webhook for its direction. Like the Telegram
+/// notifier it follows the broadcast channel, so only the broadcasting instance posts.
+pub async fn discord_task(tx: broadcast::Sender<WsMessage>) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    info!(
+        long = webhook_url(&SignalType::Long).is_some(),
+        short = webhook_url(&SignalType::Short).is_some(),
+        "Discord webhooks enabled"
+    );
+    let mut rx = tx.subscribe();
+    while let Some(signal) = crate::notifier::next_signal(&mut rx).await {
+        // Re-read per signal so rotated webhook URLs apply without a restart
+        let Some(url) = webhook_url(&signal.signal_type) else {
+            continue;
+        };
+        if deliver(&url, &embed(&signal), &signal.symbol).await {
+            info!(symbol = %signal.symbol, signal_id = %signal.id, "Discord webhook posted");
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::actor::ActorContext;
+use crate::exchange::{split, Exchange, MarketType};
+use crate::model::MarketData;
+use crate::scanner::{ReasonCode, Signal};
+
+// Spot vs futures volume on the same Binance pair, when both are scanned
+// (`EXCHANGES=binance,binance_spot`). A signal whose minute volume spiked on one market while
+// the other's stayed under `DIVERGENCE_FLAT_RATIO` times its average (default 1.5) gets a
+// `SpotFuturesDivergence` reason: heavy spot buying with flat futures reads as real demand,
+// a futures-only spike as leverage. The other market is read from this instance's store, so
+// with sharding it only works when both keys land on the same shard.
+
+fn flat_ratio() -> f64 {
+    std::env::var("DIVERGENCE_FLAT_RATIO").ok().and_then(|v| v.parse().ok()).unwrap_or(1.5)
+}
+
+// The same pair on the other Binance market
+fn counterpart(symbol: &str) -> Option<String> {
+    match split(symbol) {
+        (Exchange::Binance, native) => Some(Exchange::BinanceSpot.tag(native)),
+        (Exchange::BinanceSpot, native) => Some(Exchange::Binance.tag(native)),
+        _ => None,
+    }
+}
+
+// A market's volume in `minute` so far vs its 1m average; None without a candle that minute
+fn volume_ratio(ctx: &ActorContext, symbol: &str, minute: i64) -> Option<f64> {
+    let volume = ctx.store.volume_cache.get(symbol).filter(|b| b.minute == minute)?.finish(symbol).volume;
+    let average = ctx.store.get(symbol)?.get_average_volume();
+    (average > 0.0).then(|| volume / average)
+}
+
+/// Adds a `SpotFuturesDivergence` reason when the other market of the signal's pair stayed
+/// flat in the signal's minute. `candle` and `avg_volume` are the signal market's 1m candle so
+/// far and average.
+pub fn annotate(ctx: &ActorContext, signal: &mut Signal, candle: &MarketData, avg_volume: f64) {
+    let Some(other) = counterpart(&signal.symbol) else {
+        return;
+    };
+    if avg_volume <= 0.0 || !crate::exchange::enabled().contains(&Exchange::BinanceSpot) {
+        return;
+    }
+    let Some(other_ratio) = volume_ratio(ctx, &other, candle.timestamp / 60_000) else {
+        return;
+    };
+    let ratio = candle.volume / avg_volume;
+    if other_ratio >= flat_ratio() || ratio < flat_ratio() {
+        return;
+    }
+    let (spot_volume_ratio, futures_volume_ratio) = match signal.market {
+        MarketType::Spot => (ratio, other_ratio),
+        MarketType::Futures => (other_ratio, ratio),
+    };
+    signal.add_reason(ReasonCode::SpotFuturesDivergence { led_by: signal.market, spot_volume_ratio, futures_volume_ratio });
+}
@@ -0,0 +1,154 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+
+// How a WebSocket client gets its messages, picked at the handshake. JSON text frames stay the
+// default; a client can offer a subprotocol instead (`new WebSocket(url, ["teeb.msgpack"])`,
+// echoed back in `Sec-WebSocket-Protocol`) or, if it can't set one, ask with `?encoding=`:
+//
+//   teeb.json           (encoding=json)     JSON text frames, as ever
+//   teeb.json.deflate   (encoding=deflate)  JSON; frames of WS_DEFLATE_MIN_BYTES (default 256)
+//                                           and up go as binary frames of raw deflate
+//                                           (DecompressionStream("deflate-raw") in a browser)
+//   teeb.msgpack        (encoding=msgpack)  binary MessagePack frames of the same objects
+//
+// warp's WebSocket (tungstenite) can't negotiate permessage-deflate, hence compression per
+// message. Whatever the encoding, clients send their commands as JSON text.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Json,
+    JsonDeflate,
+    MessagePack,
+}
+
+const PROTOCOLS: [(&str, Encoding); 3] =
+    [("teeb.json", Encoding::Json), ("teeb.json.deflate", Encoding::JsonDeflate), ("teeb.msgpack", Encoding::MessagePack)];
+
+fn deflate_min_bytes() -> usize {
+    std::env::var("WS_DEFLATE_MIN_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(256)
+}
+
+impl Encoding {
+    /// The first known subprotocol of `offered` (the `Sec-WebSocket-Protocol` header), to be
+    /// echoed back; otherwise `?encoding=`, otherwise JSON. Unknown names are ignored.
+    pub fn negotiate(offered: Option<&str>, query: Option<&str>) -> (Encoding, Option<&'static str>) {
+        let offered = offered.into_iter().flat_map(|o| o.split(',')).map(str::trim);
+        for name in offered {
+            if let Some((protocol, encoding)) = PROTOCOLS.iter().find(|(p, _)| p.eq_ignore_ascii_case(name)) {
+                return (*encoding, Some(protocol));
+            }
+        }
+        let encoding = match query.map(str::to_ascii_lowercase).as_deref() {
+            Some("deflate") => Encoding::JsonDeflate,
+            Some("msgpack") => Encoding::MessagePack,
+            _ => Encoding::Json,
+        };
+        (encoding, None)
+    }
+
+    pub fn frame<T: Serialize>(self, message: &T) -> Result<warp::ws::Message, serde_json::Error> {
+        match self {
+            Encoding::Json => Ok(warp::ws::Message::text(serde_json::to_string(message)?)),
+            Encoding::JsonDeflate => {
+                let json = serde_json::to_string(message)?;
+                if json.len() < deflate_min_bytes() {
+                    return Ok(warp::ws::Message::text(json));
+                }
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(json.as_bytes()).map_err(serde_json::Error::io)?;
+                Ok(warp::ws::Message::binary(encoder.finish().map_err(serde_json::Error::io)?))
+            }
+            Encoding::MessagePack => {
+                let mut bytes = Vec::new();
+                msgpack(&serde_json::to_value(message)?, &mut bytes);
+                Ok(warp::ws::Message::binary(bytes))
+            }
+        }
+    }
+}
+
+// Length-prefixed MessagePack header: the fix form below `fix_max`, else 8/16/32-bit lengths
+// (`markers`, with None where the format has no 8-bit form)
+fn header(out: &mut Vec<u8>, len: usize, fix: u8, fix_max: usize, markers: [Option<u8>; 3]) {
+    match (len, markers) {
+        (len, _) if len < fix_max => out.push(fix | len as u8),
+        (len, [Some(marker), _, _]) if len <= u8::MAX as usize => out.extend([marker, len as u8]),
+        (len, [_, Some(marker), _]) if len <= u16::MAX as usize => {
+            out.push(marker);
+            out.extend((len as u16).to_be_bytes());
+        }
+        (len, [_, _, Some(marker)]) => {
+            out.push(marker);
+            out.extend((len as u32).to_be_bytes());
+        }
+        _ => {}
+    }
+}
+
+fn msgpack(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                match u {
+                    0..=0x7f => out.push(u as u8),
+                    0x80..=0xff => out.extend([0xcc, u as u8]),
+                    0x100..=0xffff => {
+                        out.push(0xcd);
+                        out.extend((u as u16).to_be_bytes());
+                    }
+                    0x1_0000..=0xffff_ffff => {
+                        out.push(0xce);
+                        out.extend((u as u32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xcf);
+                        out.extend(u.to_be_bytes());
+                    }
+                }
+            } else if let Some(i) = n.as_i64() {
+                // Negative only; non-negative values took the branch above
+                match i {
+                    -32..=-1 => out.push(i as i8 as u8),
+                    -128..=-33 => out.extend([0xd0, i as i8 as u8]),
+                    -32768..=-129 => {
+                        out.push(0xd1);
+                        out.extend((i as i16).to_be_bytes());
+                    }
+                    -2_147_483_648..=-32769 => {
+                        out.push(0xd2);
+                        out.extend((i as i32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xd3);
+                        out.extend(i.to_be_bytes());
+                    }
+                }
+            } else {
+                out.push(0xcb);
+                out.extend(n.as_f64().unwrap_or(0.0).to_be_bytes());
+            }
+        }
+        Value::String(s) => {
+            header(out, s.len(), 0xa0, 32, [Some(0xd9), Some(0xda), Some(0xdb)]);
+            out.extend(s.as_bytes());
+        }
+        Value::Array(items) => {
+            header(out, items.len(), 0x90, 16, [None, Some(0xdc), Some(0xdd)]);
+            for item in items {
+                msgpack(item, out);
+            }
+        }
+        Value::Object(map) => {
+            header(out, map.len(), 0x80, 16, [None, Some(0xde), Some(0xdf)]);
+            for (key, item) in map {
+                header(out, key.len(), 0xa0, 32, [Some(0xd9), Some(0xda), Some(0xdb)]);
+                out.extend(key.as_bytes());
+                msgpack(item, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::exchange::ClientError;
+use crate::history::HistoryError;
+use crate::system_event::EventLevel;
+use std::net::SocketAddr;
+
+// The crate-wide error. Each module keeps its own enum for the detail (`ClientError`,
+// `HistoryError`, ...); this one is what crosses module lines: the exchange feeds and the
+// WebSocket server return it to the supervisor, and the verifier and history report it as a
+// degraded-mode `SystemEvent` (see `system_event::degraded`).
+
+#[derive(Debug, thiserror::Error)]
+pub enum TeebError {
+    #[error("exchange: {0}")]
+    Exchange(#[from] ClientError),
+    #[error(transparent)] // Already says "history ..."
+    History(#[from] HistoryError),
+    #[error("cannot listen on {addr}: {source}")]
+    Bind { addr: SocketAddr, source: warp::Error },
+}
+
+impl TeebError {
+    /// How bad it is for the dashboard: a rate limit passes by itself, the rest needs a look.
+    pub fn level(&self) -> EventLevel {
+        match self {
+            TeebError::Exchange(ClientError::RateLimited { .. }) => EventLevel::Warning,
+            _ => EventLevel::Error,
+        }
+    }
+}
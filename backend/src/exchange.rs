@@ -0,0 +1,310 @@
+use crate::actor::ActorContext;
+use crate::error::TeebError;
+use crate::model::{KlineRange, Tick};
+use crate::rest::Cache;
+use crate::shutdown::Shutdown;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::LazyLock;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Venues the scanner can watch side by side, picked with `EXCHANGES` (default `binance`).
+// Symbols are keyed in the store and published tagged with their exchange ("BYBIT:BTCUSDT"),
+// except Binance's, which stay bare so existing history, watch rules and clients keep working.
+// Binance spot (`binance_spot`) counts as a venue of its own, tagged "SPOT:BTCUSDT".
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, TS, ToSchema)]
+pub enum Exchange {
+    #[default]
+    Binance,
+    Bybit,
+    BinanceSpot,
+}
+
+/// Which kind of market a symbol trades on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, TS, ToSchema)]
+pub enum MarketType {
+    #[default]
+    Futures, // Perpetual contracts
+    Spot,
+}
+
+impl Exchange {
+    pub const ALL: [Exchange; 3] = [Exchange::Binance, Exchange::Bybit, Exchange::BinanceSpot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Exchange::Binance => "binance",
+            Exchange::Bybit => "bybit",
+            Exchange::BinanceSpot => "binance_spot",
+        }
+    }
+
+    pub fn market(self) -> MarketType {
+        match self {
+            Exchange::Binance | Exchange::Bybit => MarketType::Futures,
+            Exchange::BinanceSpot => MarketType::Spot,
+        }
+    }
+
+    // Store key prefix; None for Binance futures, which stay bare
+    fn prefix(self) -> Option<&'static str> {
+        match self {
+            Exchange::Binance => None,
+            Exchange::Bybit => Some("BYBIT"),
+            Exchange::BinanceSpot => Some("SPOT"),
+        }
+    }
+
+    /// The store / signal key for an exchange-native symbol.
+    pub fn tag(self, symbol: &str) -> String {
+        match self.prefix() {
+            None => symbol.to_string(),
+            Some(prefix) => format!("{}:{}", prefix, symbol),
+        }
+    }
+}
+
+impl std::str::FromStr for Exchange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Exchange::ALL
+            .into_iter()
+            .find(|e| e.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown exchange '{}'", s.trim()))
+    }
+}
+
+/// Splits a store key into its exchange and exchange-native symbol. Untagged keys are Binance's.
+pub fn split(symbol: &str) -> (Exchange, &str) {
+    if let Some((prefix, native)) = symbol.split_once(':') {
+        if let Some(exchange) = Exchange::ALL.into_iter().find(|e| e.prefix().is_some_and(|p| p.eq_ignore_ascii_case(prefix))) {
+            return (exchange, native);
+        }
+    }
+    (Exchange::Binance, symbol)
+}
+
+/// Exchanges listed in `EXCHANGES` (comma-separated). Unknown names are ignored here and
+/// reported by `--check`; an empty or entirely unknown list falls back to Binance.
+pub fn enabled() -> Vec<Exchange> {
+    let mut exchanges: Vec<Exchange> = Vec::new();
+    for exchange in std::env::var("EXCHANGES").unwrap_or_default().split(',').filter_map(|name| name.parse().ok()) {
+        if !exchanges.contains(&exchange) {
+            exchanges.push(exchange);
+        }
+    }
+    if exchanges.is_empty() {
+        exchanges.push(Exchange::Binance);
+    }
+    exchanges
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("invalid stream url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("websocket error: {0}")]
+    Ws(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("stream closed by server")]
+    Closed,
+    #[error("no message from the stream for {0}s")]
+    Idle(u64),
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("{exchange} error {code}: {msg}")]
+    Api { exchange: &'static str, code: i64, msg: String },
+    #[error("malformed response: {0}")]
+    Malformed(String),
+    #[error("{host} rate limit, backing off for {secs}s")]
+    RateLimited { host: String, secs: u64 },
+    #[error("{exchange} has no {what}")]
+    Unsupported { exchange: &'static str, what: &'static str },
+}
+
+impl ClientError {
+    /// A refusal asking again won't change (unknown symbol, bad request), unlike an outage or
+    /// a rate limit.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            ClientError::Status(status) => status.is_client_error(),
+            ClientError::Api { .. } | ClientError::Unsupported { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Top of the book as `[price, quantity]` strings, the way both venues send it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+/// A finished 1m candle's prices, as read back from an exchange's klines endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct PriceBar {
+    pub open_time: i64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// One exchange's market data. Symbols here are exchange-native (no tag); implementations
+/// tag them with `Self::EXCHANGE` before routing ticks into the store.
+pub trait ExchangeClient: Send + Sync + 'static {
+    const EXCHANGE: Exchange;
+
+    /// Perpetual contracts (spot pairs for a spot venue) currently trading.
+    fn symbols(&self) -> impl Future<Output = Result<Vec<String>, ClientError>> + Send;
+
+    /// Streams 1m ticks into the symbol actors. Returns Ok only on `shutdown`, after closing the
+    /// connections; failures (or the server closing the stream) are errors and the supervisor
+    /// reconnects.
+    fn stream_ticks(&self, ctx: ActorContext, shutdown: Shutdown) -> impl Future<Output = Result<(), ClientError>> + Send;
+
+    /// The `limit` best levels on each side.
+    fn depth(&self, symbol: &str, limit: usize) -> impl Future<Output = Result<OrderBook, ClientError>> + Send;
+
+    /// Open interest in contracts (base asset). `Unsupported` on spot.
+    fn open_interest(&self, symbol: &str) -> impl Future<Output = Result<f64, ClientError>> + Send;
+
+    /// 1m candles opening in `[from, to)`, oldest first; the one still trading is left out.
+    /// Up to 1000 per call.
+    fn minute_bars(&self, symbol: &str, from: i64, to: i64) -> impl Future<Output = Result<Vec<PriceBar>, ClientError>> + Send;
+}
+
+static DEPTH_CACHE: LazyLock<Cache<OrderBook>> = LazyLock::new(Cache::new);
+static OI_CACHE: LazyLock<Cache<f64>> = LazyLock::new(Cache::new);
+
+/// Depth for a store key, from whichever exchange it belongs to. Briefly cached.
+pub async fn depth(symbol: &str, limit: usize) -> Result<OrderBook, ClientError> {
+    DEPTH_CACHE
+        .get_or_fetch(format!("{}/{}", symbol, limit), async {
+            match split(symbol) {
+                (Exchange::Binance, native) => crate::binance_client::CLIENT.depth(native, limit).await,
+                (Exchange::Bybit, native) => crate::bybit::CLIENT.depth(native, limit).await,
+                (Exchange::BinanceSpot, native) => crate::binance_spot::CLIENT.depth(native, limit).await,
+            }
+        })
+        .await
+}
+
+/// Open interest for a store key, from whichever exchange it belongs to. Briefly cached.
+pub async fn open_interest(symbol: &str) -> Result<f64, ClientError> {
+    OI_CACHE
+        .get_or_fetch(symbol.to_string(), async {
+            match split(symbol) {
+                (Exchange::Binance, native) => crate::binance_client::CLIENT.open_interest(native).await,
+                (Exchange::Bybit, native) => crate::bybit::CLIENT.open_interest(native).await,
+                (Exchange::BinanceSpot, native) => crate::binance_spot::CLIENT.open_interest(native).await,
+            }
+        })
+        .await
+}
+
+/// 1m candles for a store key, from whichever exchange it belongs to.
+pub async fn minute_bars(symbol: &str, from: i64, to: i64) -> Result<Vec<PriceBar>, ClientError> {
+    match split(symbol) {
+        (Exchange::Binance, native) => crate::binance_client::CLIENT.minute_bars(native, from, to).await,
+        (Exchange::Bybit, native) => crate::bybit::CLIENT.minute_bars(native, from, to).await,
+        (Exchange::BinanceSpot, native) => crate::binance_spot::CLIENT.minute_bars(native, from, to).await,
+    }
+}
+
+// The `limit` for a klines request covering `from..to`: Binance weighs the request by it
+pub(crate) fn bar_limit(from: i64, to: i64) -> i64 {
+    ((to - from + 59_999) / 60_000).clamp(1, 1000)
+}
+
+// The bars that had closed by now
+pub(crate) fn finished(mut bars: Vec<PriceBar>) -> Vec<PriceBar> {
+    let now = chrono::Utc::now().timestamp_millis();
+    bars.retain(|b| b.open_time + 60_000 <= now);
+    bars
+}
+
+/// Feed task for one exchange, as spawned (and restarted) by the supervisor.
+pub async fn exchange_task(exchange: Exchange, ctx: ActorContext, shutdown: Shutdown) -> Result<(), TeebError> {
+    let result = match exchange {
+        Exchange::Binance => crate::binance_client::CLIENT.stream_ticks(ctx, shutdown).await,
+        Exchange::Bybit => crate::bybit::CLIENT.stream_ticks(ctx, shutdown).await,
+        Exchange::BinanceSpot => crate::binance_spot::CLIENT.stream_ticks(ctx, shutdown).await,
+    };
+    Ok(result?)
+}
+
+// Rate-limited and retried; see `rest`
+pub(crate) async fn fetch_json<T: serde::de::DeserializeOwned>(client: &reqwest::Client, url: &str) -> Result<T, ClientError> {
+    let resp = crate::rest::get(client, url).await?;
+    Ok(resp.json::<T>().await?)
+}
+
+/// A 1m kline as far as it has traded, from either exchange's stream.
+pub(crate) struct KlineNumbers {
+    pub start: i64,
+    pub end: i64,
+    pub event_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trades: u64,                           // 0 where the exchange doesn't report it
+    pub taker_buy_volume: Option<f64>,         // Binance only
+    pub taker_buy_quote_volume: Option<f64>,
+}
+
+// Turns per-kline values into the running totals and increments a `Tick` carries: volumes
+// keep counting across minutes, taker-buy volume is the part added since the last event.
+#[derive(Default)]
+pub(crate) struct KlineTotals {
+    start: i64,
+    volume_before: f64, // Sum of the finished klines' volume
+    quote_volume_before: f64,
+    trades_before: u64,
+    volume: f64, // Current kline so far
+    quote_volume: f64,
+    trades: u64,
+    taker_buy_volume: f64,
+    taker_buy_quote_volume: f64,
+}
+
+impl KlineTotals {
+    pub fn tick(&mut self, k: &KlineNumbers) -> Tick {
+        if k.start > self.start {
+            self.volume_before += self.volume;
+            self.quote_volume_before += self.quote_volume;
+            self.trades_before += self.trades;
+            self.start = k.start;
+            self.taker_buy_volume = 0.0;
+            self.taker_buy_quote_volume = 0.0;
+        }
+        let taker_buy = k.taker_buy_volume.map(|v| (v - self.taker_buy_volume).max(0.0));
+        let taker_buy_quote = k.taker_buy_quote_volume.map(|v| (v - self.taker_buy_quote_volume).max(0.0));
+        self.volume = k.volume;
+        self.quote_volume = k.quote_volume;
+        self.trades = k.trades;
+        self.taker_buy_volume = k.taker_buy_volume.unwrap_or(0.0);
+        self.taker_buy_quote_volume = k.taker_buy_quote_volume.unwrap_or(0.0);
+        Tick {
+            price: k.close,
+            volume_total: self.volume_before + k.volume,
+            quote_volume_total: self.quote_volume_before + k.quote_volume,
+            trades_total: self.trades_before + k.trades,
+            taker_buy_volume: taker_buy,
+            taker_buy_quote_volume: taker_buy_quote,
+            range: Some(KlineRange { open: k.open, high: k.high, low: k.low }),
+            day_high: None,
+            day_low: None,
+            day_change_pct: None,
+            // The closing event can arrive just after the minute; it still belongs to it
+            event_time: k.event_time.min(k.end),
+        }
+    }
+}
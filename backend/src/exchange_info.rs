@@ -0,0 +1,176 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::store::SharedState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExchangeInfoError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected status {0}")]
+    Status(reqwest::StatusCode),
+}
+
+/// Static per-contract facts from `/fapi/v1/exchangeInfo`, for display and order sizing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMeta {
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub price_precision: u32,
+    pub quantity_precision: u32,
+    pub contract_type: String, // PERPETUAL, CURRENT_QUARTER, ...
+    pub tick_size: Option<f64>,
+    #[serde(default)]
+    pub status: String, // TRADING, SETTLING, ...
+    #[serde(default)]
+    pub step_size: Option<f64>, // Quantity increment for market orders
+    #[serde(default)]
+    pub onboard_date: Option<i64>, // Listing time (ms)
+    #[serde(default)]
+    pub min_qty: Option<f64>, // Smallest market order quantity
+    #[serde(default)]
+    pub min_notional: Option<f64>, // Smallest order value, quote asset
+}
+
+impl SymbolMeta {
+    pub fn format_price(&self, price: f64) -> String {
+        format!("{:.*}", self.price_precision as usize, price)
+    }
+
+    pub fn is_trading(&self) -> bool {
+        self.status == "TRADING"
+    }
+
+    /// `price` on the contract's tick grid (nearest tick).
+    pub fn round_price(&self, price: f64) -> f64 {
+        match self.tick_size.filter(|t| *t > 0.0) {
+            Some(tick) => (price / tick).round() * tick,
+            None => price,
+        }
+    }
+
+    /// `quantity` rounded down to the lot step; None when that is below the minimum
+    /// quantity or, at `price`, the minimum order value.
+    pub fn order_quantity(&self, quantity: f64, price: f64) -> Option<f64> {
+        let quantity = match self.step_size.filter(|s| *s > 0.0) {
+            Some(step) => (quantity / step + 1e-9).floor() * step,
+            None => quantity,
+        };
+        let too_small = !quantity.is_finite()
+            || quantity <= 0.0
+            || self.min_qty.is_some_and(|min| quantity < min)
+            || self.min_notional.is_some_and(|min| quantity * price < min);
+        (!too_small).then_some(quantity)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<RawSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSymbol {
+    symbol: String,
+    base_asset: String,
+    quote_asset: String,
+    price_precision: u32,
+    quantity_precision: u32,
+    #[serde(default)]
+    contract_type: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    filters: Vec<serde_json::Value>,
+    #[serde(default)]
+    onboard_date: Option<i64>,
+}
+
+impl RawSymbol {
+    fn filter(&self, filter_type: &str, key: &str) -> Option<f64> {
+        self.filters
+            .iter()
+            .find(|f| f.get("filterType").and_then(|t| t.as_str()) == Some(filter_type))
+            .and_then(|f| f.get(key)?.as_str()?.parse().ok())
+    }
+
+    fn tick_size(&self) -> Option<f64> {
+        self.filter("PRICE_FILTER", "tickSize")
+    }
+
+    fn step_size(&self) -> Option<f64> {
+        self.filter("MARKET_LOT_SIZE", "stepSize").or_else(|| self.filter("LOT_SIZE", "stepSize"))
+    }
+
+    fn min_qty(&self) -> Option<f64> {
+        self.filter("MARKET_LOT_SIZE", "minQty").or_else(|| self.filter("LOT_SIZE", "minQty"))
+    }
+
+    fn min_notional(&self) -> Option<f64> {
+        self.filter("MIN_NOTIONAL", "notional")
+    }
+}
+
+// Latest metadata for every listed contract. Symbol states pick theirs up from here.
+static SYMBOL_META: LazyLock<DashMap<String, SymbolMeta>> = LazyLock::new(DashMap::new);
+
+pub fn get(symbol: &str) -> Option<SymbolMeta> {
+    SYMBOL_META.get(symbol).map(|m| m.clone())
+}
+
+/// Perpetual contracts currently trading, sorted. Empty until exchangeInfo first loads.
+pub fn perpetuals() -> Vec<String> {
+    let mut symbols: Vec<String> = SYMBOL_META
+        .iter()
+        .filter(|m| m.contract_type == "PERPETUAL" && m.is_trading())
+        .map(|m| m.key().clone())
+        .collect();
+    symbols.sort();
+    symbols
+}
+
+/// Every contract currently trading, perpetual or dated.
+pub fn trading() -> HashSet<String> {
+    SYMBOL_META.iter().filter(|m| m.is_trading()).map(|m| m.key().clone()).collect()
+}
+
+/// Fetches exchangeInfo and updates the metadata map and every symbol already in the store.
+/// Contracts no longer listed are dropped from the map.
+pub async fn refresh(store: &SharedState) -> Result<usize, ExchangeInfoError> {
+    let url = format!("{}/fapi/v1/exchangeInfo", FUTURES_REST_URL);
+    let resp = reqwest::get(&url).await?;
+    if !resp.status().is_success() {
+        return Err(ExchangeInfoError::Status(resp.status()));
+    }
+    let info: ExchangeInfo = resp.json().await?;
+
+    for raw in &info.symbols {
+        let meta = SymbolMeta {
+            base_asset: raw.base_asset.clone(),
+            quote_asset: raw.quote_asset.clone(),
+            price_precision: raw.price_precision,
+            quantity_precision: raw.quantity_precision,
+            contract_type: raw.contract_type.clone(),
+            tick_size: raw.tick_size(),
+            status: raw.status.clone(),
+            step_size: raw.step_size(),
+            onboard_date: raw.onboard_date,
+            min_qty: raw.min_qty(),
+            min_notional: raw.min_notional(),
+        };
+        if let Some(mut state) = store.get_mut(&raw.symbol) {
+            state.meta = Some(meta.clone());
+        }
+        SYMBOL_META.insert(raw.symbol.clone(), meta);
+    }
+    let listed: HashSet<&str> = info.symbols.iter().map(|raw| raw.symbol.as_str()).collect();
+    SYMBOL_META.retain(|symbol, _| listed.contains(symbol.as_str()));
+    crate::listing::observe(
+        info.symbols.iter().map(|raw| (raw.symbol.clone(), raw.onboard_date)),
+        chrono::Utc::now().timestamp_millis(),
+    );
+    Ok(info.symbols.len())
+}
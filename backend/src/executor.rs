@@ -0,0 +1,301 @@
+// Places real Binance Futures orders for published signals: a market entry sized as a percent
+// of account equity, then a mandatory stop-loss / take-profit bracket. Off unless
+// EXECUTION_ENABLED=true, and even then a dry run (orders logged, not sent) unless
+// EXECUTION_DRY_RUN=false. Needs BINANCE_API_KEY / BINANCE_API_SECRET in both modes, since
+// sizing reads the account.
+use crate::binance_client::FUTURES_REST_URL;
+use crate::metrics::METRICS;
+use crate::scanner::{Signal, SignalType, WsMessage};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+
+pub const KEY_SECRET: &str = "BINANCE_API_KEY";
+pub const SECRET_SECRET: &str = "BINANCE_API_SECRET";
+const RECV_WINDOW_MS: u64 = 5000;
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Only the literal `true`: a typo must leave execution off.
+pub fn enabled() -> bool {
+    std::env::var("EXECUTION_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+/// Dry run unless explicitly turned off.
+pub fn dry_run() -> bool {
+    !std::env::var("EXECUTION_DRY_RUN").is_ok_and(|v| v.eq_ignore_ascii_case("false"))
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionConfig {
+    pub equity_pct: f64,      // EXECUTION_EQUITY_PCT, margin per trade as percent of equity
+    pub leverage: u32,        // EXECUTION_LEVERAGE
+    pub stop_loss_pct: f64,   // EXECUTION_STOP_LOSS_PCT, defaults to the invalidation threshold
+    pub take_profit_pct: f64, // EXECUTION_TAKE_PROFIT_PCT
+    pub max_positions: usize, // EXECUTION_MAX_POSITIONS, open positions across all symbols
+}
+
+impl ExecutionConfig {
+    pub fn from_env() -> Self {
+        Self {
+            equity_pct: env_or("EXECUTION_EQUITY_PCT", 1.0),
+            leverage: env_or("EXECUTION_LEVERAGE", 3u32).clamp(1, 125),
+            stop_loss_pct: env_or("EXECUTION_STOP_LOSS_PCT", crate::actor::invalidation_pct()),
+            take_profit_pct: env_or("EXECUTION_TAKE_PROFIT_PCT", 1.0),
+            max_positions: env_or("EXECUTION_MAX_POSITIONS", 3),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExecutorError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("binance error {code}: {msg}")]
+    Api { code: i64, msg: String },
+    #[error("unexpected status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("BINANCE_API_KEY / BINANCE_API_SECRET not configured")]
+    MissingCredentials,
+    #[error("malformed response: {0}")]
+    Malformed(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Account {
+    total_margin_balance: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionRisk {
+    symbol: String,
+    position_amt: String,
+}
+
+/// What would be (or was) sent for one signal.
+#[derive(Debug, Clone)]
+pub struct OrderPlan {
+    pub symbol: String,
+    pub side: &'static str, // Entry side; the bracket uses the other one
+    pub quantity: String,
+    pub leverage: u32,
+    pub stop_price: String,
+    pub take_profit_price: String,
+}
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default()
+});
+
+fn hmac_hex(secret: &str, payload: &str) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// A USER_DATA / TRADE endpoint: timestamped, signed query, API key header
+pub(crate) async fn signed<T: serde::de::DeserializeOwned>(
+    method: reqwest::Method,
+    path: &str,
+    params: &[(&str, String)],
+) -> Result<T, ExecutorError> {
+    let (Some(key), Some(secret)) = (crate::secrets::get(KEY_SECRET), crate::secrets::get(SECRET_SECRET)) else {
+        return Err(ExecutorError::MissingCredentials);
+    };
+    let mut query: String = params.iter().map(|(k, v)| format!("{}={}&", k, v)).collect();
+    query += &format!("recvWindow={}&timestamp={}", RECV_WINDOW_MS, chrono::Utc::now().timestamp_millis());
+    let signature = hmac_hex(secret.expose(), &query);
+    let url = format!("{}{}?{}&signature={}", FUTURES_REST_URL, path, query, signature);
+    let resp = CLIENT.request(method, &url).header("X-MBX-APIKEY", key.expose()).send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(match resp.json::<ApiError>().await {
+            Ok(e) => ExecutorError::Api { code: e.code, msg: e.msg },
+            Err(_) => ExecutorError::Status(status),
+        });
+    }
+    Ok(resp.json().await?)
+}
+
+pub(crate) fn parse(value: &str) -> Result<f64, ExecutorError> {
+    value.parse().map_err(|_| ExecutorError::Malformed(format!("'{}' is not a number", value)))
+}
+
+/// Sizes the trade and derives the bracket: margin `equity_pct` of equity at `leverage`,
+/// stop and target at the configured distances from the signal price. None when the size
+/// rounds down to nothing or below the contract's minimum order.
+pub fn plan(signal: &Signal, equity: f64, config: &ExecutionConfig) -> Option<OrderPlan> {
+    let meta = crate::exchange_info::get(&signal.symbol);
+    let notional = equity * config.equity_pct / 100.0 * config.leverage as f64;
+    let raw_quantity = notional / signal.price;
+    let quantity = match &meta {
+        Some(meta) => meta.order_quantity(raw_quantity, signal.price)?,
+        None => raw_quantity,
+    };
+    if !quantity.is_finite() || quantity <= 0.0 {
+        return None;
+    }
+    let (sl, tp) = (config.stop_loss_pct / 100.0, config.take_profit_pct / 100.0);
+    let (side, stop, target) = match signal.signal_type {
+        SignalType::Long | SignalType::NewListing | SignalType::Breakout => ("BUY", signal.price * (1.0 - sl), signal.price * (1.0 + tp)),
+        SignalType::Short => ("SELL", signal.price * (1.0 + sl), signal.price * (1.0 - tp)),
+    };
+    let round = |price: f64| meta.as_ref().map_or(price, |m| m.round_price(price));
+    let price_decimals = meta.as_ref().map_or(8, |m| m.price_precision as usize);
+    let quantity_decimals = meta.as_ref().map_or(3, |m| m.quantity_precision as usize);
+    Some(OrderPlan {
+        symbol: signal.symbol.clone(),
+        side,
+        quantity: format!("{:.*}", quantity_decimals, quantity),
+        leverage: config.leverage,
+        stop_price: format!("{:.*}", price_decimals, round(stop)),
+        take_profit_price: format!("{:.*}", price_decimals, round(target)),
+    })
+}
+
+async fn open_positions() -> Result<Vec<String>, ExecutorError> {
+    let risks: Vec<PositionRisk> = signed(reqwest::Method::GET, "/fapi/v2/positionRisk", &[]).await?;
+    let mut open = Vec::new();
+    for risk in risks {
+        if parse(&risk.position_amt)? != 0.0 {
+            open.push(risk.symbol);
+        }
+    }
+    Ok(open)
+}
+
+async fn equity() -> Result<f64, ExecutorError> {
+    let account: Account = signed(reqwest::Method::GET, "/fapi/v2/account", &[]).await?;
+    parse(&account.total_margin_balance)
+}
+
+async fn order(params: &[(&str, String)]) -> Result<serde_json::Value, ExecutorError> {
+    signed(reqwest::Method::POST, "/fapi/v1/order", params).await
+}
+
+// Entry, then both legs of the bracket. A position without its stop is not acceptable: if
+// either leg fails the entry is flattened again and the symbol's open orders cancelled.
+async fn place(plan: &OrderPlan) -> Result<(), ExecutorError> {
+    let exit_side = if plan.side == "BUY" { "SELL" } else { "BUY" };
+    let symbol = plan.symbol.clone();
+    signed::<serde_json::Value>(
+        reqwest::Method::POST,
+        "/fapi/v1/leverage",
+        &[("symbol", symbol.clone()), ("leverage", plan.leverage.to_string())],
+    )
+    .await?;
+    order(&[
+        ("symbol", symbol.clone()),
+        ("side", plan.side.to_string()),
+        ("type", "MARKET".to_string()),
+        ("quantity", plan.quantity.clone()),
+        ("newOrderRespType", "RESULT".to_string()),
+    ])
+    .await?;
+
+    let bracket = |kind: &'static str, price: String| {
+        [
+            ("symbol", symbol.clone()),
+            ("side", exit_side.to_string()),
+            ("type", kind.to_string()),
+            ("stopPrice", price),
+            ("closePosition", "true".to_string()),
+            ("workingType", "MARK_PRICE".to_string()),
+        ]
+    };
+    let legs = async {
+        order(&bracket("STOP_MARKET", plan.stop_price.clone())).await?;
+        order(&bracket("TAKE_PROFIT_MARKET", plan.take_profit_price.clone())).await
+    };
+    if let Err(e) = legs.await {
+        METRICS.record_error("executor");
+        error!(symbol = %symbol, error = %e, "Bracket order failed, flattening the position");
+        let cancelled = signed::<serde_json::Value>(reqwest::Method::DELETE, "/fapi/v1/allOpenOrders", &[("symbol", symbol.clone())]).await;
+        let flattened = order(&[
+            ("symbol", symbol.clone()),
+            ("side", exit_side.to_string()),
+            ("type", "MARKET".to_string()),
+            ("quantity", plan.quantity.clone()),
+            ("reduceOnly", "true".to_string()),
+        ])
+        .await;
+        if let Err(e) = cancelled.and(flattened) {
+            error!(symbol = %symbol, error = %e, "Failed to flatten unprotected position, CHECK THE ACCOUNT");
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+async fn execute(signal: &Signal, config: &ExecutionConfig, dry_run: bool) -> Result<(), ExecutorError> {
+    if signal.low_confidence {
+        info!(symbol = %signal.symbol, signal_id = %signal.id, "Not executing low-confidence signal");
+        return Ok(());
+    }
+    if signal.exchange != crate::exchange::Exchange::Binance {
+        info!(symbol = %signal.symbol, signal_id = %signal.id, "Not executing signal from another exchange");
+        return Ok(());
+    }
+    let open = open_positions().await?;
+    if open.contains(&signal.symbol) {
+        info!(symbol = %signal.symbol, "Already in a position, not executing");
+        return Ok(());
+    }
+    if open.len() >= config.max_positions {
+        info!(symbol = %signal.symbol, open = open.len(), max = config.max_positions, "Position limit reached, not executing");
+        return Ok(());
+    }
+    let equity = equity().await?;
+    let Some(plan) = plan(signal, equity, config) else {
+        warn!(symbol = %signal.symbol, equity, "Position size rounds to zero, not executing");
+        return Ok(());
+    };
+    if dry_run {
+        info!(symbol = %plan.symbol, side = plan.side, quantity = %plan.quantity, leverage = plan.leverage, stop = %plan.stop_price, take_profit = %plan.take_profit_price, equity, "DRY RUN: would place bracket order");
+        return Ok(());
+    }
+    place(&plan).await?;
+    info!(symbol = %plan.symbol, side = plan.side, quantity = %plan.quantity, leverage = plan.leverage, stop = %plan.stop_price, take_profit = %plan.take_profit_price, "Bracket order placed");
+    Ok(())
+}
+
+/// Executes every published signal, one at a time. Follows the broadcast channel like the
+/// notifiers, so only the leader (or aggregator) trades.
+pub async fn executor_task(tx: broadcast::Sender<WsMessage>) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    let config = ExecutionConfig::from_env();
+    let dry_run = dry_run();
+    if dry_run {
+        info!(config = ?config, "Order execution enabled in DRY RUN mode");
+    } else {
+        warn!(config = ?config, "Order execution enabled: LIVE orders will be placed");
+    }
+    let mut rx = tx.subscribe();
+    while let Some(signal) = crate::notifier::next_signal(&mut rx).await {
+        // A signal older than a minute has moved on; entering late is a different trade
+        if chrono::Utc::now().timestamp_millis() - signal.timestamp > 60_000 {
+            warn!(symbol = %signal.symbol, signal_id = %signal.id, "Signal too old to execute");
+            continue;
+        }
+        if let Err(e) = execute(&signal, &config, dry_run).await {
+            METRICS.record_error("executor");
+            error!(symbol = %signal.symbol, signal_id = %signal.id, error = %e, "Order execution failed");
+        }
+    }
+}
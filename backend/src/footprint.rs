@@ -0,0 +1,204 @@
+use crate::binance_client::{FUTURES_STREAM_URL, MAX_STREAMS_PER_CONNECTION};
+use crate::metrics::METRICS;
+use crate::model::SymbolState;
+use crate::store::SharedState;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, info};
+use ts_rs::TS;
+use url::Url;
+use utoipa::ToSchema;
+
+// Minutes of footprints kept per symbol, like the candle window
+const MAX_MINUTES: usize = 60;
+// How often the set of symbols with a live signal is re-checked
+const RESCAN: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+// Signals older than this no longer get a footprint, same horizon as live updates
+const ACTIVE_MS: i64 = 60 * 60 * 1000;
+
+pub fn enabled() -> bool {
+    std::env::var("FOOTPRINT_ENABLED").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+fn bucket_ticks() -> f64 {
+    std::env::var("FOOTPRINT_BUCKET_TICKS").ok().and_then(|v| v.parse().ok()).unwrap_or(10.0)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FootprintError {
+    #[error("invalid stream url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("websocket error: {0}")]
+    Ws(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("stream closed by server")]
+    Closed,
+}
+
+/// Taker volume traded within one price bucket.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FootprintLevel {
+    pub price: f64, // Bucket floor
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+}
+
+/// One minute of order flow by price, next to the 1m candle of the same `minute`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Footprint {
+    pub minute: i64,
+    pub bucket_size: f64,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub levels: Vec<FootprintLevel>, // Ascending by price
+}
+
+impl Footprint {
+    fn add(&mut self, price: f64, quantity: f64, taker_buy: bool) {
+        let floor = (price / self.bucket_size).floor() * self.bucket_size;
+        let index = match self.levels.binary_search_by(|l| l.price.total_cmp(&floor)) {
+            Ok(index) => index,
+            Err(index) => {
+                self.levels.insert(index, FootprintLevel { price: floor, buy_volume: 0.0, sell_volume: 0.0 });
+                index
+            }
+        };
+        let level = &mut self.levels[index];
+        if taker_buy {
+            level.buy_volume += quantity;
+            self.buy_volume += quantity;
+        } else {
+            level.sell_volume += quantity;
+            self.sell_volume += quantity;
+        }
+    }
+}
+
+// `FOOTPRINT_BUCKET_TICKS` ticks per bucket; without exchangeInfo, ~0.1% of the price
+fn bucket_size(state: &SymbolState, price: f64) -> f64 {
+    let tick = state
+        .meta
+        .as_ref()
+        .and_then(|m| m.tick_size)
+        .filter(|t| *t > 0.0)
+        .unwrap_or_else(|| 10f64.powi(price.log10().floor() as i32 - 3));
+    tick * bucket_ticks()
+}
+
+fn record(state: &mut SymbolState, trade: &AggTrade) {
+    let (Ok(price), Ok(quantity)) = (trade.price.parse::<f64>(), trade.quantity.parse::<f64>()) else {
+        METRICS.record_error("footprint");
+        return;
+    };
+    if price <= 0.0 {
+        return;
+    }
+    let minute = trade.trade_time / 60_000 * 60_000;
+    match state.footprints.back() {
+        Some(last) if last.minute > minute => return, // Late trade for a minute already moved past
+        Some(last) if last.minute == minute => {}
+        _ => {
+            let bucket_size = bucket_size(state, price);
+            state.footprints.push_back(Footprint { minute, bucket_size, buy_volume: 0.0, sell_volume: 0.0, levels: Vec::new() });
+            if state.footprints.len() > MAX_MINUTES {
+                state.footprints.pop_front();
+            }
+        }
+    }
+    if let Some(footprint) = state.footprints.back_mut() {
+        // The buyer being the maker means the taker sold
+        footprint.add(price, quantity, !trade.buyer_maker);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Combined {
+    pub data: AggTrade,
+}
+
+// Also read by `order_flow`
+#[derive(Debug, Deserialize)]
+pub(crate) struct AggTrade {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub quantity: String,
+    #[serde(rename = "m")]
+    pub buyer_maker: bool,
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+}
+
+// Binance symbols with a live, still-valid signal (aggTrades come from Binance streams)
+fn watched(store: &SharedState) -> BTreeSet<String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    store
+        .iter()
+        .filter(|e| crate::exchange::split(e.key()).0 == crate::exchange::Exchange::Binance)
+        .filter(|e| {
+            let state = e.value();
+            state.active_signal.as_ref().is_some_and(|a| !a.invalidated)
+                && state.last_signal_time.is_some_and(|t| now - t < ACTIVE_MS)
+        })
+        .map(|e| e.key().clone())
+        .take(MAX_STREAMS_PER_CONNECTION)
+        .collect()
+}
+
+// Streams aggTrades for `symbols` until that set changes (Ok) or the connection fails
+async fn stream(store: &SharedState, symbols: &BTreeSet<String>) -> Result<(), FootprintError> {
+    let streams: Vec<String> = symbols.iter().map(|s| format!("{}@aggTrade", s.to_lowercase())).collect();
+    let url = Url::parse(&format!("{}?streams={}", FUTURES_STREAM_URL, streams.join("/")))?;
+    info!(symbols = symbols.len(), "Connecting to aggTrade streams for footprints");
+    let (ws_stream, _) = connect_async(url).await?;
+    let (_, mut read) = ws_stream.split();
+
+    let mut rescan = tokio::time::interval(RESCAN);
+    rescan.tick().await;
+    loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Combined>(&text) {
+                    Ok(combined) => {
+                        if let Some(mut state) = store.get_mut(&combined.data.symbol) {
+                            record(&mut state, &combined.data);
+                        }
+                    }
+                    Err(e) => {
+                        METRICS.record_error("footprint");
+                        debug!(error = %e, "Failed to parse aggTrade message");
+                    }
+                },
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(FootprintError::Closed),
+            },
+            _ = rescan.tick() => {
+                if watched(store) != *symbols {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Builds per-minute footprints for symbols with a live signal from their aggTrade streams,
+/// reconnecting with the new set whenever a signal starts or ends. Off unless `FOOTPRINT_ENABLED=1`.
+pub async fn footprint_task(store: SharedState) -> Result<(), FootprintError> {
+    if !enabled() {
+        std::future::pending::<()>().await;
+    }
+    loop {
+        let symbols = watched(&store);
+        if symbols.is_empty() {
+            tokio::time::sleep(RESCAN).await;
+            continue;
+        }
+        stream(&store, &symbols).await?;
+    }
+}
@@ -0,0 +1,101 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::exchange::{fetch_json, ClientError};
+use crate::metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use tracing::{debug, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Binance perps' current funding, refreshed from `/fapi/v1/premiumIndex` (one request for the
+// whole market) every `FUNDING_REFRESH_SECS`. Read by the verifier and `/api/funding`.
+
+fn refresh_secs() -> u64 {
+    std::env::var("FUNDING_REFRESH_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(300)
+}
+
+/// Funding rate (in percent per interval) past which the verifier calls it extreme.
+pub fn extreme_pct() -> f64 {
+    std::env::var("FUNDING_EXTREME_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or(0.05)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PremiumIndex {
+    symbol: String,
+    mark_price: String,
+    index_price: String,
+    last_funding_rate: String,
+    next_funding_time: i64,
+}
+
+/// A perp's funding as of the last refresh. Positive: longs pay shorts.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    pub rate: f64, // Per funding interval, as a fraction (0.0001 = 0.01%)
+    pub mark_price: f64,
+    pub index_price: f64, // The mark's premium over it shows which side perp traders press
+    pub next_funding_time: i64,
+    pub updated_at: i64,
+}
+
+static RATES: LazyLock<RwLock<HashMap<String, FundingRate>>> = LazyLock::new(Default::default);
+
+/// Funding for a store key. None for other exchanges and before the first refresh.
+pub fn get(symbol: &str) -> Option<FundingRate> {
+    RATES.read().ok()?.get(symbol).cloned()
+}
+
+/// Every cached rate, by symbol.
+pub fn all() -> Vec<FundingRate> {
+    let mut rates: Vec<FundingRate> = RATES.read().map(|r| r.values().cloned().collect()).unwrap_or_default();
+    rates.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    rates
+}
+
+async fn refresh(client: &reqwest::Client) -> Result<usize, ClientError> {
+    let indexes: Vec<PremiumIndex> = fetch_json(client, &format!("{}/fapi/v1/premiumIndex", FUTURES_REST_URL)).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let rates: HashMap<String, FundingRate> = indexes
+        .into_iter()
+        .filter_map(|i| {
+            let rate = FundingRate {
+                rate: i.last_funding_rate.parse().ok()?,
+                mark_price: i.mark_price.parse().ok()?,
+                index_price: i.index_price.parse().ok()?,
+                next_funding_time: i.next_funding_time,
+                updated_at: now,
+                symbol: i.symbol,
+            };
+            Some((rate.symbol.clone(), rate))
+        })
+        .collect();
+    let count = rates.len();
+    if let Ok(mut cache) = RATES.write() {
+        *cache = rates;
+    }
+    Ok(count)
+}
+
+/// Keeps the funding cache fresh. A failed refresh keeps the previous rates. Parked unless
+/// Binance is one of `EXCHANGES`.
+pub async fn funding_task() {
+    if !crate::exchange::enabled().contains(&crate::exchange::Exchange::Binance) {
+        std::future::pending::<()>().await;
+    }
+    let client = crate::rest::client();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(refresh_secs().max(1)));
+    loop {
+        interval.tick().await;
+        match refresh(&client).await {
+            Ok(count) => debug!(symbols = count, "Funding rates refreshed"),
+            Err(e) => {
+                METRICS.record_error("funding");
+                warn!(error = %e, "Failed to refresh funding rates");
+            }
+        }
+    }
+}
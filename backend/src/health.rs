@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use crate::history::HistoryManager;
+use crate::metrics::METRICS;
+use crate::scanner::WsMessage;
+use crate::store::SharedState;
+use crate::supervisor::{Supervisor, TaskStatus};
+use crate::warmup::Readiness;
+
+// What the probes look at besides the supervised tasks: a stuck process keeps every task
+// "running" while the exchange socket has gone quiet, so liveness also needs the feed to have
+// delivered something recently. The same age limit gates the systemd watchdog ping.
+
+/// Longest the feed may stay silent before the process counts as stuck.
+pub fn max_feed_age_ms() -> i64 {
+    std::env::var("WATCHDOG_MAX_FEED_AGE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60) * 1000
+}
+
+/// Age of the newest exchange message, None before the first one.
+pub fn feed_age_ms() -> Option<i64> {
+    let last = METRICS.last_feed_message_ms.load(Ordering::Relaxed);
+    (last > 0).then(|| chrono::Utc::now().timestamp_millis() - last)
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedStatus {
+    pub last_message_ms: Option<i64>,
+    pub age_ms: Option<i64>,
+    pub max_age_ms: i64,
+    pub fresh: bool, // A message within max_age_ms
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatus {
+    pub persistent: bool, // False when history lives in memory only
+    pub degraded: bool,   // A history notice is in force (writes failing)
+}
+
+/// The data path from feed to clients.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStatus {
+    pub feed: FeedStatus,
+    pub tracked_symbols: usize,
+    pub history: HistoryStatus,
+    pub subscribers: usize, // Broadcast receivers: connected clients plus internal consumers
+}
+
+/// `/health`: healthy while every supervised task runs and the feed is fresh.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub tasks: BTreeMap<String, TaskStatus>,
+    pub pipeline: PipelineStatus,
+}
+
+/// `/ready`: ready once enough windows are warm and the feed is fresh.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyReport {
+    pub ready: bool,
+    pub warmup: Readiness,
+    pub pipeline: PipelineStatus,
+}
+
+pub fn feed_status() -> FeedStatus {
+    let last = METRICS.last_feed_message_ms.load(Ordering::Relaxed);
+    let age_ms = feed_age_ms();
+    let max_age_ms = max_feed_age_ms();
+    FeedStatus {
+        last_message_ms: (last > 0).then_some(last),
+        age_ms,
+        max_age_ms,
+        fresh: age_ms.is_some_and(|age| age <= max_age_ms),
+    }
+}
+
+pub fn pipeline(store: &SharedState, history: &HistoryManager, tx: &broadcast::Sender<WsMessage>) -> PipelineStatus {
+    PipelineStatus {
+        feed: feed_status(),
+        tracked_symbols: store.len(),
+        history: HistoryStatus { persistent: history.is_persistent(), degraded: crate::system_event::is_active("history") },
+        subscribers: tx.receiver_count(),
+    }
+}
+
+pub fn health(supervisor: &Supervisor, pipeline: PipelineStatus) -> HealthReport {
+    let tasks = supervisor.report();
+    HealthReport { healthy: tasks.healthy && pipeline.feed.fresh, tasks: tasks.tasks, pipeline }
+}
+
+pub fn ready(store: &SharedState, pipeline: PipelineStatus) -> ReadyReport {
+    let warmup = crate::warmup::readiness(store);
+    ReadyReport { ready: warmup.ready && pipeline.feed.fresh, warmup, pipeline }
+}
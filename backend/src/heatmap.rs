@@ -0,0 +1,69 @@
+use crate::leaderboard::mover;
+use crate::store::SharedState;
+use serde::Serialize;
+use std::sync::{Arc, LazyLock, RwLock};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+fn refresh_secs() -> u64 {
+    std::env::var("HEATMAP_REFRESH_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(5)
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapCell {
+    pub symbol: String,
+    pub price: f64,
+    pub volume_ratio: f64,
+    pub change_15m_pct: Option<f64>,
+    pub day_change_pct: Option<f64>,
+    pub day_quote_volume: Option<f64>, // For sizing tiles
+}
+
+/// Every tracked symbol's current volume ratio and price change, for `GET /api/heatmap`.
+#[derive(Debug, Clone, Default, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Heatmap {
+    pub cells: Vec<HeatmapCell>,
+    pub timestamp: i64, // When it was computed
+}
+
+// Requests read the last computed map instead of walking the store each time
+static HEATMAP: LazyLock<RwLock<Arc<Heatmap>>> = LazyLock::new(Default::default);
+
+pub fn current() -> Arc<Heatmap> {
+    HEATMAP.read().map(|h| h.clone()).unwrap_or_default()
+}
+
+pub fn compute(store: &SharedState) -> Heatmap {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut cells: Vec<HeatmapCell> = store
+        .iter()
+        .filter_map(|e| {
+            let state = e.value();
+            let m = mover(state, now)?;
+            Some(HeatmapCell {
+                symbol: m.symbol,
+                price: m.price,
+                volume_ratio: m.volume_ratio,
+                change_15m_pct: m.change_15m_pct,
+                day_change_pct: state.day.change_pct,
+                day_quote_volume: (state.day.quote_volume > 0.0).then_some(state.day.quote_volume),
+            })
+        })
+        .collect();
+    cells.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    Heatmap { cells, timestamp: now }
+}
+
+/// Recomputes the heatmap every `HEATMAP_REFRESH_SECS`.
+pub async fn heatmap_task(store: SharedState) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(refresh_secs().max(1)));
+    loop {
+        interval.tick().await;
+        let heatmap = Arc::new(compute(&store));
+        if let Ok(mut current) = HEATMAP.write() {
+            *current = heatmap;
+        }
+    }
+}
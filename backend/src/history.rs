@@ -1,55 +1,556 @@
-use crate::scanner::Signal;
-use crate::store::SharedState;
+use crate::lifecycle::{SignalClosed, SignalState, StateTransition, EXPIRY_MS};
+use crate::scanner::{Invalidation, Signal, WsMessage};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::{IntoParams, ToSchema};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::sync::{Arc, Mutex};
+use crate::shard::{forward_transition, SHARD};
+use crate::shutdown::Shutdown;
+use crate::exchange::PriceBar;
 use tokio::sync::broadcast;
+use tracing::{error, info, warn, Instrument};
+use crate::metrics::METRICS;
+use crate::error::TeebError;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryError {
+    #[error("history io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("history (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("history database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("history task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+// Stored records predate camelCase, hence the aliases
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SignalOutcome {
+    #[serde(alias = "price_at_15m")]
     pub price_at_15m: Option<f64>,
+    #[serde(alias = "price_at_30m")]
     pub price_at_30m: Option<f64>,
+    #[serde(alias = "price_at_60m")]
     pub price_at_60m: Option<f64>,
-    pub success: bool,
-    pub max_gain_percent: f64,
+    pub success: bool, // Take-profit hit before the stop; before TP/SL, any best move past 1%
+    #[serde(alias = "max_gain_percent")]
+    pub max_gain_percent: f64, // Fraction, 0.012 = +1.2%
+    #[serde(default)]
+    pub max_drawdown_percent: f64, // Worst move against the entry, fraction, 0.008 = -0.8%
+    #[serde(default)]
+    pub hit_tp: bool,
+    #[serde(default)]
+    pub hit_sl: bool,
+    #[serde(default)]
+    pub time_to_target_ms: Option<i64>, // From the signal to the minute TP or SL was hit
+    #[serde(default)]
+    pub take_profit_pct: Option<f64>, // Thresholds the signal is judged by, fixed when recorded
+    #[serde(default)]
+    pub stop_loss_pct: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SignalRecord {
     pub signal: Signal,
     pub outcome: SignalOutcome,
-    pub recorded_at: i64,
+    #[serde(alias = "recorded_at")]
+    pub recorded_at: i64, // Unix seconds
+    #[serde(default)]
+    pub invalidation: Option<Invalidation>,
+    #[serde(default)]
+    pub feedback: Option<SignalFeedback>,
+    #[serde(default)]
+    pub transitions: Vec<StateTransition>, // Oldest first; empty for records from before lifecycles
+    #[serde(default)]
+    pub paper_trade: Option<crate::paper_trader::Position>, // The closed simulated position (PAPER_TRADING_ENABLED)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SignalRecord {
+    pub fn state(&self) -> Option<SignalState> {
+        self.transitions.last().map(|t| t.to)
+    }
+
+    // Appends the transition if the state machine allows it, with `from` filled in
+    fn advance(&mut self, mut transition: StateTransition) -> Option<StateTransition> {
+        let from = self.state();
+        if !SignalState::allows(from, transition.to) {
+            return None;
+        }
+        transition.from = from;
+        self.transitions.push(transition.clone());
+        Some(transition)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum FeedbackKind {
+    Taken,
+    Skipped,
+    Good,
+    Bad,
+}
+
+/// A trader marks a signal (WS `Feedback` command or `POST /api/feedback`).
+#[derive(Debug, Clone, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackRequest {
+    pub signal_id: String,
+    pub kind: FeedbackKind,
+}
+
+/// What traders said about a signal: whether it was traded, and whether it was any good.
+/// Each is the latest mark on that axis; None until someone says.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalFeedback {
+    pub signal_id: String,
+    pub taken: Option<bool>,
+    pub good: Option<bool>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackStats {
+    pub taken: usize,
+    pub skipped: usize,
+    pub good: usize,
+    pub bad: usize,
+    pub taken_win_rate: Option<f64>, // Win rate of the signals traders actually took
+    pub approval_rate: Option<f64>,  // Good / (good + bad)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Stats {
     pub total_signals: usize,
     pub win_rate: f64,
     pub top_gainer: String, // e.g. "LINK +4.5%"
+    #[serde(default)]
+    pub feedback: FeedbackStats,
+    #[serde(default)]
+    pub market_context: Option<crate::sentiment::MarketContext>,
+    #[serde(default)]
+    pub paper: Option<crate::paper_trader::PaperStats>, // Only with paper trading enabled
+    #[serde(default)]
+    pub by_strategy: Vec<StrategyStats>,
+    #[serde(default)]
+    pub by_signal_type: Vec<BucketStats>,
+    #[serde(default)]
+    pub by_symbol: Vec<BucketStats>, // The most signalled symbols, see `STATS_SYMBOLS`
+    #[serde(default)]
+    pub by_day: Vec<BucketStats>, // Newest first, UTC days
+    #[serde(default)]
+    pub by_week: Vec<BucketStats>, // Newest first, keyed by the week's Monday
+}
+
+/// Signal count and win rate of one strategy, for `Stats::by_strategy`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyStats {
+    pub strategy: String,
+    pub total_signals: usize,
+    pub win_rate: f64,
+    #[serde(default)]
+    pub avg_gain: f64, // Mean max gain, percent
+}
+
+/// Performance of the signals sharing one key: a symbol, "Long"/"Short", a strategy, a UTC
+/// day ("2024-05-06") or a week (its Monday's date).
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketStats {
+    pub key: String,
+    pub total_signals: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub avg_gain: f64, // Mean max gain, percent
+}
+
+/// What `HistoryManager::breakdown` groups signals by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsGroup {
+    #[default]
+    Symbol,
+    SignalType,
+    Strategy,
+    Day,
+    Week,
+}
+
+impl StatsGroup {
+    // The SQL expression of the group's key
+    fn key_sql(self) -> &'static str {
+        match self {
+            StatsGroup::Symbol => "symbol",
+            StatsGroup::SignalType => "COALESCE(json_extract(record, '$.signal.signalType'), 'Long')",
+            StatsGroup::Strategy => STRATEGY_SQL,
+            StatsGroup::Day => "date(timestamp / 1000, 'unixepoch')",
+            StatsGroup::Week => "date(timestamp / 1000, 'unixepoch', 'weekday 0', '-6 days')",
+        }
+    }
+
+    fn order_sql(self) -> &'static str {
+        match self {
+            StatsGroup::Symbol => "COUNT(*) DESC, key",
+            StatsGroup::Day | StatsGroup::Week => "key DESC",
+            StatsGroup::SignalType | StatsGroup::Strategy => "key",
+        }
+    }
+}
+
+/// `/api/stats/breakdown` reply.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsBreakdown {
+    pub by: StatsGroup,
+    pub groups: Vec<BucketStats>,
+}
+
+/// Grouping and signal-time range for `HistoryManager::breakdown`.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// symbol, signal_type, strategy, day or week; default symbol
+    #[serde(default)]
+    #[param(inline)]
+    pub by: StatsGroup,
+    /// Signal time from, Unix ms, inclusive
+    pub from: Option<i64>,
+    /// Signal time to, Unix ms, exclusive
+    pub to: Option<i64>,
+    /// Most groups returned, default all
+    pub limit: Option<usize>,
+}
+
+/// Legacy JSON history, imported into the database on first start.
+pub fn history_path() -> String {
+    std::env::var("HISTORY_PATH").unwrap_or_else(|_| "history.json".to_string())
+}
+
+pub fn history_db_path() -> String {
+    std::env::var("HISTORY_DB_PATH").unwrap_or_else(|_| "history.db".to_string())
 }
 
+/// Move in the signal's favour (percent of entry) that makes it a success.
+pub fn take_profit_pct() -> f64 {
+    std::env::var("OUTCOME_TAKE_PROFIT_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or(1.0)
+}
+
+// Move against the entry (percent) that fails the signal, by default the live invalidation's
+fn stop_loss_pct() -> f64 {
+    std::env::var("OUTCOME_STOP_LOSS_PCT").ok().and_then(|v| v.parse().ok()).unwrap_or_else(crate::actor::invalidation_pct)
+}
+
+// Outcomes are tracked up to the close of the 60m milestone's bar, which comes after expiry
+const MINUTE_MS: i64 = 60_000;
+const TRACK_MS: i64 = EXPIRY_MS + MINUTE_MS;
+// Signals still missing a milestone (late bars, or the instance was down) are tracked this far back
+const CATCH_UP_MS: i64 = 24 * 60 * 60 * 1000;
+// Signals replayed to new clients
+const RECENT_MS: i64 = 60 * 60 * 1000;
+const DEFAULT_PAGE: usize = 100;
+const MAX_PAGE: usize = 1000;
+
+// A record's strategy; records from before strategies are the Silent Watcher's
+const STRATEGY_SQL: &str = "COALESCE(json_extract(record, '$.signal.strategyName'), 'silent_watcher')";
+// Groups in the stats broadcast; `/api/stats/breakdown` has all of them
+const STATS_SYMBOLS: usize = 20;
+const STATS_DAYS: usize = 14;
+const STATS_WEEKS: usize = 8;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS signals (
+        id TEXT PRIMARY KEY,
+        symbol TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        success INTEGER NOT NULL,
+        max_gain REAL NOT NULL,
+        taken INTEGER,
+        good INTEGER,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS signals_symbol_time ON signals (symbol, timestamp);
+    CREATE INDEX IF NOT EXISTS signals_time ON signals (timestamp);
+    CREATE TABLE IF NOT EXISTS rejected (
+        id TEXT PRIMARY KEY,
+        symbol TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        confidence REAL,
+        signal TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS rejected_time ON rejected (timestamp);
+";
+
+/// Filter and page for `HistoryManager::query`, newest first.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// Contract symbol, e.g. BTCUSDT
+    pub symbol: Option<String>,
+    /// Signal time from, Unix ms, inclusive
+    pub from: Option<i64>,
+    /// Signal time to, Unix ms, exclusive
+    pub to: Option<i64>,
+    /// Page size, default 100, at most 1000
+    pub limit: Option<usize>,
+    /// Records to skip
+    pub offset: Option<usize>,
+    /// Strategy that produced the signal, e.g. silent_watcher
+    pub strategy: Option<String>,
+}
+
+/// One page of signal history, for `GET /api/signals`.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    pub records: Vec<SignalRecord>,
+    pub total: usize, // Matching records, over all pages
+}
+
+fn open(path: &str) -> Result<Connection, HistoryError> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+// A second connection to a database `open` already set up, for queries only. WAL lets it read
+// while the writer holds its own connection.
+fn open_reader(path: &str) -> Result<Connection, HistoryError> {
+    Ok(Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?)
+}
+
+// The record is stored whole as JSON; the other columns are copies for indexes and stats
+fn put(conn: &Connection, record: &SignalRecord) -> Result<(), HistoryError> {
+    let feedback = record.feedback.as_ref();
+    conn.prepare_cached(
+        "INSERT OR REPLACE INTO signals (id, symbol, timestamp, success, max_gain, taken, good, record)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?
+    .execute(params![
+        record.signal.id,
+        record.signal.symbol,
+        record.signal.timestamp,
+        record.outcome.success,
+        record.outcome.max_gain_percent,
+        feedback.and_then(|f| f.taken),
+        feedback.and_then(|f| f.good),
+        serde_json::to_string(record)?,
+    ])?;
+    Ok(())
+}
+
+fn get(conn: &Connection, id: &str) -> Result<Option<SignalRecord>, HistoryError> {
+    let json: Option<String> = conn
+        .prepare_cached("SELECT record FROM signals WHERE id = ?1")?
+        .query_row([id], |row| row.get(0))
+        .optional()?;
+    Ok(json.map(|j| serde_json::from_str(&j)).transpose()?)
+}
+
+fn select(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<Vec<SignalRecord>, HistoryError> {
+    let mut stmt = conn.prepare_cached(sql)?;
+    let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+    let mut records = Vec::new();
+    for json in rows {
+        records.push(serde_json::from_str(&json?)?);
+    }
+    Ok(records)
+}
+
+// Per-group counts, win rate and mean max gain of signals in [from, to)
+fn group_stats(
+    conn: &Connection,
+    group: StatsGroup,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<BucketStats>, HistoryError> {
+    let sql = format!(
+        "SELECT {} AS key, COUNT(*), COALESCE(SUM(success), 0), AVG(max_gain) FROM signals
+         WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp < ?2)
+         GROUP BY key ORDER BY {} LIMIT ?3",
+        group.key_sql(),
+        group.order_sql()
+    );
+    let limit = limit.map_or(-1, |l| l as i64);
+    let mut stmt = conn.prepare_cached(&sql)?;
+    let rows = stmt.query_map(params![from, to, limit], |row| {
+        let (total, wins): (i64, i64) = (row.get(1)?, row.get(2)?);
+        Ok(BucketStats {
+            key: row.get(0)?,
+            total_signals: total as usize,
+            wins: wins as usize,
+            win_rate: if total > 0 { wins as f64 / total as f64 * 100.0 } else { 0.0 },
+            avg_gain: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0) * 100.0,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+// `Stats`' grouped sections, computed together with the totals
+#[derive(Default)]
+struct Groups {
+    by_strategy: Vec<StrategyStats>,
+    by_signal_type: Vec<BucketStats>,
+    by_symbol: Vec<BucketStats>,
+    by_day: Vec<BucketStats>,
+    by_week: Vec<BucketStats>,
+}
+
+fn recent(conn: &Connection) -> Result<Vec<SignalRecord>, HistoryError> {
+    let since = chrono::Utc::now().timestamp_millis() - RECENT_MS;
+    select(conn, "SELECT record FROM signals WHERE timestamp > ?1 ORDER BY timestamp", [since])
+}
+
+/// Every record in `db_path`, oldest first (for `--export-state`).
+pub fn read_all(db_path: &str) -> Result<Vec<SignalRecord>, HistoryError> {
+    select(&open(db_path)?, "SELECT record FROM signals ORDER BY timestamp", [])
+}
+
+/// Replaces the contents of `db_path` with `records` (for `--restore-state`).
+pub fn write_all(db_path: &str, records: &[SignalRecord]) -> Result<(), HistoryError> {
+    let mut conn = open(db_path)?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM signals", [])?;
+    for record in records {
+        put(&tx, record)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Signal history with outcome tracking, in SQLite.
+///
+/// Each change writes just the affected rows, so history size no longer costs anything per
+/// signal. Writes and queries each have a connection behind a mutex, so REST and dashboard
+/// reads never wait on a write; every access runs on the blocking pool, off the async
+/// workers. Failures are logged and counted, and the caller gets an empty result.
 pub struct HistoryManager {
-    records: Arc<Mutex<Vec<SignalRecord>>>,
-    file_path: String,
+    db: Arc<Mutex<Connection>>,
+    reader: Arc<Mutex<Connection>>, // Read-only; the writer itself when history is in memory
+    // Signals whose venue refused their klines for good (e.g. a TradingView ticker it doesn't
+    // list): not asked again, they just expire
+    untrackable: Mutex<HashSet<String>>,
+    path: String,
+    persistent: bool, // False when the database couldn't be opened and history lives in memory
 }
 
 impl HistoryManager {
-    pub fn new(file_path: &str) -> Self {
-        let records = if let Ok(data) = fs::read_to_string(file_path) {
-            serde_json::from_str(&data).unwrap_or_else(|_| Vec::new())
-        } else {
-            Vec::new()
+    /// Opens (or creates) the database. An empty one imports the legacy JSON at `legacy_path`.
+    pub fn new(db_path: &str, legacy_path: &str) -> Self {
+        let (conn, persistent) = match open(db_path) {
+            Ok(conn) => (conn, true),
+            Err(e) => {
+                // Keep scanning without persistence rather than not at all
+                METRICS.record_error("history");
+                error!(path = db_path, error = %e, "Cannot open history database, keeping history in memory only");
+                let e = TeebError::from(e);
+                crate::system_event::raise("history", e.level(), format!("{}; history is kept in memory only", e));
+                let conn = Connection::open_in_memory().and_then(|c| c.execute_batch(SCHEMA).map(|_| c)).expect("in-memory SQLite");
+                (conn, false)
+            }
+        };
+        if let Err(e) = import_legacy(&conn, legacy_path) {
+            METRICS.record_error("history");
+            error!(path = legacy_path, error = %e, "Failed to import legacy history file");
+        }
+        let db = Arc::new(Mutex::new(conn));
+        let reader = match persistent.then(|| open_reader(db_path)) {
+            Some(Ok(reader)) => Arc::new(Mutex::new(reader)),
+            Some(Err(e)) => {
+                METRICS.record_error("history");
+                warn!(path = db_path, error = %e, "Cannot open history read connection, sharing the write connection");
+                db.clone()
+            }
+            None => db.clone(),
         };
+        Self { db, reader, untrackable: Mutex::default(), path: db_path.to_string(), persistent }
+    }
+
+    /// False when the database couldn't be opened and history lives in memory.
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
 
-        Self {
-            records: Arc::new(Mutex::new(records)),
-            file_path: file_path.to_string(),
+    pub fn load(file_path: &str) -> Result<Vec<SignalRecord>, HistoryError> {
+        let data = fs::read_to_string(file_path)?;
+        let mut records: Vec<SignalRecord> = serde_json::from_str(&data)?;
+        for record in &mut records {
+            record.signal.ensure_id();
         }
+        Ok(records)
     }
 
-    pub fn add_signal(&self, signal: Signal) {
-        let mut records = self.records.lock().unwrap();
-        records.push(SignalRecord {
+    async fn run<T, F>(&self, f: F) -> Result<T, HistoryError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, HistoryError> + Send + 'static,
+    {
+        on(self.db.clone(), f).await
+    }
+
+    // `run` on the read-only connection
+    async fn read<T, F>(&self, f: F) -> Result<T, HistoryError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, HistoryError> + Send + 'static,
+    {
+        on(self.reader.clone(), f).await
+    }
+
+    // `run`, with failures logged, raised as a degraded-mode notice and turned into the empty
+    // result. The next success clears the notice (unless it's the in-memory one, which stays).
+    async fn run_or_default<T, F>(&self, what: &str, f: F) -> T
+    where
+        T: Default + Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, HistoryError> + Send + 'static,
+    {
+        let result = self.run(f).await;
+        self.or_default(what, result)
+    }
+
+    // `read`, with failures handled as in `run_or_default`
+    async fn read_or_default<T, F>(&self, what: &str, f: F) -> T
+    where
+        T: Default + Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, HistoryError> + Send + 'static,
+    {
+        let result = self.read(f).await;
+        self.or_default(what, result)
+    }
+
+    fn or_default<T: Default>(&self, what: &str, result: Result<T, HistoryError>) -> T {
+        match result {
+            Ok(value) => {
+                if self.persistent {
+                    crate::system_event::recover("history", "History database working again");
+                }
+                value
+            }
+            Err(e) => {
+                METRICS.record_error("history");
+                warn!(path = %self.path, error = %e, "History {} failed", what);
+                crate::system_event::degraded("history", &e.into());
+                T::default()
+            }
+        }
+    }
+
+    /// Records a published signal and returns its opening transitions (Created at detection,
+    /// then Verified and Active now) for broadcast.
+    pub async fn add_signal(&self, signal: Signal) -> Vec<StateTransition> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut record = SignalRecord {
             signal,
             outcome: SignalOutcome {
                 price_at_15m: None,
@@ -57,121 +558,520 @@ impl HistoryManager {
                 price_at_60m: None,
                 success: false,
                 max_gain_percent: 0.0,
+                max_drawdown_percent: 0.0,
+                hit_tp: false,
+                hit_sl: false,
+                time_to_target_ms: None,
+                take_profit_pct: Some(take_profit_pct()),
+                stop_loss_pct: Some(stop_loss_pct()),
             },
             recorded_at: chrono::Utc::now().timestamp(),
-        });
-        self.save(&records);
+            invalidation: None,
+            feedback: None,
+            transitions: Vec::new(),
+            paper_trade: None,
+        };
+        let (id, symbol, detected_at) = (record.signal.id.clone(), record.signal.symbol.clone(), record.signal.timestamp);
+        let transitions: Vec<StateTransition> = [
+            StateTransition::new(&id, &symbol, SignalState::Created, None, detected_at),
+            StateTransition::new(&id, &symbol, SignalState::Verified, None, now),
+            StateTransition::new(&id, &symbol, SignalState::Active, None, now),
+        ]
+        .into_iter()
+        .filter_map(|t| record.advance(t))
+        .collect();
+        self.run_or_default("insert", move |conn| put(conn, &record)).await;
+        transitions
     }
 
-    fn save(&self, records: &Vec<SignalRecord>) {
-        if let Ok(json) = serde_json::to_string(records) {
-            let _ = fs::write(&self.file_path, json);
-        }
+    /// Keeps a signal strict verification dropped, apart from published history (no outcome
+    /// tracking, no stats), for tuning `VERIFY_MIN_CONFIDENCE`.
+    pub async fn add_rejected(&self, signal: Signal) {
+        self.run_or_default("insert rejected", move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO rejected (id, symbol, timestamp, confidence, signal) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![signal.id, signal.symbol, signal.timestamp, signal.confidence, serde_json::to_string(&signal)?],
+            )?;
+            Ok(())
+        })
+        .await
     }
 
-    pub fn get_stats(&self) -> Stats {
-        let records = self.records.lock().unwrap();
-        let total = records.len();
-        if total == 0 {
-            return Stats { total_signals: 0, win_rate: 0.0, top_gainer: "None".to_string() };
-        }
+    /// Published signals with `from <= timestamp < to`, oldest first.
+    pub async fn between(&self, from: i64, to: i64) -> Vec<SignalRecord> {
+        self.read_or_default("read", move |conn| {
+            select(conn, "SELECT record FROM signals WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp", [from, to])
+        })
+        .await
+    }
 
-        let wins = records.iter().filter(|r| r.outcome.success).count();
-        let win_rate = (wins as f64 / total as f64) * 100.0;
+    /// Signals strict verification dropped with `from <= timestamp < to`, oldest first.
+    pub async fn rejected_between(&self, from: i64, to: i64) -> Vec<Signal> {
+        self.read_or_default("read rejected", move |conn| {
+            let mut stmt = conn.prepare_cached("SELECT signal FROM rejected WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp")?;
+            let rows = stmt.query_map([from, to], |row| row.get::<_, String>(0))?;
+            let mut signals = Vec::new();
+            for json in rows {
+                signals.push(serde_json::from_str(&json?)?);
+            }
+            Ok(signals)
+        })
+        .await
+    }
 
-        let best = records.iter()
-            .max_by(|a, b| a.outcome.max_gain_percent.partial_cmp(&b.outcome.max_gain_percent).unwrap_or(std::cmp::Ordering::Equal));
-        
-        let top_gainer = match best {
-            Some(r) => format!("{} {:.1}%", r.signal.symbol, r.outcome.max_gain_percent * 100.0),
-            None => "None".to_string(),
+    pub async fn get_stats(&self) -> Stats {
+        let counts = self
+            .read(|conn| {
+                let totals = conn.query_row(
+                    "SELECT COUNT(*), COALESCE(SUM(success), 0),
+                            COALESCE(SUM(taken = 1), 0), COALESCE(SUM(taken = 0), 0),
+                            COALESCE(SUM(good = 1), 0), COALESCE(SUM(good = 0), 0),
+                            COALESCE(SUM(taken = 1 AND success = 1), 0)
+                     FROM signals",
+                    [],
+                    |row| Ok([row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?]),
+                )?;
+                let paper = conn.query_row(
+                    "SELECT COUNT(pnl), COALESCE(SUM(pnl > 0), 0), COALESCE(SUM(pnl), 0.0)
+                     FROM (SELECT json_extract(record, '$.paperTrade.realizedPnl') AS pnl FROM signals)",
+                    [],
+                    |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, f64>(2)?)),
+                )?;
+                let best = conn
+                    .query_row("SELECT symbol, max_gain FROM signals ORDER BY max_gain DESC LIMIT 1", [], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+                    })
+                    .optional()?;
+                let by_strategy = group_stats(conn, StatsGroup::Strategy, None, None, None)?
+                    .into_iter()
+                    .map(|b| StrategyStats { strategy: b.key, total_signals: b.total_signals, win_rate: b.win_rate, avg_gain: b.avg_gain })
+                    .collect();
+                let groups = Groups {
+                    by_strategy,
+                    by_signal_type: group_stats(conn, StatsGroup::SignalType, None, None, None)?,
+                    by_symbol: group_stats(conn, StatsGroup::Symbol, None, None, Some(STATS_SYMBOLS))?,
+                    by_day: group_stats(conn, StatsGroup::Day, None, None, Some(STATS_DAYS))?,
+                    by_week: group_stats(conn, StatsGroup::Week, None, None, Some(STATS_WEEKS))?,
+                };
+                Ok((totals, paper, best, groups))
+            })
+            .await;
+        let ([total, wins, taken, skipped, good, bad, taken_wins], (paper_trades, paper_wins, realized), best, groups) =
+            counts.unwrap_or_else(|e| {
+                METRICS.record_error("history");
+                warn!(path = %self.path, error = %e, "History stats failed");
+                ([0; 7], (0, 0, 0.0), None, Groups::default())
+            });
+        let mut feedback = FeedbackStats {
+            taken: taken as usize,
+            skipped: skipped as usize,
+            good: good as usize,
+            bad: bad as usize,
+            ..Default::default()
         };
+        feedback.taken_win_rate = (taken > 0).then(|| taken_wins as f64 / taken as f64 * 100.0);
+        feedback.approval_rate = (good + bad > 0).then(|| good as f64 / (good + bad) as f64 * 100.0);
 
         Stats {
-            total_signals: total,
-            win_rate,
-            top_gainer,
+            total_signals: total as usize,
+            win_rate: if total > 0 { wins as f64 / total as f64 * 100.0 } else { 0.0 },
+            top_gainer: best.map_or("None".to_string(), |(symbol, gain)| format!("{} {:.1}%", symbol, gain * 100.0)),
+            feedback,
+            market_context: crate::sentiment::current(),
+            paper: crate::paper_trader::enabled().then(|| {
+                let open = crate::paper_trader::current().unwrap_or_default();
+                crate::paper_trader::PaperStats {
+                    closed_trades: paper_trades as usize,
+                    wins: paper_wins as usize,
+                    realized_pnl: realized,
+                    open_positions: open.open.len(),
+                    unrealized_pnl: open.unrealized_pnl,
+                }
+            }),
+            by_strategy: groups.by_strategy,
+            by_signal_type: groups.by_signal_type,
+            by_symbol: groups.by_symbol,
+            by_day: groups.by_day,
+            by_week: groups.by_week,
         }
     }
 
-    pub fn get_recent_signals(&self) -> Vec<Signal> {
-        let records = self.records.lock().unwrap();
-        let now = chrono::Utc::now().timestamp_millis();
+    /// Win rate and average gain per `query.by` group, over signals in the query's time range.
+    pub async fn breakdown(&self, query: StatsQuery) -> StatsBreakdown {
+        let groups = self
+            .read_or_default("breakdown", move |conn| group_stats(conn, query.by, query.from, query.to, query.limit))
+            .await;
+        StatsBreakdown { by: query.by, groups }
+    }
+
+    pub async fn get_recent_signals(&self) -> Vec<Signal> {
         // Return signals from last 60 mins
-        records.iter()
-            .filter(|r| now - r.signal.timestamp < 60 * 60 * 1000)
-            .map(|r| r.signal.clone())
-            .collect()
+        let records = self.read_or_default("read", |conn| recent(conn)).await;
+        records.into_iter().map(|r| r.signal).collect()
     }
 
-    pub fn update_outcomes(&self, store: SharedState) {
-        let mut records = self.records.lock().unwrap();
-        let now = chrono::Utc::now().timestamp_millis();
-        let mut updated = false;
-
-        for record in records.iter_mut() {
-            // Check milestones
-            let elapsed_mins = (now - record.signal.timestamp) / 60000;
-            
-            // We need current price from store
-            if let Some(state) = store.get(&record.signal.symbol) {
-                 if let Some(last_data) = state.window.back() {
-                     let current_price = last_data.price;
-                     let entry_price = record.signal.price;
-                     
-                     // Calculate Gain for stats
-                     let gain = match record.signal.signal_type {
-                         crate::scanner::SignalType::Long => (current_price - entry_price) / entry_price,
-                         crate::scanner::SignalType::Short => (entry_price - current_price) / entry_price,
-                     };
-                     
-                     if gain > record.outcome.max_gain_percent {
-                         record.outcome.max_gain_percent = gain;
-                         updated = true;
-                     }
-                     
-                     // Mark Success if gain > 1%
-                     if gain > 0.01 {
-                         record.outcome.success = true;
-                         updated = true;
-                     }
-
-                     if elapsed_mins >= 15 && record.outcome.price_at_15m.is_none() {
-                         record.outcome.price_at_15m = Some(current_price);
-                         updated = true;
-                     }
-                     if elapsed_mins >= 30 && record.outcome.price_at_30m.is_none() {
-                         record.outcome.price_at_30m = Some(current_price);
-                         updated = true;
-                     }
-                     if elapsed_mins >= 60 && record.outcome.price_at_60m.is_none() {
-                         record.outcome.price_at_60m = Some(current_price);
-                         updated = true;
-                     }
-                 }
+    pub async fn last_signal_for(&self, symbol: &str) -> Option<SignalRecord> {
+        let query = HistoryQuery { symbol: Some(symbol.to_string()), limit: Some(1), ..Default::default() };
+        self.query(query).await.records.into_iter().next()
+    }
+
+    pub async fn get(&self, signal_id: &str) -> Option<SignalRecord> {
+        let id = signal_id.to_string();
+        self.read_or_default("read", move |conn| get(conn, &id)).await
+    }
+
+    /// Records matching `query`, newest first, by signal time.
+    pub async fn query(&self, query: HistoryQuery) -> HistoryPage {
+        let (records, total) = self
+            .read_or_default("query", move |conn| {
+                let filter = format!(
+                    "FROM signals WHERE (?1 IS NULL OR symbol = ?1) AND (?2 IS NULL OR timestamp >= ?2) AND (?3 IS NULL OR timestamp < ?3) AND (?4 IS NULL OR {} = ?4)",
+                    STRATEGY_SQL
+                );
+                let filters = params![query.symbol, query.from, query.to, query.strategy];
+                let total: i64 = conn.query_row(&format!("SELECT COUNT(*) {}", filter), filters, |row| row.get(0))?;
+                let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE) as i64;
+                let offset = query.offset.unwrap_or(0) as i64;
+                let records = select(
+                    conn,
+                    &format!("SELECT record {} ORDER BY timestamp DESC LIMIT ?5 OFFSET ?6", filter),
+                    params![query.symbol, query.from, query.to, query.strategy, limit, offset],
+                )?;
+                Ok((records, total as usize))
+            })
+            .await;
+        HistoryPage { records, total }
+    }
+
+    // Reads, changes and writes back one record while holding the connection
+    async fn modify<T, F>(&self, signal_id: &str, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SignalRecord) -> Option<T> + Send + 'static,
+    {
+        let id = signal_id.to_string();
+        self.run_or_default("update", move |conn| {
+            let Some(mut record) = get(conn, &id)? else {
+                return Ok(None);
+            };
+            let result = f(&mut record);
+            if result.is_some() {
+                put(conn, &record)?;
+            }
+            Ok(result)
+        })
+        .await
+    }
+
+    /// Stores a closed paper position with its signal. False if there is no such signal.
+    pub async fn record_paper_trade(&self, position: &crate::paper_trader::Position) -> bool {
+        let position = position.clone();
+        self.modify(&position.signal_id.clone(), move |record| {
+            record.paper_trade = Some(position);
+            Some(())
+        })
+        .await
+        .is_some()
+    }
+
+    /// Marks the signal's record as invalidated. False if there is no such signal or it
+    /// was already invalidated, so each invalidation is broadcast once.
+    pub async fn invalidate(&self, invalidation: &Invalidation) -> bool {
+        let invalidation = invalidation.clone();
+        self.modify(&invalidation.signal_id.clone(), move |record| {
+            if record.invalidation.is_some() {
+                return None;
+            }
+            record.invalidation = Some(invalidation);
+            Some(())
+        })
+        .await
+        .is_some()
+    }
+
+    /// Moves the signal to `transition.to`. None if there is no such signal or its current
+    /// state doesn't allow the move (e.g. it already ended), so each transition is broadcast once.
+    pub async fn apply_transition(&self, transition: StateTransition) -> Option<StateTransition> {
+        self.modify(&transition.signal_id.clone(), move |record| record.advance(transition)).await
+    }
+
+    /// Broadcasts an applied transition, followed by `SignalClosed` with the record's outcome
+    /// when it ended the signal.
+    pub async fn announce(&self, tx: &broadcast::Sender<WsMessage>, transition: StateTransition) {
+        let closed = match transition.to.is_terminal() {
+            true => self.get(&transition.signal_id).await.map(|record| SignalClosed {
+                signal_id: transition.signal_id.clone(),
+                symbol: transition.symbol.clone(),
+                state: transition.to,
+                detail: transition.detail.clone(),
+                outcome: record.outcome,
+                closed_at: transition.timestamp,
+            }),
+            false => None,
+        };
+        let _ = tx.send(WsMessage::SignalState(transition));
+        if let Some(closed) = closed {
+            let _ = tx.send(WsMessage::SignalClosed(closed));
+        }
+    }
+
+    /// Applies a trader's mark to the signal's record. None if there is no such signal.
+    pub async fn record_feedback(&self, request: &FeedbackRequest) -> Option<SignalFeedback> {
+        let request = request.clone();
+        self.modify(&request.signal_id.clone(), move |record| {
+            let feedback = record.feedback.get_or_insert_with(|| SignalFeedback {
+                signal_id: request.signal_id.clone(),
+                taken: None,
+                good: None,
+                updated_at: 0,
+            });
+            match request.kind {
+                FeedbackKind::Taken => feedback.taken = Some(true),
+                FeedbackKind::Skipped => feedback.taken = Some(false),
+                FeedbackKind::Good => feedback.good = Some(true),
+                FeedbackKind::Bad => feedback.good = Some(false),
             }
+            feedback.updated_at = chrono::Utc::now().timestamp_millis();
+            Some(feedback.clone())
+        })
+        .await
+    }
+
+    // Feedback on the signals `get_recent_signals` returns, replayed to new clients
+    pub async fn get_recent_feedback(&self) -> Vec<SignalFeedback> {
+        let records = self.read_or_default("read", |conn| recent(conn)).await;
+        records.into_iter().filter_map(|r| r.feedback).collect()
+    }
+
+    // Lifecycles of the signals `get_recent_signals` returns, replayed to new clients
+    pub async fn get_recent_transitions(&self) -> Vec<StateTransition> {
+        let records = self.read_or_default("read", |conn| recent(conn)).await;
+        records.into_iter().flat_map(|r| r.transitions).collect()
+    }
+
+    // Invalidations of the signals `get_recent_signals` returns, replayed to new clients
+    pub async fn get_recent_invalidations(&self) -> Vec<Invalidation> {
+        let records = self.read_or_default("read", |conn| recent(conn)).await;
+        records.into_iter().filter_map(|r| r.invalidation).collect()
+    }
+
+    /// Folds the write-ahead log into the database file, so a stopped instance leaves a single
+    /// complete `.db` (e.g. for `--export-state` or a copy to a new host).
+    pub async fn checkpoint(&self) {
+        self.run_or_default("checkpoint", |conn| {
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Tracks price outcomes of the signals still missing their 60m milestone (back to
+    /// `CATCH_UP_MS`) from the exchange's 1m klines, and returns the TargetHit / Expired
+    /// transitions they caused. Once that milestone is in, the outcome is complete. Prices come from REST rather than the scanner's window, so
+    /// outcomes survive a restart and are the same on an aggregator as on its workers.
+    pub async fn update_outcomes(&self) -> Vec<StateTransition> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let tracked = self
+            .read_or_default("outcome update", move |conn| {
+                select(
+                    conn,
+                    "SELECT record FROM signals WHERE timestamp > ?1 AND json_extract(record, '$.outcome.priceAt60m') IS NULL",
+                    [now - CATCH_UP_MS],
+                )
+            })
+            .await;
+        // Klines first, with no connection held, then one short write pass for all of them
+        let mut fetched = Vec::with_capacity(tracked.len());
+        let mut spans = HashMap::with_capacity(tracked.len());
+        for record in tracked {
+            let signal = &record.signal;
+            let span = signal.span();
+            // From the first bar after the signal's minute (see `track_outcome`) to the 60m one
+            let signal_minute = signal.timestamp / MINUTE_MS * MINUTE_MS;
+            let (from, to) = (signal_minute + MINUTE_MS, (signal_minute + TRACK_MS).min(now));
+            let untrackable = self.untrackable.lock().unwrap_or_else(|e| e.into_inner()).contains(&signal.id);
+            // A failed lookup only delays the milestones; expiry needs no prices
+            let bars = if untrackable || to <= from {
+                Vec::new()
+            } else {
+                match crate::exchange::minute_bars(&signal.symbol, from, to).instrument(span.clone()).await {
+                    Ok(bars) => bars,
+                    Err(e) => {
+                        METRICS.record_error("history");
+                        if e.is_permanent() {
+                            span.in_scope(|| warn!(error = %e, "No klines for outcome, no longer tracking its prices"));
+                            self.untrackable.lock().unwrap_or_else(|e| e.into_inner()).insert(signal.id.clone());
+                        } else {
+                            span.in_scope(|| warn!(error = %e, "Failed to fetch klines for outcome"));
+                        }
+                        Vec::new()
+                    }
+                }
+            };
+            spans.insert(signal.id.clone(), span);
+            fetched.push((signal.id.clone(), bars));
         }
-        
-        if updated {
-            self.save(&records);
+        self.untrackable.lock().unwrap_or_else(|e| e.into_inner()).retain(|id| spans.contains_key(id));
+        let transitions: Vec<StateTransition> = self
+            .run_or_default("outcome update", move |conn| {
+                let tx = conn.transaction()?;
+                let mut transitions = Vec::new();
+                for (id, bars) in fetched {
+                    // Read again under the lock, so feedback or an invalidation since the query stays
+                    let Some(mut record) = get(&tx, &id)? else {
+                        continue;
+                    };
+                    let (updated, transition) = track_outcome(&mut record, &bars, now);
+                    if updated {
+                        put(&tx, &record)?;
+                        transitions.extend(transition);
+                    }
+                }
+                tx.commit()?;
+                Ok(transitions)
+            })
+            .await;
+        for transition in &transitions {
+            if let Some(span) = spans.get(&transition.signal_id) {
+                span.in_scope(|| info!(state = ?transition.to, "Signal state changed"));
+            }
         }
+        transitions
+    }
+}
+
+// Runs `f` with the connection locked, on the blocking pool
+async fn on<T, F>(db: Arc<Mutex<Connection>>, f: F) -> Result<T, HistoryError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T, HistoryError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&mut db.lock().unwrap_or_else(|e| e.into_inner()))).await?
+}
+
+fn import_legacy(conn: &Connection, legacy_path: &str) -> Result<(), HistoryError> {
+    let existing: i64 = conn.query_row("SELECT COUNT(*) FROM signals", [], |row| row.get(0))?;
+    if existing > 0 {
+        return Ok(());
+    }
+    let records = match HistoryManager::load(legacy_path) {
+        Ok(records) => records,
+        Err(HistoryError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    conn.execute_batch("BEGIN")?;
+    for record in &records {
+        put(conn, record)?;
     }
+    conn.execute_batch("COMMIT")?;
+    info!(path = legacy_path, records = records.len(), "Imported legacy history into the database");
+    Ok(())
 }
 
-pub async fn track_history(manager: Arc<HistoryManager>, store: SharedState, mut rx: broadcast::Receiver<crate::scanner::WsMessage>) {
-    // 1. Listen for new signals
-    let manager_clone = manager.clone();
-    tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            if let crate::scanner::WsMessage::Signal(signal) = msg {
-                manager_clone.add_signal(signal);
+// Updates one record's outcome from the 1m bars since its signal minute: (whether it changed,
+// the transition it caused)
+fn track_outcome(record: &mut SignalRecord, bars: &[PriceBar], now: i64) -> (bool, Option<StateTransition>) {
+    let mut updated = false;
+    let mut transition = None;
+    let entry_price = record.signal.price;
+    let signal_minute = record.signal.timestamp / MINUTE_MS * MINUTE_MS;
+    let take_profit = record.outcome.take_profit_pct.unwrap_or_else(take_profit_pct) / 100.0;
+    let stop_loss = record.outcome.stop_loss_pct.unwrap_or_else(stop_loss_pct) / 100.0;
+
+    // Excursions use the wicks: a Long that touched +1.2% intrabar did hit +1%. They start with
+    // the minute after the signal's: its own bar's range includes trading from before the entry.
+    for bar in bars.iter().filter(|b| b.open_time >= signal_minute + MINUTE_MS) {
+        let high = bar.high.max(bar.close);
+        let low = if bar.low > 0.0 { bar.low } else { bar.close };
+        let (gain, drawdown) = match record.signal.signal_type {
+            crate::scanner::SignalType::Long | crate::scanner::SignalType::NewListing | crate::scanner::SignalType::Breakout => ((high - entry_price) / entry_price, (entry_price - low) / entry_price),
+            crate::scanner::SignalType::Short => ((entry_price - low) / entry_price, (high - entry_price) / entry_price),
+        };
+
+        if gain > record.outcome.max_gain_percent {
+            record.outcome.max_gain_percent = gain;
+            updated = true;
+        }
+        if drawdown > record.outcome.max_drawdown_percent {
+            record.outcome.max_drawdown_percent = drawdown;
+            updated = true;
+        }
+
+        // Whichever of TP and SL is hit first within the expiry decides, as of the bar that got
+        // there. A bar that touched both counts as stopped, since the order within it is unknown.
+        let resolved = record.outcome.hit_tp || record.outcome.hit_sl;
+        if resolved || bar.open_time >= record.signal.timestamp + EXPIRY_MS {
+            continue;
+        }
+        let at = (bar.open_time + MINUTE_MS).min(now);
+        let (state, detail) = if drawdown > stop_loss {
+            record.outcome.hit_sl = true;
+            (SignalState::StoppedOut, format!("-{:.2}% stop loss", drawdown * 100.0))
+        } else if gain > take_profit {
+            record.outcome.hit_tp = true;
+            record.outcome.success = true;
+            (SignalState::TargetHit, format!("+{:.2}% best move", gain * 100.0))
+        } else {
+            continue;
+        };
+        record.outcome.time_to_target_ms = Some((at - record.signal.timestamp).max(0));
+        updated = true;
+        let hit = StateTransition::new(&record.signal.id, &record.signal.symbol, state, Some(detail), at);
+        transition = record.advance(hit);
+    }
+
+    // Milestones are the close of the candle at T+N, once it has closed
+    let close_at = |mins: i64| {
+        let minute = signal_minute + mins * MINUTE_MS;
+        bars.iter().find(|b| b.open_time == minute).map(|b| b.close)
+    };
+    for (mins, price) in [
+        (15, &mut record.outcome.price_at_15m),
+        (30, &mut record.outcome.price_at_30m),
+        (60, &mut record.outcome.price_at_60m),
+    ] {
+        if price.is_none() {
+            if let Some(close) = close_at(mins) {
+                *price = Some(close);
+                updated = true;
             }
         }
-    });
+    }
+
+    // Expiry needs no prices, so it isn't held up by a failed lookup
+    let expires_at = record.signal.timestamp + EXPIRY_MS;
+    if record.state() == Some(SignalState::Active) && now >= expires_at {
+        let detail = format!("No target within {}m", EXPIRY_MS / 60000);
+        let expired = StateTransition::new(&record.signal.id, &record.signal.symbol, SignalState::Expired, Some(detail), expires_at);
+        transition = transition.or(record.advance(expired));
+        updated = true;
+    }
+    (updated, transition)
+}
 
-    // 2. Periodic Outcome Check (every 1 min)
+// Signals are recorded directly by the emitter (see `actor::publish_signal`), not via the
+// broadcast channel, so a lagging receiver can never lose one. This task only tracks outcomes,
+// and announces the lifecycle transitions they cause (shard workers hand them to the aggregator).
+// On shutdown it records the outcomes due by then and checkpoints the database before returning.
+pub async fn track_history(manager: Arc<HistoryManager>, tx: broadcast::Sender<WsMessage>, shutdown: Shutdown) {
+    // Periodic Outcome Check (every 1 min)
     loop {
-        manager.update_outcomes(store.clone());
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+        track_outcomes(&manager, &tx).await;
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(60)) => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+    track_outcomes(&manager, &tx).await;
+    manager.checkpoint().await;
+    info!("History flushed");
+}
+
+async fn track_outcomes(manager: &HistoryManager, tx: &broadcast::Sender<WsMessage>) {
+    for transition in manager.update_outcomes().await {
+        match &SHARD.aggregator_url {
+            Some(url) => forward_transition(url, &transition).await,
+            None => manager.announce(tx, transition).await,
+        }
     }
 }
@@ -0,0 +1,188 @@
+use crate::model::MarketData;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Classic indicators over finished 1m candles, updated in O(1) as each minute is added to a
+// `SymbolState` (VWAP is `model::VwapState`, kept alongside). Unlike the window they don't
+// forget old candles: EMA and Wilder smoothing weigh them down instead of dropping them.
+
+pub const RSI_PERIOD: usize = 14;
+pub const ATR_PERIOD: usize = 14;
+pub const EMA_FAST: usize = 9;
+pub const EMA_SLOW: usize = 21;
+
+/// Exponential moving average, seeded with the simple average of the first `period` values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ema {
+    period: usize,
+    count: usize,
+    value: f64, // Running sum until `count` reaches `period`
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self { period, count: 0, value: 0.0 }
+    }
+
+    pub fn add(&mut self, x: f64) {
+        self.count += 1;
+        if self.count < self.period {
+            self.value += x;
+        } else if self.count == self.period {
+            self.value = (self.value + x) / self.period as f64;
+        } else {
+            let k = 2.0 / (self.period as f64 + 1.0);
+            self.value += k * (x - self.value);
+        }
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        (self.count >= self.period).then_some(self.value)
+    }
+}
+
+// Wilder's smoothing (RSI, ATR): a simple average to start, then avg += (x - avg) / period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Wilder {
+    period: usize,
+    count: usize,
+    value: f64,
+}
+
+impl Wilder {
+    fn new(period: usize) -> Self {
+        Self { period, count: 0, value: 0.0 }
+    }
+
+    fn add(&mut self, x: f64) {
+        self.count += 1;
+        if self.count <= self.period {
+            self.value += (x - self.value) / self.count as f64;
+        } else {
+            self.value += (x - self.value) / self.period as f64;
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.count >= self.period).then_some(self.value)
+    }
+}
+
+/// Relative strength index of closes, 0..100.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rsi {
+    prev_close: Option<f64>,
+    gain: Wilder,
+    loss: Wilder,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self { prev_close: None, gain: Wilder::new(period), loss: Wilder::new(period) }
+    }
+
+    pub fn add(&mut self, close: f64) {
+        if let Some(prev) = self.prev_close {
+            let change = close - prev;
+            self.gain.add(change.max(0.0));
+            self.loss.add((-change).max(0.0));
+        }
+        self.prev_close = Some(close);
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        let (gain, loss) = (self.gain.value()?, self.loss.value()?);
+        Some(if loss > 0.0 { 100.0 - 100.0 / (1.0 + gain / loss) } else if gain > 0.0 { 100.0 } else { 50.0 })
+    }
+}
+
+/// Average true range, in price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Atr {
+    prev_close: Option<f64>,
+    range: Wilder,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self { prev_close: None, range: Wilder::new(period) }
+    }
+
+    pub fn add(&mut self, candle: &MarketData) {
+        let true_range = match self.prev_close {
+            Some(prev) => (candle.high - candle.low).max((candle.high - prev).abs()).max((candle.low - prev).abs()),
+            None => candle.high - candle.low,
+        };
+        self.range.add(true_range);
+        self.prev_close = Some(candle.close);
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.range.value()
+    }
+}
+
+/// The indicators a `SymbolState` keeps, fed one finished 1m candle at a time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Indicators {
+    candles: usize,
+    rsi: Rsi,
+    ema_fast: Ema,
+    ema_slow: Ema,
+    atr: Atr,
+}
+
+impl Default for Indicators {
+    fn default() -> Self {
+        Self {
+            candles: 0,
+            rsi: Rsi::new(RSI_PERIOD),
+            ema_fast: Ema::new(EMA_FAST),
+            ema_slow: Ema::new(EMA_SLOW),
+            atr: Atr::new(ATR_PERIOD),
+        }
+    }
+}
+
+impl Indicators {
+    pub fn add(&mut self, candle: &MarketData) {
+        self.candles += 1;
+        self.rsi.add(candle.close);
+        self.ema_fast.add(candle.close);
+        self.ema_slow.add(candle.close);
+        self.atr.add(candle);
+    }
+
+    /// Candles seen so far (0 for snapshots taken before indicators existed).
+    pub fn candles(&self) -> usize {
+        self.candles
+    }
+
+    pub fn rsi(&self) -> Option<f64> {
+        self.rsi.value()
+    }
+
+    pub fn ema_fast(&self) -> Option<f64> {
+        self.ema_fast.value()
+    }
+
+    pub fn ema_slow(&self) -> Option<f64> {
+        self.ema_slow.value()
+    }
+
+    pub fn atr(&self) -> Option<f64> {
+        self.atr.value()
+    }
+}
+
+/// Indicator values as of the last finished 1m candle, each None until it has enough candles.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IndicatorValues {
+    pub rsi: Option<f64>,   // RSI(14), 0..100
+    pub ema9: Option<f64>,
+    pub ema21: Option<f64>,
+    pub vwap: Option<f64>,  // Over the 1m window, as `Signal::vwap`
+    pub atr: Option<f64>,   // ATR(14) in price
+}
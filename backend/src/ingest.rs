@@ -0,0 +1,81 @@
+use crate::metrics::METRICS;
+use crate::shard::fnv1a;
+use std::sync::atomic::Ordering;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+// Socket readers only read: each raw frame is handed to a parse worker, so a slow parse or a
+// burst of routing never holds up the connection. Frames are hash-partitioned by symbol over
+// `INGEST_WORKERS` workers (default: CPU count, at most 8), each with its own feed, so a
+// symbol is always parsed by the same worker and its ticks reach its actor in order. A
+// `!ticker@arr` frame carries every symbol at once and goes to a single worker. Worker queues
+// hold `INGEST_QUEUE_CAPACITY` frames (default 1024); when one is full the frame is dropped
+// (`ingestDropped` in /metrics) rather than stalling the socket.
+
+pub fn workers() -> usize {
+    std::env::var("INGEST_WORKERS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get().min(8)).unwrap_or(1))
+        .max(1)
+}
+
+fn queue_capacity() -> usize {
+    std::env::var("INGEST_QUEUE_CAPACITY").ok().and_then(|v| v.parse().ok()).unwrap_or(1024)
+}
+
+/// A feed that turns raw frames into ticks for the symbol actors.
+pub trait FrameHandler: Send + 'static {
+    fn handle(&mut self, text: &str);
+}
+
+/// Parse workers for one connection. Dropping it closes their queues; each worker finishes
+/// what it holds and exits, taking its feed (and router) with it.
+pub struct Partitioned {
+    shards: Vec<mpsc::Sender<String>>,
+    key_field: &'static str, // Frame field naming the symbol's stream, hashed to pick a worker
+}
+
+impl Partitioned {
+    /// Starts `workers` parse workers, each running its own handler from `make`.
+    pub fn spawn<H: FrameHandler>(workers: usize, key_field: &'static str, mut make: impl FnMut() -> H) -> Self {
+        let capacity = queue_capacity();
+        let shards = (0..workers.max(1))
+            .map(|_| {
+                let (tx, rx) = mpsc::channel(capacity);
+                tokio::spawn(run_worker(make(), rx));
+                tx
+            })
+            .collect();
+        Self { shards, key_field }
+    }
+
+    /// Queues `frame` on the worker owning its symbol.
+    pub fn send(&self, frame: String) {
+        let shard = match self.shards.len() {
+            1 => 0,
+            n => (fnv1a(string_field(&frame, self.key_field).as_bytes()) % n as u64) as usize,
+        };
+        if self.shards[shard].try_send(frame).is_err() {
+            METRICS.ingest_dropped.fetch_add(1, Ordering::Relaxed);
+            debug!(shard, "Ingest queue full, dropping frame");
+        }
+    }
+}
+
+async fn run_worker<H: FrameHandler>(mut handler: H, mut rx: mpsc::Receiver<String>) {
+    while let Some(frame) = rx.recv().await {
+        handler.handle(&frame);
+    }
+}
+
+/// The string value of the first `"<name>":"..."` in a frame, without parsing it. Used to
+/// pick a worker from a combined stream's `stream` or Bybit's `topic`; empty when absent
+/// (the frame still gets parsed, and its errors counted, on a fixed worker).
+fn string_field<'a>(text: &'a str, name: &str) -> &'a str {
+    let pattern = format!("\"{}\":\"", name);
+    text.find(&pattern)
+        .map(|i| &text[i + pattern.len()..])
+        .and_then(|rest| rest.find('"').map(|end| &rest[..end]))
+        .unwrap_or("")
+}
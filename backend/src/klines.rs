@@ -0,0 +1,239 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::metrics::METRICS;
+use crate::model::Interval;
+use crate::store::SharedState;
+use crate::symbol_detail::Candle;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock};
+use tracing::warn;
+use ts_rs::TS;
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1500; // Binance's own cap
+const MAX_CACHE_ENTRIES: usize = 1000;
+const INTERVALS: [&str; 15] = ["1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "12h", "1d", "3d", "1w", "1M"];
+
+fn cache_ms() -> i64 {
+    std::env::var("KLINES_CACHE_SECS").ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(30) * 1000
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KlinesError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("unexpected status {0}")]
+    Status(reqwest::StatusCode),
+    #[error("malformed kline in response")]
+    Malformed,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct KlineQuery {
+    /// Contract symbol, e.g. BTCUSDT (case-insensitive)
+    pub symbol: String,
+    /// Binance interval (1m, 5m, 15m, 1h, ...), default 1m
+    pub interval: Option<String>,
+    /// Number of candles, default 100, at most 1500
+    pub limit: Option<usize>,
+}
+
+/// `GET /api/klines/{symbol}`'s query: `KlineQuery` without the symbol.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct KlineParams {
+    /// Binance interval (1m, 5m, 15m, 1h, ...), default 1m
+    pub interval: Option<String>,
+    /// Number of candles, default 100, at most 1500
+    pub limit: Option<usize>,
+}
+
+impl KlineParams {
+    pub fn for_symbol(self, symbol: String) -> KlineQuery {
+        KlineQuery { symbol, interval: self.interval, limit: self.limit }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS, ToSchema)]
+pub enum KlineSource {
+    Store,   // The scanner's own finished candles
+    Binance, // Fetched from Binance just now
+    Cache,   // Fetched from Binance within the last `KLINES_CACHE_SECS`
+    Mixed,   // The scanner's candles, with older ones from Binance (or its cache) in front
+}
+
+/// Candles for charts, oldest first, for `GET /api/klines`.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Klines {
+    pub symbol: String,
+    pub interval: String,
+    pub source: KlineSource,
+    pub candles: Vec<Candle>,
+}
+
+struct CacheEntry {
+    fetched_at: i64,
+    candles: Arc<Vec<Candle>>,
+}
+
+static CACHE: LazyLock<DashMap<(String, String, usize), CacheEntry>> = LazyLock::new(DashMap::new);
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+fn internal_interval(interval: &str) -> Option<Interval> {
+    interval.parse().ok()
+}
+
+// The window's last `limit` candles, or all of them if it holds fewer (None if it holds none)
+fn from_store(store: &SharedState, symbol: &str, interval: &str, limit: usize) -> Option<Vec<Candle>> {
+    let state = store.get(symbol)?;
+    let candles = state.candles(internal_interval(interval)?);
+    let candles: Vec<Candle> = candles.iter().skip(candles.len().saturating_sub(limit)).map(Candle::from).collect();
+    (!candles.is_empty()).then_some(candles)
+}
+
+fn parse_kline(row: &[serde_json::Value]) -> Option<Candle> {
+    let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+    Some(Candle {
+        timestamp: row.first()?.as_i64()?,
+        open: number(1)?,
+        high: number(2)?,
+        low: number(3)?,
+        close: number(4)?,
+        volume: number(5)?,
+        quote_volume: number(7)?,
+        trades: row.get(8)?.as_u64()?,
+        taker_buy_volume: number(9),
+    })
+}
+
+async fn fetch(symbol: &str, interval: &str, limit: usize) -> Result<Vec<Candle>, KlinesError> {
+    let url = format!(
+        "{}/fapi/v1/klines?symbol={}&interval={}&limit={}",
+        FUTURES_REST_URL, symbol, interval, limit
+    );
+    let resp = CLIENT.get(&url).send().await?;
+    if !resp.status().is_success() {
+        return Err(KlinesError::Status(resp.status()));
+    }
+    let rows: Vec<Vec<serde_json::Value>> = resp.json().await?;
+    rows.iter().map(|row| parse_kline(row)).collect::<Option<Vec<_>>>().ok_or(KlinesError::Malformed)
+}
+
+// Binance's candles, through the cache; the flag says whether they came from it
+async fn fetch_cached(symbol: &str, interval: &str, limit: usize) -> Result<(Vec<Candle>, bool), KlinesError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let key = (symbol.to_string(), interval.to_string(), limit);
+    if let Some(entry) = CACHE.get(&key).filter(|e| now - e.fetched_at < cache_ms()) {
+        return Ok((entry.candles.as_ref().clone(), true));
+    }
+
+    let candles = fetch(symbol, interval, limit).await.inspect_err(|e| {
+        METRICS.record_error("klines");
+        warn!(symbol = %symbol, interval = %interval, error = %e, "Failed to fetch klines");
+    })?;
+    if CACHE.len() >= MAX_CACHE_ENTRIES {
+        CACHE.retain(|_, e| now - e.fetched_at < cache_ms());
+    }
+    CACHE.insert(key, CacheEntry { fetched_at: now, candles: Arc::new(candles.clone()) });
+    Ok((candles, false))
+}
+
+/// Serves from the scanner's windows when they cover the request, otherwise from Binance
+/// through a short-lived cache, so many open charts cost one upstream request. A window that
+/// covers only the recent part gets Binance's older candles put in front of it; if Binance
+/// can't be reached, it is served as it is.
+pub async fn klines(store: &SharedState, query: KlineQuery) -> Result<Klines, KlinesError> {
+    let symbol = query.symbol.trim().to_uppercase();
+    if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(KlinesError::Invalid("invalid symbol".to_string()));
+    }
+    let interval = query.interval.unwrap_or_else(|| "1m".to_string());
+    if !INTERVALS.contains(&interval.as_str()) {
+        return Err(KlinesError::Invalid(format!("unsupported interval {}", interval)));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let stored = from_store(store, &symbol, &interval, limit);
+    let recent = match stored {
+        Some(candles) if candles.len() >= limit => {
+            return Ok(Klines { symbol, interval, source: KlineSource::Store, candles });
+        }
+        Some(candles) => candles,
+        None => {
+            let (candles, cached) = fetch_cached(&symbol, &interval, limit).await?;
+            let source = if cached { KlineSource::Cache } else { KlineSource::Binance };
+            return Ok(Klines { symbol, interval, source, candles });
+        }
+    };
+
+    let first = recent[0].timestamp;
+    match fetch_cached(&symbol, &interval, limit).await {
+        Ok((older, _)) => {
+            let mut candles: Vec<Candle> = older.into_iter().filter(|c| c.timestamp < first).collect();
+            candles.extend(recent);
+            let candles = candles.split_off(candles.len().saturating_sub(limit));
+            Ok(Klines { symbol, interval, source: KlineSource::Mixed, candles })
+        }
+        Err(_) => Ok(Klines { symbol, interval, source: KlineSource::Store, candles: recent }),
+    }
+}
+
+/// The minute in progress of a symbol, pushed to WebSocket clients that asked for it
+/// (`{"action": "stream_candles", "symbol": ...}`).
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveCandle {
+    pub symbol: String,
+    pub candle: Candle,
+    pub closed: bool, // The minute's final candle, sent once the next one starts
+}
+
+fn stream_interval_ms() -> u64 {
+    std::env::var("CANDLE_STREAM_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(1000).max(100)
+}
+
+/// How often `CandleStream::poll` is called.
+pub fn stream_interval() -> std::time::Duration {
+    std::time::Duration::from_millis(stream_interval_ms())
+}
+
+/// One client's stream of a symbol's in-progress candle: what was sent last, so an unchanged
+/// candle isn't sent again and a finished minute goes out once with `closed` set.
+#[derive(Debug, Clone)]
+pub struct CandleStream {
+    symbol: String,
+    last: Option<Candle>,
+}
+
+impl CandleStream {
+    pub fn new(symbol: String) -> Self {
+        Self { symbol, last: None }
+    }
+
+    /// What to send now: the minute that finished since the last poll (from the window),
+    /// then the one in progress if it changed.
+    pub fn poll(&mut self, store: &SharedState) -> Vec<LiveCandle> {
+        let Some(current) = store.volume_cache.get(&self.symbol).map(|b| Candle::from(&b.finish(&self.symbol))) else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        if let Some(last) = self.last.as_ref().filter(|c| c.timestamp < current.timestamp) {
+            let finished = store
+                .get(&self.symbol)
+                .and_then(|s| s.window.iter().rev().find(|c| c.timestamp == last.timestamp).map(Candle::from));
+            if let Some(candle) = finished {
+                out.push(LiveCandle { symbol: self.symbol.clone(), candle, closed: true });
+            }
+        }
+        if self.last.as_ref() != Some(&current) {
+            out.push(LiveCandle { symbol: self.symbol.clone(), candle: current.clone(), closed: false });
+            self.last = Some(current);
+        }
+        out
+    }
+}
@@ -0,0 +1,163 @@
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+use crate::metrics::METRICS;
+
+// Lease record stored in the shared lock file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Lease {
+    holder: String,
+    expires_at: i64, // ms
+}
+
+/// Whether this instance is currently allowed to emit signals.
+///
+/// With neither `LEADER_LOCK_FILE` nor `CLUSTER_REDIS_URL` set we run standalone and are
+/// always leader. Otherwise redundant instances compete for a lease, in that file (on a volume
+/// both can see) or in Redis; the holder renews it every third of `LEADER_LEASE_SECS`. A
+/// standby keeps consuming the feed so its windows are warm, and takes over once the lease
+/// expires. Redis wins when both are set.
+#[derive(Clone)]
+pub struct Leadership {
+    is_leader: Arc<AtomicBool>,
+    pub instance_id: String,
+}
+
+impl Leadership {
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, leader: bool) {
+        let was = self.is_leader.swap(leader, Ordering::Relaxed);
+        METRICS.is_leader.store(leader, Ordering::Relaxed);
+        if was != leader {
+            if leader {
+                info!(instance = %self.instance_id, "Acquired leadership, emitting signals");
+            } else {
+                warn!(instance = %self.instance_id, "Lost leadership, standing by");
+            }
+        }
+    }
+}
+
+pub fn lock_file() -> Option<String> {
+    std::env::var("LEADER_LOCK_FILE").ok().filter(|v| !v.is_empty())
+}
+
+fn lease_duration() -> Duration {
+    let secs = std::env::var("LEADER_LEASE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(15);
+    Duration::from_secs(secs)
+}
+
+pub fn init() -> Leadership {
+    let instance_id = std::env::var("INSTANCE_ID").unwrap_or_else(|_| {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "teeb".to_string());
+        format!("{}-{}", host, std::process::id())
+    });
+    // Standalone starts as leader; clustered starts as standby until the first election.
+    let standalone = lock_file().is_none() && crate::cluster::redis_url().is_none();
+    METRICS.is_leader.store(standalone, Ordering::Relaxed);
+    Leadership { is_leader: Arc::new(AtomicBool::new(standalone)), instance_id }
+}
+
+fn read_lease(path: &str) -> Option<Lease> {
+    fs::read_to_string(path).ok().and_then(|data| serde_json::from_str(&data).ok())
+}
+
+fn write_lease(path: &str, lease: &Lease) -> std::io::Result<()> {
+    let tmp = format!("{}.{}.tmp", path, lease.holder);
+    fs::write(&tmp, serde_json::to_string(lease)?)?;
+    fs::rename(&tmp, path)
+}
+
+pub async fn election_task(leadership: Leadership) {
+    if let Some(url) = crate::cluster::redis_url() {
+        return redis_election(url, leadership).await;
+    }
+    let Some(path) = lock_file() else {
+        // Standalone: nothing to elect
+        return std::future::pending().await;
+    };
+    let lease = lease_duration();
+    let renew_every = lease / 3;
+    info!(path = %path, instance = %leadership.instance_id, lease_secs = lease.as_secs(), "Leader election enabled");
+
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        let current = read_lease(&path);
+        let can_take = match &current {
+            None => true,
+            Some(l) => l.holder == leadership.instance_id || l.expires_at < now,
+        };
+
+        if can_take {
+            let ours = Lease { holder: leadership.instance_id.clone(), expires_at: now + lease.as_millis() as i64 };
+            match write_lease(&path, &ours) {
+                Ok(()) => {
+                    // Two standbys may race for an expired lease; re-read after a beat and
+                    // let whoever's write landed last win.
+                    sleep(Duration::from_millis(200)).await;
+                    let won = read_lease(&path).is_some_and(|l| l.holder == leadership.instance_id);
+                    leadership.set(won);
+                }
+                Err(e) => {
+                    METRICS.record_error("leader");
+                    warn!(path = %path, error = ?e, "Failed to write leader lease");
+                    leadership.set(false);
+                }
+            }
+        } else {
+            leadership.set(false);
+        }
+
+        sleep(renew_every).await;
+    }
+}
+
+// Renews the lease only while we still hold it, atomically
+const RENEW_SCRIPT: &str = "if redis.call('get', KEYS[1]) == ARGV[1] then return redis.call('pexpire', KEYS[1], ARGV[2]) else return 0 end";
+
+// The same lease as a Redis key with a TTL: SET NX takes a free one, the script renews ours.
+// Losing Redis means standing down; it's the only way to know no one else took over. A round
+// that doesn't finish within `renew_every` counts as lost too, so a half-open connection has us
+// stand down while our lease still has a third of its time left.
+async fn redis_election(url: String, leadership: Leadership) {
+    let key = crate::cluster::leader_key();
+    let lease = lease_duration();
+    let lease_ms = lease.as_millis().to_string();
+    let renew_every = lease / 3;
+    info!(key = %key, instance = %leadership.instance_id, lease_secs = lease.as_secs(), "Leader election enabled (redis)");
+
+    let mut conn: Option<crate::redis::Connection> = None;
+    loop {
+        let attempt = async {
+            let conn = match &mut conn {
+                Some(conn) => conn,
+                None => conn.insert(crate::redis::Connection::connect(&url).await?),
+            };
+            let id = leadership.instance_id.as_str();
+            if conn.command(&["SET", &key, id, "NX", "PX", &lease_ms]).await? != crate::redis::Reply::Bulk(None) {
+                return Ok(true);
+            }
+            let renewed = conn.command(&["EVAL", RENEW_SCRIPT, "1", &key, id, &lease_ms]).await?;
+            Ok::<_, crate::redis::RedisError>(renewed == crate::redis::Reply::Int(1))
+        };
+        let result = tokio::time::timeout(renew_every, attempt)
+            .await
+            .unwrap_or(Err(crate::redis::RedisError::Timeout(renew_every)));
+        match result {
+            Ok(won) => leadership.set(won),
+            Err(e) => {
+                METRICS.record_error("leader");
+                warn!(error = %e, "Leader lease in redis failed");
+                conn = None;
+                leadership.set(false);
+            }
+        }
+        sleep(renew_every).await;
+    }
+}
@@ -0,0 +1,219 @@
+use crate::model::SymbolState;
+use crate::scanner::{SignalType, WsMessage};
+use crate::shard::SHARD;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+
+const TOP_N: usize = 10;
+// Symbols without a finished candle this recent are left off the board
+const MAX_AGE_MS: i64 = 5 * 60 * 1000;
+// Signals get live updates this long after firing (see `actor`); the board ranks those
+const LIVE_MS: i64 = 60 * 60 * 1000;
+
+fn interval_secs() -> u64 {
+    std::env::var("LEADERBOARD_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(10)
+}
+
+fn overview_interval_secs() -> u64 {
+    std::env::var("MARKET_OVERVIEW_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(30)
+}
+
+/// One symbol's recent activity, from its finished 1m candles.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct Mover {
+    pub symbol: String,
+    pub price: f64,
+    pub volume_ratio: f64, // Last minute's volume vs the window average
+    #[serde(default)]
+    pub change_5m_pct: Option<f64>, // None until 5 minutes of candles exist
+    pub change_15m_pct: Option<f64>, // None until 15 minutes of candles exist
+}
+
+/// A live signal's place on the board.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedSignal {
+    pub signal_id: String,
+    pub symbol: String,
+    pub signal_type: SignalType,
+    pub score: f64, // `Signal::score` total, 0..100
+    pub entry_price: f64,
+    pub price: f64, // Last close
+    pub fired_at: i64,
+}
+
+/// Market color between signals: the most active symbols and the biggest 15m moves, and
+/// the signals still live, best score first.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    pub top_volume: Vec<Mover>,
+    pub gainers: Vec<Mover>,
+    pub losers: Vec<Mover>,
+    #[serde(default)]
+    pub signals: Vec<RankedSignal>,
+    pub timestamp: i64,
+}
+
+/// The whole tracked market at a glance, every `MARKET_OVERVIEW_INTERVAL_SECS`: volume
+/// gainers, the biggest 5m moves either way, and aggregate volume, so there is something to
+/// show when no signals fire.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketOverview {
+    pub volume_gainers: Vec<Mover>, // By last-minute volume ratio
+    pub movers_5m: Vec<Mover>,      // By absolute 5m change
+    pub symbols: usize,             // With a recent finished candle
+    pub quote_volume_1m: f64,       // Traded in the last finished minute, all symbols
+    pub volume_ratio: f64,          // That vs the symbols' summed window averages
+    pub day_quote_volume: f64,      // 24h, where the feed reports it
+    pub advancing_5m: usize,
+    pub declining_5m: usize,
+    pub timestamp: i64,
+}
+
+// The latest overview, sent to clients as they connect
+static OVERVIEW: LazyLock<RwLock<Option<MarketOverview>>> = LazyLock::new(Default::default);
+
+pub fn current_overview() -> Option<MarketOverview> {
+    OVERVIEW.read().ok().and_then(|o| o.clone())
+}
+
+// Change from the open `minutes` finished candles back to the last close
+fn change_pct(state: &SymbolState, minutes: usize) -> Option<f64> {
+    let last = state.window.back()?;
+    state
+        .window
+        .iter()
+        .rev()
+        .nth(minutes - 1)
+        .filter(|start| start.open > 0.0)
+        .map(|start| (last.close - start.open) / start.open * 100.0)
+}
+
+pub fn mover(state: &SymbolState, now: i64) -> Option<Mover> {
+    let last = state.window.back()?;
+    if now - last.timestamp > MAX_AGE_MS {
+        return None;
+    }
+    let avg = state.get_average_volume();
+    let volume_ratio = if avg > 0.0 { last.volume / avg } else { 0.0 };
+    Some(Mover {
+        symbol: state.symbol.clone(),
+        price: last.close,
+        volume_ratio,
+        change_5m_pct: change_pct(state, 5),
+        change_15m_pct: change_pct(state, 15),
+    })
+}
+
+// The symbol's signal, if it is still live (fired within the hour, not invalidated) and scored
+fn ranked(state: &SymbolState, now: i64) -> Option<RankedSignal> {
+    let active = state.active_signal.as_ref().filter(|a| !a.invalidated)?;
+    let fired_at = state.last_signal_time.filter(|at| now - at < LIVE_MS)?;
+    Some(RankedSignal {
+        signal_id: active.id.clone(),
+        symbol: state.symbol.clone(),
+        signal_type: active.signal_type.clone(),
+        score: active.score?,
+        entry_price: active.entry_price,
+        price: state.window.back().map(|c| c.close).unwrap_or(active.entry_price),
+        fired_at,
+    })
+}
+
+pub fn compute(store: &SharedState) -> Leaderboard {
+    let now = chrono::Utc::now().timestamp_millis();
+    let movers: Vec<Mover> = store.iter().filter_map(|e| mover(e.value(), now)).collect();
+    let mut signals: Vec<RankedSignal> = store.iter().filter_map(|e| ranked(e.value(), now)).collect();
+    signals.sort_by(|a, b| b.score.total_cmp(&a.score));
+    signals.truncate(TOP_N);
+
+    let mut top_volume = movers.clone();
+    top_volume.sort_by(|a, b| b.volume_ratio.total_cmp(&a.volume_ratio));
+    top_volume.truncate(TOP_N);
+
+    let mut changed: Vec<Mover> = movers.into_iter().filter(|m| m.change_15m_pct.is_some()).collect();
+    changed.sort_by(|a, b| b.change_15m_pct.unwrap_or(0.0).total_cmp(&a.change_15m_pct.unwrap_or(0.0)));
+    let gainers = changed.iter().filter(|m| m.change_15m_pct > Some(0.0)).take(TOP_N).cloned().collect();
+    let losers = changed.iter().rev().filter(|m| m.change_15m_pct < Some(0.0)).take(TOP_N).cloned().collect();
+
+    Leaderboard { top_volume, gainers, losers, signals, timestamp: now }
+}
+
+/// One pass over the store for the market overview.
+pub fn compute_overview(store: &SharedState) -> MarketOverview {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut movers = Vec::new();
+    let (mut quote_volume_1m, mut average_quote_volume, mut day_quote_volume) = (0.0, 0.0, 0.0);
+    for entry in store.iter() {
+        let state = entry.value();
+        let Some(mover) = mover(state, now) else {
+            continue;
+        };
+        if let Some(last) = state.window.back() {
+            quote_volume_1m += last.quote_volume;
+            average_quote_volume += state.window.iter().map(|d| d.quote_volume).sum::<f64>() / state.window.len() as f64;
+        }
+        day_quote_volume += state.day.quote_volume;
+        movers.push(mover);
+    }
+
+    let change = |m: &Mover| m.change_5m_pct.unwrap_or(0.0);
+    let advancing_5m = movers.iter().filter(|m| change(m) > 0.0).count();
+    let declining_5m = movers.iter().filter(|m| change(m) < 0.0).count();
+    let symbols = movers.len();
+
+    let mut volume_gainers = movers.clone();
+    volume_gainers.sort_by(|a, b| b.volume_ratio.total_cmp(&a.volume_ratio));
+    volume_gainers.truncate(TOP_N);
+
+    let mut movers_5m: Vec<Mover> = movers.into_iter().filter(|m| m.change_5m_pct.is_some()).collect();
+    movers_5m.sort_by(|a, b| change(b).abs().total_cmp(&change(a).abs()));
+    movers_5m.truncate(TOP_N);
+
+    MarketOverview {
+        volume_gainers,
+        movers_5m,
+        symbols,
+        quote_volume_1m,
+        volume_ratio: if average_quote_volume > 0.0 { quote_volume_1m / average_quote_volume } else { 0.0 },
+        day_quote_volume,
+        advancing_5m,
+        declining_5m,
+        timestamp: now,
+    }
+}
+
+/// Broadcasts the leaderboard every `LEADERBOARD_INTERVAL_SECS` and the market overview
+/// every `MARKET_OVERVIEW_INTERVAL_SECS`.
+///
+/// Shard workers skip both: they have no clients, and their board would only cover their
+/// own symbols. The aggregator has no symbols of its own, so it has nothing to send either.
+pub async fn leaderboard_task(store: SharedState, tx: broadcast::Sender<WsMessage>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs().max(1)));
+    let mut overview = tokio::time::interval(tokio::time::Duration::from_secs(overview_interval_secs().max(1)));
+    loop {
+        let leaderboard = tokio::select! {
+            _ = interval.tick() => true,
+            _ = overview.tick() => false,
+        };
+        if SHARD.aggregator_url.is_some() || store.is_empty() {
+            continue;
+        }
+        if leaderboard {
+            if tx.receiver_count() > 0 {
+                let _ = tx.send(WsMessage::Leaderboard(compute(&store)));
+            }
+            continue;
+        }
+        // Kept up to date without clients too, for the next one to connect
+        let computed = compute_overview(&store);
+        *OVERVIEW.write().unwrap_or_else(|e| e.into_inner()) = Some(computed.clone());
+        let _ = tx.send(WsMessage::MarketOverview(computed));
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// A signal still Active this long after it fired expires, same horizon as the 60m outcome
+pub const EXPIRY_MS: i64 = 60 * 60 * 1000;
+
+/// Where a signal is in its life:
+/// Created → Verified → Active → one of TargetHit / StoppedOut / Expired / Invalidated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum SignalState {
+    Created,     // Detected by the scanner
+    Verified,    // Order book backed it
+    Active,      // Published to clients
+    TargetHit,   // Best move past `OUTCOME_TAKE_PROFIT_PCT` before the stop
+    StoppedOut,  // Moved `SIGNAL_INVALIDATION_PCT` live, or `OUTCOME_STOP_LOSS_PCT`, against the entry
+    Expired,     // Neither within `EXPIRY_MS`
+    Invalidated, // Order book turned against it on re-verification
+}
+
+impl SignalState {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, SignalState::TargetHit | SignalState::StoppedOut | SignalState::Expired | SignalState::Invalidated)
+    }
+
+    /// Whether a signal in `from` (None: not yet created) may move to `to`.
+    pub fn allows(from: Option<SignalState>, to: SignalState) -> bool {
+        match from {
+            None => to == SignalState::Created,
+            Some(SignalState::Created) => to == SignalState::Verified,
+            Some(SignalState::Verified) => to == SignalState::Active,
+            Some(SignalState::Active) => to.is_terminal(),
+            Some(_) => false,
+        }
+    }
+}
+
+/// One step in a signal's life, persisted with its history record and broadcast as
+/// `WsMessage::SignalState`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTransition {
+    pub signal_id: String,
+    pub symbol: String,
+    pub from: Option<SignalState>, // Filled in from the record when applied
+    pub to: SignalState,
+    pub detail: Option<String>,
+    pub timestamp: i64,
+}
+
+impl StateTransition {
+    pub fn new(signal_id: &str, symbol: &str, to: SignalState, detail: Option<String>, timestamp: i64) -> Self {
+        Self { signal_id: signal_id.to_string(), symbol: symbol.to_string(), from: None, to, detail, timestamp }
+    }
+}
+
+/// A signal's life ended, and how it did by then. Broadcast as `WsMessage::SignalClosed`
+/// right after the terminal `SignalState`, so clients can tell live alerts from dead ones
+/// without tracking states themselves.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalClosed {
+    pub signal_id: String,
+    pub symbol: String,
+    pub state: SignalState, // TargetHit, StoppedOut, Expired or Invalidated
+    pub detail: Option<String>,
+    pub outcome: crate::history::SignalOutcome,
+    pub closed_at: i64,
+}
@@ -0,0 +1,175 @@
+use crate::binance_client::FUTURES_STREAM_URL;
+use crate::exchange::Exchange;
+use crate::metrics::METRICS;
+use crate::store::SharedState;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, info};
+use url::Url;
+
+// Forced liquidations on Binance futures, from the all-market `!forceOrder@arr` stream, summed
+// per symbol and minute into `SymbolState::liquidations`. Binance pushes at most the largest
+// liquidation per symbol per second, so the sums are a lower bound of what was liquidated.
+
+// Minutes of liquidations kept per symbol, like the candle window
+const MAX_MINUTES: i64 = 60;
+const MINUTE_MS: i64 = 60_000;
+
+/// Streamed when `LIQUIDATIONS_ENABLED=true` or the `liquidation_cascade` strategy is enabled.
+pub fn enabled(store: &SharedState) -> bool {
+    std::env::var("LIQUIDATIONS_ENABLED").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        || store.strategies.names().contains(&crate::strategy::LIQUIDATION_CASCADE)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LiquidationError {
+    #[error("invalid stream url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("websocket error: {0}")]
+    Ws(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("stream closed by server")]
+    Closed,
+}
+
+/// Notional liquidated in one minute, by the side of the positions that were closed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LiquidationMinute {
+    pub minute: i64,
+    pub long_value: f64,  // Longs force-sold
+    pub short_value: f64, // Shorts force-bought
+}
+
+impl LiquidationMinute {
+    pub fn total(&self) -> f64 {
+        self.long_value + self.short_value
+    }
+}
+
+/// A symbol's last hour of liquidations, oldest first. Only minutes that had any are kept.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Liquidations {
+    minutes: VecDeque<LiquidationMinute>,
+}
+
+impl Liquidations {
+    pub fn record(&mut self, time: i64, long: bool, value: f64) {
+        let minute = time.div_euclid(MINUTE_MS) * MINUTE_MS;
+        match self.minutes.back() {
+            Some(last) if last.minute > minute => return, // Late event for a minute already moved past
+            Some(last) if last.minute == minute => {}
+            _ => self.minutes.push_back(LiquidationMinute { minute, ..Default::default() }),
+        }
+        while self.minutes.front().is_some_and(|m| m.minute <= minute - MAX_MINUTES * MINUTE_MS) {
+            self.minutes.pop_front();
+        }
+        if let Some(last) = self.minutes.back_mut() {
+            if long {
+                last.long_value += value;
+            } else {
+                last.short_value += value;
+            }
+        }
+    }
+
+    /// Summed from `from` (ms, inclusive) on, with `minute` set to `from`.
+    pub fn since(&self, from: i64) -> LiquidationMinute {
+        self.minutes.iter().filter(|m| m.minute + MINUTE_MS > from).fold(
+            LiquidationMinute { minute: from, ..Default::default() },
+            |mut sum, m| {
+                sum.long_value += m.long_value;
+                sum.short_value += m.short_value;
+                sum
+            },
+        )
+    }
+
+    /// Average liquidated per minute over the hour before `minute`, quiet minutes included.
+    pub fn average_before(&self, minute: i64) -> f64 {
+        let total: f64 = self
+            .minutes
+            .iter()
+            .filter(|m| m.minute < minute && m.minute >= minute - MAX_MINUTES * MINUTE_MS)
+            .map(LiquidationMinute::total)
+            .sum();
+        total / MAX_MINUTES as f64
+    }
+
+    /// How many standard deviations `total` is above the per-minute totals of the hour before
+    /// `minute`, quiet minutes included. None when that hour was flat (usually: quiet).
+    pub fn zscore_before(&self, minute: i64, total: f64) -> Option<f64> {
+        let (sum, sum_sq) = self
+            .minutes
+            .iter()
+            .filter(|m| m.minute < minute && m.minute >= minute - MAX_MINUTES * MINUTE_MS)
+            .map(LiquidationMinute::total)
+            .fold((0.0, 0.0), |(sum, sum_sq), v| (sum + v, sum_sq + v * v));
+        let mean = sum / MAX_MINUTES as f64;
+        let sd = (sum_sq / MAX_MINUTES as f64 - mean * mean).max(0.0).sqrt();
+        (sd > 0.0).then(|| (total - mean) / sd)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Combined {
+    data: ForceOrderEvent,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForceOrderEvent {
+    #[serde(rename = "o")]
+    order: ForceOrder,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForceOrder {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "S")]
+    side: String, // SELL closes a long, BUY a short
+    #[serde(rename = "ap")]
+    average_price: String,
+    #[serde(rename = "z")]
+    filled_quantity: String,
+    #[serde(rename = "T")]
+    trade_time: i64,
+}
+
+fn record(store: &SharedState, order: &ForceOrder) {
+    let (Ok(price), Ok(quantity)) = (order.average_price.parse::<f64>(), order.filled_quantity.parse::<f64>()) else {
+        METRICS.record_error("liquidations");
+        return;
+    };
+    // Only symbols this instance scans; the rest of the market is someone else's shard
+    if let Some(mut state) = store.get_mut(&order.symbol) {
+        state.liquidations.record(order.trade_time, order.side == "SELL", price * quantity);
+    }
+}
+
+/// Feeds Binance liquidations into the symbols' state for the scanner. Parked unless
+/// `enabled` and Binance is one of `EXCHANGES`; the supervisor reconnects on failure.
+pub async fn liquidation_task(store: SharedState) -> Result<(), LiquidationError> {
+    if !enabled(&store) || !crate::exchange::enabled().contains(&Exchange::Binance) {
+        std::future::pending::<()>().await;
+    }
+    let url = Url::parse(&format!("{}?streams=!forceOrder@arr", FUTURES_STREAM_URL))?;
+    let (ws_stream, _) = connect_async(url).await?;
+    let (_, mut read) = ws_stream.split();
+    info!("Connected to the liquidation stream");
+
+    loop {
+        match read.next().await {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str::<Combined>(&text) {
+                Ok(combined) => record(&store, &combined.data.order),
+                Err(e) => {
+                    METRICS.record_error("liquidations");
+                    debug!(error = %e, "Failed to parse forceOrder message");
+                }
+            },
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err(LiquidationError::Closed),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::exchange::Exchange;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use tracing::info;
+
+// Freshly listed futures contracts often move hardest in their first hours, long before the
+// scanner has the history its averages need. The first exchangeInfo load fixes the known
+// universe: every contract listed more than `NEW_LISTING_MAX_AGE_HOURS` ago (default 24, 0
+// turns detection off). A Binance futures symbol outside it that then trades gets a single
+// `NewListing` signal, past the warm-up and volume filters. Its listing time is exchangeInfo's
+// `onboardDate`, or its first tick when it went live between refreshes.
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+fn max_age_ms() -> i64 {
+    std::env::var("NEW_LISTING_MAX_AGE_HOURS").ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(24).max(0) * HOUR_MS
+}
+
+struct Universe {
+    known: HashSet<String>,       // Contracts that count as listed already (or were announced)
+    onboarded: HashMap<String, i64>, // Listing time per contract, from exchangeInfo
+}
+
+// None until exchangeInfo first loads: until then nothing counts as new
+static UNIVERSE: LazyLock<Mutex<Option<Universe>>> = LazyLock::new(Default::default);
+
+/// Records exchangeInfo's contracts and their listing times. The first call seeds the known
+/// universe with everything older than the cutoff.
+pub fn observe(contracts: impl IntoIterator<Item = (String, Option<i64>)>, now: i64) {
+    let cutoff = now - max_age_ms();
+    let mut universe = UNIVERSE.lock().unwrap_or_else(|e| e.into_inner());
+    let seeding = universe.is_none();
+    let universe = universe.get_or_insert_with(|| Universe { known: HashSet::new(), onboarded: HashMap::new() });
+    for (symbol, onboarded) in contracts {
+        if seeding && onboarded.is_none_or(|at| at < cutoff) {
+            universe.known.insert(symbol.clone());
+        }
+        if let Some(at) = onboarded {
+            universe.onboarded.insert(symbol, at);
+        }
+    }
+}
+
+/// The listing time of a Binance futures symbol trading for the first time since it listed,
+/// once per symbol. None for everything else, and before exchangeInfo has loaded.
+pub fn claim(symbol: &str, now: i64) -> Option<i64> {
+    let max_age = max_age_ms();
+    if max_age == 0 || crate::exchange::split(symbol).0 != Exchange::Binance {
+        return None;
+    }
+    let mut universe = UNIVERSE.lock().unwrap_or_else(|e| e.into_inner());
+    let universe = universe.as_mut()?;
+    if !universe.known.insert(symbol.to_string()) {
+        return None;
+    }
+    let listed_at = universe.onboarded.get(symbol).copied().unwrap_or(now);
+    // Listed long ago but missing from the first load (e.g. relisted): nothing new about it
+    if now - listed_at > max_age {
+        return None;
+    }
+    info!(symbol, listed_at, "New listing");
+    Some(listed_at)
+}
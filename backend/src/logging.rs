@@ -0,0 +1,182 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+// Default filter when RUST_LOG is not set.
+// Our own crate at info, noisy dependencies (hyper, tungstenite, reqwest) at warn.
+const DEFAULT_FILTER: &str = "warn,teeb_trade_backend=info";
+
+/// Keeps the background file writer alive; dropping it flushes pending log lines.
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+}
+
+/// Initializes the global tracing subscriber.
+///
+/// Per-module levels come from `RUST_LOG` (e.g. `teeb_trade_backend::scanner=debug,warp=info`).
+/// Output is JSON by default so logs can be queried by field (symbol, latency_ms, ...).
+/// Set `LOG_FORMAT=pretty` for human readable output during local development.
+///
+/// With `LOG_FILE_DIR` set, logs are also written (always as JSON) to rotating files in that
+/// directory, filtered independently by `LOG_FILE_FILTER` (default `info`).
+pub fn init() -> LogGuard {
+    let console_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string());
+
+    let console = if format.eq_ignore_ascii_case("pretty") {
+        fmt::layer().with_target(true).with_filter(console_filter).boxed()
+    } else {
+        fmt::layer()
+            .json()
+            .with_current_span(true)
+            .flatten_event(true)
+            .with_target(true)
+            .with_filter(console_filter)
+            .boxed()
+    };
+
+    let (file, guard) = match file_config() {
+        Some(config) => match RotatingFile::open(config) {
+            Ok(writer) => {
+                let (non_blocking, guard) = tracing_appender::non_blocking(writer);
+                let filter = EnvFilter::new(std::env::var("LOG_FILE_FILTER").unwrap_or_else(|_| "info".to_string()));
+                let layer = fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .flatten_event(true)
+                    .with_ansi(false)
+                    .with_writer(non_blocking)
+                    .with_filter(filter)
+                    .boxed();
+                (Some(layer), Some(guard))
+            }
+            Err(e) => {
+                eprintln!("Failed to open log file, logging to console only: {}", e);
+                (None, None)
+            }
+        },
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry().with(console).with(file).init();
+    LogGuard { _file: guard }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileConfig {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub rotation: Rotation,
+    pub max_bytes: u64, // 0 = no size limit
+    pub max_files: usize, // Rotated files to keep
+}
+
+fn file_config() -> Option<FileConfig> {
+    let dir = std::env::var("LOG_FILE_DIR").ok().filter(|d| !d.is_empty())?;
+    let env_u64 = |key: &str, default: u64| std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+    Some(FileConfig {
+        dir: PathBuf::from(dir),
+        prefix: std::env::var("LOG_FILE_PREFIX").unwrap_or_else(|_| "teeb_backend".to_string()),
+        rotation: match std::env::var("LOG_FILE_ROTATION").as_deref() {
+            Ok("hourly") => Rotation::Hourly,
+            Ok("never") => Rotation::Never,
+            _ => Rotation::Daily,
+        },
+        max_bytes: env_u64("LOG_FILE_MAX_BYTES", 100 * 1024 * 1024),
+        max_files: env_u64("LOG_FILE_MAX_FILES", 7) as usize,
+    })
+}
+
+/// Appends to `<dir>/<prefix>.log`, rotating when the period (hour/day) changes or the file
+/// grows past `max_bytes`. Rotated files are renamed with a timestamp and only the newest
+/// `max_files` are kept. Runs on the non-blocking writer thread, never on the runtime.
+pub struct RotatingFile {
+    config: FileConfig,
+    file: File,
+    written: u64,
+    period: String,
+}
+
+impl RotatingFile {
+    pub fn open(config: FileConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let path = config.dir.join(format!("{}.log", config.prefix));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        let period = period_key(config.rotation);
+        Ok(Self { config, file, written, period })
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.config.dir.join(format!("{}.log", self.config.prefix))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        let rotated = self.config.dir.join(format!("{}.{}.log", self.config.prefix, stamp));
+        fs::rename(self.active_path(), rotated)?;
+        self.file = OpenOptions::new().create(true).append(true).open(self.active_path())?;
+        self.written = 0;
+        self.prune();
+        Ok(())
+    }
+
+    // Retention: timestamps sort lexicographically, so the oldest rotated files come first
+    fn prune(&self) {
+        let rotated_prefix = format!("{}.", self.config.prefix);
+        let active = format!("{}.log", self.config.prefix);
+        let Ok(entries) = fs::read_dir(&self.config.dir) else {
+            return;
+        };
+        let mut rotated: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                name.starts_with(&rotated_prefix) && name.ends_with(".log") && name != active
+            })
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.config.max_files);
+        for path in rotated.into_iter().take(excess) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let period = period_key(self.config.rotation);
+        let too_big = self.config.max_bytes > 0 && self.written + buf.len() as u64 > self.config.max_bytes;
+        if (period != self.period || too_big) && self.written > 0 {
+            self.rotate()?;
+        }
+        self.period = period;
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn period_key(rotation: Rotation) -> String {
+    let now = chrono::Utc::now();
+    match rotation {
+        Rotation::Hourly => now.format("%Y%m%d%H").to_string(),
+        Rotation::Daily => now.format("%Y%m%d").to_string(),
+        Rotation::Never => String::new(),
+    }
+}
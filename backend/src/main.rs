@@ -1,58 +1,552 @@
+// The warp route tree in ws_server nests deeper than the default limit of 128
+#![recursion_limit = "256"]
+
 mod model;
+mod config;
+mod stats;
+mod indicators;
 mod store;
 mod scanner;
+mod strategy;
+mod suppressed;
+mod wire;
+mod exchange;
+mod rest;
 mod binance_client;
+mod bybit;
+mod binance_spot;
 mod ws_server;
+mod subscription;
+mod client_queue;
+mod encoding;
+mod auth;
+mod control;
+mod shutdown;
+mod openapi;
 mod verifier;
+mod score;
 mod history;
+mod logging;
+mod snapshot;
+mod metrics;
+mod actor;
+mod update_batcher;
+mod supervisor;
+mod error;
+mod system_event;
+mod ticker;
+mod ingest;
+mod self_check;
+mod leader;
+mod redis;
+mod cluster;
+mod systemd;
+mod health;
+mod warmup;
+mod secrets;
+mod exchange_info;
+mod universe;
+mod listing;
+mod backfill;
+mod migrate;
+mod shard;
+mod leaderboard;
+mod heatmap;
+mod symbol_detail;
+mod market_alert;
+mod correlation;
+mod divergence;
+mod replay;
+mod cli;
+mod recorder;
+mod watchlist;
+mod price_alert;
+mod carry;
+mod funding;
+mod positioning;
+mod oi_tracker;
+mod bias;
+mod sentiment;
+mod footprint;
+mod order_flow;
+mod depth_tracker;
+mod liquidation_client;
+mod klines;
+mod lifecycle;
+mod notifier;
+mod discord;
+mod webhook;
+mod backtest;
+mod paper_trader;
+mod executor;
+mod tradingview;
+mod portfolio;
+mod smtp;
+mod report;
+mod tuner;
 
 use tokio::sync::broadcast;
-use log::info;
+use tracing::info;
 use dotenv::dotenv;
+use supervisor::{RestartPolicy, Supervisor};
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::init();
+
+    let args: Vec<String> = std::env::args().collect();
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{} (`help` lists the commands)", e);
+            std::process::exit(2);
+        }
+    };
+    let mut replay_arg = None;
+    let code = match command {
+        cli::Command::Run { replay } => {
+            replay_arg = replay;
+            None
+        }
+        cli::Command::Help => {
+            println!("{}", cli::USAGE);
+            Some(0)
+        }
+        // Validate the deployment and exit, without starting the scanner
+        cli::Command::ValidateConfig => {
+            let report = self_check::run().await;
+            report.print();
+            Some(if report.passed() { 0 } else { 1 })
+        }
+        // Encrypt a name->value map into SECRETS_STORE_PATH with SECRETS_KEY
+        cli::Command::SealSecrets { input } => Some(match seal_secrets(input.as_ref()) {
+            Ok(path) => {
+                println!("Sealed secrets written to {}", path);
+                0
+            }
+            Err(e) => {
+                eprintln!("Failed to seal secrets: {}", e);
+                1
+            }
+        }),
+        // Move an instance to a new host
+        cli::Command::ExportState { path } => Some(export_state(path.as_ref())),
+        cli::Command::RestoreState { path } => Some(restore_state(path.as_ref())),
+        // Regenerate the frontend's TypeScript types from the outbound structs
+        cli::Command::ExportTypes { dir } => {
+            let dir = dir.as_deref().unwrap_or(wire::DEFAULT_TYPES_DIR);
+            Some(match wire::export_types(dir) {
+                Ok(()) => {
+                    println!("TypeScript types written to {}", dir);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("Failed to export types: {}", e);
+                    1
+                }
+            })
+        }
+        // Replay history through the scanner
+        cli::Command::Backtest { targets, from, to, days, report } => {
+            Some(run_backtest(targets, from, to, days, report.as_deref()).await)
+        }
+        cli::Command::Stats { json } => Some(cli::stats(json).await),
+        cli::Command::Export { format, out, query } => Some(cli::export(format, out.as_deref(), query).await),
+    };
+    if let Some(code) = code {
+        std::process::exit(code);
+    }
+
+    // `run --replay <recording>` (or REPLAY_FILE): a recorded session stands in for the exchange feeds
+    let replay_file = replay::replay_file(replay_arg);
+    if let Some(path) = replay_file.as_deref().filter(|p| !std::path::Path::new(p).exists()) {
+        eprintln!("Replay file {} not found", path);
+        std::process::exit(1);
+    }
+
+    let _log_guard = logging::init();
 
     info!("Starting Teeb Trade Backend (Rust)...");
 
+    // Every long-running task is spawned through the supervisor so a panic gets restarted with backoff
+    let supervisor = Supervisor::new();
+
+    // Secrets (files / docker secrets / env / encrypted store), re-read periodically for rotation
+    supervisor.spawn("secrets_refresh", RestartPolicy::Always, secrets::refresh_task);
+
+    shard::log_config();
+
     // Initialize Shared State
-    let store = store::init_store();
+    let store = store::init_store(strategy::Registry::init());
+
+    // Restore windows/cooldowns from the last run so the scanner isn't blind while windows refill.
+    // A replay starts cold and never saves: its state must not become the live scanner's.
+    let snapshot_path = snapshot::snapshot_path();
+    if replay_file.is_none() {
+        snapshot::restore(&snapshot_path, &store);
+    }
+
+    // Contract metadata (precisions, lot sizes) and the trading universe, purging delistings
+    let (universe_store, replaying) = (store.clone(), replay_file.is_some());
+    supervisor.spawn("universe", RestartPolicy::Always, move || universe::universe_task(universe_store.clone(), replaying));
+
+    // Spawn Store Eviction (idle symbols + memory budget)
+    let eviction_store = store.clone();
+    supervisor.spawn("store_eviction", RestartPolicy::Always, move || store::eviction_task(eviction_store.clone(), replaying));
+
+    // Spawn periodic Store Snapshots
+    if replay_file.is_none() {
+        let snapshot_store = store.clone();
+        supervisor.spawn("store_snapshot", RestartPolicy::Always, move || snapshot::snapshot_task(snapshot_store.clone()));
+    }
 
     use scanner::WsMessage;
     // Initialize Signal Channel
-    let (tx, _rx) = broadcast::channel::<WsMessage>(100);
+    // Capacity is per receiver backlog; slow receivers beyond it get `Lagged` and lose messages
+    let broadcast_capacity = std::env::var("BROADCAST_CAPACITY").ok().and_then(|v| v.parse().ok()).unwrap_or(1024);
+    let (tx, _rx) = broadcast::channel::<WsMessage>(broadcast_capacity);
+    // Degraded-mode notices raised so far (and from now on) reach the dashboard
+    system_event::init(tx.clone());
 
     // Initialize History Manager
-    let history_manager = std::sync::Arc::new(history::HistoryManager::new("history.json"));
+    let history_manager = std::sync::Arc::new(history::HistoryManager::new(&history::history_db_path(), &history::history_path()));
     
-    // Spawn History Tracker
-    let history_store = store.clone();
-    let history_tx = tx.clone();
+    // Spawn History Outcome Tracker
     let history_manager_clone = history_manager.clone();
-    tokio::spawn(async move {
-        // subscribe to rx for history
-        let rx = history_tx.subscribe();
-        // We need to implement the async function properly in history.rs or call methods.
-        // Wait, `track_history` takes `rx`.
-        history::track_history(history_manager_clone, history_store, rx).await;
+    let history_tx = tx.clone();
+    let history_shutdown = supervisor.shutdown_signal();
+    supervisor.spawn_graceful("history_tracker", RestartPolicy::Always, move || {
+        history::track_history(history_manager_clone.clone(), history_tx.clone(), history_shutdown.clone())
+    });
+
+    // Spawn Update Batcher (one WsMessage::Updates per cadence)
+    let pending_updates = update_batcher::init_pending_updates();
+    let batcher_pending = pending_updates.clone();
+    let batcher_tx = tx.clone();
+    supervisor.spawn("update_batcher", RestartPolicy::Always, move || {
+        update_batcher::update_batcher_task(batcher_pending.clone(), batcher_tx.clone())
+    });
+
+    // Periodic top-movers broadcast
+    let leaderboard_store = store.clone();
+    let leaderboard_tx = tx.clone();
+    supervisor.spawn("leaderboard", RestartPolicy::Always, move || {
+        leaderboard::leaderboard_task(leaderboard_store.clone(), leaderboard_tx.clone())
+    });
+
+    // Heatmap for GET /api/heatmap, precomputed so requests never walk the store
+    let heatmap_store = store.clone();
+    supervisor.spawn("heatmap", RestartPolicy::Always, move || heatmap::heatmap_task(heatmap_store.clone()));
+
+    // Market-wide anomaly detection (raises per-symbol thresholds during cascades)
+    let alert_store = store.clone();
+    let alert_tx = tx.clone();
+    supervisor.spawn("market_alert", RestartPolicy::Always, move || {
+        market_alert::market_alert_task(alert_store.clone(), alert_tx.clone())
+    });
+
+    // User watch rules: price/volume checked per tick by the actors, OI polled here
+    let watchlist = std::sync::Arc::new(watchlist::Watchlist::new(&watchlist::watchlist_path()));
+    let persist_watchlist = watchlist.clone();
+    supervisor.spawn("watchlist_persist", RestartPolicy::Always, move || {
+        watchlist::persist_task(persist_watchlist.clone())
+    });
+    let oi_watchlist = watchlist.clone();
+    let oi_tx = tx.clone();
+    supervisor.spawn("watchlist_oi", RestartPolicy::Always, move || {
+        watchlist::oi_task(oi_watchlist.clone(), oi_tx.clone())
+    });
+
+    supervisor.spawn("funding", RestartPolicy::Always, funding::funding_task);
+
+    let oi_store = store.clone();
+    supervisor.spawn("oi_tracker", RestartPolicy::Always, move || oi_tracker::oi_task(oi_store.clone()));
+
+    let carry_store = store.clone();
+    let carry_tx = tx.clone();
+    supervisor.spawn("carry_scanner", RestartPolicy::Always, move || {
+        carry::carry_task(carry_store.clone(), carry_tx.clone())
+    });
+
+    let bias_store = store.clone();
+    let bias_tx = tx.clone();
+    supervisor.spawn("market_bias", RestartPolicy::Always, move || {
+        bias::bias_task(bias_store.clone(), bias_tx.clone())
+    });
+
+    supervisor.spawn("sentiment", RestartPolicy::Always, sentiment::sentiment_task);
+
+    // Telegram alerts for published signals (TELEGRAM_BOT_TOKEN + TELEGRAM_CHAT_ID)
+    let notifier_tx = tx.clone();
+    supervisor.spawn("notifier", RestartPolicy::Always, move || notifier::notifier_task(notifier_tx.clone()));
+    // Discord embeds, per-direction webhooks (DISCORD_WEBHOOK_URL[_LONG|_SHORT])
+    let discord_tx = tx.clone();
+    supervisor.spawn("discord", RestartPolicy::Always, move || discord::discord_task(discord_tx.clone()));
+
+    // Signed JSON webhooks to WEBHOOK_URLS, failed deliveries queued on disk and retried
+    let webhook_queue = std::sync::Arc::new(webhook::WebhookQueue::new(&webhook::webhook_queue_path()));
+    let persist_webhook_queue = webhook_queue.clone();
+    supervisor.spawn("webhook_queue_persist", RestartPolicy::Always, move || {
+        webhook::persist_task(persist_webhook_queue.clone())
+    });
+    let dispatch_webhook_queue = webhook_queue.clone();
+    let webhook_tx = tx.clone();
+    supervisor.spawn("webhook", RestartPolicy::Always, move || {
+        webhook::webhook_task(dispatch_webhook_queue.clone(), webhook_tx.clone())
+    });
+
+    let footprint_store = store.clone();
+    supervisor.spawn("footprint", RestartPolicy::Always, move || {
+        footprint::footprint_task(footprint_store.clone())
+    });
+
+    // Streamed book imbalance for live signals (on unless DEPTH_TRACKER_ENABLED=0), and taker
+    // flow for ramping symbols on feeds without it; a replay has neither live book nor trades
+    if replay_file.is_none() {
+        let order_flow_store = store.clone();
+        supervisor.spawn("order_flow", RestartPolicy::Always, move || {
+            order_flow::order_flow_task(order_flow_store.clone())
+        });
+        let depth_store = store.clone();
+        supervisor.spawn("depth_tracker", RestartPolicy::Always, move || {
+            depth_tracker::depth_task(depth_store.clone())
+        });
+    }
+
+    let liquidation_store = store.clone();
+    supervisor.spawn("liquidations", RestartPolicy::Always, move || {
+        liquidation_client::liquidation_task(liquidation_store.clone())
+    });
+
+    let price_alerts = std::sync::Arc::new(price_alert::PriceAlerts::new(&price_alert::price_alerts_path()));
+    let persist_price_alerts = price_alerts.clone();
+    supervisor.spawn("price_alerts_persist", RestartPolicy::Always, move || {
+        price_alert::persist_task(persist_price_alerts.clone())
+    });
+
+    // Virtual positions on published signals, marked to the store's prices
+    let paper_trader = std::sync::Arc::new(paper_trader::PaperTrader::new(&paper_trader::paper_positions_path()));
+    let persist_paper_trader = paper_trader.clone();
+    supervisor.spawn("paper_positions_persist", RestartPolicy::Always, move || {
+        paper_trader::persist_task(persist_paper_trader.clone())
+    });
+    let (paper_store, paper_history, paper_tx) = (store.clone(), history_manager.clone(), tx.clone());
+    let trading_paper_trader = paper_trader.clone();
+    supervisor.spawn("paper_trader", RestartPolicy::Always, move || {
+        paper_trader::paper_trader_task(
+            trading_paper_trader.clone(),
+            paper_store.clone(),
+            paper_history.clone(),
+            paper_tx.clone(),
+        )
+    });
+
+    // Real Binance Futures orders, only with EXECUTION_ENABLED=true (dry run by default); never
+    // on replayed signals
+    if replay_file.is_none() {
+        let executor_tx = tx.clone();
+        supervisor.spawn("executor", RestartPolicy::Always, move || executor::executor_task(executor_tx.clone()));
+    }
+    // Read-only account balance, positions and fills vs signals, only with PORTFOLIO_ENABLED=true
+    let (portfolio_history, portfolio_tx) = (history_manager.clone(), tx.clone());
+    supervisor.spawn("portfolio", RestartPolicy::Always, move || {
+        portfolio::portfolio_task(portfolio_history.clone(), portfolio_tx.clone())
     });
 
     // Spawn Binance WebSocket Client
-    let store_clone = store.clone();
-    let tx_clone = tx.clone();
-    tokio::spawn(async move {
-        binance_client::binance_ws_task(store_clone, tx_clone).await;
+    // Leader Election (only relevant with LEADER_LOCK_FILE or CLUSTER_REDIS_URL set for redundant deployments)
+    let leadership = leader::init();
+    let election_leadership = leadership.clone();
+    supervisor.spawn("leader_election", RestartPolicy::Always, move || {
+        leader::election_task(election_leadership.clone())
+    });
+    // Daily performance report by email and/or Telegram, only with REPORT_ENABLED=true
+    let (report_history, report_leadership) = (history_manager.clone(), leadership.clone());
+    supervisor.spawn("report", RestartPolicy::Always, move || {
+        report::report_task(report_history.clone(), report_leadership.clone())
     });
+    // Spike ratios adapted to how the signals turned out, only with TUNER_ENABLED=true
+    let (tuner_store, tuner_history) = (store.clone(), history_manager.clone());
+    supervisor.spawn("tuner", RestartPolicy::Always, move || tuner::tuner_task(tuner_store.clone(), tuner_history.clone()));
+    // Signals to and from the other instances (only with CLUSTER_REDIS_URL)
+    let bus_history = history_manager.clone();
+    let bus_tx = tx.clone();
+    let bus_instance = leadership.instance_id.clone();
+    supervisor.spawn("cluster_bus", RestartPolicy::Always, move || {
+        cluster::bus_task(bus_history.clone(), bus_tx.clone(), bus_instance.clone())
+    });
+
+    let actor_ctx = actor::ActorContext {
+        store: store.clone(),
+        tx: tx.clone(),
+        pending_updates,
+        history: history_manager.clone(),
+        leadership,
+        watchlist: watchlist.clone(),
+        price_alerts: price_alerts.clone(),
+    };
+    // One feed per exchange in EXCHANGES, all routing into the same store.
+    // Always restart: the stream ending (Binance drops connections every 24h) is a failure too
+    if let Some(path) = replay_file.clone() {
+        let replay_ctx = actor_ctx.clone();
+        let replay_shutdown = supervisor.shutdown_signal();
+        supervisor.spawn_graceful("replay", RestartPolicy::OnFailure, move || {
+            replay::replay_task(replay_ctx.clone(), path.clone(), replay_shutdown.clone())
+        });
+    } else {
+        // Recent klines for each symbol's window as its actor starts, so averages don't take an hour
+        let backfill_store = store.clone();
+        supervisor.spawn("backfill", RestartPolicy::Always, move || backfill::backfill_task(backfill_store.clone()));
+        // Raw frames to RECORD_DIR for later replay, before the feeds start producing them
+        if let Some(dir) = recorder::record_dir() {
+            let recorder_shutdown = supervisor.shutdown_signal();
+            supervisor.spawn_graceful("recorder", RestartPolicy::Always, move || {
+                recorder::recorder_task(dir.clone(), recorder_shutdown.clone())
+            });
+        }
+        for exchange in exchange::enabled() {
+            let exchange_ctx = actor_ctx.clone();
+            let exchange_shutdown = supervisor.shutdown_signal();
+            supervisor.spawn_graceful(&format!("{}_client", exchange.name()), RestartPolicy::Always, move || {
+                exchange::exchange_task(exchange, exchange_ctx.clone(), exchange_shutdown.clone())
+            });
+        }
+    }
 
     // Spawn Frontend WebSocket Server
     let history_manager_for_server = history_manager.clone();
-    tokio::spawn(async move {
-        ws_server::start_ws_server(tx, history_manager_for_server).await;
+    let server_supervisor = supervisor.clone();
+    let server_store = store.clone();
+    let server_watchlist = watchlist.clone();
+    let server_price_alerts = price_alerts.clone();
+    supervisor.spawn_graceful("ws_server", RestartPolicy::OnFailure, move || {
+        ws_server::start_ws_server(
+            tx.clone(),
+            history_manager_for_server.clone(),
+            server_supervisor.clone(),
+            server_store.clone(),
+            server_watchlist.clone(),
+            server_price_alerts.clone(),
+        )
     });
 
-    // Keep main thread alive
-    tokio::signal::ctrl_c().await.unwrap();
+    // Pipeline rate computation for /metrics
+    supervisor.spawn("metrics_rates", RestartPolicy::Always, metrics::rates_task);
+
+    // systemd readiness + watchdog (no-op outside systemd)
+    let watchdog_supervisor = supervisor.clone();
+    supervisor.spawn("systemd_watchdog", RestartPolicy::Always, move || {
+        systemd::watchdog_task(watchdog_supervisor.clone())
+    });
+
+    // Run until Ctrl-C / SIGTERM, then let the feeds, clients and history tracker finish
+    // before saving what's left; the snapshot comes last so it sees the final ticks
+    shutdown::signal().await;
     info!("Shutting down...");
+    systemd::stopping();
+    supervisor.shutdown().await;
+    if replay_file.is_none() {
+        snapshot::save(&snapshot_path, &store);
+    }
+    watchlist.save().await;
+    price_alerts.save().await;
+    webhook_queue.save().await;
+    paper_trader.save().await;
+    info!("Shutdown complete");
+}
+
+async fn run_backtest(targets: Vec<String>, from: Option<i64>, to: Option<i64>, days: i64, report_path: Option<&str>) -> i32 {
+    let source = if targets.iter().all(|t| t.ends_with(".csv")) {
+        backtest::Source::Csv { paths: targets, from, to }
+    } else {
+        let to = to.unwrap_or_else(|| chrono::Utc::now().timestamp_millis()) / 60000 * 60000;
+        let from = from.unwrap_or(to - days * 24 * 60 * 60 * 1000);
+        let symbols = targets.iter().map(|t| t.to_uppercase()).collect();
+        backtest::Source::Binance { symbols, from, to }
+    };
+    // The same strategies and thresholds the live scanner would use, so a config.toml can be tried here first
+    let strategies = match strategy::Registry::load(&config::config_path()) {
+        Ok(strategies) => strategies,
+        Err(e) => {
+            eprintln!("Invalid scanner config: {}", e);
+            return 1;
+        }
+    };
+    let report = match backtest::run(source, &strategies).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Backtest failed: {}", e);
+            return 1;
+        }
+    };
+    report.print();
+    if let Some(path) = report_path {
+        let written = serde_json::to_string_pretty(&report).map_err(|e| e.to_string()).and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("Failed to write report: {}", e);
+            return 1;
+        }
+        println!("Report written to {}", path);
+    }
+    0
+}
+
+fn seal_secrets(input: Option<&String>) -> Result<String, String> {
+    let input = input.ok_or("usage: --seal-secrets <plain.json>")?;
+    let path = secrets::store_path().ok_or("SECRETS_STORE_PATH is not set")?;
+    let key = std::env::var("SECRETS_KEY").map_err(|_| "SECRETS_KEY is not set")?;
+    let plain = std::fs::read_to_string(input).map_err(|e| e.to_string())?;
+    let values: std::collections::HashMap<String, String> = serde_json::from_str(&plain).map_err(|e| e.to_string())?;
+    let sealed = secrets::seal(&values, &key).map_err(|e| e.to_string())?;
+    std::fs::write(&path, sealed).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn export_state(path: Option<&String>) -> i32 {
+    let Some(path) = path else {
+        eprintln!("usage: --export-state <archive.json>");
+        return 1;
+    };
+    match migrate::export(path) {
+        Ok(archive) => {
+            println!(
+                "Exported {} history records, {} symbols to {} (config {})",
+                archive.history.len(),
+                archive.snapshot.as_ref().map_or(0, |s| s.symbols.len()),
+                path,
+                &archive.config_fingerprint[..12]
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to export state: {}", e);
+            1
+        }
+    }
+}
+
+fn restore_state(path: Option<&String>) -> i32 {
+    let Some(path) = path else {
+        eprintln!("usage: --restore-state <archive.json>");
+        return 1;
+    };
+    match migrate::restore(path) {
+        Ok(archive) => {
+            println!(
+                "Restored {} history records, {} symbols from {}",
+                archive.history.len(),
+                archive.snapshot.as_ref().map_or(0, |s| s.symbols.len()),
+                path
+            );
+            let drift = migrate::config_drift(&archive);
+            if !drift.is_empty() {
+                println!("Config differs from the archived instance: {}", drift.join(", "));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to restore state: {}", e);
+            1
+        }
+    }
 }
@@ -0,0 +1,184 @@
+use crate::scanner::WsMessage;
+use crate::shard::SHARD;
+use crate::stats::RollingStats;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use ts_rs::TS;
+
+// Same bar as a per-symbol "normal spike" in the scanner
+const SPIKE_RATIO: f64 = 3.0;
+// Too few symbols (fresh start, small shard) make the spiking share meaningless
+const MIN_SYMBOLS: usize = 20;
+// Minutes of aggregate volume the z-score is measured against
+const AGGREGATE_WINDOW: usize = 60;
+const MIN_AGGREGATE_SAMPLES: usize = 15;
+
+struct MarketAlertConfig {
+    spike_share: f64,
+    volume_zscore: f64,
+    hold_ms: i64,
+    threshold_mult: f64,
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+static CONFIG: LazyLock<MarketAlertConfig> = LazyLock::new(|| MarketAlertConfig {
+    // Share of tracked symbols spiking in the same minute
+    spike_share: env_or("MARKET_SPIKE_SHARE", 0.3),
+    // Z-score of the whole market's quote volume for the minute
+    volume_zscore: env_or("MARKET_VOLUME_ZSCORE", 4.0),
+    hold_ms: env_or("MARKET_ALERT_HOLD_SECS", 900_i64) * 1000,
+    // Per-symbol volume-ratio thresholds are multiplied by this while an alert is active
+    threshold_mult: env_or("MARKET_ALERT_THRESHOLD_MULT", 2.0),
+});
+
+// End of the current market-wide event (ms), 0 when there is none
+static ELEVATED_UNTIL: AtomicI64 = AtomicI64::new(0);
+
+// The alert that started the current event, replayed to clients that connect during it
+static ACTIVE_ALERT: LazyLock<Mutex<Option<MarketAlert>>> = LazyLock::new(Default::default);
+
+pub fn active_alert() -> Option<MarketAlert> {
+    ACTIVE_ALERT.lock().ok().and_then(|a| a.clone())
+}
+
+/// Multiplier for the scanner's volume-ratio thresholds at `now`: during a full-market
+/// cascade every symbol spikes, so an individual "silent" spike means little.
+pub fn threshold_multiplier(now: i64) -> f64 {
+    if now < ELEVATED_UNTIL.load(Ordering::Relaxed) {
+        CONFIG.threshold_mult
+    } else {
+        1.0
+    }
+}
+
+/// Start (`active`) or end of a market-wide event.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlert {
+    pub active: bool,
+    pub reason: String,
+    pub spiking_symbols: usize,
+    pub tracked_symbols: usize,
+    pub volume_zscore: Option<f64>,
+    pub threshold_multiplier: f64,
+    pub until: i64, // When thresholds return to normal unless the event continues
+    pub timestamp: i64,
+}
+
+struct MinuteSummary {
+    spiking: usize,
+    tracked: usize,
+    quote_volume: f64,
+}
+
+// Looks at each symbol's candle for `minute` (ms); symbols that didn't trade then don't count
+fn summarize(store: &SharedState, minute: i64) -> MinuteSummary {
+    let mut summary = MinuteSummary { spiking: 0, tracked: 0, quote_volume: 0.0 };
+    for entry in store.iter() {
+        let state = entry.value();
+        let Some(last) = state.window.back().filter(|c| c.timestamp == minute) else {
+            continue;
+        };
+        summary.tracked += 1;
+        summary.quote_volume += last.quote_volume;
+        let avg = state.get_average_volume();
+        if avg > 0.0 && last.volume / avg > SPIKE_RATIO {
+            summary.spiking += 1;
+        }
+    }
+    summary
+}
+
+/// Checks every finished minute for a market-wide event, raises the scanner's thresholds
+/// while one lasts and tells clients when it starts and ends.
+///
+/// Shard workers raise their own thresholds but send no alert (they have no clients).
+pub async fn market_alert_task(store: SharedState, tx: broadcast::Sender<WsMessage>) {
+    let mut aggregate = RollingStats::default();
+    let mut minutes: VecDeque<f64> = VecDeque::with_capacity(AGGREGATE_WINDOW);
+    let mut active = false;
+    loop {
+        // A few seconds past the minute, so actors have finished the previous candle
+        let now = chrono::Utc::now().timestamp_millis();
+        let next = (now / 60_000 + 1) * 60_000 + 5_000;
+        tokio::time::sleep(tokio::time::Duration::from_millis((next - now) as u64)).await;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let minute = (now / 60_000 - 1) * 60_000;
+        let summary = summarize(&store, minute);
+        if summary.tracked == 0 {
+            continue;
+        }
+
+        // Scored against the minutes before, so the spike doesn't dampen itself
+        let zscore = (minutes.len() >= MIN_AGGREGATE_SAMPLES)
+            .then(|| aggregate.zscore(summary.quote_volume))
+            .flatten();
+        aggregate.push(summary.quote_volume);
+        minutes.push_back(summary.quote_volume);
+        if minutes.len() > AGGREGATE_WINDOW {
+            if let Some(old) = minutes.pop_front() {
+                aggregate.remove_oldest(old);
+            }
+        }
+
+        let share = summary.spiking as f64 / summary.tracked as f64;
+        let broad = summary.tracked >= MIN_SYMBOLS && share > CONFIG.spike_share;
+        let blowout = zscore.is_some_and(|z| z > CONFIG.volume_zscore);
+
+        let alert = if broad || blowout {
+            let until = now + CONFIG.hold_ms;
+            ELEVATED_UNTIL.store(until, Ordering::Relaxed);
+            let reason = if broad {
+                format!("{:.0}% of symbols spiking at once", share * 100.0)
+            } else {
+                format!("Market volume z-score {:.1}", zscore.unwrap_or_default())
+            };
+            warn!(spiking = summary.spiking, tracked = summary.tracked, zscore = ?zscore, %reason, "Market-wide anomaly");
+            let started = !active;
+            active = true;
+            started.then(|| MarketAlert {
+                active: true,
+                reason,
+                spiking_symbols: summary.spiking,
+                tracked_symbols: summary.tracked,
+                volume_zscore: zscore,
+                threshold_multiplier: CONFIG.threshold_mult,
+                until,
+                timestamp: now,
+            })
+        } else if active && now >= ELEVATED_UNTIL.load(Ordering::Relaxed) {
+            active = false;
+            info!("Market-wide anomaly over, thresholds back to normal");
+            Some(MarketAlert {
+                active: false,
+                reason: "Market back to normal".to_string(),
+                spiking_symbols: summary.spiking,
+                tracked_symbols: summary.tracked,
+                volume_zscore: zscore,
+                threshold_multiplier: 1.0,
+                until: now,
+                timestamp: now,
+            })
+        } else {
+            None
+        };
+
+        if let Some(alert) = alert {
+            if let Ok(mut current) = ACTIVE_ALERT.lock() {
+                *current = alert.active.then(|| alert.clone());
+            }
+            if SHARD.aggregator_url.is_none() {
+                let _ = tx.send(WsMessage::MarketAlert(alert));
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use dashmap::DashMap;
+
+// Process-wide counters/gauges. Updated from the hot path with relaxed atomics,
+// read by the `/metrics` route.
+pub struct Metrics {
+    pub store_symbols: AtomicU64,
+    pub store_candle_builders: AtomicU64, // Candles in progress, one per symbol that ticked recently
+    pub store_bytes_estimate: AtomicU64,
+    pub evicted_idle: AtomicU64,
+    pub evicted_budget: AtomicU64,
+    pub symbols_delisted: AtomicU64, // Purged after they stopped trading (see `universe`)
+    pub actors: AtomicU64,
+    pub actor_dropped_ticks: AtomicU64,
+    pub broadcast_dropped: AtomicU64,
+    // Messages lost to `RecvError::Lagged`, per subscriber name
+    pub broadcast_lagged: Mutex<BTreeMap<String, u64>>,
+    pub slow_clients_disconnected: AtomicU64, // WebSocket clients cut off for lagging too long
+    pub ws_bytes_sent: AtomicU64, // Payload bytes written to WebSocket clients, after encoding
+    pub is_leader: AtomicBool,
+    // Sharding: events for other shards' symbols, signals sent to / received by the aggregator
+    pub shard_skipped: AtomicU64,
+    pub shard_forwarded: AtomicU64,
+    pub shard_ingested: AtomicU64,
+    pub tradingview_alerts: AtomicU64, // Alerts accepted at /api/tradingview (before verification)
+    pub last_feed_message_ms: AtomicI64,
+    pub recorder_dropped: AtomicU64, // Frames the recorder's queue had no room for
+    pub ingest_dropped: AtomicU64,   // Frames a parse worker's queue had no room for
+    pub rest_retries: AtomicU64,     // REST calls repeated after a timeout or 5xx
+    pub rest_rate_limited: AtomicU64, // 429/418 responses from an exchange
+    pub rest_cache_hits: AtomicU64,  // Depth/OI lookups answered from the cache
+    pub cluster_published: AtomicU64, // Messages sent to the other instances
+    pub cluster_relayed: AtomicU64,   // Messages received from them
+    pub symbols_backfilled: AtomicU64, // Windows filled from REST klines at startup / first sight
+    pub pipeline: PipelineCounters,
+    // Per-second rates of the pipeline counters, refreshed by `rates_task`
+    pub rates: Mutex<BTreeMap<&'static str, f64>>,
+    // Handled errors per module (logged and degraded gracefully, never fatal)
+    pub errors: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+// End-to-end ingest counters: each stage should roughly account for the previous one.
+pub struct PipelineCounters {
+    pub messages_received: AtomicU64, // WS text frames
+    pub events_parsed: AtomicU64,     // Ticker events inside those frames
+    pub parse_errors: AtomicU64,
+    pub candles_finalized: AtomicU64,
+    pub signals_evaluated: AtomicU64,
+    pub signals_detected: AtomicU64,
+    pub signals_emitted: AtomicU64,
+    pub signals_invalidated: AtomicU64,
+    pub signals_rejected: AtomicU64, // Dropped by strict verification
+    pub signals_suppressed: AtomicU64, // Market-wide, dropped with CORRELATION_MODE=suppress
+    pub signals_priced_in: AtomicU64,  // Candidates on a coin that already ran (see `suppressed`)
+}
+
+impl PipelineCounters {
+    const fn new() -> Self {
+        Self {
+            messages_received: AtomicU64::new(0),
+            events_parsed: AtomicU64::new(0),
+            parse_errors: AtomicU64::new(0),
+            candles_finalized: AtomicU64::new(0),
+            signals_evaluated: AtomicU64::new(0),
+            signals_detected: AtomicU64::new(0),
+            signals_emitted: AtomicU64::new(0),
+            signals_invalidated: AtomicU64::new(0),
+            signals_rejected: AtomicU64::new(0),
+            signals_suppressed: AtomicU64::new(0),
+            signals_priced_in: AtomicU64::new(0),
+        }
+    }
+
+    fn values(&self) -> [(&'static str, u64); 11] {
+        [
+            ("messages_received", self.messages_received.load(Ordering::Relaxed)),
+            ("events_parsed", self.events_parsed.load(Ordering::Relaxed)),
+            ("parse_errors", self.parse_errors.load(Ordering::Relaxed)),
+            ("candles_finalized", self.candles_finalized.load(Ordering::Relaxed)),
+            ("signals_evaluated", self.signals_evaluated.load(Ordering::Relaxed)),
+            ("signals_detected", self.signals_detected.load(Ordering::Relaxed)),
+            ("signals_emitted", self.signals_emitted.load(Ordering::Relaxed)),
+            ("signals_invalidated", self.signals_invalidated.load(Ordering::Relaxed)),
+            ("signals_rejected", self.signals_rejected.load(Ordering::Relaxed)),
+            ("signals_suppressed", self.signals_suppressed.load(Ordering::Relaxed)),
+            ("signals_priced_in", self.signals_priced_in.load(Ordering::Relaxed)),
+        ]
+    }
+}
+
+// Per-symbol counters. Each symbol actor holds an Arc to its own entry,
+// so counting in the hot path never touches the map.
+#[derive(Debug, Default)]
+pub struct SymbolCounters {
+    pub events: AtomicU64,
+    pub candles_finalized: AtomicU64,
+    pub signals_evaluated: AtomicU64,
+    pub signals_detected: AtomicU64,
+    pub last_event_ms: AtomicI64,
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolCountersSnapshot {
+    pub events: u64,
+    pub candles_finalized: u64,
+    pub signals_evaluated: u64,
+    pub signals_detected: u64,
+    pub last_event_ms: i64,
+}
+
+pub static SYMBOL_COUNTERS: LazyLock<DashMap<String, Arc<SymbolCounters>>> = LazyLock::new(DashMap::new);
+
+pub fn symbol_counters(symbol: &str) -> Arc<SymbolCounters> {
+    SYMBOL_COUNTERS.entry(symbol.to_string()).or_default().clone()
+}
+
+pub fn symbol_counters_snapshot() -> BTreeMap<String, SymbolCountersSnapshot> {
+    SYMBOL_COUNTERS
+        .iter()
+        .map(|e| {
+            let c = e.value();
+            (e.key().clone(), SymbolCountersSnapshot {
+                events: c.events.load(Ordering::Relaxed),
+                candles_finalized: c.candles_finalized.load(Ordering::Relaxed),
+                signals_evaluated: c.signals_evaluated.load(Ordering::Relaxed),
+                signals_detected: c.signals_detected.load(Ordering::Relaxed),
+                last_event_ms: c.last_event_ms.load(Ordering::Relaxed),
+            })
+        })
+        .collect()
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            store_symbols: AtomicU64::new(0),
+            store_candle_builders: AtomicU64::new(0),
+            store_bytes_estimate: AtomicU64::new(0),
+            evicted_idle: AtomicU64::new(0),
+            evicted_budget: AtomicU64::new(0),
+            symbols_delisted: AtomicU64::new(0),
+            actors: AtomicU64::new(0),
+            actor_dropped_ticks: AtomicU64::new(0),
+            broadcast_dropped: AtomicU64::new(0),
+            broadcast_lagged: Mutex::new(BTreeMap::new()),
+            slow_clients_disconnected: AtomicU64::new(0),
+            ws_bytes_sent: AtomicU64::new(0),
+            is_leader: AtomicBool::new(true),
+            shard_skipped: AtomicU64::new(0),
+            shard_forwarded: AtomicU64::new(0),
+            shard_ingested: AtomicU64::new(0),
+            tradingview_alerts: AtomicU64::new(0),
+            last_feed_message_ms: AtomicI64::new(0),
+            recorder_dropped: AtomicU64::new(0),
+            ingest_dropped: AtomicU64::new(0),
+            rest_retries: AtomicU64::new(0),
+            rest_rate_limited: AtomicU64::new(0),
+            rest_cache_hits: AtomicU64::new(0),
+            cluster_published: AtomicU64::new(0),
+            cluster_relayed: AtomicU64::new(0),
+            symbols_backfilled: AtomicU64::new(0),
+            pipeline: PipelineCounters::new(),
+            rates: Mutex::new(BTreeMap::new()),
+            errors: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            store_symbols: self.store_symbols.load(Ordering::Relaxed),
+            store_candle_builders: self.store_candle_builders.load(Ordering::Relaxed),
+            store_bytes_estimate: self.store_bytes_estimate.load(Ordering::Relaxed),
+            evicted_idle: self.evicted_idle.load(Ordering::Relaxed),
+            evicted_budget: self.evicted_budget.load(Ordering::Relaxed),
+            symbols_delisted: self.symbols_delisted.load(Ordering::Relaxed),
+            actors: self.actors.load(Ordering::Relaxed),
+            actor_dropped_ticks: self.actor_dropped_ticks.load(Ordering::Relaxed),
+            broadcast_subscribers: 0,
+            broadcast_dropped: self.broadcast_dropped.load(Ordering::Relaxed),
+            broadcast_lagged: self.broadcast_lagged.lock().unwrap().clone(),
+            slow_clients_disconnected: self.slow_clients_disconnected.load(Ordering::Relaxed),
+            ws_bytes_sent: self.ws_bytes_sent.load(Ordering::Relaxed),
+            is_leader: self.is_leader.load(Ordering::Relaxed),
+            shard_skipped: self.shard_skipped.load(Ordering::Relaxed),
+            shard_forwarded: self.shard_forwarded.load(Ordering::Relaxed),
+            shard_ingested: self.shard_ingested.load(Ordering::Relaxed),
+            tradingview_alerts: self.tradingview_alerts.load(Ordering::Relaxed),
+            last_feed_message_ms: self.last_feed_message_ms.load(Ordering::Relaxed),
+            recorder_dropped: self.recorder_dropped.load(Ordering::Relaxed),
+            ingest_dropped: self.ingest_dropped.load(Ordering::Relaxed),
+            rest_retries: self.rest_retries.load(Ordering::Relaxed),
+            rest_rate_limited: self.rest_rate_limited.load(Ordering::Relaxed),
+            rest_cache_hits: self.rest_cache_hits.load(Ordering::Relaxed),
+            cluster_published: self.cluster_published.load(Ordering::Relaxed),
+            cluster_relayed: self.cluster_relayed.load(Ordering::Relaxed),
+            symbols_backfilled: self.symbols_backfilled.load(Ordering::Relaxed),
+            pipeline: self.pipeline.values().into_iter().collect(),
+            pipeline_rates_per_sec: self.rates.lock().unwrap().clone(),
+            tracked_symbols: SYMBOL_COUNTERS.len() as u64,
+            errors: self.errors.lock().unwrap().clone(),
+        }
+    }
+
+    pub fn record_error(&self, module: &'static str) {
+        *self.errors.lock().unwrap().entry(module).or_insert(0) += 1;
+    }
+
+    // Called by a broadcast receiver that got `RecvError::Lagged(skipped)`
+    pub fn record_lag(&self, subscriber: &str, skipped: u64) {
+        self.broadcast_dropped.fetch_add(skipped, Ordering::Relaxed);
+        *self.broadcast_lagged.lock().unwrap().entry(subscriber.to_string()).or_insert(0) += skipped;
+    }
+
+    // Short-lived subscribers (frontend clients) drop their entry on disconnect; the total stays.
+    pub fn forget_subscriber(&self, subscriber: &str) {
+        self.broadcast_lagged.lock().unwrap().remove(subscriber);
+    }
+}
+
+pub static METRICS: Metrics = Metrics::new();
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    pub store_symbols: u64,
+    pub store_candle_builders: u64,
+    pub store_bytes_estimate: u64,
+    pub evicted_idle: u64,
+    pub evicted_budget: u64,
+    pub symbols_delisted: u64,
+    pub actors: u64,
+    pub actor_dropped_ticks: u64,
+    pub broadcast_subscribers: u64,
+    pub broadcast_dropped: u64,
+    pub broadcast_lagged: BTreeMap<String, u64>,
+    pub slow_clients_disconnected: u64,
+    pub ws_bytes_sent: u64,
+    pub is_leader: bool,
+    pub shard_skipped: u64,
+    pub shard_forwarded: u64,
+    pub shard_ingested: u64,
+    pub tradingview_alerts: u64,
+    pub last_feed_message_ms: i64,
+    pub recorder_dropped: u64,
+    pub ingest_dropped: u64,
+    pub rest_retries: u64,
+    pub rest_rate_limited: u64,
+    pub rest_cache_hits: u64,
+    pub cluster_published: u64,
+    pub cluster_relayed: u64,
+    pub symbols_backfilled: u64,
+    pub pipeline: BTreeMap<&'static str, u64>,
+    pub pipeline_rates_per_sec: BTreeMap<&'static str, f64>,
+    pub tracked_symbols: u64,
+    pub errors: BTreeMap<&'static str, u64>,
+}
+
+const RATE_WINDOW_SECS: u64 = 10;
+
+/// Refreshes per-second rates of the pipeline counters every 10s.
+pub async fn rates_task() {
+    let mut previous = METRICS.pipeline.values();
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(RATE_WINDOW_SECS)).await;
+        let current = METRICS.pipeline.values();
+        let rates = current
+            .iter()
+            .zip(previous.iter())
+            .map(|((name, now), (_, before))| (*name, now.saturating_sub(*before) as f64 / RATE_WINDOW_SECS as f64))
+            .collect();
+        *METRICS.rates.lock().unwrap() = rates;
+        previous = current;
+    }
+}
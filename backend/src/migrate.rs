@@ -0,0 +1,219 @@
+use crate::history::{HistoryError, SignalRecord};
+use crate::paper_trader::PaperError;
+use crate::snapshot::{self, SnapshotError, StoreSnapshot};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// Bump when the archive layout changes in a way older binaries can't read
+const ARCHIVE_VERSION: u32 = 1;
+
+// Non-numeric settings that change behaviour and belong in the fingerprint.
+// Secrets are deliberately excluded: the archive may be copied around.
+const CONFIG_ENV: &[&str] = &[
+    "WARMUP_MODE",
+    "WARMUP_READY_RATIO",
+    "LOG_FORMAT",
+    "LEADER_LOCK_FILE",
+    "CLUSTER_KEY_PREFIX",
+    "FEED_MODE",
+    "REPLAY_FILE",
+    "REPLAY_SPEED",
+    "RECORD_DIR",
+    "EXCHANGES",
+    "SCANNER_MIN_VALUE",
+    "SCANNER_MIN_AVG_VALUE",
+    "SCANNER_DEAD_AVG_VALUE",
+    "SCANNER_SPIKE_RATIO",
+    "SCANNER_DEAD_SPIKE_RATIO",
+    "SCANNER_TRIGGER",
+    "SCANNER_ZSCORE_THRESHOLD",
+    "SCANNER_DEAD_ZSCORE_THRESHOLD",
+    "SCANNER_MAX_PRICE_CHANGE_PCT",
+    "SCANNER_MAX_PRIOR_MOVE_PCT",
+    "SCANNER_TIMEFRAME",
+    "SCANNER_TREND_TIMEFRAME",
+    "STRATEGIES",
+    "FUNDING_EXTREME_PCT",
+    "VERIFY_MODE",
+    "VERIFY_MIN_CONFIDENCE",
+    "VERIFY_RECORD_REJECTED",
+    "CORRELATION_MODE",
+    "CORRELATION_LEADER_VOLUME_RATIO",
+    "CORRELATION_LEADER_MOVE_PCT",
+    "DIVERGENCE_FLAT_RATIO",
+    "DEPTH_TRACKER_ENABLED",
+    "ORDER_FLOW_RAMP_RATIO",
+    "WATCHLIST_BOOST",
+    "OUTCOME_TAKE_PROFIT_PCT",
+    "OUTCOME_STOP_LOSS_PCT",
+    "LIQUIDATIONS_ENABLED",
+    "LIQUIDATION_MIN_VALUE",
+    "LIQUIDATION_RATIO",
+    "LIQUIDATION_TRIGGER",
+    "LIQUIDATION_ZSCORE_THRESHOLD",
+    "SHORT_PRESSURE_MIN_VALUE",
+    "SHORT_PRESSURE_RATIO",
+    "SHORT_PRESSURE_MIN_OI_DELTA",
+    "SHORT_PRESSURE_MIN_WALL_RATIO",
+    "BREAKOUT_MIN_VALUE",
+    "BREAKOUT_RATIO",
+    "BREAKOUT_MIN_CHANGE_PCT",
+    "SYMBOLS_INCLUDE",
+    "SYMBOLS_EXCLUDE",
+    "SYMBOLS_QUOTE_ASSETS",
+    "SYMBOLS_MIN_DAY_VOLUME",
+    "TELEGRAM_CHAT_ID",
+    "WEBHOOK_URLS",
+    "PAPER_TRADING_ENABLED",
+    "PAPER_POSITION_SIZE",
+    "PAPER_TAKE_PROFIT_PCT",
+    "PAPER_STOP_LOSS_PCT",
+    "EXECUTION_ENABLED",
+    "EXECUTION_DRY_RUN",
+    "EXECUTION_EQUITY_PCT",
+    "EXECUTION_STOP_LOSS_PCT",
+    "EXECUTION_TAKE_PROFIT_PCT",
+    "PORTFOLIO_ENABLED",
+    "REPORT_ENABLED",
+    "REPORT_CRON",
+    "REPORT_EMAIL_TO",
+    "REPORT_TELEGRAM",
+    "TUNER_ENABLED",
+    "TUNER_MIN_WIN_RATE",
+    "TUNER_MAX_FACTOR",
+    "TUNER_SYMBOL_FACTOR",
+    "SMTP_HOST",
+    "SMTP_TLS",
+    "SMTP_USERNAME",
+    "SMTP_FROM",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrateError {
+    #[error("archive io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("archive (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("history error: {0}")]
+    History(#[from] HistoryError),
+    #[error("snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
+    #[error("paper positions error: {0}")]
+    Paper(#[from] PaperError),
+    #[error("unsupported archive version {0} (expected {ARCHIVE_VERSION})")]
+    Version(u32),
+}
+
+/// Everything needed to move an instance to a new host, in one file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateArchive {
+    pub version: u32,
+    pub created_at: i64, // ms
+    pub config_fingerprint: String,
+    pub config: BTreeMap<String, String>,
+    pub history: Vec<SignalRecord>,
+    pub snapshot: Option<StoreSnapshot>,
+    // Open paper positions (`paper_trader`); closed ones are in `history`
+    #[serde(default)]
+    pub positions: Vec<serde_json::Value>,
+}
+
+/// Settings currently in effect, as `KEY -> value` for every variable that is set.
+pub fn current_config() -> BTreeMap<String, String> {
+    crate::self_check::NUMERIC_ENV
+        .iter()
+        .chain(CONFIG_ENV)
+        .filter_map(|key| std::env::var(key).ok().map(|v| (key.to_string(), v)))
+        .collect()
+}
+
+pub fn fingerprint(config: &BTreeMap<String, String>) -> String {
+    let mut hasher = Sha256::new();
+    for (key, value) in config {
+        hasher.update(key.as_bytes());
+        hasher.update(b"=");
+        hasher.update(value.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bundles history and the last store snapshot into `archive_path`.
+///
+/// Reads the files the running service writes, so it can be run next to a live instance;
+/// the snapshot is at most `SNAPSHOT_INTERVAL_SECS` old.
+pub fn export(archive_path: &str) -> Result<StateArchive, MigrateError> {
+    let db_path = crate::history::history_db_path();
+    let history = if Path::new(&db_path).exists() { crate::history::read_all(&db_path)? } else { Vec::new() };
+    let snapshot = match snapshot::read_snapshot(&snapshot::snapshot_path()) {
+        Ok(snapshot) => Some(snapshot),
+        Err(SnapshotError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let positions = match crate::paper_trader::read_positions(&crate::paper_trader::paper_positions_path()) {
+        Ok(positions) => positions.iter().map(serde_json::to_value).collect::<Result<_, _>>()?,
+        Err(PaperError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let config = current_config();
+    let archive = StateArchive {
+        version: ARCHIVE_VERSION,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        config_fingerprint: fingerprint(&config),
+        config,
+        history,
+        snapshot,
+        positions,
+    };
+    fs::write(archive_path, serde_json::to_string(&archive)?)?;
+    Ok(archive)
+}
+
+/// Writes an archive back to `HISTORY_DB_PATH`, `SNAPSHOT_PATH` and `PAPER_POSITIONS_PATH`. Run with the service stopped;
+/// it picks the state up on the next start. Existing files are kept as `.pre-restore-<ts>`.
+pub fn restore(archive_path: &str) -> Result<StateArchive, MigrateError> {
+    let archive: StateArchive = serde_json::from_str(&fs::read_to_string(archive_path)?)?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(MigrateError::Version(archive.version));
+    }
+
+    let stamp = chrono::Utc::now().timestamp();
+    let db_path = crate::history::history_db_path();
+    backup(&db_path, stamp)?;
+    crate::history::write_all(&db_path, &archive.history)?;
+
+    if let Some(snapshot) = &archive.snapshot {
+        let snapshot_path = snapshot::snapshot_path();
+        backup(&snapshot_path, stamp)?;
+        snapshot::write_snapshot(&snapshot_path, snapshot)?;
+    }
+
+    if !archive.positions.is_empty() {
+        let positions_path = crate::paper_trader::paper_positions_path();
+        backup(&positions_path, stamp)?;
+        fs::write(&positions_path, serde_json::to_string_pretty(&archive.positions)?)?;
+    }
+    Ok(archive)
+}
+
+fn backup(path: &str, stamp: i64) -> std::io::Result<()> {
+    if Path::new(path).exists() {
+        fs::copy(path, format!("{}.pre-restore-{}", path, stamp))?;
+    }
+    Ok(())
+}
+
+/// Config keys whose values differ between the archive and the current environment.
+pub fn config_drift(archive: &StateArchive) -> Vec<String> {
+    let current = current_config();
+    let mut keys: Vec<&String> = archive.config.keys().chain(current.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|k| archive.config.get(*k) != current.get(*k))
+        .cloned()
+        .collect()
+}
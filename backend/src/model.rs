@@ -1,43 +1,720 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+use crate::exchange_info::SymbolMeta;
+use crate::scanner::SignalType;
+use crate::stats::{Ewma, HourlyBaseline, RollingStats};
+use crate::indicators::{IndicatorValues, Indicators};
+use crate::liquidation_client::Liquidations;
+use crate::oi_tracker::OiHistory;
 
+// One raw ticker observation, already parsed by the ingestion layer.
+// No symbol: the receiving actor already knows it, which keeps routing allocation-free.
+// Totals are Binance's rolling 24h figures; per-minute values come from their deltas.
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    pub price: f64,
+    pub volume_total: f64,       // Base asset
+    pub quote_volume_total: f64, // Quote asset (USDT)
+    pub trades_total: u64,
+    // Taker-buy volume traded since the previous event, when the source reports it
+    // (`!ticker@arr` does not; kline and aggTrade streams do)
+    pub taker_buy_volume: Option<f64>,
+    pub taker_buy_quote_volume: Option<f64>,
+    // The exchange's own open/high/low for the minute, when the source is a kline stream
+    pub range: Option<KlineRange>,
+    // 24h context from the ticker
+    pub day_high: Option<f64>,
+    pub day_low: Option<f64>,
+    pub day_change_pct: Option<f64>,
+    pub event_time: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KlineRange {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+}
+
+// One 1-minute OHLCV candle. While a minute is still open the actor keeps a scratch
+// candle with the values so far, `timestamp` then being the latest event time.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub symbol: String,
-    pub price: f64,
-    pub volume: f64,
-    pub timestamp: i64,
+    #[serde(default)]
+    pub open: f64,
+    #[serde(default)]
+    pub high: f64,
+    #[serde(default)]
+    pub low: f64,
+    #[serde(alias = "price")] // Snapshots from before OHLC only had the close
+    pub close: f64,
+    pub volume: f64, // Base asset
+    #[serde(default)]
+    pub quote_volume: f64,
+    #[serde(default)]
+    pub trades: u64,
+    // Volume bought by takers (aggressive buyers). None when the source doesn't report it.
+    #[serde(default)]
+    pub taker_buy_volume: Option<f64>,
+    #[serde(default)]
+    pub taker_buy_quote_volume: Option<f64>,
+    pub timestamp: i64, // Minute open time (ms) once finalized
+}
+
+impl MarketData {
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+
+    // Extends this candle with the next one in time (higher-timeframe aggregation)
+    pub fn merge(&mut self, next: &MarketData) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume += next.volume;
+        self.quote_volume += next.quote_volume;
+        self.trades += next.trades;
+        self.taker_buy_volume = add_optional(self.taker_buy_volume, next.taker_buy_volume);
+        self.taker_buy_quote_volume = add_optional(self.taker_buy_quote_volume, next.taker_buy_quote_volume);
+    }
+
+    // Base volume per trade: many small trades (retail) vs few large ones (size moving)
+    pub fn avg_trade_size(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.volume / self.trades as f64)
+    }
+
+    pub fn taker_sell_volume(&self) -> Option<f64> {
+        self.taker_buy_volume.map(|buy| (self.volume - buy).max(0.0))
+    }
+
+    // Candles restored from a pre-OHLC snapshot only know their close
+    pub fn fill_missing_ohlc(&mut self) {
+        if self.open == 0.0 && self.high == 0.0 && self.low == 0.0 {
+            self.open = self.close;
+            self.high = self.close;
+            self.low = self.close;
+        }
+    }
+}
+
+/// The candle being built for the current minute, plus the 24h totals at its start.
+///
+/// The rolling 24h totals can shrink (trades older than 24h roll out faster than new ones come
+/// in), so a negative delta is clamped to zero rather than treated as a reset.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CandleBuilder {
+    pub minute: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    start_volume: f64,
+    start_quote_volume: f64,
+    start_trades: u64,
+    last_volume: f64,
+    last_quote_volume: f64,
+    last_trades: u64,
+    taker_buy_volume: Option<f64>,
+    taker_buy_quote_volume: Option<f64>,
+}
+
+impl CandleBuilder {
+    pub fn new(minute: i64, tick: &Tick) -> Self {
+        let range = tick.range.unwrap_or(KlineRange { open: tick.price, high: tick.price, low: tick.price });
+        Self {
+            minute,
+            open: range.open,
+            high: range.high.max(tick.price),
+            low: range.low.min(tick.price),
+            close: tick.price,
+            start_volume: tick.volume_total,
+            start_quote_volume: tick.quote_volume_total,
+            start_trades: tick.trades_total,
+            last_volume: tick.volume_total,
+            last_quote_volume: tick.quote_volume_total,
+            last_trades: tick.trades_total,
+            taker_buy_volume: tick.taker_buy_volume,
+            taker_buy_quote_volume: tick.taker_buy_quote_volume,
+        }
+    }
+
+    // Next minute starts where the last tick of this one left off, so nothing falls between
+    pub fn next(&self, minute: i64, tick: &Tick) -> Self {
+        Self {
+            start_volume: self.last_volume,
+            start_quote_volume: self.last_quote_volume,
+            start_trades: self.last_trades,
+            ..Self::new(minute, tick)
+        }
+    }
+
+    pub fn update(&mut self, tick: &Tick) {
+        if let Some(range) = tick.range {
+            self.open = range.open;
+            self.high = self.high.max(range.high);
+            self.low = self.low.min(range.low);
+        }
+        self.high = self.high.max(tick.price);
+        self.low = self.low.min(tick.price);
+        self.close = tick.price;
+        self.last_volume = tick.volume_total;
+        self.last_quote_volume = tick.quote_volume_total;
+        self.last_trades = tick.trades_total;
+        self.taker_buy_volume = add_optional(self.taker_buy_volume, tick.taker_buy_volume);
+        self.taker_buy_quote_volume = add_optional(self.taker_buy_quote_volume, tick.taker_buy_quote_volume);
+    }
+
+    /// Writes the candle so far into `out`, reusing its allocation.
+    pub fn fill(&self, out: &mut MarketData, timestamp: i64) {
+        out.open = self.open;
+        out.high = self.high;
+        out.low = self.low;
+        out.close = self.close;
+        out.volume = (self.last_volume - self.start_volume).max(0.0);
+        out.quote_volume = (self.last_quote_volume - self.start_quote_volume).max(0.0);
+        out.trades = self.last_trades.saturating_sub(self.start_trades);
+        out.taker_buy_volume = self.taker_buy_volume;
+        out.taker_buy_quote_volume = self.taker_buy_quote_volume;
+        out.timestamp = timestamp;
+    }
+
+    pub fn finish(&self, symbol: &str) -> MarketData {
+        let mut candle = MarketData {
+            symbol: symbol.to_string(),
+            open: 0.0,
+            high: 0.0,
+            low: 0.0,
+            close: 0.0,
+            volume: 0.0,
+            quote_volume: 0.0,
+            trades: 0,
+            taker_buy_volume: None,
+            taker_buy_quote_volume: None,
+            timestamp: 0,
+        };
+        self.fill(&mut candle, self.minute * 60000);
+        candle
+    }
+}
+
+fn add_optional(total: Option<f64>, increment: Option<f64>) -> Option<f64> {
+    match increment {
+        Some(inc) => Some(total.unwrap_or(0.0) + inc),
+        None => total,
+    }
+}
+
+/// Candle intervals derived from the 1m feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS, ToSchema)]
+pub enum Interval {
+    #[default]
+    #[serde(rename = "1m")]
+    M1,
+    #[serde(rename = "5m")]
+    M5,
+    #[serde(rename = "15m")]
+    M15,
+}
+
+impl Interval {
+    pub fn minutes(self) -> i64 {
+        match self {
+            Interval::M1 => 1,
+            Interval::M5 => 5,
+            Interval::M15 => 15,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Interval::M1 => "1m",
+            Interval::M5 => "5m",
+            Interval::M15 => "15m",
+        }
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Interval::M1, Interval::M5, Interval::M15]
+            .into_iter()
+            .find(|i| i.label() == s)
+            .ok_or_else(|| format!("'{}' is not one of 1m, 5m, 15m", s))
+    }
+}
+
+/// Higher-timeframe candles built from finished 1m candles, aligned to the interval
+/// (a 5m candle covers :00-:04, :05-:09, ...). `partial` is the bucket still filling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedWindow {
+    minutes: i64,
+    capacity: usize,
+    pub candles: VecDeque<MarketData>,
+    partial: Option<MarketData>,
+}
+
+impl AggregatedWindow {
+    fn new(interval: Interval, capacity: usize) -> Self {
+        Self { minutes: interval.minutes(), capacity, candles: VecDeque::new(), partial: None }
+    }
+
+    fn push_minute(&mut self, minute: &MarketData) {
+        let span = self.minutes * 60000;
+        let bucket_start = minute.timestamp.div_euclid(span) * span;
+
+        // A gap in the feed can skip a bucket's last minute: close it when the next one starts
+        if self.partial.as_ref().is_some_and(|p| p.timestamp != bucket_start) {
+            self.finish_partial();
+        }
+        match &mut self.partial {
+            Some(partial) => partial.merge(minute),
+            None => {
+                let mut candle = minute.clone();
+                candle.timestamp = bucket_start;
+                self.partial = Some(candle);
+            }
+        }
+        if minute.timestamp + 60000 >= bucket_start + span {
+            self.finish_partial();
+        }
+    }
+
+    // The bucket still filling extended by the minute in progress, stamped with its time
+    fn current(&self, minute: &MarketData) -> MarketData {
+        let span = self.minutes * 60000;
+        let bucket_start = minute.timestamp.div_euclid(span) * span;
+        let mut candle = match &self.partial {
+            Some(partial) if partial.timestamp == bucket_start => {
+                let mut candle = partial.clone();
+                candle.merge(minute);
+                candle
+            }
+            _ => minute.clone(),
+        };
+        candle.timestamp = minute.timestamp;
+        candle
+    }
+
+    fn finish_partial(&mut self) {
+        if let Some(candle) = self.partial.take() {
+            if self.candles.len() >= self.capacity {
+                self.candles.pop_front();
+            }
+            self.candles.push_back(candle);
+        }
+    }
+}
+
+// 3h of 5m candles, 8h of 15m candles
+const WINDOW_5M: usize = 36;
+const WINDOW_15M: usize = 32;
+
+fn default_window_5m() -> AggregatedWindow {
+    AggregatedWindow::new(Interval::M5, WINDOW_5M)
+}
+
+fn default_window_15m() -> AggregatedWindow {
+    AggregatedWindow::new(Interval::M15, WINDOW_15M)
+}
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+// The 1m window: an hour
+pub const WINDOW_CANDLES: usize = 60;
+
+// Baseline of 1m volume for z-scores: an EWMA with a 4h half-life, trusted after 2h of minutes
+const BASELINE_HALF_LIFE_MINUTES: f64 = 240.0;
+const BASELINE_MIN_MINUTES: u64 = 120;
+
+// Per hour of the day (each gets 60 minutes a day): a 3-day half-life, trusted after two days
+const HOURLY_HALF_LIFE_MINUTES: f64 = 3.0 * 60.0;
+const HOURLY_MIN_MINUTES: u64 = 2 * 60;
+
+/// Running sums for VWAP over the 1m window and over the current UTC day (session).
+///
+/// Updated as candles enter (and leave) the window so reading a VWAP is O(1). Uses the
+/// typical price (H+L+C)/3 of each candle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VwapState {
+    window_pv: f64,
+    window_volume: f64,
+    session_start: i64, // ms, UTC midnight
+    session_pv: f64,
+    session_volume: f64,
+}
+
+impl VwapState {
+    fn add(&mut self, candle: &MarketData) {
+        let pv = candle.typical_price() * candle.volume;
+        self.window_pv += pv;
+        self.window_volume += candle.volume;
+
+        let session_start = candle.timestamp.div_euclid(DAY_MS) * DAY_MS;
+        if session_start != self.session_start {
+            self.session_start = session_start;
+            self.session_pv = 0.0;
+            self.session_volume = 0.0;
+        }
+        self.session_pv += pv;
+        self.session_volume += candle.volume;
+    }
+
+    fn remove(&mut self, candle: &MarketData) {
+        self.window_pv = (self.window_pv - candle.typical_price() * candle.volume).max(0.0);
+        self.window_volume = (self.window_volume - candle.volume).max(0.0);
+    }
+
+    pub fn window(&self) -> Option<f64> {
+        (self.window_volume > 0.0).then(|| self.window_pv / self.window_volume)
+    }
+
+    pub fn session(&self) -> Option<f64> {
+        (self.session_volume > 0.0).then(|| self.session_pv / self.session_volume)
+    }
+}
+
+/// Where the symbol stands in its rolling 24h, as last reported by the ticker.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DayStats {
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub change_pct: Option<f64>,
+    pub quote_volume: f64,
+}
+
+impl DayStats {
+    pub fn update(&mut self, tick: &Tick) {
+        self.high = tick.day_high.or(self.high);
+        self.low = tick.day_low.or(self.low);
+        self.change_pct = tick.day_change_pct.or(self.change_pct);
+        self.quote_volume = tick.quote_volume_total;
+    }
+
+    /// 0.0 at the 24h low, 1.0 at the 24h high.
+    pub fn range_position(&self, price: f64) -> Option<f64> {
+        let (high, low) = (self.high?, self.low?);
+        (high > low).then(|| ((price - low) / (high - low)).clamp(0.0, 1.0))
+    }
+}
+
+// The symbol's latest published signal, watched for invalidation while it is live
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSignal {
+    pub id: String,
+    #[serde(default)]
+    pub trace_id: String,
+    pub signal_type: SignalType,
+    pub entry_price: f64,
+    pub invalidated: bool,
+    // `Signal::score` total, ranked on the leaderboard; the verifier's replaces the scanner's
+    #[serde(default)]
+    pub score: Option<f64>,
+}
+
+impl ActiveSignal {
+    /// How far `price` has moved against the signal, as a fraction of the entry (negative when in profit).
+    pub fn adverse_move(&self, price: f64) -> f64 {
+        if self.entry_price <= 0.0 {
+            return 0.0;
+        }
+        match self.signal_type {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => (self.entry_price - price) / self.entry_price,
+            SignalType::Short => (price - self.entry_price) / self.entry_price,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolState {
     pub symbol: String,
     // Sliding window of the last 60 minutes
     pub window: VecDeque<MarketData>,
-    pub last_signal_time: Option<i64>,
+    // Derived from `window` as minutes finish; see `candles(Interval)`
+    #[serde(default = "default_window_5m")]
+    pub window_5m: AggregatedWindow,
+    #[serde(default = "default_window_15m")]
+    pub window_15m: AggregatedWindow,
+    #[serde(default)]
+    pub vwap: VwapState,
+    // RSI, EMAs and ATR of the finished 1m candles; see `indicators()`
+    #[serde(default)]
+    pub indicators: Indicators,
+    // Incremental stats of 1m candle volume over `window`; rebuilt from it after a restore
+    #[serde(skip)]
+    pub volume_stats: RollingStats,
+    // Hours-long EWMA of 1m candle volume, for z-score triggers; see `volume_zscore`
+    #[serde(default)]
+    pub volume_baseline: Ewma,
+    // 1m candle volume by UTC hour of day, learned over days; see `expected_volume`
+    #[serde(default)]
+    pub hourly_volume: HourlyBaseline,
+    #[serde(default)]
+    pub day: DayStats,
+    // Forced liquidations per minute, fed by `liquidation_client` when it runs
+    #[serde(default)]
+    pub liquidations: Liquidations,
+    // Open interest samples while the symbol is active, polled by `oi_tracker`
+    #[serde(default)]
+    pub open_interest: OiHistory,
+    // Contract metadata from exchangeInfo (None until it has been fetched)
+    #[serde(default)]
+    pub meta: Option<SymbolMeta>,
+    pub last_signal_time: Option<i64>, // Latest signal of any strategy
+    // Latest signal per strategy name, for per-strategy cooldowns
+    #[serde(default)]
+    pub strategy_signals: HashMap<String, i64>,
+    #[serde(default)]
+    pub active_signal: Option<ActiveSignal>,
+    // Per-minute order flow by price, only while a signal is live (FOOTPRINT_ENABLED)
+    #[serde(default)]
+    pub footprints: VecDeque<crate::footprint::Footprint>,
+    // Bid/ask wall ratios while a signal is live, fed by `depth_tracker`
+    #[serde(skip)]
+    pub depth: crate::depth_tracker::DepthHistory,
+    // Taker buy/sell per minute while the volume ramps on a feed without it, fed by `order_flow`
+    #[serde(skip)]
+    pub order_flow: crate::order_flow::OrderFlow,
 }
 
 impl SymbolState {
     pub fn new(symbol: String) -> Self {
+        let meta = crate::exchange_info::get(&symbol);
         Self {
             symbol,
             window: VecDeque::new(),
+            window_5m: default_window_5m(),
+            window_15m: default_window_15m(),
+            vwap: VwapState::default(),
+            indicators: Indicators::default(),
+            volume_stats: RollingStats::default(),
+            volume_baseline: Ewma::default(),
+            hourly_volume: HourlyBaseline::default(),
+            day: DayStats::default(),
+            liquidations: Liquidations::default(),
+            open_interest: OiHistory::default(),
+            meta,
             last_signal_time: None,
+            strategy_signals: HashMap::new(),
+            active_signal: None,
+            footprints: VecDeque::new(),
+            depth: Default::default(),
+            order_flow: Default::default(),
         }
     }
 
     pub fn add_data(&mut self, data: MarketData) {
-        if self.window.len() >= 60 {
-            self.window.pop_front();
+        self.window_5m.push_minute(&data);
+        self.window_15m.push_minute(&data);
+        self.vwap.add(&data);
+        self.indicators.add(&data);
+        self.volume_stats.push(data.volume);
+        self.volume_baseline.push(data.volume, Ewma::alpha(BASELINE_HALF_LIFE_MINUTES));
+        self.hourly_volume.push(data.timestamp, data.volume, Ewma::alpha(HOURLY_HALF_LIFE_MINUTES));
+        if self.window.len() >= WINDOW_CANDLES {
+            if let Some(old) = self.window.pop_front() {
+                self.vwap.remove(&old);
+                self.volume_stats.remove_oldest(old.volume);
+            }
         }
         self.window.push_back(data);
     }
-    
+
+    /// Puts candles from before the window (backfilled from REST) in front of it, and
+    /// recomputes what is derived from the window. Candles it already covers are skipped.
+    /// Returns how many were added.
+    pub fn prepend(&mut self, older: Vec<MarketData>) -> usize {
+        let first = self.window.front().map(|c| c.timestamp);
+        let older: Vec<MarketData> = older.into_iter().filter(|c| first.is_none_or(|first| c.timestamp < first)).collect();
+        if older.is_empty() {
+            return 0;
+        }
+        let added = older.len();
+        let recent = std::mem::take(&mut self.window);
+        // A baseline that knows more than the window (restored from a snapshot) stays as it is
+        let rebuild_baseline = self.volume_baseline.samples() as usize <= recent.len();
+        let baseline = std::mem::take(&mut self.volume_baseline);
+        // The hourly baselines have seen the window already; only the older candles are new to them
+        let hourly = self.hourly_volume.clone();
+        let new_volumes: Vec<(i64, f64)> = older.iter().map(|c| (c.timestamp, c.volume)).collect();
+        self.window_5m = default_window_5m();
+        self.window_15m = default_window_15m();
+        self.vwap = VwapState::default();
+        self.indicators = Indicators::default();
+        self.volume_stats = RollingStats::default();
+        for candle in older.into_iter().chain(recent) {
+            self.add_data(candle);
+        }
+        if !rebuild_baseline {
+            self.volume_baseline = baseline;
+        }
+        self.hourly_volume = hourly;
+        for (timestamp, volume) in new_volumes {
+            self.hourly_volume.push(timestamp, volume, Ewma::alpha(HOURLY_HALF_LIFE_MINUTES));
+        }
+        added
+    }
+
+    /// Rebuilds state derived from the 1m window after a restore: volume stats (never
+    /// persisted), and higher timeframes / VWAP / indicators for snapshots taken before they
+    /// existed.
+    pub fn rebuild_derived(&mut self) {
+        self.volume_stats = RollingStats::default();
+        for candle in &self.window {
+            self.volume_stats.push(candle.volume);
+        }
+        // Snapshots from before the baseline existed start it from the hour they have
+        if self.volume_baseline.samples() == 0 {
+            for candle in &self.window {
+                self.volume_baseline.push(candle.volume, Ewma::alpha(BASELINE_HALF_LIFE_MINUTES));
+            }
+        }
+        if self.window_5m.candles.is_empty() && !self.window.is_empty() {
+            self.window_5m = default_window_5m();
+            self.window_15m = default_window_15m();
+            for candle in &self.window {
+                self.window_5m.push_minute(candle);
+                self.window_15m.push_minute(candle);
+            }
+        }
+        // Session VWAP can only be recovered for the part of the day still in the window
+        if self.vwap.window().is_none() {
+            self.vwap = VwapState::default();
+            for candle in &self.window {
+                self.vwap.add(candle);
+            }
+        }
+        if self.indicators.candles() == 0 {
+            for candle in &self.window {
+                self.indicators.add(candle);
+            }
+        }
+    }
+
+    /// Indicator values as of the last finished minute, for strategies, signals and updates.
+    pub fn indicators(&self) -> IndicatorValues {
+        IndicatorValues {
+            rsi: self.indicators.rsi(),
+            ema9: self.indicators.ema_fast(),
+            ema21: self.indicators.ema_slow(),
+            vwap: self.vwap.window(),
+            atr: self.indicators.atr(),
+        }
+    }
+
+    /// When `strategy` last signalled here. Snapshots from before per-strategy cooldowns only
+    /// know the latest signal overall, which then counts for every strategy.
+    pub fn last_signal_of(&self, strategy: &str) -> Option<i64> {
+        match self.strategy_signals.get(strategy) {
+            Some(t) => Some(*t),
+            None if self.strategy_signals.is_empty() => self.last_signal_time,
+            None => None,
+        }
+    }
+
+    /// Lets each of `strategies` signal again right away. Recorded as a signal at the epoch
+    /// rather than removed, so an empty map isn't taken for a pre-cooldown snapshot.
+    pub fn clear_cooldowns(&mut self, strategies: &[&str]) {
+        for strategy in strategies {
+            self.strategy_signals.insert(strategy.to_string(), 0);
+        }
+    }
+
+    pub fn record_signal(&mut self, strategy: &str, timestamp: i64) {
+        self.last_signal_time = Some(self.last_signal_time.map_or(timestamp, |t| t.max(timestamp)));
+        self.strategy_signals.insert(strategy.to_string(), timestamp);
+    }
+
+    /// Finished candles of the given interval, oldest first.
+    pub fn candles(&self, interval: Interval) -> &VecDeque<MarketData> {
+        match interval {
+            Interval::M1 => &self.window,
+            Interval::M5 => &self.window_5m.candles,
+            Interval::M15 => &self.window_15m.candles,
+        }
+    }
+
+    /// The candle of `interval` that `minute` (the 1m candle in progress) belongs to, so far.
+    pub fn current_candle(&self, interval: Interval, minute: &MarketData) -> MarketData {
+        match interval {
+            Interval::M1 => minute.clone(),
+            Interval::M5 => self.window_5m.current(minute),
+            Interval::M15 => self.window_15m.current(minute),
+        }
+    }
+
     pub fn get_average_volume(&self) -> f64 {
-        if self.window.is_empty() {
+        self.average_volume(Interval::M1)
+    }
+
+    pub fn average_volume(&self, interval: Interval) -> f64 {
+        if interval == Interval::M1 {
+            return self.volume_stats.mean();
+        }
+        let candles = self.candles(interval);
+        if candles.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = candles.iter().map(|d| d.volume).sum();
+        sum / candles.len() as f64
+    }
+
+    // Population standard deviation of candle volume
+    pub fn volume_stddev(&self, interval: Interval) -> f64 {
+        if interval == Interval::M1 {
+            return self.volume_stats.stddev();
+        }
+        let candles = self.candles(interval);
+        if candles.len() < 2 {
             return 0.0;
         }
-        let sum: f64 = self.window.iter().map(|d| d.volume).sum();
-        sum / self.window.len() as f64
+        let mean = self.average_volume(interval);
+        let variance = candles.iter().map(|d| (d.volume - mean).powi(2)).sum::<f64>() / candles.len() as f64;
+        variance.sqrt()
+    }
+
+    /// How unusual `volume` is for a candle of `interval`, in standard deviations above its
+    /// baseline: the hours-long 1m EWMA, or the 5m/15m window (3h/8h). None until there is
+    /// enough history (or while volume has been flat).
+    pub fn volume_zscore(&self, interval: Interval, volume: f64) -> Option<f64> {
+        if interval == Interval::M1 {
+            if self.volume_baseline.samples() < BASELINE_MIN_MINUTES {
+                return None;
+            }
+            return self.volume_baseline.zscore(volume);
+        }
+        let sd = self.volume_stddev(interval);
+        (sd > 0.0).then(|| (volume - self.average_volume(interval)) / sd)
+    }
+
+    /// A fresh state that keeps only this one's hourly baselines, for a snapshot too old for
+    /// the rest of it.
+    pub fn hourly_only(self) -> SymbolState {
+        SymbolState { hourly_volume: self.hourly_volume, ..SymbolState::new(self.symbol) }
+    }
+
+    /// Typical volume for a candle of `interval` at `timestamp`'s hour of day, from the hourly
+    /// baselines; None until that hour has been seen on enough days.
+    pub fn expected_volume(&self, interval: Interval, timestamp: i64) -> Option<f64> {
+        let per_minute = self.hourly_volume.expected(timestamp, HOURLY_MIN_MINUTES)?;
+        Some(per_minute * interval.minutes() as f64)
+    }
+
+    // Timestamp (ms) of the newest candle, used to find idle symbols.
+    pub fn last_active(&self) -> Option<i64> {
+        self.window.back().map(|d| d.timestamp)
+    }
+
+    // Rough heap + inline footprint, good enough for budgeting the store.
+    pub fn estimated_bytes(&self) -> usize {
+        let per_entry = std::mem::size_of::<MarketData>();
+        let windows = [&self.window, &self.window_5m.candles, &self.window_15m.candles];
+        let entries: usize = windows.iter().map(|w| w.capacity() * per_entry).sum();
+        let strings: usize = windows.iter().flat_map(|w| w.iter()).map(|d| d.symbol.capacity()).sum();
+        std::mem::size_of::<Self>() + self.symbol.capacity() + entries + strings
     }
 }
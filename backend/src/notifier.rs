@@ -0,0 +1,230 @@
+// Pushes published signals, and users' price alerts marked `notify`, to a Telegram chat, for
+// alerts away from the dashboard. Disabled unless both TELEGRAM_BOT_TOKEN (a secret) and
+// TELEGRAM_CHAT_ID are set.
+use crate::metrics::METRICS;
+use crate::price_alert::{AlertKind, Direction, PriceAlert};
+use crate::scanner::{Signal, SignalType, WsMessage};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{info, warn};
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+pub const TOKEN_SECRET: &str = "TELEGRAM_BOT_TOKEN";
+// Telegram caps a message at 4096 characters; ten signal or alert blocks stay well under it
+const SIGNALS_PER_MESSAGE: usize = 10;
+// Telegram allows about one message per second per chat
+const MIN_SEND_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_ATTEMPTS: u32 = 4;
+
+pub fn chat_id() -> Option<String> {
+    std::env::var("TELEGRAM_CHAT_ID").ok().filter(|v| !v.trim().is_empty())
+}
+
+pub fn enabled() -> bool {
+    chat_id().is_some() && crate::secrets::get(TOKEN_SECRET).is_some_and(|t| !t.expose().is_empty())
+}
+
+// Signals arriving within this window go out as one message
+fn batch_secs() -> u64 {
+    std::env::var("NOTIFY_BATCH_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(5)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifierError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("rate limited, retry after {0}s")]
+    RateLimited(u64),
+    #[error("telegram error {0}: {1}")]
+    Api(u16, String),
+    #[error("TELEGRAM_BOT_TOKEN is not set")]
+    MissingToken,
+}
+
+#[derive(Serialize)]
+struct SendMessage<'a> {
+    chat_id: &'a str,
+    text: &'a str,
+    disable_web_page_preview: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    parameters: Option<ResponseParameters>,
+}
+
+#[derive(Deserialize)]
+struct ResponseParameters {
+    retry_after: Option<u64>,
+}
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default()
+});
+
+/// One signal as a few lines of plain text: direction, symbol, price, volume ratio and the
+/// scanner/verifier notes, one per line.
+pub fn format_signal(signal: &Signal) -> String {
+    let (icon, side) = match signal.signal_type {
+        SignalType::Long => ("🟢", "LONG"),
+        SignalType::Short => ("🔴", "SHORT"),
+        SignalType::NewListing => ("🆕", "NEW LISTING"),
+        SignalType::Breakout => ("🚀", "BREAKOUT"),
+    };
+    let price = match signal.price_precision {
+        Some(precision) => format!("{:.*}", precision as usize, signal.price),
+        None => signal.price.to_string(),
+    };
+    let ratio = if signal.avg_volume > 0.0 { signal.volume / signal.avg_volume } else { 0.0 };
+    let mut text = format!("{} {} {} @ {}\nVolume {:.1}x average", icon, side, signal.symbol, price, ratio);
+    if signal.low_confidence {
+        text += " (warming up)";
+    }
+    for note in signal.reason.split(" | ").filter(|n| !n.is_empty()) {
+        text += "\n• ";
+        text += note;
+    }
+    text
+}
+
+/// A fired price alert as one line, plus its note.
+pub fn format_alert(alert: &PriceAlert) -> String {
+    let mut text = match alert.kind {
+        AlertKind::Price => {
+            let side = match alert.direction {
+                Some(Direction::Above) => "at or above",
+                Some(Direction::Below) => "at or below",
+                None => "crossed",
+            };
+            format!("🔔 {} {} {} (last {})", alert.symbol, side, alert.level, alert.triggered_price.unwrap_or_default())
+        }
+        AlertKind::QuoteVolume => format!(
+            "🔔 {} 1m volume ${:.0} past ${:.0}",
+            alert.symbol,
+            alert.triggered_quote_volume.unwrap_or_default(),
+            alert.level
+        ),
+    };
+    if let Some(note) = alert.note.as_deref().filter(|n| !n.is_empty()) {
+        text += "\n• ";
+        text += note;
+    }
+    text
+}
+
+async fn send(text: &str) -> Result<(), NotifierError> {
+    let token = crate::secrets::get(TOKEN_SECRET).ok_or(NotifierError::MissingToken)?;
+    let chat_id = chat_id().unwrap_or_default();
+    let url = format!("{}/bot{}/sendMessage", TELEGRAM_API_URL, token.expose());
+    let body = SendMessage { chat_id: &chat_id, text, disable_web_page_preview: true };
+    // reqwest errors carry the URL, which carries the token
+    let resp = CLIENT.post(&url).json(&body).send().await.map_err(|e| e.without_url())?;
+    let status = resp.status();
+    let reply: ApiResponse = resp.json().await.map_err(|e| e.without_url())?;
+    if reply.ok {
+        return Ok(());
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = reply.parameters.and_then(|p| p.retry_after).unwrap_or(5);
+        return Err(NotifierError::RateLimited(retry_after));
+    }
+    Err(NotifierError::Api(status.as_u16(), reply.description.unwrap_or_default()))
+}
+
+/// Sends `text` to the chat, retrying with backoff (or Telegram's own `retry_after`); a client
+/// error other than 429 is permanent and dropped straight away.
+pub async fn deliver(text: &str) -> bool {
+    let mut delay = Duration::from_secs(1);
+    for attempt in 1..=MAX_ATTEMPTS {
+        match send(text).await {
+            Ok(()) => return true,
+            Err(e) => {
+                METRICS.record_error("notifier");
+                warn!(attempt, error = %e, "Failed to send Telegram notification");
+                let wait = match e {
+                    NotifierError::RateLimited(secs) => Duration::from_secs(secs),
+                    NotifierError::Api(code, _) if (400..500).contains(&code) => return false,
+                    NotifierError::MissingToken => return false,
+                    _ => delay,
+                };
+                if attempt < MAX_ATTEMPTS {
+                    sleep(wait).await;
+                }
+                delay *= 4;
+            }
+        }
+    }
+    false
+}
+
+/// Forwards every published signal and `notify` price alert to Telegram, batching those within
+/// `NOTIFY_BATCH_SECS` into one message and spacing messages to stay under Telegram's rate limit.
+///
+/// Subscribes to the broadcast channel, so it only sees what this instance publishes:
+/// standby instances and shard workers stay quiet, the leader or aggregator sends. Signals a
+/// standby relays from the cluster leader are skipped too.
+pub async fn notifier_task(tx: broadcast::Sender<WsMessage>) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    info!(batch_secs = batch_secs(), "Telegram notifications enabled");
+    let mut rx = tx.subscribe();
+    let window = Duration::from_secs(batch_secs());
+    let mut last_sent = Instant::now() - MIN_SEND_INTERVAL;
+    loop {
+        let mut batch = match next_notification(&mut rx).await {
+            Some(text) => vec![text],
+            None => return,
+        };
+        let deadline = Instant::now() + window;
+        while let Ok(Some(text)) = tokio::time::timeout_at(deadline, next_notification(&mut rx)).await {
+            batch.push(text);
+        }
+        for chunk in batch.chunks(SIGNALS_PER_MESSAGE) {
+            let text = chunk.join("\n\n");
+            sleep(MIN_SEND_INTERVAL.saturating_sub(last_sent.elapsed())).await;
+            if deliver(&text).await {
+                info!(items = chunk.len(), "Telegram notification sent");
+            }
+            last_sent = Instant::now();
+        }
+    }
+}
+
+// Next published signal or `notify` alert, formatted; None once the channel is closed
+async fn next_notification(rx: &mut broadcast::Receiver<WsMessage>) -> Option<String> {
+    loop {
+        match rx.recv().await {
+            Ok(WsMessage::Signal(signal)) if !signal.relayed => return Some(format_signal(&signal)),
+            Ok(WsMessage::AlertTriggered(alert)) if alert.notify => return Some(format_alert(&alert)),
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                METRICS.record_error("notifier");
+                warn!(skipped, "Notifier lagged behind the broadcast channel");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Next signal this instance published, from the broadcast channel; None once it is closed.
+/// Signals relayed from another cluster instance are passed over, that instance acted on them.
+pub async fn next_signal(rx: &mut broadcast::Receiver<WsMessage>) -> Option<Signal> {
+    loop {
+        match rx.recv().await {
+            Ok(WsMessage::Signal(signal)) if !signal.relayed => return Some(*signal),
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                METRICS.record_error("notifier");
+                warn!(skipped, "Notifier lagged behind the broadcast channel");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::exchange::MarketType;
+use crate::metrics::METRICS;
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tracing::{debug, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Open interest history for the symbols that are moving, so a signal can tell new positions
+// (OI rising with it) from positions being closed (OI falling). A single snapshot, as the
+// verifier fetches, says nothing about direction.
+
+// Samples are kept a little past the longest change measured
+const KEEP_MS: i64 = 70 * 60 * 1000;
+// Signals stay live (and keep their symbol tracked) this long, as for live updates
+const ACTIVE_MS: i64 = 60 * 60 * 1000;
+
+fn poll_secs() -> u64 {
+    std::env::var("OI_POLL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60)
+}
+
+// Symbols polled per round besides those with a live signal; 0 turns tracking off
+fn max_symbols() -> usize {
+    std::env::var("OI_TRACK_MAX_SYMBOLS").ok().and_then(|v| v.parse().ok()).unwrap_or(50)
+}
+
+/// A symbol's polled open interest (contracts), oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OiHistory {
+    samples: VecDeque<(i64, f64)>, // (ms, open interest)
+}
+
+impl OiHistory {
+    pub fn record(&mut self, time: i64, open_interest: f64) {
+        if self.samples.back().is_some_and(|(t, _)| *t >= time) {
+            return;
+        }
+        self.samples.push_back((time, open_interest));
+        while self.samples.front().is_some_and(|(t, _)| *t < time - KEEP_MS) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Percent change from the last sample at least `minutes` before the latest one. None
+    /// until the symbol has been tracked that long, or if polling had a gap around then.
+    pub fn change_pct(&self, minutes: i64) -> Option<f64> {
+        let (latest_time, latest) = *self.samples.back()?;
+        let target = latest_time - minutes * 60_000;
+        let (time, base) = self.samples.iter().rev().find(|(t, _)| *t <= target)?;
+        let slack = 2 * poll_secs() as i64 * 1000;
+        (*time >= target - slack && *base > 0.0).then(|| (latest - base) / base * 100.0)
+    }
+
+    /// Changes over 5, 15 and 60 minutes; None if not even the 5 minute one is known yet.
+    pub fn changes(&self) -> Option<OiChange> {
+        Some(OiChange { m5: self.change_pct(5)?, m15: self.change_pct(15), m60: self.change_pct(60) })
+    }
+}
+
+/// Open interest change in percent up to the signal.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OiChange {
+    pub m5: f64,
+    pub m15: Option<f64>,
+    pub m60: Option<f64>,
+}
+
+// Symbols with a live signal, then the busiest last minutes by volume vs their average
+fn tracked(store: &SharedState, now: i64) -> Vec<String> {
+    let max = max_symbols();
+    if max == 0 {
+        return Vec::new();
+    }
+    let mut live = Vec::new();
+    let mut active: Vec<(String, f64)> = Vec::new();
+    for entry in store.iter() {
+        // Spot has no open interest
+        if crate::exchange::split(entry.key()).0.market() == MarketType::Spot {
+            continue;
+        }
+        let state = entry.value();
+        if state.active_signal.is_some() && state.last_signal_time.is_some_and(|t| now - t < ACTIVE_MS) {
+            live.push(entry.key().clone());
+            continue;
+        }
+        let average = state.get_average_volume();
+        if let Some(last) = state.window.back().filter(|_| average > 0.0) {
+            active.push((entry.key().clone(), last.volume / average));
+        }
+    }
+    active.sort_by(|a, b| b.1.total_cmp(&a.1));
+    live.extend(active.into_iter().take(max).map(|(symbol, _)| symbol));
+    live
+}
+
+/// Polls open interest every `OI_POLL_SECS` for the symbols that are moving (see `tracked`)
+/// into their `SymbolState::open_interest`. Off with `OI_TRACK_MAX_SYMBOLS=0`.
+pub async fn oi_task(store: SharedState) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(poll_secs().max(1)));
+    loop {
+        interval.tick().await;
+        let symbols = tracked(&store, chrono::Utc::now().timestamp_millis());
+        for symbol in &symbols {
+            // Fetched outside the store entry, never hold a shard lock across an await
+            match crate::exchange::open_interest(symbol).await {
+                Ok(oi) => {
+                    if let Some(mut state) = store.get_mut(symbol) {
+                        state.open_interest.record(chrono::Utc::now().timestamp_millis(), oi);
+                    }
+                }
+                Err(e) => {
+                    METRICS.record_error("oi_tracker");
+                    warn!(symbol = %symbol, error = %e, "Failed to fetch open interest");
+                }
+            }
+        }
+        debug!(symbols = symbols.len(), "Open interest polled");
+    }
+}
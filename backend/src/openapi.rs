@@ -0,0 +1,97 @@
+use std::sync::Arc;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::Config;
+use warp::http::{StatusCode, Uri};
+use warp::path::{FullPath, Tail};
+use warp::{Filter, Rejection, Reply};
+
+pub const SPEC_PATH: &str = "/api/openapi.json";
+
+/// OpenAPI document for the REST routes. Path docs live on the handlers in `ws_server`.
+///
+/// The live feed is a WebSocket (`/ws`) and can't be described here; its message shapes
+/// are the `Signal`/`SignalUpdate` schemas below, wrapped as documented in `wire`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Teeb Trade Scanner API", description = "Monitoring and shard ingest endpoints of the scanner backend."),
+    paths(
+        crate::ws_server::metrics,
+        crate::ws_server::symbol_metrics,
+        crate::ws_server::ready,
+        crate::ws_server::health,
+        crate::ws_server::heatmap,
+        crate::ws_server::carry,
+        crate::ws_server::funding_rates,
+        crate::ws_server::funding_rate,
+        crate::ws_server::symbol_detail,
+        crate::ws_server::klines,
+        crate::ws_server::klines_for_symbol,
+        crate::ws_server::symbols,
+        crate::ws_server::signals,
+        crate::ws_server::suppressed,
+        crate::ws_server::stats,
+        crate::ws_server::stats_breakdown,
+        crate::ws_server::outcome,
+        crate::ws_server::list_watch_rules,
+        crate::ws_server::add_watch_rule,
+        crate::ws_server::remove_watch_rule,
+        crate::ws_server::list_watched_symbols,
+        crate::ws_server::watch_symbol,
+        crate::ws_server::unwatch_symbol,
+        crate::ws_server::list_price_alerts,
+        crate::ws_server::add_price_alert,
+        crate::ws_server::remove_price_alert,
+        crate::ws_server::signal_feedback,
+        crate::ws_server::admin_config,
+        crate::ws_server::admin_command,
+        crate::ws_server::ingest_signal,
+        crate::ws_server::ingest_updates,
+        crate::ws_server::ingest_invalidation,
+        crate::ws_server::ingest_transition,
+        crate::ws_server::tradingview_alert,
+        crate::ws_server::portfolio,
+    ),
+    tags(
+        (name = "monitoring", description = "Metrics, health and readiness"),
+        (name = "market", description = "Market data computed from the scanner's windows"),
+        (name = "history", description = "Recorded signals, their outcomes and stats"),
+        (name = "watchlist", description = "User watch rules, alerted on the WebSocket as WatchAlert, and watched symbols"),
+        (name = "alerts", description = "One-shot price and volume alerts, delivered on the WebSocket as AlertTriggered"),
+        (name = "feedback", description = "Traders' verdicts on signals"),
+        (name = "admin", description = "Runtime changes to the scanner; need an admin token"),
+        (name = "sharding", description = "Served by the aggregator; workers forward signals here"),
+        (name = "signals", description = "External signal sources (TradingView alerts) and candidates the scanner dropped"),
+        (name = "portfolio", description = "Read-only Binance Futures account, correlated with signals"),
+    )
+)]
+pub struct ApiDoc;
+
+/// `/api/openapi.json` plus a Swagger UI (bundled into the binary) at `/api/docs/`.
+pub fn routes() -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    let spec = ApiDoc::openapi();
+    let spec_route = warp::path!("api" / "openapi.json")
+        .and(warp::get())
+        .map(move || Box::new(warp::reply::json(&spec)) as Box<dyn Reply>);
+
+    let config = Arc::new(Config::from(SPEC_PATH));
+    let docs_route = warp::path("api")
+        .and(warp::path("docs"))
+        .and(warp::get())
+        .and(warp::path::full())
+        .and(warp::path::tail())
+        .map(move |full: FullPath, tail: Tail| serve_swagger_ui(full, tail, config.clone()));
+
+    spec_route.or(docs_route).unify()
+}
+
+fn serve_swagger_ui(full: FullPath, tail: Tail, config: Arc<Config<'static>>) -> Box<dyn Reply> {
+    // The UI loads its assets relative to the page, so it must be served from a trailing slash
+    if full.as_str() == "/api/docs" {
+        return Box::new(warp::redirect::found(Uri::from_static("/api/docs/")));
+    }
+    match utoipa_swagger_ui::serve(tail.as_str(), config) {
+        Ok(Some(file)) => Box::new(warp::reply::with_header(file.bytes.into_owned(), "Content-Type", file.content_type)),
+        Ok(None) => Box::new(StatusCode::NOT_FOUND),
+        Err(e) => Box::new(warp::reply::with_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR)),
+    }
+}
@@ -0,0 +1,210 @@
+use crate::binance_client::{FUTURES_STREAM_URL, MAX_STREAMS_PER_CONNECTION};
+use crate::footprint::{AggTrade, Combined};
+use crate::metrics::METRICS;
+use crate::model::MarketData;
+use crate::store::SharedState;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tracing::{debug, info};
+use url::Url;
+
+// Taker buy vs sell for feeds that don't report it (`!ticker@arr`), so a spike's direction
+// follows real order flow instead of falling through to Short. A Binance futures symbol whose
+// minute volume so far reaches `ORDER_FLOW_RAMP_RATIO` times its average (default 1.5, below
+// the spike bar so the flow is streaming by the time it fires; 0 turns this off) gets its
+// `<symbol>@aggTrade` stream, for `HOLD_MS` after the last time it did. Takers' buy and sell
+// notional is summed per minute, and the candle's taker-buy volume is its volume times the
+// bought share. The set moves every few seconds, so streams are (un)subscribed on one open
+// connection rather than reconnecting for every change.
+
+// How often the wanted set is compared with what's subscribed
+const RESCAN: tokio::time::Duration = tokio::time::Duration::from_secs(2);
+// Kept subscribed this long after the volume last ramped: the rest of its minute and the next
+const HOLD_MS: i64 = 3 * 60 * 1000;
+// Minutes of flow kept per symbol: the one in progress and the one just finished
+const MAX_MINUTES: usize = 2;
+
+static RAMP_RATIO: LazyLock<f64> =
+    LazyLock::new(|| std::env::var("ORDER_FLOW_RAMP_RATIO").ok().and_then(|v| v.parse().ok()).unwrap_or(1.5));
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrderFlowError {
+    #[error("invalid stream url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("websocket error: {0}")]
+    Ws(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("stream closed by server")]
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FlowMinute {
+    minute: i64,
+    buy: f64, // Taker notional, quote asset
+    sell: f64,
+}
+
+/// A symbol's streamed taker flow, newest minute last. Not snapshotted.
+#[derive(Debug, Clone, Default)]
+pub struct OrderFlow {
+    minutes: VecDeque<FlowMinute>,
+}
+
+impl OrderFlow {
+    fn record(&mut self, trade_time: i64, notional: f64, taker_buy: bool) {
+        let minute = trade_time / 60_000 * 60_000;
+        match self.minutes.back() {
+            Some(last) if last.minute > minute => return, // Late trade for a minute already moved past
+            Some(last) if last.minute == minute => {}
+            _ => {
+                self.minutes.push_back(FlowMinute { minute, buy: 0.0, sell: 0.0 });
+                if self.minutes.len() > MAX_MINUTES {
+                    self.minutes.pop_front();
+                }
+            }
+        }
+        if let Some(flow) = self.minutes.back_mut() {
+            if taker_buy {
+                flow.buy += notional;
+            } else {
+                flow.sell += notional;
+            }
+        }
+    }
+
+    /// Gives a candle whose feed had no taker split one from the streamed trades of its minute.
+    /// Trades only count from the subscription on, so it's the share that is applied.
+    pub fn fill(&self, candle: &mut MarketData) {
+        if candle.taker_buy_volume.is_some() {
+            return;
+        }
+        let minute = candle.timestamp.div_euclid(60_000) * 60_000;
+        let Some(flow) = self.minutes.iter().find(|m| m.minute == minute) else {
+            return;
+        };
+        let total = flow.buy + flow.sell;
+        if total > 0.0 {
+            let bought = flow.buy / total;
+            candle.taker_buy_volume = Some(candle.volume * bought);
+            candle.taker_buy_quote_volume = Some(candle.quote_volume * bought);
+        }
+    }
+}
+
+// Symbol -> when its volume last ramped (event time)
+static WANTED: LazyLock<Mutex<HashMap<String, i64>>> = LazyLock::new(Default::default);
+
+/// Asks for `symbol`'s trades while its minute volume is ramping. Called by the actor on
+/// ticks without a taker split.
+pub fn watch_if_ramping(symbol: &str, volume: f64, avg_volume: f64, now: i64) {
+    let ratio = *RAMP_RATIO;
+    if ratio <= 0.0 || avg_volume <= 0.0 || volume < avg_volume * ratio {
+        return;
+    }
+    if crate::exchange::split(symbol).0 != crate::exchange::Exchange::Binance {
+        return;
+    }
+    WANTED.lock().unwrap_or_else(|e| e.into_inner()).insert(symbol.to_string(), now);
+}
+
+/// Stops streaming `symbol`'s trades (on the next rescan), e.g. once it is delisted.
+pub fn forget(symbol: &str) {
+    WANTED.lock().unwrap_or_else(|e| e.into_inner()).remove(symbol);
+}
+
+// The symbols to stream now, most recently ramped first up to one connection's worth
+fn wanted(now: i64) -> BTreeSet<String> {
+    let mut wanted = WANTED.lock().unwrap_or_else(|e| e.into_inner());
+    wanted.retain(|_, at| now - *at < HOLD_MS);
+    let mut recent: Vec<(&String, &i64)> = wanted.iter().collect();
+    recent.sort_by(|a, b| b.1.cmp(a.1));
+    recent.into_iter().take(MAX_STREAMS_PER_CONNECTION).map(|(symbol, _)| symbol.clone()).collect()
+}
+
+fn record(store: &SharedState, trade: &AggTrade) {
+    let (Ok(price), Ok(quantity)) = (trade.price.parse::<f64>(), trade.quantity.parse::<f64>()) else {
+        METRICS.record_error("order_flow");
+        return;
+    };
+    if let Some(mut state) = store.get_mut(&trade.symbol) {
+        // The buyer being the maker means the taker sold
+        state.order_flow.record(trade.trade_time, price * quantity, !trade.buyer_maker);
+    }
+}
+
+// A trade, or the reply to a (un)subscribe
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Frame {
+    Trade(Combined),
+    Reply { id: u64 },
+}
+
+fn method(method: &str, symbols: &[&String], id: u64) -> Message {
+    let params: Vec<String> = symbols.iter().map(|s| format!("{}@aggTrade", s.to_lowercase())).collect();
+    Message::Text(serde_json::json!({ "method": method, "params": params, "id": id }).to_string())
+}
+
+// One connection, following the wanted set until nothing is wanted (Ok) or it fails
+async fn stream(store: &SharedState) -> Result<(), OrderFlowError> {
+    let (ws_stream, _) = connect_async(Url::parse(FUTURES_STREAM_URL)?).await?;
+    let (mut write, mut read) = ws_stream.split();
+    info!("Connected to aggTrade streams for order flow");
+
+    let mut subscribed = BTreeSet::new();
+    let mut next_id = 0;
+    let mut rescan = tokio::time::interval(RESCAN);
+    loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Frame>(&text) {
+                    Ok(Frame::Trade(combined)) => record(store, &combined.data),
+                    Ok(Frame::Reply { id }) => debug!(id, "Order flow subscription changed"),
+                    Err(e) => {
+                        METRICS.record_error("order_flow");
+                        debug!(error = %e, "Failed to parse aggTrade message");
+                    }
+                },
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(OrderFlowError::Closed),
+            },
+            _ = rescan.tick() => {
+                let wanted = wanted(chrono::Utc::now().timestamp_millis());
+                if wanted.is_empty() {
+                    return Ok(());
+                }
+                let added: Vec<&String> = wanted.difference(&subscribed).collect();
+                let removed: Vec<&String> = subscribed.difference(&wanted).collect();
+                if !removed.is_empty() {
+                    next_id += 1;
+                    write.send(method("UNSUBSCRIBE", &removed, next_id)).await?;
+                }
+                if !added.is_empty() {
+                    next_id += 1;
+                    write.send(method("SUBSCRIBE", &added, next_id)).await?;
+                    debug!(added = added.len(), streams = wanted.len(), "Order flow streams changed");
+                }
+                subscribed = wanted;
+            }
+        }
+    }
+}
+
+/// Streams aggTrades for symbols whose volume is ramping on a feed without taker volume,
+/// connected only while there are any. Off with `ORDER_FLOW_RAMP_RATIO=0`.
+pub async fn order_flow_task(store: SharedState) -> Result<(), OrderFlowError> {
+    if *RAMP_RATIO <= 0.0 {
+        std::future::pending::<()>().await;
+    }
+    loop {
+        if wanted(chrono::Utc::now().timestamp_millis()).is_empty() {
+            tokio::time::sleep(RESCAN).await;
+            continue;
+        }
+        stream(&store).await?;
+    }
+}
@@ -0,0 +1,356 @@
+// Paper trading: a virtual position on every published signal, closed at take-profit,
+// stop-loss or after a maximum hold, with the realized PnL stored in the signal's history
+// record. Opt-in with PAPER_TRADING_ENABLED=1.
+use crate::history::HistoryManager;
+use crate::metrics::METRICS;
+use crate::scanner::{Signal, SignalType, WsMessage};
+use crate::store::SharedState;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::{Arc, LazyLock, RwLock};
+use tokio::sync::{broadcast, Notify};
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// Open positions go out at most this often, and whenever one opens or closes
+const BROADCAST_EVERY_SECS: u64 = 5;
+
+pub fn enabled() -> bool {
+    std::env::var("PAPER_TRADING_ENABLED").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+pub fn paper_positions_path() -> String {
+    std::env::var("PAPER_POSITIONS_PATH").unwrap_or_else(|_| "paper_positions.json".to_string())
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+/// Position sizing and exits, from the environment.
+#[derive(Debug, Clone)]
+pub struct PaperConfig {
+    pub size_usdt: f64,        // PAPER_POSITION_SIZE, notional per position
+    pub take_profit_pct: f64,  // PAPER_TAKE_PROFIT_PCT
+    pub stop_loss_pct: f64,    // PAPER_STOP_LOSS_PCT, defaults to the invalidation threshold
+    pub max_hold_secs: i64,    // PAPER_MAX_HOLD_SECS, closed at market after this
+}
+
+impl PaperConfig {
+    pub fn from_env() -> Self {
+        Self {
+            size_usdt: env_or("PAPER_POSITION_SIZE", 1000.0),
+            take_profit_pct: env_or("PAPER_TAKE_PROFIT_PCT", 1.0),
+            stop_loss_pct: env_or("PAPER_STOP_LOSS_PCT", crate::actor::invalidation_pct()),
+            max_hold_secs: env_or("PAPER_MAX_HOLD_SECS", 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PaperError {
+    #[error("paper positions io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("paper positions (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum CloseReason {
+    TakeProfit,
+    StopLoss,
+    Expired, // Held for `PAPER_MAX_HOLD_SECS` without reaching either
+}
+
+/// One simulated position, opened at the signal price. PnL is in USDT, without fees.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub signal_id: String,
+    pub symbol: String,
+    pub side: SignalType,
+    pub entry_price: f64,
+    pub quantity: f64, // Base asset
+    pub take_profit: f64,
+    pub stop_loss: f64,
+    pub opened_at: i64,
+    pub mark_price: f64,
+    pub unrealized_pnl: f64,
+    pub closed_at: Option<i64>,
+    pub exit_price: Option<f64>,
+    pub realized_pnl: Option<f64>,
+    pub close_reason: Option<CloseReason>,
+}
+
+impl Position {
+    fn open(signal: &Signal, config: &PaperConfig, now: i64) -> Option<Self> {
+        if signal.price <= 0.0 {
+            return None;
+        }
+        let (tp, sl) = (config.take_profit_pct / 100.0, config.stop_loss_pct / 100.0);
+        let (take_profit, stop_loss) = match signal.signal_type {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => (signal.price * (1.0 + tp), signal.price * (1.0 - sl)),
+            SignalType::Short => (signal.price * (1.0 - tp), signal.price * (1.0 + sl)),
+        };
+        Some(Self {
+            signal_id: signal.id.clone(),
+            symbol: signal.symbol.clone(),
+            side: signal.signal_type.clone(),
+            entry_price: signal.price,
+            quantity: config.size_usdt / signal.price,
+            take_profit,
+            stop_loss,
+            opened_at: now,
+            mark_price: signal.price,
+            unrealized_pnl: 0.0,
+            closed_at: None,
+            exit_price: None,
+            realized_pnl: None,
+            close_reason: None,
+        })
+    }
+
+    fn pnl_at(&self, price: f64) -> f64 {
+        match self.side {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => (price - self.entry_price) * self.quantity,
+            SignalType::Short => (self.entry_price - price) * self.quantity,
+        }
+    }
+
+    // Marks to `price` and says whether that closes the position
+    fn mark(&mut self, price: f64, now: i64, max_hold_ms: i64) -> Option<CloseReason> {
+        self.mark_price = price;
+        self.unrealized_pnl = self.pnl_at(price);
+        let (tp_hit, sl_hit) = match self.side {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => (price >= self.take_profit, price <= self.stop_loss),
+            SignalType::Short => (price <= self.take_profit, price >= self.stop_loss),
+        };
+        if sl_hit {
+            Some(CloseReason::StopLoss)
+        } else if tp_hit {
+            Some(CloseReason::TakeProfit)
+        } else if now - self.opened_at >= max_hold_ms {
+            Some(CloseReason::Expired)
+        } else {
+            None
+        }
+    }
+
+    fn close(&mut self, reason: CloseReason, now: i64) {
+        self.closed_at = Some(now);
+        self.exit_price = Some(self.mark_price);
+        self.realized_pnl = Some(self.unrealized_pnl);
+        self.unrealized_pnl = 0.0;
+        self.close_reason = Some(reason);
+    }
+}
+
+/// Open positions, as broadcast in `WsMessage::Positions`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Positions {
+    pub open: Vec<Position>,
+    pub unrealized_pnl: f64,
+    pub updated_at: i64,
+}
+
+/// Paper trading totals for `Stats`: closed trades from history, open ones from the trader.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperStats {
+    pub closed_trades: usize,
+    pub wins: usize,
+    pub realized_pnl: f64,
+    pub open_positions: usize,
+    pub unrealized_pnl: f64,
+}
+
+static CURRENT: LazyLock<RwLock<Option<Positions>>> = LazyLock::new(Default::default);
+
+/// The latest open positions; None unless paper trading is enabled.
+pub fn current() -> Option<Positions> {
+    CURRENT.read().ok().and_then(|c| c.clone())
+}
+
+/// Open positions by signal id, persisted to `PAPER_POSITIONS_PATH` so a restart doesn't
+/// forget them. Closed positions live in history.
+pub struct PaperTrader {
+    positions: DashMap<String, Position>,
+    config: PaperConfig,
+    file_path: String,
+    dirty: Notify,
+}
+
+impl PaperTrader {
+    pub fn new(file_path: &str) -> Self {
+        let positions = match read_positions(file_path) {
+            Ok(positions) => positions,
+            Err(PaperError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                METRICS.record_error("paper_trader");
+                let backup = format!("{}.corrupt-{}", file_path, chrono::Utc::now().timestamp());
+                error!(path = file_path, backup = %backup, error = %e, "Unreadable paper positions file, starting empty");
+                if let Err(e) = fs::rename(file_path, &backup) {
+                    error!(path = file_path, error = %e, "Failed to back up unreadable paper positions file");
+                }
+                Vec::new()
+            }
+        };
+        let map = DashMap::new();
+        for position in positions {
+            map.insert(position.signal_id.clone(), position);
+        }
+        Self { positions: map, config: PaperConfig::from_env(), file_path: file_path.to_string(), dirty: Notify::new() }
+    }
+
+    pub fn list(&self) -> Vec<Position> {
+        let mut positions: Vec<Position> = self.positions.iter().map(|p| p.value().clone()).collect();
+        positions.sort_by_key(|p| p.opened_at);
+        positions
+    }
+
+    /// Opens a position for the signal, once per signal id.
+    pub fn open(&self, signal: &Signal, now: i64) -> Option<Position> {
+        if self.positions.contains_key(&signal.id) {
+            return None;
+        }
+        let position = Position::open(signal, &self.config, now)?;
+        self.positions.insert(position.signal_id.clone(), position.clone());
+        self.dirty.notify_one();
+        Some(position)
+    }
+
+    /// Marks every position to the latest price and returns the ones that closed.
+    pub fn mark(&self, store: &SharedState, now: i64) -> Vec<Position> {
+        let max_hold_ms = self.config.max_hold_secs * 1000;
+        let mut closed = Vec::new();
+        for mut entry in self.positions.iter_mut() {
+            let position = entry.value_mut();
+            let price = last_price(store, &position.symbol).unwrap_or(position.mark_price);
+            if let Some(reason) = position.mark(price, now, max_hold_ms) {
+                position.close(reason, now);
+                closed.push(position.clone());
+            }
+        }
+        for position in &closed {
+            self.positions.remove(&position.signal_id);
+        }
+        if !closed.is_empty() {
+            self.dirty.notify_one();
+        }
+        closed
+    }
+
+    pub async fn save(&self) {
+        let json = match serde_json::to_string_pretty(&self.list()) {
+            Ok(json) => json,
+            Err(e) => {
+                METRICS.record_error("paper_trader");
+                warn!(path = %self.file_path, error = %e, "Failed to serialize paper positions");
+                return;
+            }
+        };
+        let path = self.file_path.clone();
+        let result = tokio::task::spawn_blocking(move || fs::write(&path, json)).await;
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        METRICS.record_error("paper_trader");
+        warn!(path = %self.file_path, error = %error, "Failed to save paper positions");
+    }
+}
+
+pub fn read_positions(file_path: &str) -> Result<Vec<Position>, PaperError> {
+    Ok(serde_json::from_str(&fs::read_to_string(file_path)?)?)
+}
+
+// The price of the last tick (the candle in progress), else the last finished minute's close
+fn last_price(store: &SharedState, symbol: &str) -> Option<f64> {
+    store
+        .volume_cache
+        .get(symbol)
+        .map(|b| b.close)
+        .or_else(|| store.get(symbol).and_then(|s| s.window.back().map(|c| c.close)))
+        .filter(|p| *p > 0.0)
+}
+
+/// Persists open positions whenever one opened or closed.
+pub async fn persist_task(trader: Arc<PaperTrader>) {
+    loop {
+        trader.dirty.notified().await;
+        trader.save().await;
+    }
+}
+
+fn publish(trader: &PaperTrader, tx: &broadcast::Sender<WsMessage>, now: i64) {
+    let open = trader.list();
+    let positions = Positions { unrealized_pnl: open.iter().fold(0.0, |sum, p| sum + p.unrealized_pnl), open, updated_at: now };
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(positions.clone());
+    }
+    let _ = tx.send(WsMessage::Positions(positions));
+}
+
+/// Opens a position on every published signal and marks open ones to market every second.
+/// Closed positions are written to the signal's history record and followed by fresh `Stats`.
+///
+/// Follows the broadcast channel like the notifiers, so it trades what this instance
+/// publishes; prices come from this instance's store, so it needs an unsharded setup.
+pub async fn paper_trader_task(
+    trader: Arc<PaperTrader>,
+    store: SharedState,
+    history: Arc<HistoryManager>,
+    tx: broadcast::Sender<WsMessage>,
+) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    info!(config = ?trader.config, open = trader.positions.len(), "Paper trading enabled");
+    let mut rx = tx.subscribe();
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    let mut ticks = 0u64;
+    publish(&trader, &tx, chrono::Utc::now().timestamp_millis());
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut changed = false;
+        tokio::select! {
+            received = rx.recv() => match received {
+                Ok(WsMessage::Signal(signal)) if !signal.relayed => {
+                    if let Some(position) = trader.open(&signal, now) {
+                        info!(symbol = %position.symbol, signal_id = %position.signal_id, side = ?position.side, entry = position.entry_price, "Paper position opened");
+                        changed = true;
+                    }
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    METRICS.record_error("paper_trader");
+                    warn!(skipped, "Paper trader lagged behind the broadcast channel");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = ticker.tick() => {
+                ticks += 1;
+                for position in trader.mark(&store, now) {
+                    info!(symbol = %position.symbol, signal_id = %position.signal_id, reason = ?position.close_reason, pnl = ?position.realized_pnl, "Paper position closed");
+                    if !history.record_paper_trade(&position).await {
+                        warn!(signal_id = %position.signal_id, "No history record for closed paper position");
+                    }
+                    changed = true;
+                }
+                if changed {
+                    let _ = tx.send(WsMessage::Stats(Box::new(history.get_stats().await)));
+                }
+                changed |= ticks.is_multiple_of(BROADCAST_EVERY_SECS) && !trader.positions.is_empty();
+            }
+        }
+        if changed {
+            publish(&trader, &tx, now);
+        }
+    }
+}
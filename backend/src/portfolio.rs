@@ -0,0 +1,378 @@
+// Read-only view of the Binance Futures account behind the dashboard: balance, open positions
+// and recent fills, each fill tied to the signal it traded where one fits. Off unless
+// PORTFOLIO_ENABLED=true; needs BINANCE_API_KEY / BINANCE_API_SECRET, and a key with only
+// "read" permission is enough (nothing here places or cancels orders).
+//
+// A fill belongs to a signal when it is on the signal's symbol, on the signal's side, and
+// within PORTFOLIO_MATCH_MINUTES after it. Fills on the other side that follow are the exits of
+// that trade, so a signal's realized P&L is what its symbol realized until the next signal's
+// entry. Fills without a matching signal are manual trades and stay unattributed.
+use crate::executor::{parse, signed, ExecutorError};
+use crate::history::{HistoryManager, HistoryQuery};
+use crate::metrics::METRICS;
+use crate::scanner::{Signal, SignalType, WsMessage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, LazyLock, RwLock};
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tracing::{error, info};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+pub fn enabled() -> bool {
+    std::env::var("PORTFOLIO_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioConfig {
+    pub poll_secs: u64,       // PORTFOLIO_POLL_SECS
+    pub lookback_hours: i64,  // PORTFOLIO_LOOKBACK_HOURS, fills and signals considered (Binance caps fills at 7 days)
+    pub match_minutes: i64,   // PORTFOLIO_MATCH_MINUTES, entry after a signal that still counts as trading it
+    pub max_symbols: usize,   // PORTFOLIO_MAX_SYMBOLS, symbols whose fills are fetched per poll
+}
+
+impl PortfolioConfig {
+    pub fn from_env() -> Self {
+        Self {
+            poll_secs: env_or("PORTFOLIO_POLL_SECS", 60u64).max(10),
+            lookback_hours: env_or("PORTFOLIO_LOOKBACK_HOURS", 24i64).clamp(1, 24 * 7),
+            match_minutes: env_or("PORTFOLIO_MATCH_MINUTES", 15),
+            max_symbols: env_or("PORTFOLIO_MAX_SYMBOLS", 20),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Account {
+    total_wallet_balance: String,
+    total_unrealized_profit: String,
+    total_margin_balance: String,
+    available_balance: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionRisk {
+    symbol: String,
+    position_amt: String,
+    entry_price: String,
+    mark_price: String,
+    un_realized_profit: String,
+    leverage: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserTrade {
+    id: u64,
+    symbol: String,
+    side: String,
+    price: String,
+    qty: String,
+    realized_pnl: String,
+    commission: String,
+    commission_asset: String,
+    time: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+    pub wallet: f64,
+    pub unrealized_pnl: f64,
+    pub margin_balance: f64,
+    pub available: f64,
+}
+
+/// An open futures position, with the signal it was entered on if any.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPosition {
+    pub symbol: String,
+    pub side: SignalType, // Long or Short
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub mark_price: f64,
+    pub unrealized_pnl: f64,
+    pub leverage: u32,
+    pub signal_id: Option<String>,
+}
+
+/// One execution on the account.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub id: u64,
+    pub symbol: String,
+    pub side: String, // BUY or SELL
+    pub price: f64,
+    pub quantity: f64,
+    pub realized_pnl: f64,
+    pub commission: f64,
+    pub commission_asset: String,
+    pub time: i64,
+    pub signal_id: Option<String>,
+}
+
+/// A signal that was actually traded, and how the trade went.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TradedSignal {
+    pub signal_id: String,
+    pub symbol: String,
+    pub signal_type: SignalType,
+    pub signal_price: f64,
+    pub signal_time: i64,
+    pub entry_price: f64, // Volume-weighted over the entry fills
+    pub quantity: f64,
+    pub realized_pnl: f64,
+    pub commission: f64, // In each fill's commission asset, summed as is (USDT unless paying in BNB)
+    pub open: bool,      // The position it opened is still on
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioSummary {
+    pub traded_signals: usize,
+    pub closed: usize,
+    pub wins: usize, // Closed with realized P&L above commission
+    pub realized_pnl: f64,
+    pub commission: f64,
+}
+
+/// The account as last polled, broadcast as `WsMessage::Portfolio`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Portfolio {
+    pub balance: AccountBalance,
+    pub positions: Vec<AccountPosition>,
+    pub fills: Vec<Fill>, // Newest first
+    pub traded: Vec<TradedSignal>, // Newest signal first
+    pub summary: PortfolioSummary,
+    pub updated_at: i64,
+}
+
+static CURRENT: LazyLock<RwLock<Option<Portfolio>>> = LazyLock::new(Default::default);
+
+/// The latest snapshot; None unless portfolio tracking is enabled and has polled once.
+pub fn current() -> Option<Portfolio> {
+    CURRENT.read().ok().and_then(|c| c.clone())
+}
+
+fn is_long(signal_type: &SignalType) -> bool {
+    !matches!(signal_type, SignalType::Short)
+}
+
+fn entry_side(signal_type: &SignalType) -> &'static str {
+    if is_long(signal_type) {
+        "BUY"
+    } else {
+        "SELL"
+    }
+}
+
+/// Ties fills to signals (see the module comment) and totals each traded signal. `fills`
+/// and `signals` may come in any order; positions get the signal of their open trade.
+pub fn correlate(fills: &mut [Fill], signals: &[Signal], positions: &mut [AccountPosition], match_ms: i64) -> Vec<TradedSignal> {
+    fills.sort_by_key(|f| f.time);
+    let mut by_symbol: BTreeMap<&str, Vec<&Signal>> = BTreeMap::new();
+    for signal in signals {
+        by_symbol.entry(signal.symbol.as_str()).or_default().push(signal);
+    }
+    for list in by_symbol.values_mut() {
+        list.sort_by_key(|s| s.timestamp);
+    }
+
+    let mut traded: BTreeMap<String, TradedSignal> = BTreeMap::new();
+    let mut entry_value: BTreeMap<String, f64> = BTreeMap::new();
+    let mut holding: BTreeMap<String, &Signal> = BTreeMap::new(); // Symbol -> signal of the trade in progress
+    for fill in fills.iter_mut() {
+        let entry = by_symbol.get(fill.symbol.as_str()).and_then(|list| {
+            list.iter()
+                .rev()
+                .find(|s| s.timestamp <= fill.time && fill.time - s.timestamp <= match_ms)
+                .filter(|s| entry_side(&s.signal_type) == fill.side)
+                .copied()
+        });
+        let signal = match entry {
+            Some(signal) => {
+                holding.insert(fill.symbol.clone(), signal);
+                let record = traded.entry(signal.id.clone()).or_insert_with(|| TradedSignal {
+                    signal_id: signal.id.clone(),
+                    symbol: signal.symbol.clone(),
+                    signal_type: signal.signal_type.clone(),
+                    signal_price: signal.price,
+                    signal_time: signal.timestamp,
+                    entry_price: 0.0,
+                    quantity: 0.0,
+                    realized_pnl: 0.0,
+                    commission: 0.0,
+                    open: false,
+                });
+                record.quantity += fill.quantity;
+                let value = entry_value.entry(signal.id.clone()).or_default();
+                *value += fill.price * fill.quantity;
+                record.entry_price = *value / record.quantity;
+                signal
+            }
+            // An exit of the trade in progress, or a manual trade
+            None => match holding.get(&fill.symbol).filter(|s| entry_side(&s.signal_type) != fill.side) {
+                Some(signal) => signal,
+                None => continue,
+            },
+        };
+        fill.signal_id = Some(signal.id.clone());
+        if let Some(record) = traded.get_mut(&signal.id) {
+            record.realized_pnl += fill.realized_pnl;
+            record.commission += fill.commission;
+        }
+    }
+
+    for position in positions.iter_mut() {
+        let Some(signal) = holding.get(&position.symbol).filter(|s| is_long(&s.signal_type) == is_long(&position.side)) else {
+            continue;
+        };
+        position.signal_id = Some(signal.id.clone());
+        if let Some(record) = traded.get_mut(&signal.id) {
+            record.open = true;
+        }
+    }
+    fills.reverse();
+    let mut traded: Vec<TradedSignal> = traded.into_values().collect();
+    traded.sort_by_key(|t| std::cmp::Reverse(t.signal_time));
+    traded
+}
+
+pub fn summarize(traded: &[TradedSignal]) -> PortfolioSummary {
+    let closed: Vec<&TradedSignal> = traded.iter().filter(|t| !t.open).collect();
+    PortfolioSummary {
+        traded_signals: traded.len(),
+        closed: closed.len(),
+        wins: closed.iter().filter(|t| t.realized_pnl > t.commission).count(),
+        realized_pnl: traded.iter().map(|t| t.realized_pnl).sum(),
+        commission: traded.iter().map(|t| t.commission).sum(),
+    }
+}
+
+async fn balance() -> Result<AccountBalance, ExecutorError> {
+    let account: Account = signed(reqwest::Method::GET, "/fapi/v2/account", &[]).await?;
+    Ok(AccountBalance {
+        wallet: parse(&account.total_wallet_balance)?,
+        unrealized_pnl: parse(&account.total_unrealized_profit)?,
+        margin_balance: parse(&account.total_margin_balance)?,
+        available: parse(&account.available_balance)?,
+    })
+}
+
+async fn positions() -> Result<Vec<AccountPosition>, ExecutorError> {
+    let risks: Vec<PositionRisk> = signed(reqwest::Method::GET, "/fapi/v2/positionRisk", &[]).await?;
+    let mut open = Vec::new();
+    for risk in risks {
+        let amount = parse(&risk.position_amt)?;
+        if amount == 0.0 {
+            continue;
+        }
+        open.push(AccountPosition {
+            symbol: risk.symbol,
+            side: if amount > 0.0 { SignalType::Long } else { SignalType::Short },
+            quantity: amount.abs(),
+            entry_price: parse(&risk.entry_price)?,
+            mark_price: parse(&risk.mark_price)?,
+            unrealized_pnl: parse(&risk.un_realized_profit)?,
+            leverage: risk.leverage.parse().unwrap_or(1),
+            signal_id: None,
+        });
+    }
+    Ok(open)
+}
+
+async fn fills(symbol: &str, since: i64) -> Result<Vec<Fill>, ExecutorError> {
+    let params = [("symbol", symbol.to_string()), ("startTime", since.to_string()), ("limit", "1000".to_string())];
+    let trades: Vec<UserTrade> = signed(reqwest::Method::GET, "/fapi/v1/userTrades", &params).await?;
+    trades
+        .into_iter()
+        .map(|t| {
+            Ok(Fill {
+                id: t.id,
+                price: parse(&t.price)?,
+                quantity: parse(&t.qty)?,
+                realized_pnl: parse(&t.realized_pnl)?,
+                commission: parse(&t.commission)?,
+                symbol: t.symbol,
+                side: t.side,
+                commission_asset: t.commission_asset,
+                time: t.time,
+                signal_id: None,
+            })
+        })
+        .collect()
+}
+
+/// Polls the account once and correlates it with the signals in history.
+pub async fn snapshot(history: &HistoryManager, config: &PortfolioConfig) -> Result<Portfolio, ExecutorError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = now - config.lookback_hours * 3_600_000;
+    let balance = balance().await?;
+    let mut positions = positions().await?;
+
+    // Binance futures signals only: the keys are for that account
+    let query = HistoryQuery { from: Some(since), limit: Some(1000), ..Default::default() };
+    let signals: Vec<Signal> = history
+        .query(query)
+        .await
+        .records
+        .into_iter()
+        .map(|r| r.signal)
+        .filter(|s| s.exchange == crate::exchange::Exchange::Binance)
+        .collect();
+
+    // Symbols with a position first, then the most recently signalled
+    let mut symbols: Vec<String> = positions.iter().map(|p| p.symbol.clone()).collect();
+    let mut seen: BTreeSet<String> = symbols.iter().cloned().collect();
+    for signal in &signals {
+        if seen.insert(signal.symbol.clone()) {
+            symbols.push(signal.symbol.clone());
+        }
+    }
+    symbols.truncate(config.max_symbols);
+
+    let mut all_fills = Vec::new();
+    for symbol in &symbols {
+        all_fills.extend(fills(symbol, since).await?);
+    }
+    let traded = correlate(&mut all_fills, &signals, &mut positions, config.match_minutes * 60_000);
+    let summary = summarize(&traded);
+    Ok(Portfolio { balance, positions, fills: all_fills, traded, summary, updated_at: now })
+}
+
+/// Polls the account every `PORTFOLIO_POLL_SECS` and broadcasts the result.
+pub async fn portfolio_task(history: Arc<HistoryManager>, tx: broadcast::Sender<WsMessage>) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    let config = PortfolioConfig::from_env();
+    info!(config = ?config, "Portfolio tracking enabled");
+    let mut interval = tokio::time::interval(Duration::from_secs(config.poll_secs));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match snapshot(&history, &config).await {
+            Ok(portfolio) => {
+                if let Ok(mut current) = CURRENT.write() {
+                    *current = Some(portfolio.clone());
+                }
+                let _ = tx.send(WsMessage::Portfolio(Box::new(portfolio)));
+            }
+            Err(e) => {
+                METRICS.record_error("portfolio");
+                error!(error = %e, "Portfolio poll failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::exchange::{fetch_json, split, ClientError, Exchange};
+use crate::rest::Cache;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+// How Binance futures traders are positioned on a symbol, for the verifier: the top traders'
+// long/short position ratio and the takers' buy/sell volume ratio over the last 5 minutes
+// (`/futures/data/*`, refreshed by Binance every 5 minutes), and the premium of the mark price
+// over the index (from the funding cache, see `funding`). Each part is None when its source
+// had nothing; the two requests are briefly cached like depth and OI.
+
+const PERIOD: &str = "5m";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LongShortRatio {
+    long_short_ratio: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TakerRatio {
+    buy_sell_ratio: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Positioning {
+    pub top_long_short_ratio: Option<f64>, // Top traders' long vs short positions
+    pub taker_buy_sell_ratio: Option<f64>, // Taker buy vs sell volume
+    pub premium_pct: Option<f64>,          // Mark over index price, percent
+}
+
+static CACHE: LazyLock<Cache<Positioning>> = LazyLock::new(Cache::new);
+
+async fn fetch(client: &reqwest::Client, symbol: &str) -> Result<Positioning, ClientError> {
+    let top_url = format!("{}/futures/data/topLongShortPositionRatio?symbol={}&period={}&limit=1", FUTURES_REST_URL, symbol, PERIOD);
+    let taker_url = format!("{}/futures/data/takerlongshortRatio?symbol={}&period={}&limit=1", FUTURES_REST_URL, symbol, PERIOD);
+    let (top, taker) = tokio::join!(
+        fetch_json::<Vec<LongShortRatio>>(client, &top_url),
+        fetch_json::<Vec<TakerRatio>>(client, &taker_url)
+    );
+    Ok(Positioning {
+        top_long_short_ratio: top?.last().and_then(|r| r.long_short_ratio.parse().ok()),
+        taker_buy_sell_ratio: taker?.last().and_then(|r| r.buy_sell_ratio.parse().ok()),
+        premium_pct: None,
+    })
+}
+
+/// Positioning for a store key. `Unsupported` off Binance futures.
+pub async fn get(symbol: &str) -> Result<Positioning, ClientError> {
+    let (exchange, native) = split(symbol);
+    if exchange != Exchange::Binance {
+        return Err(ClientError::Unsupported { exchange: exchange.name(), what: "trader positioning" });
+    }
+    let native = native.to_string();
+    let mut positioning = CACHE.get_or_fetch(native.clone(), async { fetch(&crate::rest::client(), &native).await }).await?;
+    positioning.premium_pct = crate::funding::get(symbol)
+        .filter(|f| f.index_price > 0.0)
+        .map(|f| (f.mark_price - f.index_price) / f.index_price * 100.0);
+    Ok(positioning)
+}
@@ -0,0 +1,231 @@
+use crate::metrics::METRICS;
+use crate::model::MarketData;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PriceAlertError {
+    #[error("price alert io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("price alert (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("invalid price alert: {0}")]
+    Invalid(String),
+}
+
+pub fn price_alerts_path() -> String {
+    std::env::var("PRICE_ALERTS_PATH").unwrap_or_else(|_| "price_alerts.json".to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum Direction {
+    Above, // Fires once price is at or above the level
+    Below, // Fires once price is at or below the level
+}
+
+/// What an alert's `level` is compared with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+pub enum AlertKind {
+    #[default]
+    Price,       // Last price
+    QuoteVolume, // USDT traded in the current 1m candle so far; fires once it reaches the level
+}
+
+/// A one-shot price or volume trigger. Stays listed after it fires, with `triggeredAt` set,
+/// until deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceAlert {
+    pub id: String,
+    pub symbol: String,
+    #[serde(default)]
+    pub kind: AlertKind,
+    pub level: f64,
+    pub direction: Option<Direction>, // None: fires when price crosses the level either way
+    pub note: Option<String>,
+    #[serde(default)]
+    pub notify: bool, // Also sent to Telegram and `WEBHOOK_URLS` when it fires
+    pub created_at: i64,
+    pub triggered_at: Option<i64>,
+    pub triggered_price: Option<f64>,
+    #[serde(default)]
+    pub triggered_quote_volume: Option<f64>, // The minute's USDT volume when it fired
+    // Last price seen, for detecting crossings
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schema(ignore)]
+    last_price: Option<f64>,
+}
+
+/// Body of `POST /api/alerts` and of the WebSocket `AddAlert` command.
+#[derive(Debug, Clone, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPriceAlert {
+    pub symbol: String,
+    #[serde(default)]
+    pub kind: AlertKind,
+    pub level: f64,
+    #[serde(default)]
+    pub direction: Option<Direction>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub notify: bool,
+}
+
+impl PriceAlert {
+    fn hit(&mut self, candle: &MarketData) -> bool {
+        if self.kind == AlertKind::QuoteVolume {
+            return candle.quote_volume >= self.level;
+        }
+        let price = candle.close;
+        let previous = self.last_price.replace(price);
+        match self.direction {
+            Some(Direction::Above) => price >= self.level,
+            Some(Direction::Below) => price <= self.level,
+            None => previous.is_some_and(|prev| (prev < self.level) != (price < self.level)),
+        }
+    }
+}
+
+/// Pending and fired price alerts, persisted to `PRICE_ALERTS_PATH`.
+///
+/// Grouped by symbol like the watchlist, so unwatched symbols cost one map miss per tick.
+pub struct PriceAlerts {
+    alerts: DashMap<String, Vec<PriceAlert>>,
+    file_path: String,
+    dirty: Notify,
+}
+
+impl PriceAlerts {
+    pub fn new(file_path: &str) -> Self {
+        let alerts = match Self::load(file_path) {
+            Ok(alerts) => alerts,
+            Err(PriceAlertError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                METRICS.record_error("price_alert");
+                let backup = format!("{}.corrupt-{}", file_path, chrono::Utc::now().timestamp());
+                error!(path = file_path, backup = %backup, error = %e, "Unreadable price alerts file, starting empty");
+                if let Err(e) = fs::rename(file_path, &backup) {
+                    error!(path = file_path, error = %e, "Failed to back up unreadable price alerts file");
+                }
+                Vec::new()
+            }
+        };
+        let map: DashMap<String, Vec<PriceAlert>> = DashMap::new();
+        for alert in alerts {
+            map.entry(alert.symbol.clone()).or_default().push(alert);
+        }
+        Self { alerts: map, file_path: file_path.to_string(), dirty: Notify::new() }
+    }
+
+    fn load(file_path: &str) -> Result<Vec<PriceAlert>, PriceAlertError> {
+        let data = fs::read_to_string(file_path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn list(&self) -> Vec<PriceAlert> {
+        let mut alerts: Vec<PriceAlert> = self.alerts.iter().flat_map(|e| e.value().clone()).collect();
+        alerts.sort_by_key(|a| a.created_at);
+        alerts
+    }
+
+    pub fn add(&self, new: NewPriceAlert) -> Result<PriceAlert, PriceAlertError> {
+        if !(new.level.is_finite() && new.level > 0.0) {
+            return Err(PriceAlertError::Invalid("level must be a positive number".to_string()));
+        }
+        let symbol = new.symbol.trim().to_uppercase();
+        if symbol.is_empty() {
+            return Err(PriceAlertError::Invalid("symbol is required".to_string()));
+        }
+        if new.kind == AlertKind::QuoteVolume && new.direction.is_some_and(|d| d != Direction::Above) {
+            return Err(PriceAlertError::Invalid("volume alerts only fire above their level".to_string()));
+        }
+        let alert = PriceAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol: symbol.clone(),
+            kind: new.kind,
+            level: new.level,
+            direction: new.direction,
+            note: new.note,
+            notify: new.notify,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            triggered_at: None,
+            triggered_price: None,
+            triggered_quote_volume: None,
+            last_price: None,
+        };
+        self.alerts.entry(symbol).or_default().push(alert.clone());
+        self.dirty.notify_one();
+        info!(id = %alert.id, symbol = %alert.symbol, kind = ?alert.kind, level = alert.level, direction = ?alert.direction, "Price alert added");
+        Ok(alert)
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        let mut removed = false;
+        self.alerts.retain(|_, alerts| {
+            let before = alerts.len();
+            alerts.retain(|a| a.id != id);
+            removed |= alerts.len() != before;
+            !alerts.is_empty()
+        });
+        if removed {
+            self.dirty.notify_one();
+        }
+        removed
+    }
+
+    /// Fires the symbol's pending alerts that its 1m candle so far satisfies; each fires only once.
+    pub fn check(&self, symbol: &str, candle: &MarketData) -> Vec<PriceAlert> {
+        let Some(mut alerts) = self.alerts.get_mut(symbol) else {
+            return Vec::new();
+        };
+        let mut fired = Vec::new();
+        for alert in alerts.iter_mut().filter(|a| a.triggered_at.is_none()) {
+            if alert.hit(candle) {
+                alert.triggered_at = Some(candle.timestamp);
+                alert.triggered_price = Some(candle.close);
+                alert.triggered_quote_volume = Some(candle.quote_volume);
+                fired.push(alert.clone());
+            }
+        }
+        if !fired.is_empty() {
+            self.dirty.notify_one();
+        }
+        fired
+    }
+
+    pub async fn save(&self) {
+        let json = match serde_json::to_string_pretty(&self.list()) {
+            Ok(json) => json,
+            Err(e) => {
+                METRICS.record_error("price_alert");
+                warn!(path = %self.file_path, error = %e, "Failed to serialize price alerts");
+                return;
+            }
+        };
+        let path = self.file_path.clone();
+        let result = tokio::task::spawn_blocking(move || fs::write(&path, json)).await;
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        METRICS.record_error("price_alert");
+        warn!(path = %self.file_path, error = %error, "Failed to save price alerts");
+    }
+}
+
+/// Persists price alerts whenever one was added, removed or fired.
+pub async fn persist_task(alerts: Arc<PriceAlerts>) {
+    loop {
+        alerts.dirty.notified().await;
+        alerts.save().await;
+    }
+}
@@ -0,0 +1,175 @@
+use crate::metrics::METRICS;
+use crate::replay::{Feed, RecordedFrame};
+use crate::shutdown::Shutdown;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{LazyLock, RwLock};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+// Records every frame the Binance feeds receive, for `--replay` and offline analysis. With
+// `RECORD_DIR` set, frames go to one gzip-compressed NDJSON file of `RecordedFrame`s per UTC
+// hour, `<dir>/binance-YYYYMMDD-HH.ndjson.gz`. Hours older than `RECORD_RETENTION_HOURS`
+// (default 48) are deleted, then the oldest until the directory fits in `RECORD_MAX_MB`
+// (0 = no limit, the default). Frames reach the writer through a bounded queue and are
+// dropped (`recorderDropped` in /metrics) when the disk can't keep up, never slowing the feed.
+
+const PREFIX: &str = "binance-";
+const SUFFIX: &str = ".ndjson.gz";
+// Frames buffered between the feeds and the writer thread
+const QUEUE: usize = 16_384;
+// Frames between flushes, so a crash loses little and the file can be read while recording
+const FLUSH_EVERY: usize = 1_000;
+
+pub fn record_dir() -> Option<PathBuf> {
+    std::env::var("RECORD_DIR").ok().filter(|d| !d.is_empty()).map(PathBuf::from)
+}
+
+fn retention_hours() -> i64 {
+    std::env::var("RECORD_RETENTION_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(48)
+}
+
+fn max_bytes() -> u64 {
+    std::env::var("RECORD_MAX_MB").ok().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0) * 1024 * 1024
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecorderError {
+    #[error("recorder io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("recorder writer stopped: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+// The running recorder's queue; None when recording is off or between restarts
+static SENDER: LazyLock<RwLock<Option<mpsc::Sender<RecordedFrame>>>> = LazyLock::new(Default::default);
+
+/// Hands one raw frame to the recorder. A no-op unless `recorder_task` is running.
+pub fn record(feed: Feed, frame: &str) {
+    let sender = SENDER.read().unwrap_or_else(|e| e.into_inner());
+    let Some(tx) = sender.as_ref() else {
+        return;
+    };
+    let recorded = RecordedFrame { received_at: chrono::Utc::now().timestamp_millis(), feed, frame: frame.to_string() };
+    if tx.try_send(recorded).is_err() {
+        METRICS.recorder_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn set_sender(tx: Option<mpsc::Sender<RecordedFrame>>) {
+    *SENDER.write().unwrap_or_else(|e| e.into_inner()) = tx;
+}
+
+/// Writes recorded frames until shutdown, then finishes the current file.
+pub async fn recorder_task(dir: PathBuf, shutdown: Shutdown) -> Result<(), RecorderError> {
+    fs::create_dir_all(&dir)?;
+    info!(dir = %dir.display(), retention_hours = retention_hours(), "Recording Binance frames");
+    let (tx, rx) = mpsc::channel(QUEUE);
+    set_sender(Some(tx));
+    let mut writer = tokio::task::spawn_blocking(move || write_frames(&dir, rx));
+
+    let result = tokio::select! {
+        result = &mut writer => result,
+        _ = shutdown.wait() => {
+            // Dropping the only sender lets the writer drain the queue and return
+            set_sender(None);
+            writer.await
+        }
+    };
+    set_sender(None);
+    result??;
+    Ok(())
+}
+
+// The file being written: its hour and the open encoder
+struct HourFile {
+    hour: String,
+    encoder: GzEncoder<BufWriter<File>>,
+}
+
+impl HourFile {
+    // Appends a new gzip member when the hour's file already exists (e.g. after a restart);
+    // readers decode concatenated members as one stream.
+    fn open(dir: &Path, hour: String) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(format!("{}{}{}", PREFIX, hour, SUFFIX)))?;
+        Ok(Self { hour, encoder: GzEncoder::new(BufWriter::new(file), Compression::default()) })
+    }
+
+    fn finish(self) -> io::Result<()> {
+        self.encoder.finish()?.flush()
+    }
+}
+
+fn hour_key(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms).unwrap_or_default().format("%Y%m%d-%H").to_string()
+}
+
+fn write_frames(dir: &Path, mut rx: mpsc::Receiver<RecordedFrame>) -> io::Result<()> {
+    prune(dir, None);
+    let mut current: Option<HourFile> = None;
+    let mut unflushed = 0;
+    while let Some(frame) = rx.blocking_recv() {
+        let hour = hour_key(frame.received_at);
+        if current.as_ref().is_none_or(|f| f.hour != hour) {
+            if let Some(done) = current.take() {
+                done.finish()?;
+            }
+            current = Some(HourFile::open(dir, hour.clone())?);
+            prune(dir, Some(&hour));
+        }
+        let file = current.as_mut().expect("opened above");
+        serde_json::to_writer(&mut file.encoder, &frame)?;
+        file.encoder.write_all(b"\n")?;
+        unflushed += 1;
+        if unflushed >= FLUSH_EVERY || rx.is_empty() {
+            file.encoder.flush()?;
+            unflushed = 0;
+        }
+    }
+    match current {
+        Some(file) => file.finish(),
+        None => Ok(()),
+    }
+}
+
+// Retention over the recorded hours; names sort chronologically. Never removes `current`.
+fn prune(dir: &Path, current: Option<&str>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(String, PathBuf, u64)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            let hour = name.strip_prefix(PREFIX)?.strip_suffix(SUFFIX)?.to_string();
+            let size = e.metadata().map(|m| m.len()).unwrap_or(0);
+            Some((hour, e.path(), size))
+        })
+        .collect();
+    files.sort();
+
+    let cutoff = hour_key(chrono::Utc::now().timestamp_millis() - retention_hours() * 3_600_000);
+    let max_bytes = max_bytes();
+    let mut total: u64 = files.iter().map(|(_, _, size)| size).sum();
+    for (hour, path, size) in files {
+        let expired = hour < cutoff;
+        let over_budget = max_bytes > 0 && total > max_bytes;
+        if !(expired || over_budget) || Some(hour.as_str()) == current {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                total -= size;
+                info!(path = %path.display(), expired, "Removed old recording");
+            }
+            Err(e) => {
+                METRICS.record_error("recorder");
+                warn!(path = %path.display(), error = %e, "Failed to remove old recording");
+            }
+        }
+    }
+}
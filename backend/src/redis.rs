@@ -0,0 +1,151 @@
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::Duration;
+
+// Just enough of the Redis protocol (RESP2) for the cluster lease and bus: commands as arrays
+// of bulk strings, and the five reply types back. One command in flight per connection; a
+// subscribed connection only reads pushes.
+
+// An unreachable host otherwise takes the OS's TCP timeout, minutes, to fail
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RedisError {
+    #[error("connection failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid redis url: {0}")]
+    Url(String),
+    #[error("protocol error: {0}")]
+    Protocol(String),
+    #[error("server error: {0}")]
+    Server(String),
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Simple(String),
+    Int(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+impl Reply {
+    fn into_string(self) -> Option<String> {
+        match self {
+            Reply::Simple(s) => Some(s),
+            Reply::Bulk(Some(bytes)) => String::from_utf8(bytes).ok(),
+            _ => None,
+        }
+    }
+}
+
+pub struct Connection {
+    stream: BufReader<TcpStream>,
+}
+
+impl Connection {
+    /// Connects to `redis://[:password@]host[:port][/db]`, authenticating and selecting the
+    /// database when the URL names them.
+    pub async fn connect(url: &str) -> Result<Self, RedisError> {
+        let parsed = url::Url::parse(url).map_err(|e| RedisError::Url(e.to_string()))?;
+        if parsed.scheme() != "redis" {
+            return Err(RedisError::Url(format!("unsupported scheme '{}'", parsed.scheme())));
+        }
+        let host = parsed.host_str().ok_or_else(|| RedisError::Url("no host".to_string()))?;
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, parsed.port().unwrap_or(6379))))
+            .await
+            .map_err(|_| RedisError::Timeout(CONNECT_TIMEOUT))??;
+        stream.set_nodelay(true)?;
+        let mut conn = Self { stream: BufReader::new(stream) };
+        if let Some(password) = parsed.password() {
+            match parsed.username() {
+                "" => conn.command(&["AUTH", password]).await?,
+                user => conn.command(&["AUTH", user, password]).await?,
+            };
+        }
+        if let Some(db) = parsed.path().trim_start_matches('/').split('/').next().filter(|db| !db.is_empty()) {
+            conn.command(&["SELECT", db]).await?;
+        }
+        Ok(conn)
+    }
+
+    /// Sends one command and reads its reply. A server error reply is an `Err`.
+    pub async fn command(&mut self, args: &[&str]) -> Result<Reply, RedisError> {
+        let mut frame = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            frame.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            frame.extend_from_slice(arg.as_bytes());
+            frame.extend_from_slice(b"\r\n");
+        }
+        self.stream.get_mut().write_all(&frame).await?;
+        self.read_reply().await
+    }
+
+    /// Turns this connection into a subscriber on `channel`.
+    pub async fn subscribe(mut self, channel: &str) -> Result<Subscription, RedisError> {
+        self.command(&["SUBSCRIBE", channel]).await?;
+        Ok(Subscription { conn: self })
+    }
+
+    async fn read_line(&mut self) -> Result<String, RedisError> {
+        let mut line = String::new();
+        if self.stream.read_line(&mut line).await? == 0 {
+            return Err(RedisError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(line.trim_end_matches("\r\n").to_string())
+    }
+
+    async fn read_reply(&mut self) -> Result<Reply, RedisError> {
+        let line = self.read_line().await?;
+        let (kind, rest) = line.split_at(line.len().min(1));
+        let length = || rest.parse::<i64>().map_err(|_| RedisError::Protocol(format!("bad length in '{}'", line)));
+        match kind {
+            "+" => Ok(Reply::Simple(rest.to_string())),
+            "-" => Err(RedisError::Server(rest.to_string())),
+            ":" => Ok(Reply::Int(length()?)),
+            "$" => match length()? {
+                n if n < 0 => Ok(Reply::Bulk(None)),
+                n => {
+                    let mut bytes = vec![0; n as usize + 2]; // With the trailing CRLF
+                    self.stream.read_exact(&mut bytes).await?;
+                    bytes.truncate(n as usize);
+                    Ok(Reply::Bulk(Some(bytes)))
+                }
+            },
+            "*" => match length()? {
+                n if n < 0 => Ok(Reply::Array(None)),
+                n => {
+                    let mut items = Vec::with_capacity(n as usize);
+                    for _ in 0..n {
+                        items.push(Box::pin(self.read_reply()).await?);
+                    }
+                    Ok(Reply::Array(Some(items)))
+                }
+            },
+            _ => Err(RedisError::Protocol(format!("unexpected reply '{}'", line))),
+        }
+    }
+}
+
+pub struct Subscription {
+    conn: Connection,
+}
+
+impl Subscription {
+    /// The next message published on the channel.
+    pub async fn next(&mut self) -> Result<String, RedisError> {
+        loop {
+            // ["message", channel, payload]; anything else (subscribe confirmations) is skipped
+            if let Reply::Array(Some(items)) = self.conn.read_reply().await? {
+                let mut items = items.into_iter();
+                if items.next().and_then(Reply::into_string).as_deref() == Some("message") {
+                    if let Some(payload) = items.nth(1).and_then(Reply::into_string) {
+                        return Ok(payload);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::actor::ActorContext;
+use crate::binance_client::{KlineFeed, TickerFeed};
+use crate::ingest::FrameHandler;
+use crate::exchange::Exchange;
+use crate::metrics::METRICS;
+use crate::shutdown::Shutdown;
+use flate2::read::MultiGzDecoder;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// Replays a recorded Binance session in place of the live feeds (`--replay <file>` or
+// `REPLAY_FILE`): every frame goes through the same parsing, actors, strategies, verifier and
+// WebSocket server as live data, at `REPLAY_SPEED` (1 = as recorded, 10 = ten times faster,
+// `instant` = as fast as the actors keep up). Recorded times are kept, so cooldowns and
+// candles behave as they did; views that compare against the wall clock (leaderboard, "ago")
+// see old data.
+//
+// A recording is NDJSON, one `RecordedFrame` per line, oldest first: a `.ndjson` file, a
+// gzipped `.ndjson.gz` one, or a directory of them such as the `recorder`'s output.
+
+// Frames read ahead of the pacing
+const QUEUE: usize = 4_096;
+
+/// One raw WebSocket frame as Binance sent it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedFrame {
+    pub received_at: i64, // ms
+    pub feed: Feed,
+    pub frame: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feed {
+    Ticker, // `!ticker@arr`
+    Kline,  // Combined `<symbol>@kline_1m` streams
+    #[serde(rename = "spot_ticker")]
+    SpotTicker, // Spot `!ticker@arr`
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("replay io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{file} line {line}: {source}")]
+    Line { file: String, line: usize, source: serde_json::Error },
+    #[error("replay reader stopped: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    Instant,
+    Times(f64),
+}
+
+impl std::str::FromStr for Speed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_end_matches(['x', 'X']);
+        if s.eq_ignore_ascii_case("instant") {
+            return Ok(Speed::Instant);
+        }
+        match s.parse::<f64>() {
+            Ok(times) if times > 0.0 => Ok(Speed::Times(times)),
+            _ => Err(format!("'{}' must be a positive multiplier (1, 10x) or instant", s)),
+        }
+    }
+}
+
+pub fn speed() -> Speed {
+    std::env::var("REPLAY_SPEED").ok().and_then(|v| v.parse().ok()).unwrap_or(Speed::Times(1.0))
+}
+
+/// The recording to replay: `run --replay <file>`, else `REPLAY_FILE`. None runs the live feeds.
+pub fn replay_file(arg: Option<String>) -> Option<String> {
+    arg.or_else(|| std::env::var("REPLAY_FILE").ok().filter(|p| !p.is_empty()))
+}
+
+/// The files of a recording, oldest first: `path` itself, or the `.ndjson` / `.ndjson.gz`
+/// files in it when it is a directory (e.g. the recorder's `RECORD_DIR`).
+fn recording_files(path: &Path) -> Result<Vec<PathBuf>, ReplayError> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.to_str().is_some_and(|name| name.ends_with(".ndjson") || name.ends_with(".ndjson.gz")))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// Reads `files` in order on a blocking thread, handing frames to the async side. Stops early
+// when the receiver is gone (shutdown).
+fn read_frames(files: Vec<PathBuf>, tx: mpsc::Sender<RecordedFrame>) -> Result<(), ReplayError> {
+    for file in files {
+        let raw = std::fs::File::open(&file)?;
+        let reader: Box<dyn BufRead> = if file.extension().is_some_and(|e| e == "gz") {
+            Box::new(BufReader::new(MultiGzDecoder::new(raw)))
+        } else {
+            Box::new(BufReader::new(raw))
+        };
+        for (index, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                // The hour being recorded when the recorder was killed has no gzip trailer
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    warn!(file = %file.display(), "Recording ends mid-file, replaying what was written");
+                    break;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame = serde_json::from_str(&line)
+                .map_err(|source| ReplayError::Line { file: file.display().to_string(), line: index + 1, source })?;
+            if tx.blocking_send(frame).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Feeds the recording at `path` to the symbol actors, then waits for shutdown so the
+/// replayed state stays up for the frontend.
+pub async fn replay_task(ctx: ActorContext, path: String, shutdown: Shutdown) -> Result<(), ReplayError> {
+    let speed = speed();
+    let files = recording_files(Path::new(&path))?;
+    info!(path = %path, files = files.len(), speed = ?speed, "Replaying recorded market data");
+    let (tx, mut rx) = mpsc::channel(QUEUE);
+    let reader = tokio::task::spawn_blocking(move || read_frames(files, tx));
+    let mut ticker = TickerFeed::new(ctx.clone(), Exchange::Binance);
+    let mut spot_ticker = TickerFeed::new(ctx.clone(), Exchange::BinanceSpot);
+    let mut kline = KlineFeed::new(ctx);
+    let mut start: Option<(i64, Instant)> = None;
+    let mut frames = 0usize;
+
+    while let Some(recorded) = rx.recv().await {
+        let (first_at, started) = *start.get_or_insert((recorded.received_at, Instant::now()));
+        let due = match speed {
+            // Let the actors drain their queues; they drop ticks when full
+            Speed::Instant => None,
+            Speed::Times(times) => {
+                let offset = (recorded.received_at - first_at).max(0) as f64 / times;
+                Some(started + Duration::from_millis(offset as u64))
+            }
+        };
+        tokio::select! {
+            _ = shutdown.wait() => return Ok(()),
+            _ = async {
+                match due {
+                    Some(due) => tokio::time::sleep_until(due).await,
+                    None => tokio::task::yield_now().await,
+                }
+            } => {}
+        }
+        // Counts as feed traffic for /health, /ready and the watchdog, as live frames do
+        METRICS.last_feed_message_ms.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+        match recorded.feed {
+            Feed::Ticker => ticker.handle(&recorded.frame),
+            Feed::Kline => kline.handle(&recorded.frame),
+            Feed::SpotTicker => spot_ticker.handle(&recorded.frame),
+        }
+        frames += 1;
+    }
+    reader.await??;
+
+    if frames == 0 {
+        warn!(path = %path, "Replay file held no frames");
+    }
+    info!(path = %path, frames, "Replay finished");
+    shutdown.wait().await;
+    Ok(())
+}
@@ -0,0 +1,391 @@
+// Daily performance report: on a cron schedule (REPORT_CRON, UTC, default midnight) the last
+// REPORT_PERIOD_HOURS (default 24) of history is summed up (signals, win rate, best and worst
+// performers, what strict verification rejected) and sent as an HTML email to REPORT_EMAIL_TO
+// (see `smtp`) and/or a Telegram message (see `notifier`). Off unless REPORT_ENABLED=true.
+//
+// Only the instance that publishes signals (the leader, or the aggregator when sharded) sends
+// it, so a redundant pair doesn't mail twice.
+use crate::history::{HistoryManager, SignalRecord};
+use crate::leader::Leadership;
+use crate::metrics::METRICS;
+use crate::scanner::{Signal, SignalType};
+use crate::shard::SHARD;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, TimeZone, Timelike, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+// Performers listed at each end
+const PERFORMERS: usize = 3;
+const REJECTED_SYMBOLS: usize = 5;
+
+pub fn enabled() -> bool {
+    std::env::var("REPORT_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+fn period_hours() -> i64 {
+    std::env::var("REPORT_PERIOD_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(24i64).max(1)
+}
+
+fn email_to() -> Vec<String> {
+    std::env::var("REPORT_EMAIL_TO")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Telegram too when it is configured, unless REPORT_TELEGRAM=false
+fn telegram() -> bool {
+    crate::notifier::enabled() && !std::env::var("REPORT_TELEGRAM").is_ok_and(|v| v.eq_ignore_ascii_case("false"))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    #[error("invalid cron expression '{0}': {1}")]
+    Cron(String, String),
+}
+
+/// A five-field cron expression (minute hour day-of-month month day-of-week), in UTC. Fields
+/// take `*`, numbers, `a-b` ranges, `/n` steps and comma lists; day-of-week 0 and 7 are Sunday.
+/// As in cron, when both day fields are restricted either one matching is enough.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    minutes: u64, // Bit per allowed value
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool, // Day-of-month was `*`
+    any_weekday: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| format!("bad step in '{}'", part))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(format!("zero step in '{}'", part));
+        }
+        let (low, high) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((low, high)) => (
+                    low.parse().map_err(|_| format!("bad value '{}'", low))?,
+                    high.parse().map_err(|_| format!("bad value '{}'", high))?,
+                ),
+                // "5/15" runs from 5 to the end
+                None => {
+                    let value: u32 = range.parse().map_err(|_| format!("bad value '{}'", range))?;
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if low < min || high > max || low > high {
+            return Err(format!("'{}' outside {}-{}", part, min, max));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl std::str::FromStr for Schedule {
+    type Err = ReportError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let error = |e: String| ReportError::Cron(expression.to_string(), e);
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(error(format!("expected 5 fields, got {}", fields.len())));
+        };
+        let mut weekday_bits = parse_field(weekdays, 0, 7).map_err(error)?;
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits |= 1; // 7 is Sunday too
+        }
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59).map_err(error)?,
+            hours: parse_field(hours, 0, 23).map_err(error)?,
+            days: parse_field(days, 1, 31).map_err(error)?,
+            months: parse_field(months, 1, 12).map_err(error)?,
+            weekdays: weekday_bits,
+            any_day: days == "*",
+            any_weekday: weekdays == "*",
+        })
+    }
+}
+
+impl Schedule {
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        let day = self.days & (1 << t.day()) != 0;
+        let weekday = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// The first matching minute strictly after `after`, within the next four years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let mut t = start;
+        let limit = start + ChronoDuration::days(4 * 366);
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 || !self.day_matches(&t) {
+                // Next day, at midnight
+                t = Utc.with_ymd_and_hms(t.year(), t.month(), t.day(), 0, 0, 0).single()? + ChronoDuration::days(1);
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + ChronoDuration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += ChronoDuration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// How one signal did, for the best / worst lists.
+#[derive(Debug, Clone)]
+pub struct Performer {
+    pub symbol: String,
+    pub signal_type: SignalType,
+    pub strategy: String,
+    pub max_gain_pct: f64,
+    pub max_drawdown_pct: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RejectedSummary {
+    pub total: usize,
+    pub avg_confidence: Option<f64>,
+    pub top_symbols: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyReport {
+    pub from: i64, // ms
+    pub to: i64,
+    pub total_signals: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub pending: usize, // Outcome not decided yet (younger than an hour, no TP/SL)
+    pub win_rate: f64,  // Percent of decided signals
+    pub best: Vec<Performer>,
+    pub worst: Vec<Performer>,
+    pub by_strategy: Vec<(String, usize, usize)>, // Strategy, signals, wins
+    pub rejected: RejectedSummary,
+}
+
+fn performer(record: &SignalRecord) -> Performer {
+    Performer {
+        symbol: record.signal.symbol.clone(),
+        signal_type: record.signal.signal_type.clone(),
+        strategy: record.signal.strategy_name.clone(),
+        max_gain_pct: record.outcome.max_gain_percent * 100.0,
+        max_drawdown_pct: record.outcome.max_drawdown_percent * 100.0,
+    }
+}
+
+/// Sums up `records` (published) and `rejected` over [from, to).
+pub fn build(from: i64, to: i64, records: &[SignalRecord], rejected: &[Signal]) -> DailyReport {
+    let decided = |r: &&SignalRecord| r.outcome.success || r.outcome.hit_sl || r.outcome.price_at_60m.is_some();
+    let wins = records.iter().filter(|r| r.outcome.success).count();
+    let losses = records.iter().filter(decided).count() - wins;
+
+    let mut by_gain: Vec<&SignalRecord> = records.iter().filter(decided).collect();
+    by_gain.sort_by(|a, b| b.outcome.max_gain_percent.total_cmp(&a.outcome.max_gain_percent));
+    let best = by_gain.iter().take(PERFORMERS).map(|r| performer(r)).collect();
+    by_gain.sort_by(|a, b| b.outcome.max_drawdown_percent.total_cmp(&a.outcome.max_drawdown_percent));
+    let worst = by_gain.iter().take(PERFORMERS).map(|r| performer(r)).collect();
+
+    let mut strategies: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for record in records {
+        let entry = strategies.entry(&record.signal.strategy_name).or_default();
+        entry.0 += 1;
+        entry.1 += record.outcome.success as usize;
+    }
+
+    let confidences: Vec<f64> = rejected.iter().filter_map(|s| s.confidence).collect();
+    let mut rejected_symbols: BTreeMap<&str, usize> = BTreeMap::new();
+    for signal in rejected {
+        *rejected_symbols.entry(&signal.symbol).or_default() += 1;
+    }
+    let mut top_symbols: Vec<(String, usize)> = rejected_symbols.into_iter().map(|(s, n)| (s.to_string(), n)).collect();
+    top_symbols.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    top_symbols.truncate(REJECTED_SYMBOLS);
+
+    DailyReport {
+        from,
+        to,
+        total_signals: records.len(),
+        wins,
+        losses,
+        pending: records.len() - wins - losses,
+        win_rate: if wins + losses > 0 { wins as f64 / (wins + losses) as f64 * 100.0 } else { 0.0 },
+        best,
+        worst,
+        by_strategy: strategies.into_iter().map(|(s, (n, w))| (s.to_string(), n, w)).collect(),
+        rejected: RejectedSummary {
+            total: rejected.len(),
+            avg_confidence: (!confidences.is_empty()).then(|| confidences.iter().sum::<f64>() / confidences.len() as f64),
+            top_symbols,
+        },
+    }
+}
+
+fn day_label(report: &DailyReport) -> String {
+    Utc.timestamp_millis_opt(report.from).single().map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+}
+
+pub fn subject(report: &DailyReport) -> String {
+    format!("Teeb Trade report: {} signals, {:.0}% win rate (since {} UTC)", report.total_signals, report.win_rate, day_label(report))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn render_html(report: &DailyReport) -> String {
+    let row = |p: &Performer| {
+        format!(
+            "<tr><td>{}</td><td>{:?}</td><td>{}</td><td>{:+.2}%</td><td>-{:.2}%</td></tr>",
+            escape(&p.symbol),
+            p.signal_type,
+            escape(&p.strategy),
+            p.max_gain_pct,
+            p.max_drawdown_pct
+        )
+    };
+    let table = |title: &str, performers: &[Performer]| {
+        if performers.is_empty() {
+            return String::new();
+        }
+        format!(
+            "<h3>{}</h3>\n<table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\n<tr><th>Symbol</th><th>Side</th><th>Strategy</th><th>Best</th><th>Worst</th></tr>\n{}\n</table>\n",
+            title,
+            performers.iter().map(row).collect::<Vec<_>>().join("\n")
+        )
+    };
+    let mut html = format!(
+        "<html><body style=\"font-family: sans-serif\">\n<h2>Teeb Trade report</h2>\n<p>Since {} UTC ({}h)</p>\n\
+         <p><b>{}</b> signals: {} won, {} lost, {} pending. Win rate <b>{:.1}%</b>.</p>\n",
+        day_label(report),
+        (report.to - report.from) / 3_600_000,
+        report.total_signals,
+        report.wins,
+        report.losses,
+        report.pending,
+        report.win_rate
+    );
+    html += &table("Best performers", &report.best);
+    html += &table("Worst drawdowns", &report.worst);
+    if !report.by_strategy.is_empty() {
+        html += "<h3>By strategy</h3>\n<ul>\n";
+        for (strategy, signals, wins) in &report.by_strategy {
+            html += &format!("<li>{}: {} signals, {} won</li>\n", escape(strategy), signals, wins);
+        }
+        html += "</ul>\n";
+    }
+    html += &format!("<h3>Rejected by verification</h3>\n<p>{}", report.rejected.total);
+    if let Some(confidence) = report.rejected.avg_confidence {
+        html += &format!(", average confidence {:.0}", confidence);
+    }
+    if !report.rejected.top_symbols.is_empty() {
+        let symbols: Vec<String> = report.rejected.top_symbols.iter().map(|(s, n)| format!("{} ({})", escape(s), n)).collect();
+        html += &format!("; most: {}", symbols.join(", "));
+    }
+    html += "</p>\n</body></html>\n";
+    html
+}
+
+pub fn render_text(report: &DailyReport) -> String {
+    let mut text = format!(
+        "📊 Report since {} UTC\n{} signals: {} won, {} lost, {} pending\nWin rate {:.1}%\n",
+        day_label(report),
+        report.total_signals,
+        report.wins,
+        report.losses,
+        report.pending,
+        report.win_rate
+    );
+    let line = |p: &Performer| format!("{} {:?} {:+.2}% / -{:.2}%", p.symbol, p.signal_type, p.max_gain_pct, p.max_drawdown_pct);
+    if !report.best.is_empty() {
+        text += &format!("\n🏆 Best\n{}\n", report.best.iter().map(line).collect::<Vec<_>>().join("\n"));
+    }
+    if !report.worst.is_empty() {
+        text += &format!("\n📉 Worst drawdowns\n{}\n", report.worst.iter().map(line).collect::<Vec<_>>().join("\n"));
+    }
+    text += &format!("\n🚫 Rejected: {}", report.rejected.total);
+    if let Some(confidence) = report.rejected.avg_confidence {
+        text += &format!(" (avg confidence {:.0})", confidence);
+    }
+    text
+}
+
+/// Compiles the report for the period ending `now` and sends it everywhere configured.
+pub async fn send(history: &HistoryManager, now: i64) {
+    let from = now - period_hours() * 3_600_000;
+    let records = history.between(from, now).await;
+    let rejected = history.rejected_between(from, now).await;
+    let report = build(from, now, &records, &rejected);
+
+    let recipients = email_to();
+    if !recipients.is_empty() {
+        match crate::smtp::SmtpConfig::from_env() {
+            Some(config) => match crate::smtp::send_html(&config, &recipients, &subject(&report), &render_html(&report)).await {
+                Ok(()) => info!(recipients = recipients.len(), signals = report.total_signals, "Report emailed"),
+                Err(e) => {
+                    METRICS.record_error("report");
+                    error!(error = %e, "Failed to email report");
+                }
+            },
+            None => warn!("REPORT_EMAIL_TO is set but SMTP_HOST / SMTP_FROM are not, report not emailed"),
+        }
+    }
+    if telegram() {
+        if crate::notifier::deliver(&render_text(&report)).await {
+            info!(signals = report.total_signals, "Report sent to Telegram");
+        } else {
+            METRICS.record_error("report");
+        }
+    }
+}
+
+/// Sends the report at every `REPORT_CRON` time.
+pub async fn report_task(history: Arc<HistoryManager>, leadership: Leadership) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    let expression = std::env::var("REPORT_CRON").unwrap_or_else(|_| "0 0 * * *".to_string());
+    let schedule: Schedule = match expression.parse() {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            METRICS.record_error("report");
+            error!(error = %e, "Reports disabled");
+            return std::future::pending().await;
+        }
+    };
+    if email_to().is_empty() && !telegram() {
+        warn!("REPORT_ENABLED is set but neither REPORT_EMAIL_TO nor Telegram is configured");
+    }
+    loop {
+        let Some(next) = schedule.next_after(Utc::now()) else {
+            error!(cron = %expression, "Report schedule never fires");
+            return std::future::pending().await;
+        };
+        info!(cron = %expression, next = %next, "Next report scheduled");
+        sleep(Duration::from_millis((next - Utc::now()).num_milliseconds().max(0) as u64)).await;
+        if leadership.is_leader() && SHARD.aggregator_url.is_none() {
+            send(&history, next.timestamp_millis()).await;
+        }
+    }
+}
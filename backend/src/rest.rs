@@ -0,0 +1,246 @@
+use crate::exchange::ClientError;
+use crate::metrics::METRICS;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{LazyLock, Mutex};
+use tokio::time::{Duration, Instant};
+use tracing::warn;
+
+// Every REST call to the exchanges goes through one pooled client and, for Binance hosts, a
+// token bucket of request weight per host: `REST_WEIGHT_LIMIT` per minute (default 2400, the
+// futures limit; spot's is higher), trued up from the `X-MBX-USED-WEIGHT-1M` header of each
+// response so other processes on the same IP count too. A call that would overdraw it waits
+// for the refill, or fails if that is more than `MAX_WAIT` away. A 429 or 418 backs the whole
+// host off for its `Retry-After`. Timeouts, connection errors and 5xx are retried up to
+// `REST_RETRIES` times (default 2) with doubling backoff.
+
+const MAX_WAIT: Duration = Duration::from_secs(10);
+const FIRST_BACKOFF: Duration = Duration::from_millis(250);
+const BAN_FALLBACK_SECS: u64 = 60; // A 429/418 without Retry-After
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn weight_limit() -> f64 {
+    std::env::var("REST_WEIGHT_LIMIT").ok().and_then(|v| v.parse::<u32>().ok()).unwrap_or(2400).max(1) as f64
+}
+
+fn retries() -> u32 {
+    std::env::var("REST_RETRIES").ok().and_then(|v| v.parse().ok()).unwrap_or(2)
+}
+
+static HTTP: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(16)
+        .build()
+        .unwrap_or_default()
+});
+
+/// The shared HTTP client. Clones share its connection pool.
+pub fn client() -> reqwest::Client {
+    HTTP.clone()
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    backoff_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: f64) -> Self {
+        Self { tokens: limit, refilled: Instant::now(), backoff_until: None }
+    }
+
+    fn refill(&mut self, limit: f64) {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.refilled).as_secs_f64() * limit / 60.0).min(limit);
+        self.refilled = now;
+    }
+}
+
+// Host -> its weight bucket
+static BUCKETS: LazyLock<Mutex<HashMap<String, Bucket>>> = LazyLock::new(Default::default);
+
+fn with_bucket<R>(host: &str, f: impl FnOnce(&mut Bucket, f64) -> R) -> R {
+    let limit = weight_limit();
+    let mut buckets = BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+    let bucket = buckets.entry(host.to_string()).or_insert_with(|| Bucket::new(limit));
+    bucket.refill(limit);
+    f(bucket, limit)
+}
+
+// Binance's request weight for the endpoints used here; anything unlisted costs 1
+fn weight(path: &str, query: &str) -> f64 {
+    let has_symbol = query.contains("symbol=");
+    match path {
+        "/fapi/v1/ticker/24hr" | "/api/v3/ticker/24hr" if !has_symbol => 40.0,
+        "/fapi/v1/premiumIndex" if !has_symbol => 10.0,
+        "/api/v3/ticker/price" if !has_symbol => 4.0,
+        "/fapi/v1/depth" | "/api/v3/depth" => 5.0, // Up to 100 levels (2 at the 20 the verifier asks for)
+        // By `limit` (default 500) on futures, flat on spot
+        "/fapi/v1/klines" => match query_limit(query).unwrap_or(500) {
+            ..100 => 1.0,
+            100..500 => 2.0,
+            500..=1000 => 5.0,
+            _ => 10.0,
+        },
+        "/api/v3/klines" => 2.0,
+        "/api/v3/exchangeInfo" => 20.0,
+        _ => 1.0,
+    }
+}
+
+fn query_limit(query: &str) -> Option<u32> {
+    query.split('&').find_map(|pair| pair.strip_prefix("limit="))?.parse().ok()
+}
+
+// Takes `weight` from the host's bucket, waiting for the refill when it is short
+async fn acquire(host: &str, weight: f64) -> Result<(), ClientError> {
+    loop {
+        let wait = with_bucket(host, |bucket, limit| {
+            if let Some(until) = bucket.backoff_until {
+                let now = Instant::now();
+                if until > now {
+                    return Err(until - now);
+                }
+                bucket.backoff_until = None;
+            }
+            if bucket.tokens >= weight.min(limit) {
+                bucket.tokens -= weight;
+                return Ok(());
+            }
+            Err(Duration::from_secs_f64((weight.min(limit) - bucket.tokens) * 60.0 / limit))
+        });
+        match wait {
+            Ok(()) => return Ok(()),
+            Err(wait) if wait <= MAX_WAIT => tokio::time::sleep(wait).await,
+            Err(wait) => return Err(ClientError::RateLimited { host: host.to_string(), secs: wait.as_secs().max(1) }),
+        }
+    }
+}
+
+// Trues the bucket up to what the server says was used this minute
+fn observe(host: &str, resp: &reqwest::Response) {
+    let used = resp.headers().get("x-mbx-used-weight-1m").and_then(|v| v.to_str().ok()).and_then(|v| v.parse::<f64>().ok());
+    if let Some(used) = used {
+        with_bucket(host, |bucket, limit| bucket.tokens = bucket.tokens.min(limit - used));
+    }
+}
+
+/// Share of the weight budget `url`'s host has left right now, for work that can wait (1.0
+/// for hosts that aren't weighed).
+pub fn headroom(url: &str) -> f64 {
+    match url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_string)) {
+        Some(host) if host.ends_with("binance.com") => with_bucket(&host, |bucket, limit| {
+            if bucket.backoff_until.is_some_and(|until| until > Instant::now()) {
+                0.0
+            } else {
+                bucket.tokens / limit
+            }
+        }),
+        _ => 1.0,
+    }
+}
+
+fn retry_after(resp: &reqwest::Response) -> u64 {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(BAN_FALLBACK_SECS)
+}
+
+/// GETs `url` within the host's rate limit, retrying transient failures. Any status other
+/// than success is an error.
+pub async fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response, ClientError> {
+    let parsed = url::Url::parse(url)?;
+    let host = parsed.host_str().unwrap_or_default().to_string();
+    // Only Binance hosts are weighed; a backoff holds for any host
+    let cost = if host.ends_with("binance.com") { weight(parsed.path(), parsed.query().unwrap_or_default()) } else { 0.0 };
+
+    let mut backoff = FIRST_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        acquire(&host, cost).await?;
+        let error = match client.get(url).send().await {
+            Ok(resp) => {
+                observe(&host, &resp);
+                let status = resp.status();
+                if status.is_success() {
+                    return Ok(resp);
+                }
+                // 429: over the limit; 418: banned for ignoring 429s. Either way the host is off
+                // limits until Retry-After, for every caller.
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status == reqwest::StatusCode::IM_A_TEAPOT {
+                    let secs = retry_after(&resp);
+                    METRICS.rest_rate_limited.fetch_add(1, Ordering::Relaxed);
+                    warn!(host = %host, status = status.as_u16(), retry_after_secs = secs, "Rate limited, backing off");
+                    with_bucket(&host, |bucket, _| {
+                        bucket.tokens = 0.0;
+                        bucket.backoff_until = Some(Instant::now() + Duration::from_secs(secs));
+                    });
+                    return Err(ClientError::RateLimited { host, secs });
+                }
+                if !status.is_server_error() {
+                    return Err(ClientError::Status(status));
+                }
+                ClientError::Status(status)
+            }
+            Err(e) if e.is_timeout() || e.is_connect() => ClientError::Http(e),
+            Err(e) => return Err(ClientError::Http(e)),
+        };
+        if attempt >= retries() {
+            return Err(error);
+        }
+        attempt += 1;
+        METRICS.rest_retries.fetch_add(1, Ordering::Relaxed);
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+/// A short-lived per-key cache of REST responses, so a burst of signals on one symbol costs
+/// one request. Entries live `REST_CACHE_MS` (default 3000, 0 = off); failures aren't cached.
+pub struct Cache<T> {
+    entries: Mutex<HashMap<String, (Instant, T)>>,
+}
+
+impl<T: Clone> Cache<T> {
+    pub fn new() -> Self {
+        Self { entries: Mutex::default() }
+    }
+
+    fn ttl() -> Duration {
+        Duration::from_millis(std::env::var("REST_CACHE_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(3000))
+    }
+
+    /// The cached value for `key`, or `fetch`'s (cached when it succeeds).
+    pub async fn get_or_fetch<E, F>(&self, key: String, fetch: F) -> Result<T, E>
+    where
+        F: std::future::Future<Output = Result<T, E>>,
+    {
+        let ttl = Self::ttl();
+        if ttl.is_zero() {
+            return fetch.await;
+        }
+        {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            match entries.get(&key) {
+                Some((at, value)) if at.elapsed() < ttl => {
+                    METRICS.rest_cache_hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(value.clone());
+                }
+                Some(_) => {
+                    entries.remove(&key);
+                }
+                None => {}
+            }
+        }
+        let value = fetch.await?;
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (at, _)| at.elapsed() < ttl);
+        entries.insert(key, (Instant::now(), value.clone()));
+        Ok(value)
+    }
+}
@@ -1,43 +1,450 @@
-use crate::model::{MarketData, SymbolState};
+use crate::config::{BreakoutConfig, LiquidationConfig, ScannerConfig, ShortPressureConfig, SpikeTrigger};
+use crate::indicators::IndicatorValues;
+use crate::oi_tracker::OiChange;
+use crate::model::{Interval, MarketData, SymbolState};
 use serde::{Deserialize, Serialize};
-use log::info;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use tracing::info;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
 pub enum SignalType {
     Long,
     Short,
+    NewListing, // First trades of a freshly listed contract (see `listing`); played long
+    Breakout,   // Price accelerating up on growing volume (see `check_breakout`); played long
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a signal came from: the scanner's own strategies, or an external alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalSource {
+    #[default]
+    Scanner,
+    TradingView, // A TradingView alert POSTed to /api/tradingview (see `tradingview`)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Signal {
-    pub symbol: String,
+    // Assigned at detection and carried by every later message about this signal
+    // (updates, invalidations, history). Records from before ids existed get one on load.
+    #[serde(default)]
+    pub id: String,
+    // Random per detection, unlike `id`: the `trace_id` of every log line about this signal,
+    // from detection through verification, publishing and outcomes. Empty on older records.
+    #[serde(default)]
+    pub trace_id: String,
+    pub symbol: String, // Tagged with the exchange unless it is Binance's, see `exchange`
+    #[serde(default)]
+    pub exchange: crate::exchange::Exchange,
+    // Futures or spot; records from before spot scanning are futures
+    #[serde(default)]
+    pub market: crate::exchange::MarketType,
+    #[serde(alias = "signal_type")]
     pub signal_type: SignalType,
+    // Candle the spike was measured on (`volume`, `avgVolume`, `high`/`low` are of this size)
+    #[serde(default)]
+    pub timeframe: Interval,
+    // The `strategy::Strategy` that produced it; everything before strategies was the Silent Watcher
+    #[serde(default = "default_strategy_name")]
+    pub strategy_name: String,
+    // Records from before external sources are the scanner's
+    #[serde(default)]
+    pub source: SignalSource,
     pub price: f64,
     pub volume: f64,
+    #[serde(alias = "avg_volume")]
     pub avg_volume: f64,
     pub timestamp: i64,
+    // `reasons` rendered as English, for clients that predate reason codes
     pub reason: String,
+    #[serde(default)]
+    pub reasons: Vec<ReasonCode>,
+    // Range and activity of the signal minute's candle so far
+    #[serde(default)]
+    pub high: f64,
+    #[serde(default)]
+    pub low: f64,
+    #[serde(default, alias = "quote_volume")]
+    pub quote_volume: f64,
+    #[serde(default)]
+    pub trades: u64,
+    #[serde(default, alias = "taker_buy_volume")]
+    pub taker_buy_volume: Option<f64>,
+    // VWAP over the 1m window and since UTC midnight, to compare the entry against
+    #[serde(default)]
+    pub vwap: Option<f64>,
+    #[serde(default, alias = "session_vwap")]
+    pub session_vwap: Option<f64>,
+    // RSI / EMA / ATR as of the minute before the signal (None on records from before them)
+    #[serde(default)]
+    pub indicators: Option<IndicatorValues>,
+    // Open interest change up to the signal, if `oi_tracker` had been polling the symbol
+    #[serde(default, alias = "oi_change")]
+    pub oi_change: Option<OiChange>,
+    // 0..100 from verification (book, OI, funding); None until verified
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    // Composite for ranking against other live signals (see `score`), refreshed on verification
+    #[serde(default)]
+    pub score: Option<crate::score::SignalScore>,
+    // Rolling 24h context: where the move sits in the day's range
+    #[serde(default, alias = "day_high")]
+    pub day_high: Option<f64>,
+    #[serde(default, alias = "day_low")]
+    pub day_low: Option<f64>,
+    #[serde(default, alias = "day_change_pct")]
+    pub day_change_pct: Option<f64>,
+    #[serde(default, alias = "day_quote_volume")]
+    pub day_quote_volume: Option<f64>,
+    #[serde(default, alias = "day_range_position")]
+    pub day_range_position: Option<f64>, // 0 = at 24h low, 1 = at 24h high
+    // Contract metadata, for display (None if exchangeInfo hasn't loaded)
+    #[serde(default, alias = "base_asset")]
+    pub base_asset: Option<String>,
+    #[serde(default, alias = "quote_asset")]
+    pub quote_asset: Option<String>,
+    #[serde(default, alias = "price_precision")]
+    pub price_precision: Option<u32>,
+    // Fired from a window still warming up (WARMUP_MODE=flag)
+    #[serde(default, alias = "low_confidence")]
+    pub low_confidence: bool,
+    // Market bias score (-100..100) when the signal fired, see `bias`
+    #[serde(default)]
+    pub market_bias: Option<f64>,
+    // Fear & Greed, funding and OI aggregates when the signal fired (SENTIMENT_ENABLED only)
+    #[serde(default)]
+    pub market_context: Option<crate::sentiment::MarketContext>,
+    // Fired along with the market rather than on its own, and the minute's group (see `correlation`)
+    #[serde(default)]
+    pub market_wide: bool,
+    #[serde(default)]
+    pub correlation: Option<crate::correlation::Correlation>,
+    // Received from another cluster instance (see `cluster`): shown and recorded here, but
+    // the instance that emitted it already notified and traded on it. Never on the wire.
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schema(ignore)]
+    pub relayed: bool,
+}
+
+// Bid/ask wall ratio the verifier calls "strong"
+pub const STRONG_WALL_RATIO: f64 = 1.2;
+
+/// Why a signal fired, one entry per check that contributed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(tag = "code", rename_all_fields = "camelCase")]
+pub enum ReasonCode {
+    // Minute volume vs the window average (or the hour of day's, if higher; see
+    // `SymbolState::expected_volume`), with the price barely moving. `zscore` is against the
+    // hours-long baseline (see `SymbolState::volume_zscore`), None before it has one.
+    VolumeSpike {
+        ratio: f64,
+        avg_value: f64,
+        price_change_pct: f64,
+        #[serde(default)]
+        zscore: Option<f64>,
+    },
+    // Bid vs ask depth near the touch (Long) ...
+    BuyWall { ratio: f64 },
+    // ... and ask vs bid (Short)
+    SellWall { ratio: f64 },
+    OpenInterest { value: f64 }, // In quote currency
+    WhaleActive { value: f64 },  // Signal minute's traded value
+    WarmingUp { candles: usize, required: usize },
+    // Fired against a strong market bias (Short into a bull tape, Long into a bear one)
+    AgainstBias { score: f64 },
+    // Higher-timeframe trend agreed with the direction (`trend_timeframe`)
+    TrendConfirmed { timeframe: Interval, change_pct: f64 },
+    // Forced liquidations in the signal's candle, by the side that was closed out (in quote);
+    // `zscore` against the hour before, when the liquidation cascade fired on it
+    Liquidations {
+        long_value: f64,
+        short_value: f64,
+        #[serde(default)]
+        zscore: Option<f64>,
+    },
+    // Current funding (percent per interval). Extreme funding paid by the signal's side means
+    // it's crowded (`against`); paid by the other side, it's fuel for a squeeze.
+    Funding { rate_pct: f64, extreme: bool, against: bool },
+    // Open interest change over the last `minutes` before the signal
+    OiChange { minutes: i64, change_pct: f64 },
+    // Fired with the rest of the market (see `correlation`): `group_size` symbols that minute,
+    // BTCUSDT's move if it was known
+    MarketWide { group_size: usize, leader_change_pct: Option<f64> },
+    // Volume spiked on one of Binance's spot and futures markets while the other stayed flat
+    // (see `divergence`): heavy spot buying with flat futures, or the reverse
+    SpotFuturesDivergence { led_by: crate::exchange::MarketType, spot_volume_ratio: f64, futures_volume_ratio: f64 },
+    // The contract went live `age_minutes` before the signal (at `listed_at`, ms)
+    NewListing { listed_at: i64, age_minutes: i64 },
+    // Fired by a TradingView alert: the Pine strategy (or alert) name and its message, if any
+    TradingView { strategy: String, comment: Option<String> },
+    // Takers net selling (`delta_pct` of their volume, negative) into open interest that rose
+    // `oi_change_pct` over 5 minutes: shorts being opened, not longs getting out (see
+    // `check_short_pressure`). The book has to show asks `min_wall_ratio` times the bids.
+    ShortPressure { volume_ratio: f64, delta_pct: f64, oi_change_pct: f64, min_wall_ratio: f64 },
+    // `candles` minutes of higher closes on growing volume, `change_pct` from the first one's
+    // open, the last minute at `volume_ratio` times the average (see `check_breakout`)
+    Breakout { candles: usize, change_pct: f64, volume_ratio: f64 },
+    // How Binance futures traders are positioned (see `positioning`), each None if unknown
+    Positioning { top_long_short_ratio: Option<f64>, taker_buy_sell_ratio: Option<f64>, premium_pct: Option<f64> },
+}
+
+impl ReasonCode {
+    pub fn render(&self) -> String {
+        match self {
+            ReasonCode::VolumeSpike { ratio, avg_value, price_change_pct, zscore } => format!(
+                "Vol: {:.1}x{} (Avg ${:.0}k), Price stable ({:.2}%)",
+                ratio,
+                zscore.map(|z| format!(" z{:.1}", z)).unwrap_or_default(),
+                avg_value / 1000.0,
+                price_change_pct
+            ),
+            ReasonCode::BuyWall { ratio } if *ratio > STRONG_WALL_RATIO => format!("Strong Buy Wall (x{:.1})", ratio),
+            ReasonCode::SellWall { ratio } if *ratio > STRONG_WALL_RATIO => format!("Strong Sell Wall (x{:.1})", ratio),
+            ReasonCode::BuyWall { ratio } | ReasonCode::SellWall { ratio } => format!("Moderate Wall (x{:.1})", ratio),
+            ReasonCode::OpenInterest { value } => format!("OI: ${:.1}M", value / 1_000_000.0),
+            ReasonCode::WhaleActive { .. } => "🐋 Whale Active".to_string(),
+            ReasonCode::WarmingUp { candles, required } => format!("⚠️ Warming up ({}/{} candles)", candles, required),
+            ReasonCode::AgainstBias { score } => format!("⚠️ Against market bias ({:+.0})", score),
+            ReasonCode::TrendConfirmed { timeframe, change_pct } => format!("{} trend {:+.2}%", timeframe.label(), change_pct),
+            ReasonCode::Liquidations { long_value, short_value, zscore } => format!(
+                "Liq: ${:.2}M longs / ${:.2}M shorts{}",
+                long_value / 1_000_000.0,
+                short_value / 1_000_000.0,
+                zscore.map(|z| format!(" (z{:.1})", z)).unwrap_or_default()
+            ),
+            ReasonCode::Funding { rate_pct, extreme: false, .. } => format!("Funding {:+.3}%", rate_pct),
+            ReasonCode::Funding { rate_pct, against: true, .. } => format!("⚠️ Crowded funding {:+.3}%", rate_pct),
+            ReasonCode::Funding { rate_pct, .. } => format!("Squeeze funding {:+.3}%", rate_pct),
+            ReasonCode::OiChange { minutes, change_pct } => format!("OI {:+.2}% ({}m)", change_pct, minutes),
+            ReasonCode::MarketWide { group_size, leader_change_pct: Some(change) } => {
+                format!("🌐 Market-wide (BTC {:+.2}%, {} signals)", change, group_size)
+            }
+            ReasonCode::MarketWide { group_size, .. } => format!("🌐 Market-wide ({} signals)", group_size),
+            ReasonCode::SpotFuturesDivergence { led_by, spot_volume_ratio, futures_volume_ratio } => format!(
+                "{}-led: spot {:.1}x vs futures {:.1}x",
+                match led_by {
+                    crate::exchange::MarketType::Spot => "Spot",
+                    crate::exchange::MarketType::Futures => "Futures",
+                },
+                spot_volume_ratio,
+                futures_volume_ratio
+            ),
+            ReasonCode::NewListing { age_minutes, .. } => format!("🆕 Listed {}m ago", age_minutes),
+            ReasonCode::TradingView { strategy, comment: Some(comment) } => format!("📺 {}: {}", strategy, comment),
+            ReasonCode::TradingView { strategy, .. } => format!("📺 {}", strategy),
+            ReasonCode::ShortPressure { volume_ratio, delta_pct, oi_change_pct, .. } => {
+                format!("Vol: {:.1}x, sellers opening (delta {:+.0}%, OI {:+.2}% 5m)", volume_ratio, delta_pct, oi_change_pct)
+            }
+            ReasonCode::Breakout { candles, change_pct, volume_ratio } => {
+                format!("🚀 {} rising minutes ({:+.2}%), Vol: {:.1}x", candles, change_pct, volume_ratio)
+            }
+            ReasonCode::Positioning { top_long_short_ratio, taker_buy_sell_ratio, premium_pct } => [
+                top_long_short_ratio.map(|r| format!("Top L/S {:.2}", r)),
+                taker_buy_sell_ratio.map(|r| format!("Taker B/S {:.2}", r)),
+                premium_pct.map(|p| format!("Premium {:+.3}%", p)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", "),
+        }
+    }
+}
+
+/// Deterministic id: a symbol fires at most once per event time, and every instance
+/// (and shard) derives the same id for the same signal.
+pub fn signal_id(symbol: &str, timestamp: i64) -> String {
+    format!("{}-{}", symbol, timestamp)
+}
+
+/// The span everything logged about one signal runs in, so each line carries its trace id.
+pub fn signal_span(trace_id: &str, signal_id: &str, symbol: &str) -> tracing::Span {
+    tracing::info_span!("signal", trace_id = %trace_id, signal_id = %signal_id, symbol = %symbol)
+}
+
+fn default_strategy_name() -> String {
+    crate::strategy::SILENT_WATCHER.to_string()
+}
+
+impl Signal {
+    pub fn span(&self) -> tracing::Span {
+        signal_span(&self.trace_id, &self.id, &self.symbol)
+    }
+
+    // Two strategies can fire on the same tick: other strategies' ids carry their name, the
+    // Silent Watcher's keep the format that predates strategies
+    pub fn set_strategy(&mut self, strategy: &str) {
+        self.strategy_name = strategy.to_string();
+        if strategy != crate::strategy::SILENT_WATCHER {
+            self.id = format!("{}-{}-{}", self.symbol, strategy, self.timestamp);
+        }
+    }
+
+    // For signals stored or sent before they carried an id
+    pub fn ensure_id(&mut self) {
+        if self.id.is_empty() {
+            self.id = signal_id(&self.symbol, self.timestamp);
+        }
+    }
+
+    pub fn add_reason(&mut self, code: ReasonCode) {
+        self.reason += " | ";
+        self.reason += &code.render();
+        self.reasons.push(code);
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SignalUpdate {
+    // The live signal this update belongs to
+    #[serde(default, alias = "signal_id")]
+    pub signal_id: Option<String>,
     pub symbol: String,
-    pub price: f64,
+    pub price: f64, // Close so far
+    #[serde(default)]
+    pub open: f64,
+    #[serde(default)]
+    pub high: f64,
+    #[serde(default)]
+    pub low: f64,
     pub volume: f64,
+    #[serde(default, alias = "quote_volume")]
+    pub quote_volume: f64,
+    #[serde(default)]
+    pub trades: u64,
+    #[serde(default, alias = "taker_buy_volume")]
+    pub taker_buy_volume: Option<f64>,
     pub timestamp: i64,
+    #[serde(default)]
+    pub indicators: Option<IndicatorValues>,
+    // Streamed order book imbalance over the last minute (see `depth_tracker`), None until tracked
+    #[serde(default)]
+    pub imbalance: Option<crate::depth_tracker::BookImbalance>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A published signal whose premise broke: price moved too far against it, or the order
+/// book no longer backs it on re-verification.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Invalidation {
+    pub signal_id: String,
+    pub symbol: String,
+    pub reason: String,
+    pub timestamp: i64,
+    #[serde(default)]
+    pub stopped_out: bool, // The adverse move, rather than a failed re-check
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", content = "payload")] // "type": "signal", "payload": { ... }
 pub enum WsMessage {
-    Signal(Signal),
-    Update(SignalUpdate),
+    Signal(Box<Signal>), // Boxed: a Signal is much larger than the other variants
+    Updates(Vec<SignalUpdate>), // Batched live updates for symbols with an active signal
     History(Vec<Signal>),
-    Stats(crate::history::Stats), 
+    Stats(Box<crate::history::Stats>), // Boxed, like Signal: the per-group breakdowns make it large
+    Invalidate(Invalidation), // Grey out the signal instead of showing it for the full hour
+    Leaderboard(crate::leaderboard::Leaderboard), // Periodic top movers, between signals
+    MarketOverview(crate::leaderboard::MarketOverview), // Periodic market-wide volume and 5m movers
+    MarketAlert(crate::market_alert::MarketAlert), // Market-wide event started / ended
+    WatchAlert(crate::watchlist::WatchAlert), // A user's watch rule fired
+    AlertTriggered(crate::price_alert::PriceAlert), // A user's price or volume alert fired (one-shot)
+    Alerts(Vec<crate::price_alert::PriceAlert>), // Every alert, after one was added or removed
+    WatchedSymbols(Vec<String>), // The watchlist's symbols, after one was added or removed
+    Feedback(crate::history::SignalFeedback), // A trader marked a signal taken/skipped/good/bad
+    Carry(crate::carry::CarryOpportunity), // Funding carry, a separate category from momentum signals
+    MarketBias(crate::bias::MarketBias), // Periodic, which way the whole tape leans
+    SignalState(crate::lifecycle::StateTransition), // A signal moved along its lifecycle
+    SignalClosed(crate::lifecycle::SignalClosed), // ...and ended, with its outcome
+    Positions(crate::paper_trader::Positions), // Open paper positions (PAPER_TRADING_ENABLED)
+    Portfolio(Box<crate::portfolio::Portfolio>), // Real account and the signals it traded (PORTFOLIO_ENABLED)
+    ConfigChanged(Box<crate::control::ConfigChange>), // An admin changed the running scanner
+    Lagged(crate::client_queue::LagNotice), // This client fell behind and missed messages
+    SystemEvent(crate::system_event::SystemEvent), // Degraded mode started / ended somewhere in the backend
+    Candle(crate::klines::LiveCandle), // The streamed symbol's minute in progress, to one client (`stream_candles`)
+}
+
+impl WsMessage {
+    /// The `type` tag the message is sent with.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WsMessage::Signal(_) => "Signal",
+            WsMessage::Updates(_) => "Updates",
+            WsMessage::History(_) => "History",
+            WsMessage::Stats(_) => "Stats",
+            WsMessage::Invalidate(_) => "Invalidate",
+            WsMessage::Leaderboard(_) => "Leaderboard",
+            WsMessage::MarketOverview(_) => "MarketOverview",
+            WsMessage::MarketAlert(_) => "MarketAlert",
+            WsMessage::WatchAlert(_) => "WatchAlert",
+            WsMessage::AlertTriggered(_) => "AlertTriggered",
+            WsMessage::Alerts(_) => "Alerts",
+            WsMessage::WatchedSymbols(_) => "WatchedSymbols",
+            WsMessage::Feedback(_) => "Feedback",
+            WsMessage::Carry(_) => "Carry",
+            WsMessage::MarketBias(_) => "MarketBias",
+            WsMessage::SignalState(_) => "SignalState",
+            WsMessage::SignalClosed(_) => "SignalClosed",
+            WsMessage::Positions(_) => "Positions",
+            WsMessage::Portfolio(_) => "Portfolio",
+            WsMessage::ConfigChanged(_) => "ConfigChanged",
+            WsMessage::Lagged(_) => "Lagged",
+            WsMessage::SystemEvent(_) => "SystemEvent",
+            WsMessage::Candle(_) => "Candle",
+        }
+    }
+
+    /// The one symbol the message is about, if it is about one.
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            WsMessage::Signal(signal) => Some(&signal.symbol),
+            WsMessage::Invalidate(invalidation) => Some(&invalidation.symbol),
+            WsMessage::WatchAlert(alert) => Some(&alert.symbol),
+            WsMessage::AlertTriggered(alert) => Some(&alert.symbol),
+            WsMessage::Carry(carry) => Some(&carry.symbol),
+            WsMessage::SignalState(transition) => Some(&transition.symbol),
+            WsMessage::SignalClosed(closed) => Some(&closed.symbol),
+            WsMessage::Candle(live) => Some(&live.symbol),
+            _ => None,
+        }
+    }
 }
 
-pub fn check_for_signals(state: &SymbolState, current_data: &MarketData, taker_buy_vol: f64) -> Option<Signal> {
-    let avg_vol = state.get_average_volume();
+// Change (fraction) from the close `candles` finished candles back to `price`; None until the
+// timeframe has that much history
+fn trend_change(state: &SymbolState, interval: Interval, candles: usize, price: f64) -> Option<f64> {
+    let window = state.candles(interval);
+    let base = window.get(window.len().checked_sub(candles)?)?.close;
+    (base > 0.0).then(|| (price - base) / base)
+}
+
+// How far the last hour (the 1m window plus `candle`) already went, as fractions of the
+// window's first open: to the latest close, up to the high and down to the low (both >= 0)
+fn prior_move(state: &SymbolState, candle: &MarketData) -> Option<(f64, f64, f64)> {
+    let base = state.window.front()?.open;
+    if base <= 0.0 {
+        return None;
+    }
+    let (high, low) = state.window.iter().fold((candle.high, candle.low), |(high, low), d| (high.max(d.high), low.min(d.low)));
+    Some(((candle.close - base) / base, (high - base) / base, (base - low) / base))
+}
+
+// Thresholds come from `config::ScannerConfig` (defaults in brackets below).
+// `current_data` is the 1m candle in progress; with a higher `timeframe` the checks run on the
+// candle of that size it belongs to, against that timeframe's average.
+pub fn check_for_signals(state: &SymbolState, current_data: &MarketData, config: &ScannerConfig) -> Option<Signal> {
+    let timeframe_candle;
+    let current_data = match config.timeframe {
+        Interval::M1 => current_data,
+        timeframe => {
+            timeframe_candle = state.current_candle(timeframe, current_data);
+            &timeframe_candle
+        }
+    };
+    let avg_vol = state.average_volume(config.timeframe);
     
     // Silent Watcher: Filter out absolute dust, but keep low-cap "dead" coins.
     // measurable "activity" usually means at least some value traded.
@@ -49,64 +456,384 @@ pub fn check_for_signals(state: &SymbolState, current_data: &MarketData, taker_b
     // In `binance_client.rs`, we parsed 'v' (Base Asset).
     // So Value = Volume * Price.
     
-    let current_value = current_data.volume * current_data.price;
-    let avg_value = avg_vol * current_data.price;
+    let current_value = current_data.volume * current_data.close;
+    let avg_value = avg_vol * current_data.close;
 
-    if current_value < 10_000.0 {
+    if current_value < config.min_value {
         return None;
     }
 
-    let volume_ratio = if avg_vol > 0.0 { current_data.volume / avg_vol } else { 0.0 };
+    // The spike is measured against the hour's average, or what this hour of the day usually
+    // trades if that is more: the US open ramping up out of a quiet hour isn't a spike.
+    let expected = state.expected_volume(config.timeframe, current_data.timestamp);
+    let reference_vol = avg_vol.max(expected.unwrap_or(0.0));
+    let volume_ratio = if reference_vol > 0.0 { current_data.volume / reference_vol } else { 0.0 };
     
     // Logic Refinement:
     // 1. Min 24h Volume (Actually avg_value of window is small for low vol coins)
-    //    We want coins with substantial volume. Let's filter avg_value > $50k
-    if avg_value < 50_000.0 {
+    //    We want coins with substantial volume. Let's filter avg_value > $50k (min_avg_value)
+    if avg_value < config.min_avg_value {
         return None;
     }
 
-    // 2. Cooldown Check (30 mins = 1800s * 1000ms)
-    if let Some(last_time) = state.last_signal_time {
-        if current_data.timestamp - last_time < 30 * 60 * 1000 {
-            return None;
-        }
-    }
-    
-    let last_close = state.window.back().map(|d| d.price).unwrap_or(current_data.price);
-    let price_change_percent = (current_data.price - last_close).abs() / last_close;
+    // 2. Cooldown: checked per strategy by `strategy::Registry`
+
+    // "Price stable" has to hold for the wicks too, not just the close: a spike that already
+    // ran 2% and came back is not silent accumulation.
+    let last_close = state.candles(config.timeframe).back().map(|d| d.close).unwrap_or(current_data.close);
+    let price_change_percent = [current_data.close, current_data.high, current_data.low]
+        .iter()
+        .filter(|p| **p > 0.0)
+        .map(|p| (p - last_close).abs() / last_close)
+        .fold(0.0, f64::max);
 
     // Logic: 
     // 1. "Dead" Coin waking up: Avg Value < 100k (Dead) AND Vol > 5x Avg. -> But we filter < 50k. So 50k-100k range.
     // 2. Active Coin spike: Vol > 3x Avg.
     
-    let is_dead_wakeup = avg_value < 100_000.0 && volume_ratio > 5.0;
-    let is_normal_spike = volume_ratio > 3.0;
+    // Both bars go up during a market-wide event (see `market_alert`). With the z-score
+    // trigger nothing fires until the symbol has a baseline.
+    let mult = crate::market_alert::threshold_multiplier(current_data.timestamp);
+    let zscore = state.volume_zscore(config.timeframe, current_data.volume);
+    let (is_dead_wakeup, is_normal_spike) = match config.trigger {
+        SpikeTrigger::Ratio => (
+            avg_value < config.dead_avg_value && volume_ratio > config.dead_spike_ratio * mult,
+            volume_ratio > config.spike_ratio * mult,
+        ),
+        SpikeTrigger::ZScore => (
+            avg_value < config.dead_avg_value && zscore.is_some_and(|z| z > config.dead_zscore_threshold * mult),
+            zscore.is_some_and(|z| z > config.zscore_threshold * mult),
+        ),
+    };
 
-    if (is_dead_wakeup || is_normal_spike) && price_change_percent < 0.008 {
+    if (is_dead_wakeup || is_normal_spike) && price_change_percent * 100.0 < config.max_price_change_pct {
          // Determine direction
-        let taker_sell_vol = current_data.volume - taker_buy_vol;
+        // Taker-buy volume comes with the candle when the feed reports it, or from the
+        // symbol's streamed trades once its volume ramped (`order_flow`). Without either both
+        // sides are 0 and the direction falls through to Short.
+        let taker_buy_vol = current_data.taker_buy_volume.unwrap_or(0.0);
+        let taker_sell_vol = current_data.taker_sell_volume().unwrap_or(0.0);
         
         let signal_type = if taker_buy_vol > taker_sell_vol {
             SignalType::Long
         } else {
             SignalType::Short
         };
+        let candle_start = current_data.timestamp - current_data.timestamp.rem_euclid(config.timeframe.minutes() * 60_000);
 
-        let current_value = current_data.volume * current_data.price; // Re-calculate for log if needed, or stick to prev variable
-        
-        info!("Silent Watcher Detected: {:?} for {} (Val: ${:.0}, Ratio: {:.1}x, Price Chg: {:.4}%)", 
-              signal_type, current_data.symbol, current_value, volume_ratio, price_change_percent*100.0);
-
-        return Some(Signal {
-            symbol: current_data.symbol.clone(),
-            signal_type,
-            price: current_data.price,
-            volume: current_data.volume,
-            avg_volume: avg_vol,
-            timestamp: current_data.timestamp,
-            reason: format!("Silent Alert! Vol: {:.1}x (Avg ${:.0}k), Price stable ({:.2}%)", volume_ratio, avg_value/1000.0, price_change_percent*100.0),
+        // Priced in: a coin that already ran the signal's way [10%] over the hour is late,
+        // whatever its volume does now. Dropped candidates are logged for /api/suppressed.
+        if let Some((change, high, low)) = prior_move(state, current_data) {
+            let ran = match signal_type {
+                SignalType::Long | SignalType::NewListing | SignalType::Breakout => change.max(high),
+                SignalType::Short => (-change).max(low),
+            };
+            if ran * 100.0 > config.max_prior_move_pct {
+                crate::suppressed::record(crate::suppressed::SuppressedCandidate {
+                    symbol: state.symbol.clone(),
+                    strategy: crate::strategy::SILENT_WATCHER.to_string(),
+                    signal_type: signal_type.clone(),
+                    candle_start,
+                    timestamp: current_data.timestamp,
+                    price: current_data.close,
+                    volume_ratio,
+                    reason: crate::suppressed::SuppressionReason::PricedIn {
+                        change_pct: change * 100.0,
+                        high_pct: high * 100.0,
+                        low_pct: low * 100.0,
+                        max_pct: config.max_prior_move_pct,
+                    },
+                });
+                return None;
+            }
+        }
+
+        // Trend confirmation: no signal against (or without enough history for) the trend
+        let trend = match config.trend_timeframe {
+            Some(timeframe) => {
+                let change = trend_change(state, timeframe, config.trend_candles, current_data.close)?;
+                let agrees = match signal_type {
+                    SignalType::Long | SignalType::NewListing | SignalType::Breakout => change > 0.0,
+                    SignalType::Short => change < 0.0,
+                };
+                if !agrees {
+                    return None;
+                }
+                Some(ReasonCode::TrendConfirmed { timeframe, change_pct: change * 100.0 })
+            }
+            None => None,
+        };
+
+        // Liquidation context, when the liquidation stream runs
+        let liquidations = state.liquidations.since(candle_start);
+        let liquidations = (liquidations.total() > 0.0).then_some(ReasonCode::Liquidations {
+            long_value: liquidations.long_value,
+            short_value: liquidations.short_value,
+            zscore: None,
         });
+
+        let current_value = current_data.volume * current_data.close; // Re-calculate for log if needed, or stick to prev variable
+        
+        info!(
+            symbol = %state.symbol,
+            signal_type = ?signal_type,
+            value_usdt = current_value,
+            volume_ratio,
+            price_change_pct = price_change_percent * 100.0,
+            trades = current_data.trades,
+            avg_trade_size = ?current_data.avg_trade_size(),
+            taker_buy_volume = ?current_data.taker_buy_volume,
+            volume_zscore = ?zscore,
+            volume_p95 = ?state.volume_stats.quantile(0.95),
+            volume_range = ?state.volume_stats.min().zip(state.volume_stats.max()),
+            day_change_pct = ?state.day.change_pct,
+            day_range_position = ?state.day.range_position(current_data.close),
+            vwap = ?state.vwap.window(),
+            session_vwap = ?state.vwap.session(),
+            rsi = ?state.indicators.rsi(),
+            atr = ?state.indicators.atr(),
+            avg_volume_5m = state.average_volume(Interval::M5),
+            volume_stddev_5m = state.volume_stddev(Interval::M5),
+            avg_volume_15m = state.average_volume(Interval::M15),
+            volume_stddev_15m = state.volume_stddev(Interval::M15),
+            expected_volume = ?expected,
+            "Silent Watcher Detected"
+        );
+
+        let spike = ReasonCode::VolumeSpike {
+            ratio: volume_ratio,
+            avg_value: reference_vol * current_data.close,
+            price_change_pct: price_change_percent * 100.0,
+            zscore,
+        };
+        let mut reasons = vec![spike];
+        reasons.extend(trend);
+        reasons.extend(liquidations);
+        return Some(build_signal(state, current_data, signal_type, config.timeframe, avg_vol, reasons, "Silent Alert"));
     }
 
     None
 }
+
+/// Liquidation cascade: a minute's forced liquidations well above the symbol's recent rate.
+/// Fires with the forced flow, Short when longs are being closed out and Long for shorts.
+pub fn check_liquidation_cascade(state: &SymbolState, current_data: &MarketData, config: &LiquidationConfig) -> Option<Signal> {
+    let minute = current_data.timestamp - current_data.timestamp.rem_euclid(60_000);
+    let liquidations = state.liquidations.since(minute);
+    let total = liquidations.total();
+    if total < config.min_value {
+        return None;
+    }
+    let average = state.liquidations.average_before(minute);
+    // A quiet hour before (no average, flat z) lets any liquidation over `min_value` through
+    let zscore = state.liquidations.zscore_before(minute, total);
+    let spiked = match config.trigger {
+        SpikeTrigger::Ratio => average == 0.0 || total >= average * config.ratio,
+        SpikeTrigger::ZScore => zscore.map_or(average == 0.0, |z| z >= config.zscore_threshold),
+    };
+    if !spiked {
+        return None;
+    }
+    let signal_type = if liquidations.long_value >= liquidations.short_value { SignalType::Short } else { SignalType::Long };
+
+    info!(
+        symbol = %state.symbol,
+        signal_type = ?signal_type,
+        long_value = liquidations.long_value,
+        short_value = liquidations.short_value,
+        average_per_minute = average,
+        zscore = ?zscore,
+        "Liquidation Cascade Detected"
+    );
+
+    let reasons = vec![ReasonCode::Liquidations {
+        long_value: liquidations.long_value,
+        short_value: liquidations.short_value,
+        zscore: (config.trigger == SpikeTrigger::ZScore).then_some(zscore).flatten(),
+    }];
+    let avg_vol = state.average_volume(Interval::M1);
+    Some(build_signal(state, current_data, signal_type, Interval::M1, avg_vol, reasons, "Liquidation Cascade"))
+}
+
+/// Short pressure: a volume spike where takers sell into rising open interest, new shorts
+/// rather than longs getting out, with heavy asks on the book. Short only, unlike the Silent
+/// Watcher whose direction is just the taker majority. The order book is checked twice: the
+/// streamed one (see `depth_tracker`) here if the symbol has it, a snapshot at verification
+/// always (see `verifier`).
+pub fn check_short_pressure(state: &SymbolState, current_data: &MarketData, config: &ShortPressureConfig) -> Option<Signal> {
+    if current_data.volume * current_data.close < config.min_value {
+        return None;
+    }
+    let avg_vol = state.average_volume(Interval::M1);
+    let mult = crate::market_alert::threshold_multiplier(current_data.timestamp);
+    let volume_ratio = if avg_vol > 0.0 { current_data.volume / avg_vol } else { 0.0 };
+    if volume_ratio < config.ratio * mult {
+        return None;
+    }
+    // OI-weighted delta: the takers' net share (-1 all selling .. +1 all buying) times the OI
+    // change. Selling into falling OI is longs closing, buying into falling OI shorts covering;
+    // only selling while OI rises counts.
+    let (buy, sell) = (current_data.taker_buy_volume?, current_data.taker_sell_volume()?);
+    if buy + sell <= 0.0 {
+        return None;
+    }
+    let delta = (buy - sell) / (buy + sell);
+    let oi_change = state.open_interest.change_pct(5)?;
+    if delta >= 0.0 || oi_change <= 0.0 || -delta * oi_change < config.min_oi_delta {
+        return None;
+    }
+    // The streamed book's bid/ask average must leave the asks `min_wall_ratio` times heavier
+    if let Some(book) = state.depth.summary(current_data.timestamp) {
+        if book.avg_ratio * config.min_wall_ratio > 1.0 {
+            return None;
+        }
+    }
+
+    info!(
+        symbol = %state.symbol,
+        volume_ratio,
+        delta_pct = delta * 100.0,
+        oi_change_pct = oi_change,
+        oi_delta = delta * oi_change,
+        "Short Pressure Detected"
+    );
+
+    let reasons = vec![ReasonCode::ShortPressure {
+        volume_ratio,
+        delta_pct: delta * 100.0,
+        oi_change_pct: oi_change,
+        min_wall_ratio: config.min_wall_ratio,
+    }];
+    Some(build_signal(state, current_data, SignalType::Short, Interval::M1, avg_vol, reasons, "Short Pressure"))
+}
+
+/// Price velocity breakout: the last `candles` minutes (this one included) each closing higher
+/// than the one before on more volume than it, up at least `min_change_pct` from the first
+/// one's open. The moves the Silent Watcher leaves out by design (`max_price_change_pct`).
+pub fn check_breakout(state: &SymbolState, current_data: &MarketData, config: &BreakoutConfig) -> Option<Signal> {
+    if current_data.volume * current_data.close < config.min_value {
+        return None;
+    }
+    let avg_vol = state.average_volume(Interval::M1);
+    let mult = crate::market_alert::threshold_multiplier(current_data.timestamp);
+    let volume_ratio = if avg_vol > 0.0 { current_data.volume / avg_vol } else { 0.0 };
+    if volume_ratio < config.ratio * mult {
+        return None;
+    }
+    // The finished minutes before this one, oldest first
+    let before = state.window.len().checked_sub(config.candles - 1)?;
+    let run: Vec<&MarketData> = state.window.iter().skip(before).chain(std::iter::once(current_data)).collect();
+    let first = run[0];
+    if first.open <= 0.0 || first.close <= first.open {
+        return None;
+    }
+    if !run.windows(2).all(|pair| pair[1].close > pair[0].close && pair[1].volume > pair[0].volume) {
+        return None;
+    }
+    let change_pct = (current_data.close - first.open) / first.open * 100.0;
+    if change_pct < config.min_change_pct {
+        return None;
+    }
+
+    info!(
+        symbol = %state.symbol,
+        candles = config.candles,
+        change_pct,
+        volume_ratio,
+        "Breakout Detected"
+    );
+
+    let reasons = vec![ReasonCode::Breakout { candles: config.candles, change_pct, volume_ratio }];
+    Some(build_signal(state, current_data, SignalType::Breakout, Interval::M1, avg_vol, reasons, "Breakout"))
+}
+
+/// A freshly listed contract's first signal. There is no history to judge it by, so it skips
+/// the spike criteria and is flagged low-confidence.
+pub fn new_listing_signal(state: &SymbolState, candle: &MarketData, listed_at: i64) -> Signal {
+    let age_minutes = (candle.timestamp - listed_at).max(0) / 60_000;
+    let reasons = vec![ReasonCode::NewListing { listed_at, age_minutes }];
+    let mut signal = build_signal(state, candle, SignalType::NewListing, Interval::M1, state.get_average_volume(), reasons, "New Listing");
+    signal.low_confidence = true;
+    signal
+}
+
+/// A TradingView alert's signal on `candle`, with whatever context `state` holds (none for a
+/// symbol the scanner doesn't track). Named after the alert's strategy; not verified yet.
+pub fn tradingview_signal(state: &SymbolState, candle: &MarketData, signal_type: SignalType, timeframe: Interval, strategy: &str, comment: Option<String>) -> Signal {
+    let reasons = vec![ReasonCode::TradingView { strategy: strategy.to_string(), comment }];
+    let headline = match signal_type {
+        SignalType::Short => "TradingView Short",
+        _ => "TradingView Long",
+    };
+    let mut signal = build_signal(state, candle, signal_type, timeframe, state.get_average_volume(), reasons, headline);
+    // The id carries the source, the name is free text
+    signal.set_strategy("tradingview");
+    signal.strategy_name = strategy.to_string();
+    signal.source = SignalSource::TradingView;
+    signal
+}
+
+// A signal on `candle` (of `timeframe`), with the symbol's context filled in from `state`
+fn build_signal(
+    state: &SymbolState,
+    candle: &MarketData,
+    signal_type: SignalType,
+    timeframe: Interval,
+    avg_volume: f64,
+    reasons: Vec<ReasonCode>,
+    headline: &str,
+) -> Signal {
+    let display_price = match &state.meta {
+        Some(meta) => meta.format_price(candle.close),
+        None => candle.close.to_string(),
+    };
+    let reason = reasons.iter().map(ReasonCode::render).collect::<Vec<_>>().join(" | ");
+    let timeframe_label = match timeframe {
+        Interval::M1 => String::new(),
+        timeframe => format!(" [{}]", timeframe.label()),
+    };
+    let mut signal = Signal {
+        id: signal_id(&candle.symbol, candle.timestamp),
+        trace_id: uuid::Uuid::new_v4().to_string(),
+        symbol: candle.symbol.clone(),
+        exchange: crate::exchange::split(&candle.symbol).0,
+        market: crate::exchange::split(&candle.symbol).0.market(),
+        signal_type,
+        timeframe,
+        strategy_name: default_strategy_name(),
+        source: SignalSource::Scanner,
+        price: candle.close,
+        volume: candle.volume,
+        avg_volume,
+        timestamp: candle.timestamp,
+        reason: format!("{} @ {}{}! {}", headline, display_price, timeframe_label, reason),
+        reasons,
+        low_confidence: false,
+        market_bias: None,
+        market_context: None,
+        market_wide: false,
+        correlation: None,
+        relayed: false,
+        high: candle.high,
+        low: candle.low,
+        quote_volume: candle.quote_volume,
+        trades: candle.trades,
+        taker_buy_volume: candle.taker_buy_volume,
+        vwap: state.vwap.window(),
+        session_vwap: state.vwap.session(),
+        indicators: Some(state.indicators()),
+        oi_change: state.open_interest.changes(),
+        confidence: None,
+        score: None,
+        day_high: state.day.high,
+        day_low: state.day.low,
+        day_change_pct: state.day.change_pct,
+        day_quote_volume: (state.day.quote_volume > 0.0).then_some(state.day.quote_volume),
+        day_range_position: state.day.range_position(candle.close),
+        base_asset: state.meta.as_ref().map(|m| m.base_asset.clone()),
+        quote_asset: state.meta.as_ref().map(|m| m.quote_asset.clone()),
+        price_precision: state.meta.as_ref().map(|m| m.price_precision),
+    };
+    crate::score::rescore(&mut signal);
+    signal
+}
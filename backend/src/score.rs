@@ -0,0 +1,76 @@
+use crate::scanner::{ReasonCode, Signal};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+// One number to rank signals by when several fire in the same minute. Four parts, each 0..1:
+// how far the volume is out of line (z-score against the baseline, else the window ratio),
+// how much the book leans the signal's way (the verifier's wall ratio), whether open interest
+// is building, and how still the price stayed through the spike. The scanner scores what it
+// measured; the verifier scores again once it has added the book and OI. A part that wasn't
+// measured counts as neutral (0.5), so an unverified signal neither tops nor trails the board.
+// Unlike `confidence` (the verifier's publish gate) this is only for ordering.
+
+const VOLUME_WEIGHT: f64 = 40.0;
+const BOOK_WEIGHT: f64 = 25.0;
+const OI_WEIGHT: f64 = 20.0;
+const STABILITY_WEIGHT: f64 = 15.0;
+
+const NEUTRAL: f64 = 0.5;
+const ZSCORE_FULL: f64 = 8.0; // Baseline z-score that maxes the volume part ...
+const RATIO_FULL: f64 = 10.0; // ... or, without a baseline, volume vs the window average
+const WALL_FULL: f64 = 2.0; // Wall ratio (or its inverse) that maxes the book part either way
+const OI_FULL_PCT: f64 = 2.0; // Open interest change that does the same
+const MOVE_FULL_PCT: f64 = 1.0; // A candle moving this much has no stability left
+
+/// A signal's composite score and what went into it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalScore {
+    pub total: f64, // 0..100
+    // Parts, 0..1; None where nothing was measured (scored as neutral)
+    pub volume: Option<f64>,
+    pub book: Option<f64>,
+    pub open_interest: Option<f64>,
+    pub stability: Option<f64>,
+}
+
+/// Scores `signal` from its reasons and candle.
+pub fn score(signal: &Signal) -> SignalScore {
+    let (mut volume, mut book, mut open_interest, mut stability) = (None, None, None, None);
+    for reason in &signal.reasons {
+        match reason {
+            ReasonCode::VolumeSpike { ratio, price_change_pct, zscore, .. } => {
+                volume = Some(match zscore {
+                    Some(z) => z / ZSCORE_FULL,
+                    None => (ratio - 1.0) / (RATIO_FULL - 1.0),
+                });
+                stability = Some(1.0 - price_change_pct.abs() / MOVE_FULL_PCT);
+            }
+            // Log scale, as in the verifier; an empty side (ratio 0) says nothing
+            ReasonCode::BuyWall { ratio } | ReasonCode::SellWall { ratio } if *ratio > 0.0 => {
+                book = Some(NEUTRAL + ratio.ln() / WALL_FULL.ln() * NEUTRAL);
+            }
+            ReasonCode::OiChange { change_pct, .. } => {
+                open_interest = Some(NEUTRAL + change_pct / OI_FULL_PCT * NEUTRAL);
+            }
+            _ => {}
+        }
+    }
+    // Signals without a volume check (liquidations, listings) still have their candle
+    if stability.is_none() && signal.low > 0.0 {
+        stability = Some(1.0 - (signal.high - signal.low) / signal.low * 100.0 / MOVE_FULL_PCT);
+    }
+
+    let [volume, book, open_interest, stability] = [volume, book, open_interest, stability].map(|part| part.map(|p: f64| p.clamp(0.0, 1.0)));
+    let total = volume.unwrap_or(NEUTRAL) * VOLUME_WEIGHT
+        + book.unwrap_or(NEUTRAL) * BOOK_WEIGHT
+        + open_interest.unwrap_or(NEUTRAL) * OI_WEIGHT
+        + stability.unwrap_or(NEUTRAL) * STABILITY_WEIGHT;
+    SignalScore { total: (total * 10.0).round() / 10.0, volume, book, open_interest, stability }
+}
+
+/// Sets (or refreshes) `signal.score`.
+pub fn rescore(signal: &mut Signal) {
+    signal.score = Some(score(signal));
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, Once, RwLock};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+// Credentials the modules may ask for. Only used for reporting (self-check) and refresh;
+// `get` works for any name.
+pub const KNOWN_SECRETS: &[&str] = &["BINANCE_API_KEY", "BINANCE_API_SECRET", "SHARD_INGEST_TOKEN", "TELEGRAM_BOT_TOKEN",
+    "DISCORD_WEBHOOK_URL", "DISCORD_WEBHOOK_URL_LONG", "DISCORD_WEBHOOK_URL_SHORT",
+    "WEBHOOK_SIGNING_KEY", "WS_TOKENS", "CLUSTER_REDIS_URL", "TRADINGVIEW_PASSPHRASE", "SMTP_PASSWORD",
+];
+
+const DOCKER_SECRETS_DIR: &str = "/run/secrets";
+
+/// A credential value. Debug/Display are redacted so it can't end up in logs by accident;
+/// use `expose()` at the point of use.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether a presented credential equals this one, in time independent of where they differ.
+    pub fn matches(&self, candidate: &str) -> bool {
+        self.0.as_bytes().ct_eq(candidate.as_bytes()).into()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SecretSource {
+    File,          // NAME_FILE=/path
+    DockerSecret,  // /run/secrets/name
+    Env,           // NAME=value
+    EncryptedStore,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretsError {
+    #[error("secrets store io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("secrets store format error: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("secrets store is not valid base64")]
+    Encoding,
+    #[error("SECRETS_KEY is not set")]
+    MissingKey,
+    #[error("failed to decrypt secrets store (wrong SECRETS_KEY?)")]
+    Decrypt,
+}
+
+// PBKDF2-HMAC-SHA256 rounds for newly sealed stores, OWASP's current recommendation
+const KDF_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+
+// On-disk format of the encrypted store: a JSON map of name -> value sealed with ChaCha20-Poly1305,
+// under a key stretched from SECRETS_KEY with the stored salt and iteration count. Stores sealed
+// before the salt existed have neither and a plain SHA-256 key; they still open, and `seal-secrets`
+// rewrites them in the current format.
+#[derive(Serialize, Deserialize)]
+struct SealedStore {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    iterations: Option<u32>,
+    nonce: String,
+    ciphertext: String,
+}
+
+// (passphrase, salt, iterations) of the last derived key: `refresh` and `source` reopen the same
+// store every time, and the derivation is deliberately slow
+type DerivedKey = (String, Vec<u8>, u32, [u8; 32]);
+static DERIVED: LazyLock<Mutex<Option<DerivedKey>>> = LazyLock::new(|| Mutex::new(None));
+
+fn cipher(passphrase: &str, salt: Option<&[u8]>, iterations: u32) -> ChaCha20Poly1305 {
+    let Some(salt) = salt else {
+        return ChaCha20Poly1305::new(&Sha256::digest(passphrase.as_bytes()));
+    };
+    let mut derived = DERIVED.lock().unwrap_or_else(|e| e.into_inner());
+    let key = match derived.as_ref() {
+        Some((p, s, i, key)) if p == passphrase && s == salt && *i == iterations => *key,
+        _ => {
+            let mut key = [0u8; 32];
+            pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+            *derived = Some((passphrase.to_string(), salt.to_vec(), iterations, key));
+            key
+        }
+    };
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+pub fn seal(values: &HashMap<String, String>, passphrase: &str) -> Result<String, SecretsError> {
+    let plaintext = serde_json::to_vec(values)?;
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(passphrase, Some(&salt), KDF_ITERATIONS)
+        .encrypt(&nonce, plaintext.as_ref())
+        .map_err(|_| SecretsError::Decrypt)?;
+    Ok(serde_json::to_string_pretty(&SealedStore {
+        salt: Some(B64.encode(salt)),
+        iterations: Some(KDF_ITERATIONS),
+        nonce: B64.encode(nonce),
+        ciphertext: B64.encode(ciphertext),
+    })?)
+}
+
+pub fn open(sealed: &str, passphrase: &str) -> Result<HashMap<String, String>, SecretsError> {
+    let store: SealedStore = serde_json::from_str(sealed)?;
+    let salt = store.salt.map(|s| B64.decode(s)).transpose().map_err(|_| SecretsError::Encoding)?;
+    let nonce = B64.decode(store.nonce).map_err(|_| SecretsError::Encoding)?;
+    let ciphertext = B64.decode(store.ciphertext).map_err(|_| SecretsError::Encoding)?;
+    if nonce.len() != 12 {
+        return Err(SecretsError::Encoding);
+    }
+    if salt.is_none() {
+        static WARNED: Once = Once::new();
+        WARNED.call_once(|| warn!("Secrets store has no salt and a weak key, reseal it with seal-secrets"));
+    }
+    let plaintext = cipher(passphrase, salt.as_deref(), store.iterations.unwrap_or(KDF_ITERATIONS))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| SecretsError::Decrypt)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+pub fn store_path() -> Option<String> {
+    std::env::var("SECRETS_STORE_PATH").ok().filter(|p| !p.is_empty())
+}
+
+fn read_store() -> Result<HashMap<String, String>, SecretsError> {
+    let Some(path) = store_path() else {
+        return Ok(HashMap::new());
+    };
+    if !Path::new(&path).exists() {
+        return Ok(HashMap::new());
+    }
+    let key = std::env::var("SECRETS_KEY").map_err(|_| SecretsError::MissingKey)?;
+    open(&fs::read_to_string(path)?, &key)
+}
+
+fn read_file(path: &str) -> Option<String> {
+    fs::read_to_string(path).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Resolves a secret. Precedence: `NAME_FILE`, docker secret `/run/secrets/name`,
+/// plain `NAME` env var, then the encrypted local store.
+pub fn resolve(name: &str, store: &HashMap<String, String>) -> Option<(Secret, SecretSource)> {
+    if let Some(v) = std::env::var(format!("{}_FILE", name)).ok().and_then(|p| read_file(&p)) {
+        return Some((Secret(v), SecretSource::File));
+    }
+    let docker_path = format!("{}/{}", DOCKER_SECRETS_DIR, name.to_lowercase());
+    if let Some(v) = read_file(&docker_path) {
+        return Some((Secret(v), SecretSource::DockerSecret));
+    }
+    if let Some(v) = std::env::var(name).ok().filter(|v| !v.is_empty()) {
+        return Some((Secret(v), SecretSource::Env));
+    }
+    store.get(name).map(|v| (Secret(v.clone()), SecretSource::EncryptedStore))
+}
+
+// Current values, refreshed periodically so rotated files/stores take effect without restart.
+// Names that resolved to nothing are cached as None, so unset optional secrets cost a lookup
+// only once per refresh.
+type Cache = HashMap<String, Option<(Secret, SecretSource)>>;
+static CACHE: LazyLock<RwLock<Cache>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Returns the current value of a secret. Consumers should call this at the point of use
+/// (per request) rather than caching the value, so rotation is picked up.
+pub fn get(name: &str) -> Option<Secret> {
+    if let Some(cached) = CACHE.read().unwrap_or_else(|e| e.into_inner()).get(name) {
+        return cached.as_ref().map(|(secret, _)| secret.clone());
+    }
+    let store = read_store().unwrap_or_default();
+    let resolved = resolve(name, &store);
+    CACHE.write().unwrap_or_else(|e| e.into_inner()).insert(name.to_string(), resolved.clone());
+    resolved.map(|(secret, _)| secret)
+}
+
+pub fn source(name: &str) -> Option<SecretSource> {
+    let store = read_store().unwrap_or_default();
+    resolve(name, &store).map(|(_, source)| source)
+}
+
+/// Re-resolves every cached and known secret. Logs names of secrets that changed, never values.
+pub fn refresh() {
+    let store = match read_store() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!(error = %e, "Failed to read encrypted secrets store");
+            HashMap::new()
+        }
+    };
+    let mut names: Vec<String> = CACHE.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
+    names.extend(KNOWN_SECRETS.iter().map(|n| n.to_string()));
+    names.sort();
+    names.dedup();
+
+    let mut cache = CACHE.write().unwrap_or_else(|e| e.into_inner());
+    for name in names {
+        let resolved = resolve(&name, &store);
+        let previous = cache.get(&name).and_then(|cached| cached.as_ref().map(|(old, _)| old));
+        match (&resolved, previous) {
+            (Some((new, source)), Some(old)) if new != old => info!(secret = %name, source = ?source, "Secret rotated"),
+            (None, Some(_)) => warn!(secret = %name, "Secret no longer available"),
+            _ => {}
+        }
+        cache.insert(name, resolved);
+    }
+}
+
+pub async fn refresh_task() {
+    let secs = std::env::var("SECRETS_REFRESH_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+    loop {
+        refresh();
+        tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await;
+    }
+}
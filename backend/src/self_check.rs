@@ -0,0 +1,366 @@
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::time::Duration;
+use futures_util::StreamExt;
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use crate::binance_client::{FUTURES_REST_URL, FUTURES_WS_URL};
+use crate::scanner::SignalType;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Numeric settings read from the environment at startup
+pub const NUMERIC_ENV: &[&str] = &[
+    "BROADCAST_CAPACITY",
+    "SHUTDOWN_TIMEOUT_SECS",
+    "ACTOR_QUEUE_CAPACITY",
+    "CLIENT_QUEUE_CAPACITY",
+    "CLIENT_LAG_DISCONNECT_SECS",
+    "WS_DEFLATE_MIN_BYTES",
+    "INGEST_WORKERS",
+    "INGEST_QUEUE_CAPACITY",
+    "REST_WEIGHT_LIMIT",
+    "REST_RETRIES",
+    "REST_CACHE_MS",
+    "BACKFILL_CANDLES",
+    "BACKFILL_CONCURRENCY",
+    "UPDATE_BATCH_MS",
+    "SNAPSHOT_INTERVAL_SECS",
+    "STORE_IDLE_EVICT_SECS",
+    "STORE_MAX_BYTES",
+    "LEADER_LEASE_SECS",
+    "WATCHDOG_MAX_FEED_AGE_SECS",
+    "WARMUP_MIN_CANDLES",
+    "NEW_LISTING_MAX_AGE_HOURS",
+    "LOG_FILE_MAX_BYTES",
+    "LOG_FILE_MAX_FILES",
+    "SECRETS_REFRESH_SECS",
+    "SHARD_COUNT",
+    "SHARD_INDEX",
+    "EXCHANGE_INFO_REFRESH_SECS",
+    "SIGNAL_REVERIFY_SECS",
+    "LEADERBOARD_INTERVAL_SECS",
+    "MARKET_OVERVIEW_INTERVAL_SECS",
+    "HEATMAP_REFRESH_SECS",
+    "MARKET_ALERT_HOLD_SECS",
+    "WATCH_ALERT_COOLDOWN_SECS",
+    "WATCH_OI_POLL_SECS",
+    "CARRY_SCAN_SECS",
+    "FUNDING_REFRESH_SECS",
+    "OI_POLL_SECS",
+    "OI_TRACK_MAX_SYMBOLS",
+    "BIAS_INTERVAL_SECS",
+    "SENTIMENT_INTERVAL_SECS",
+    "KLINES_CACHE_SECS",
+    "CANDLE_STREAM_MS",
+    "SCANNER_COOLDOWN_SECS",
+    "SCANNER_TREND_CANDLES",
+    "LIQUIDATION_COOLDOWN_SECS",
+    "SHORT_PRESSURE_COOLDOWN_SECS",
+    "BREAKOUT_CANDLES",
+    "BREAKOUT_COOLDOWN_SECS",
+    "NOTIFY_BATCH_SECS",
+    "WEBHOOK_MAX_ATTEMPTS",
+    "PAPER_MAX_HOLD_SECS",
+    "EXECUTION_LEVERAGE",
+    "EXECUTION_MAX_POSITIONS",
+    "PORTFOLIO_POLL_SECS",
+    "PORTFOLIO_LOOKBACK_HOURS",
+    "PORTFOLIO_MATCH_MINUTES",
+    "PORTFOLIO_MAX_SYMBOLS",
+    "REPORT_PERIOD_HOURS",
+    "SMTP_PORT",
+    "SUPPRESSED_LOG_SIZE",
+    "TUNER_INTERVAL_SECS",
+    "TUNER_LOOKBACK_DAYS",
+    "TUNER_MIN_SIGNALS",
+    "AUTH_TIMEOUT_SECS",
+    "CORRELATION_MIN_SIGNALS",
+    "SNAPSHOT_MAX_AGE_SECS",
+    "RECORD_RETENTION_HOURS",
+    "RECORD_MAX_MB",
+    "DEPTH_WINDOW_SECS",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub outcome: Outcome,
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    fn push(&mut self, name: &str, outcome: Outcome, detail: impl Into<String>) {
+        self.results.push(CheckResult { name: name.to_string(), outcome, detail: detail.into() });
+    }
+
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.outcome != Outcome::Fail)
+    }
+
+    pub fn print(&self) {
+        println!("Teeb Trade self-check");
+        println!("=====================");
+        for r in &self.results {
+            let tag = match r.outcome {
+                Outcome::Pass => "PASS",
+                Outcome::Fail => "FAIL",
+                Outcome::Skip => "SKIP",
+            };
+            println!("[{}] {:<24} {}", tag, r.name, r.detail);
+        }
+        let failed = self.results.iter().filter(|r| r.outcome == Outcome::Fail).count();
+        println!();
+        if failed == 0 {
+            println!("All checks passed.");
+        } else {
+            println!("{} check(s) failed.", failed);
+        }
+    }
+}
+
+/// Validates config, filesystem and network prerequisites and returns a report.
+/// Used by `--check`; nothing here starts background tasks.
+pub async fn run() -> CheckReport {
+    let mut report = CheckReport::default();
+    check_config(&mut report);
+    check_writable(&mut report, "history database", &crate::history::history_db_path());
+    check_writable(&mut report, "snapshot path", &crate::snapshot::snapshot_path());
+    check_writable(&mut report, "watchlist path", &crate::watchlist::watchlist_path());
+    check_writable(&mut report, "price alerts path", &crate::price_alert::price_alerts_path());
+    check_writable(&mut report, "webhook queue path", &crate::webhook::webhook_queue_path());
+    check_writable(&mut report, "paper positions path", &crate::paper_trader::paper_positions_path());
+    if let Some(dir) = crate::recorder::record_dir() {
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => check_writable(&mut report, "record dir", &dir.join(".write-check").to_string_lossy()),
+            Err(e) => report.push("record dir", Outcome::Fail, format!("{}: {}", dir.display(), e)),
+        }
+    }
+    check_rest(&mut report).await;
+    check_ws(&mut report).await;
+    check_secrets(&mut report);
+    check_notifiers(&mut report);
+    check_execution(&mut report);
+    report
+}
+
+// Reports where each credential comes from, never its value
+fn check_secrets(report: &mut CheckReport) {
+    if crate::secrets::store_path().is_some() {
+        crate::secrets::refresh();
+    }
+    for name in crate::secrets::KNOWN_SECRETS {
+        match (crate::secrets::get(name), crate::secrets::source(name)) {
+            (Some(secret), Some(source)) if !secret.expose().is_empty() => report.push(&format!("secret {}", name), Outcome::Pass, format!("from {:?}", source)),
+            _ => report.push(&format!("secret {}", name), Outcome::Skip, "not configured"),
+        }
+    }
+    // An aggregator without it refuses every worker
+    if crate::shard::SHARD.is_aggregator && crate::secrets::get(crate::shard::TOKEN_SECRET).is_none_or(|t| t.expose().is_empty()) {
+        report.push("shard ingest token", Outcome::Fail, format!("SHARD_ROLE=aggregator needs {}", crate::shard::TOKEN_SECRET));
+    }
+}
+
+fn check_config(report: &mut CheckReport) {
+    for key in NUMERIC_ENV {
+        match std::env::var(key) {
+            Ok(v) if v.parse::<u64>().is_err() => {
+                report.push(&format!("config {}", key), Outcome::Fail, format!("'{}' is not a non-negative integer", v))
+            }
+            Ok(v) => report.push(&format!("config {}", key), Outcome::Pass, v),
+            Err(_) => {}
+        }
+    }
+
+    match std::env::var("LOG_FORMAT") {
+        Ok(v) if !v.eq_ignore_ascii_case("json") && !v.eq_ignore_ascii_case("pretty") => {
+            report.push("config LOG_FORMAT", Outcome::Fail, format!("'{}' must be json or pretty", v))
+        }
+        _ => {}
+    }
+
+    match std::env::var("VERIFY_MODE") {
+        Ok(v) if !v.eq_ignore_ascii_case("soft") && !v.eq_ignore_ascii_case("strict") => {
+            report.push("config VERIFY_MODE", Outcome::Fail, format!("'{}' must be soft or strict", v))
+        }
+        _ => {}
+    }
+
+    match std::env::var("CORRELATION_MODE") {
+        Ok(v) if !v.eq_ignore_ascii_case("label") && !v.eq_ignore_ascii_case("suppress") => {
+            report.push("config CORRELATION_MODE", Outcome::Fail, format!("'{}' must be label or suppress", v))
+        }
+        _ => {}
+    }
+
+    if let Ok(v) = std::env::var("REPLAY_SPEED") {
+        match v.parse::<crate::replay::Speed>() {
+            Ok(_) => report.push("config REPLAY_SPEED", Outcome::Pass, v),
+            Err(e) => report.push("config REPLAY_SPEED", Outcome::Fail, e),
+        }
+    }
+
+    if let Some(path) = std::env::var("REPLAY_FILE").ok().filter(|p| !p.is_empty()) {
+        if std::path::Path::new(&path).exists() {
+            report.push("config REPLAY_FILE", Outcome::Pass, format!("{} (replaying instead of live feeds)", path));
+        } else {
+            report.push("config REPLAY_FILE", Outcome::Fail, format!("{} not found", path));
+        }
+    }
+
+    match std::env::var("FEED_MODE") {
+        Ok(v) if !v.eq_ignore_ascii_case("kline") && !v.eq_ignore_ascii_case("ticker") => {
+            report.push("config FEED_MODE", Outcome::Fail, format!("'{}' must be kline or ticker", v))
+        }
+        _ => {}
+    }
+
+    if let Ok(v) = std::env::var("EXCHANGES") {
+        match v.split(',').map(str::parse::<crate::exchange::Exchange>).collect::<Result<Vec<_>, _>>() {
+            Ok(_) => report.push("config EXCHANGES", Outcome::Pass, v),
+            Err(e) => report.push("config EXCHANGES", Outcome::Fail, format!("{} (known: binance, bybit, binance_spot)", e)),
+        }
+    }
+
+    if let Ok(filter) = std::env::var("RUST_LOG") {
+        match tracing_subscriber::EnvFilter::try_new(&filter) {
+            Ok(_) => report.push("config RUST_LOG", Outcome::Pass, filter),
+            Err(e) => report.push("config RUST_LOG", Outcome::Fail, e.to_string()),
+        }
+    }
+
+    let path = crate::config::config_path();
+    match crate::config::ScannerConfig::load(&path) {
+        Ok(config) => report.push("config scanner", Outcome::Pass, format!("{:?}", config)),
+        Err(e) => report.push("config scanner", Outcome::Fail, format!("{}: {}", path, e)),
+    }
+    match crate::config::SymbolFilter::load(&path) {
+        Ok(filter) => report.push("config symbols", Outcome::Pass, format!("{:?}", filter)),
+        Err(e) => report.push("config symbols", Outcome::Fail, format!("{}: {}", path, e)),
+    }
+    match crate::config::AuthConfig::load(&path) {
+        Ok(auth) if auth.tokens.is_empty() => report.push("config auth", Outcome::Pass, "no tokens, /ws is open"),
+        Ok(auth) => report.push("config auth", Outcome::Pass, format!("{} token(s)", auth.tokens.len())),
+        Err(e) => report.push("config auth", Outcome::Fail, format!("{}: {}", path, e)),
+    }
+    match crate::strategy::Registry::load(&path) {
+        Ok(registry) => report.push("config strategies", Outcome::Pass, registry.names().join(", ")),
+        Err(e) => report.push("config strategies", Outcome::Fail, format!("{}: {}", path, e)),
+    }
+
+    report.push("config", Outcome::Pass, "environment parsed");
+}
+
+fn check_writable(report: &mut CheckReport, name: &str, path: &str) {
+    let existed = Path::new(path).exists();
+    match OpenOptions::new().append(true).create(true).open(path) {
+        Ok(_) => {
+            if !existed {
+                let _ = std::fs::remove_file(path);
+            }
+            report.push(name, Outcome::Pass, format!("{} is writable", path));
+        }
+        Err(e) => report.push(name, Outcome::Fail, format!("{}: {}", path, e)),
+    }
+}
+
+async fn check_rest(report: &mut CheckReport) {
+    let url = format!("{}/fapi/v1/ping", FUTURES_REST_URL);
+    let client = reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build();
+    let result = match client {
+        Ok(client) => client.get(&url).send().await,
+        Err(e) => {
+            report.push("binance rest", Outcome::Fail, e.to_string());
+            return;
+        }
+    };
+    match result {
+        Ok(resp) if resp.status().is_success() => report.push("binance rest", Outcome::Pass, url),
+        Ok(resp) => report.push("binance rest", Outcome::Fail, format!("{} returned {}", url, resp.status())),
+        Err(e) => report.push("binance rest", Outcome::Fail, format!("{}: {}", url, e)),
+    }
+}
+
+async fn check_ws(report: &mut CheckReport) {
+    let connect = timeout(NETWORK_TIMEOUT, connect_async(FUTURES_WS_URL)).await;
+    let (mut ws, _) = match connect {
+        Ok(Ok(conn)) => conn,
+        Ok(Err(e)) => return report.push("binance ws", Outcome::Fail, format!("{}: {}", FUTURES_WS_URL, e)),
+        Err(_) => return report.push("binance ws", Outcome::Fail, format!("{}: connect timed out", FUTURES_WS_URL)),
+    };
+
+    // Connected is not enough, we want to see actual market data
+    let first = timeout(NETWORK_TIMEOUT, async {
+        while let Some(msg) = ws.next().await {
+            if let Ok(Message::Text(text)) = msg {
+                return Some(text.len());
+            }
+        }
+        None
+    })
+    .await;
+
+    match first {
+        Ok(Some(bytes)) => report.push("binance ws", Outcome::Pass, format!("received {} bytes of ticker data", bytes)),
+        Ok(None) => report.push("binance ws", Outcome::Fail, "stream closed before any data"),
+        Err(_) => report.push("binance ws", Outcome::Fail, "no ticker data within timeout"),
+    }
+}
+
+fn check_execution(report: &mut CheckReport) {
+    if !crate::executor::enabled() {
+        return;
+    }
+    let present = |name| crate::secrets::get(name).is_some_and(|s| !s.expose().is_empty());
+    if !present(crate::executor::KEY_SECRET) || !present(crate::executor::SECRET_SECRET) {
+        report.push("execution", Outcome::Fail, "EXECUTION_ENABLED=true but BINANCE_API_KEY / BINANCE_API_SECRET missing");
+    } else if crate::executor::dry_run() {
+        report.push("execution", Outcome::Pass, "dry run, orders are logged only");
+    } else {
+        let config = crate::executor::ExecutionConfig::from_env();
+        report.push(
+            "execution",
+            Outcome::Pass,
+            format!("LIVE: {}% of equity at {}x, up to {} positions", config.equity_pct, config.leverage, config.max_positions),
+        );
+    }
+}
+
+fn check_notifiers(report: &mut CheckReport) {
+    let token = crate::secrets::get(crate::notifier::TOKEN_SECRET).is_some_and(|t| !t.expose().is_empty());
+    match (token, crate::notifier::chat_id()) {
+        (true, Some(chat_id)) => report.push("notifications telegram", Outcome::Pass, format!("chat {}", chat_id)),
+        (true, None) => report.push("notifications telegram", Outcome::Fail, "TELEGRAM_BOT_TOKEN set but TELEGRAM_CHAT_ID missing"),
+        (false, Some(_)) => report.push("notifications telegram", Outcome::Fail, "TELEGRAM_CHAT_ID set but TELEGRAM_BOT_TOKEN missing"),
+        (false, None) if !crate::discord::enabled() => report.push("notifications", Outcome::Skip, "no notification channels configured"),
+        (false, None) => {}
+    }
+    if crate::discord::enabled() {
+        let routes = [SignalType::Long, SignalType::Short].map(|t| match crate::discord::webhook_url(&t) {
+            Some(_) => format!("{:?} posted", t),
+            None => format!("{:?} not posted", t),
+        });
+        report.push("notifications discord", Outcome::Pass, routes.join(", "));
+    }
+    let urls = crate::webhook::webhook_urls();
+    match urls.iter().find(|u| url::Url::parse(u).map_or(true, |u| !matches!(u.scheme(), "http" | "https"))) {
+        Some(bad) => report.push("notifications webhooks", Outcome::Fail, format!("'{}' is not an http(s) URL", bad)),
+        None if urls.is_empty() => {}
+        None => {
+            let signed = if crate::secrets::get(crate::webhook::SIGNING_SECRET).is_some() { "signed" } else { "unsigned" };
+            report.push("notifications webhooks", Outcome::Pass, format!("{} receivers, {}", urls.len(), signed));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::binance_client::FUTURES_REST_URL;
+use crate::exchange::{fetch_json, ClientError};
+use crate::metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+const FEAR_GREED_URL: &str = "https://api.alternative.me/fng/?limit=1";
+// Open interest is summed over these; the rest of the market follows them closely enough
+const OI_SYMBOLS: [&str; 2] = ["BTCUSDT", "ETHUSDT"];
+
+pub fn enabled() -> bool {
+    std::env::var("SENTIMENT_ENABLED").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+fn interval_secs() -> u64 {
+    std::env::var("SENTIMENT_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SentimentError {
+    #[error(transparent)]
+    Request(#[from] ClientError),
+    #[error("malformed response: {0}")]
+    Malformed(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct FearGreedResponse {
+    data: Vec<FearGreedEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FearGreedEntry {
+    value: String,
+    value_classification: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PremiumIndex {
+    symbol: String,
+    mark_price: String,
+    last_funding_rate: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenInterest {
+    open_interest: String,
+}
+
+/// Market-wide context from outside the scanner's own windows. Each part is None when its
+/// source failed on the last refresh.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketContext {
+    pub fear_greed: Option<u32>,          // 0 (extreme fear) .. 100 (extreme greed)
+    pub fear_greed_label: Option<String>, // e.g. "Greed"
+    pub avg_funding_rate: Option<f64>,    // Mean last funding rate over all perps
+    pub positive_funding_share: Option<f64>, // Share of perps where longs pay, 0..1
+    pub open_interest: Option<f64>,       // BTC + ETH, in USDT
+    pub open_interest_change_pct: Option<f64>, // vs the previous refresh
+    pub updated_at: i64,
+}
+
+static CURRENT: LazyLock<RwLock<Option<MarketContext>>> = LazyLock::new(Default::default);
+
+pub fn current() -> Option<MarketContext> {
+    CURRENT.read().ok().and_then(|c| c.clone())
+}
+
+async fn fear_greed(client: &reqwest::Client) -> Result<(u32, String), SentimentError> {
+    let response: FearGreedResponse = fetch_json(client, FEAR_GREED_URL).await?;
+    let entry = response.data.into_iter().next().ok_or_else(|| SentimentError::Malformed("no data".to_string()))?;
+    let value = entry.value.parse().map_err(|e: std::num::ParseIntError| SentimentError::Malformed(e.to_string()))?;
+    Ok((value, entry.value_classification))
+}
+
+// (average funding rate, share positive, BTC+ETH open interest in USDT)
+async fn derivatives(client: &reqwest::Client) -> Result<(f64, f64, f64), SentimentError> {
+    let indexes: Vec<PremiumIndex> = fetch_json(client, &format!("{}/fapi/v1/premiumIndex", FUTURES_REST_URL)).await?;
+    let rates: Vec<f64> = indexes.iter().filter_map(|i| i.last_funding_rate.parse().ok()).collect();
+    if rates.is_empty() {
+        return Err(SentimentError::Malformed("no funding rates".to_string()));
+    }
+    let avg = rates.iter().sum::<f64>() / rates.len() as f64;
+    let positive = rates.iter().filter(|r| **r > 0.0).count() as f64 / rates.len() as f64;
+
+    let mut open_interest = 0.0;
+    for symbol in OI_SYMBOLS {
+        let mark: f64 = indexes
+            .iter()
+            .find(|i| i.symbol == symbol)
+            .and_then(|i| i.mark_price.parse().ok())
+            .ok_or_else(|| SentimentError::Malformed(format!("no mark price for {}", symbol)))?;
+        let oi: OpenInterest =
+            fetch_json(client, &format!("{}/fapi/v1/openInterest?symbol={}", FUTURES_REST_URL, symbol)).await?;
+        let contracts: f64 = oi.open_interest.parse().map_err(|e: std::num::ParseFloatError| SentimentError::Malformed(e.to_string()))?;
+        open_interest += contracts * mark;
+    }
+    Ok((avg, positive, open_interest))
+}
+
+/// Refreshes the market context every `SENTIMENT_INTERVAL_SECS` (hourly by default).
+/// Only runs with `SENTIMENT_ENABLED=1`; otherwise stats and signals carry no context.
+pub async fn sentiment_task() {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    let client = crate::rest::client();
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs().max(60)));
+    loop {
+        interval.tick().await;
+        let previous = current();
+        let mut context = MarketContext { updated_at: chrono::Utc::now().timestamp_millis(), ..Default::default() };
+        match fear_greed(&client).await {
+            Ok((value, label)) => {
+                context.fear_greed = Some(value);
+                context.fear_greed_label = Some(label);
+            }
+            Err(e) => {
+                METRICS.record_error("sentiment");
+                warn!(error = %e, "Failed to fetch Fear & Greed index");
+            }
+        }
+        match derivatives(&client).await {
+            Ok((avg, positive, open_interest)) => {
+                context.avg_funding_rate = Some(avg);
+                context.positive_funding_share = Some(positive);
+                context.open_interest = Some(open_interest);
+                context.open_interest_change_pct = previous
+                    .and_then(|p| p.open_interest)
+                    .filter(|p| *p > 0.0)
+                    .map(|p| (open_interest - p) / p * 100.0);
+            }
+            Err(e) => {
+                METRICS.record_error("sentiment");
+                warn!(error = %e, "Failed to fetch funding/OI aggregates");
+            }
+        }
+        info!(fear_greed = ?context.fear_greed, avg_funding_rate = ?context.avg_funding_rate, open_interest = ?context.open_interest, "Market context refreshed");
+        if let Ok(mut current) = CURRENT.write() {
+            *current = Some(context);
+        }
+    }
+}
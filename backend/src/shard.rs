@@ -0,0 +1,153 @@
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+use crate::lifecycle::StateTransition;
+use crate::metrics::METRICS;
+use crate::scanner::{Invalidation, Signal, SignalUpdate};
+
+// Points per shard on the hash ring. More points = more even split of the ~300 symbols.
+const VNODES: u32 = 64;
+
+/// Horizontal sharding of the symbol universe.
+///
+/// With `SHARD_COUNT=N` each worker (`SHARD_INDEX` 0..N) only processes the symbols that
+/// hash onto its slice of a consistent-hash ring, so N instances split the per-symbol work.
+/// Workers with `SHARD_AGGREGATOR_URL` set forward their signals and live updates to that
+/// instance instead of serving them, and the aggregator (`SHARD_ROLE=aggregator`) accepts
+/// them on `/ingest/*` and publishes them like its own. Going from N to N+1 shards moves
+/// only ~1/(N+1) of the symbols.
+pub struct ShardConfig {
+    pub index: u32,
+    pub count: u32,
+    pub aggregator_url: Option<String>,
+    pub is_aggregator: bool,
+    ring: Vec<(u64, u32)>, // (point, shard), sorted by point
+}
+
+impl ShardConfig {
+    fn from_env() -> Self {
+        let env_u32 = |key: &str, default: u32| std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        let count = env_u32("SHARD_COUNT", 1).max(1);
+        let index = env_u32("SHARD_INDEX", 0);
+        let index = if index < count {
+            index
+        } else {
+            warn!(index, count, "SHARD_INDEX out of range, using shard 0");
+            0
+        };
+        let mut ring: Vec<(u64, u32)> = (0..count)
+            .flat_map(|shard| (0..VNODES).map(move |v| (fnv1a(format!("shard-{}-{}", shard, v).as_bytes()), shard)))
+            .collect();
+        ring.sort_unstable();
+        Self {
+            index,
+            count,
+            aggregator_url: std::env::var("SHARD_AGGREGATOR_URL").ok().filter(|v| !v.is_empty()).map(|v| v.trim_end_matches('/').to_string()),
+            is_aggregator: std::env::var("SHARD_ROLE").is_ok_and(|v| v.eq_ignore_ascii_case("aggregator")),
+            ring,
+        }
+    }
+
+    pub fn shard_of(&self, symbol: &str) -> u32 {
+        if self.count == 1 {
+            return 0;
+        }
+        let hash = fnv1a(symbol.as_bytes());
+        let pos = self.ring.partition_point(|(point, _)| *point < hash);
+        self.ring.get(pos).unwrap_or(&self.ring[0]).1
+    }
+
+    pub fn owns(&self, symbol: &str) -> bool {
+        self.count == 1 || self.shard_of(symbol) == self.index
+    }
+}
+
+pub static SHARD: LazyLock<ShardConfig> = LazyLock::new(ShardConfig::from_env);
+
+// FNV-1a: stable across builds and Rust versions (std's DefaultHasher is not), so workers
+// running different binaries still agree on the split.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+pub fn log_config() {
+    let shard = &*SHARD;
+    if shard.count > 1 || shard.is_aggregator {
+        info!(
+            index = shard.index,
+            count = shard.count,
+            aggregator = ?shard.aggregator_url,
+            is_aggregator = shard.is_aggregator,
+            "Sharding enabled"
+        );
+    }
+    if shard.is_aggregator && crate::secrets::get(TOKEN_SECRET).is_none_or(|t| t.expose().is_empty()) {
+        warn!("{} is not set, the aggregator refuses all shard ingest", TOKEN_SECRET);
+    }
+}
+
+// Shared token the aggregator checks on `/ingest/*` (header `x-shard-token`), via the secrets layer
+pub const TOKEN_SECRET: &str = "SHARD_INGEST_TOKEN";
+pub const TOKEN_HEADER: &str = "x-shard-token";
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap_or_default()
+});
+
+async fn post<T: serde::Serialize>(url: &str, body: &T) -> Result<(), reqwest::Error> {
+    let mut request = CLIENT.post(url).json(body);
+    if let Some(token) = crate::secrets::get(TOKEN_SECRET) {
+        request = request.header(TOKEN_HEADER, token.expose());
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Sends a signal to the aggregator. Signals are retried a few times; losing one is worse
+/// than delivering it late.
+pub async fn forward_signal(base: &str, signal: &Signal) {
+    if deliver(&format!("{}/ingest/signal", base), signal, &signal.symbol).await {
+        METRICS.shard_forwarded.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Retried like signals: a missed invalidation leaves a dead signal on every client
+pub async fn forward_invalidation(base: &str, invalidation: &Invalidation) {
+    deliver(&format!("{}/ingest/invalidate", base), invalidation, &invalidation.symbol).await;
+}
+
+// Outcome transitions (TargetHit, Expired). The aggregator tracks outcomes too; whichever copy
+// lands first is the one broadcast
+pub async fn forward_transition(base: &str, transition: &StateTransition) {
+    deliver(&format!("{}/ingest/state", base), transition, &transition.symbol).await;
+}
+
+async fn deliver<T: serde::Serialize>(url: &str, body: &T, symbol: &str) -> bool {
+    let mut delay = Duration::from_millis(250);
+    for attempt in 1..=3 {
+        match post(url, body).await {
+            Ok(()) => return true,
+            Err(e) => {
+                METRICS.record_error("shard");
+                warn!(symbol, url, attempt, error = %e, "Failed to forward to aggregator");
+                sleep(delay).await;
+                delay *= 4;
+            }
+        }
+    }
+    false
+}
+
+// Live updates are superseded by the next batch anyway, so no retry
+pub async fn forward_updates(base: &str, updates: &[SignalUpdate]) {
+    if let Err(e) = post(&format!("{}/ingest/updates", base), &updates).await {
+        METRICS.record_error("shard");
+        warn!(count = updates.len(), error = %e, "Failed to forward updates to aggregator");
+    }
+}
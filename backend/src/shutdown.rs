@@ -0,0 +1,42 @@
+use tokio::sync::watch;
+
+// Graceful shutdown. On Ctrl-C or SIGTERM `main` has the supervisor trigger it: the tasks with
+// something to finish (exchange feeds, WebSocket clients, outcome tracking) watch it and
+// return, everything else is stopped, and nothing is restarted.
+
+/// Grace the supervisor gives watching tasks before aborting them.
+pub fn timeout_secs() -> u64 {
+    std::env::var("SHUTDOWN_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(10)
+}
+
+/// A task's view of the shutdown signal. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    rx: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    pub(crate) fn new(rx: watch::Receiver<bool>) -> Self {
+        Self { rx }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Resolves once shutdown is triggered, at once if it already is.
+    pub async fn wait(&self) {
+        let mut rx = self.rx.clone();
+        // The sender only goes away with the supervisor, i.e. on the way out anyway
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Ctrl-C, or SIGTERM from systemd / docker.
+pub async fn signal() {
+    let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).expect("SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = term.recv() => {}
+    }
+}
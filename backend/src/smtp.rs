@@ -0,0 +1,199 @@
+// Just enough SMTP to send a report: EHLO, optional STARTTLS (or TLS from the start on 465),
+// AUTH PLAIN, one message to a few recipients. Configured with SMTP_HOST, SMTP_PORT (default
+// 587), SMTP_TLS (starttls, tls or none; default tls on 465, starttls otherwise), SMTP_USERNAME,
+// the SMTP_PASSWORD credential and SMTP_FROM.
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+
+pub const PASSWORD_SECRET: &str = "SMTP_PASSWORD";
+// Per command; a server slower than this is as good as down
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum SmtpError {
+    #[error("smtp io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("tls error: {0}")]
+    Tls(#[from] native_tls::Error),
+    #[error("smtp server timed out")]
+    Timeout,
+    #[error("unexpected reply to {command}: {reply}")]
+    Reply { command: String, reply: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encryption {
+    StartTls,
+    Implicit,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: Encryption,
+    pub username: Option<String>,
+    pub from: String,
+}
+
+impl SmtpConfig {
+    /// None unless SMTP_HOST and SMTP_FROM are set.
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let host = var("SMTP_HOST")?;
+        let from = var("SMTP_FROM")?;
+        let port = var("SMTP_PORT").and_then(|v| v.parse().ok()).unwrap_or(587);
+        let tls = match var("SMTP_TLS").as_deref().map(str::to_ascii_lowercase).as_deref() {
+            Some("none") => Encryption::None,
+            Some("tls") => Encryption::Implicit,
+            Some("starttls") => Encryption::StartTls,
+            _ if port == 465 => Encryption::Implicit,
+            _ => Encryption::StartTls,
+        };
+        Some(Self { host, port, tls, username: var("SMTP_USERNAME"), from })
+    }
+}
+
+struct Connection<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    fn new(stream: S) -> Self {
+        Self { stream: BufReader::new(stream) }
+    }
+
+    // A reply, possibly multi-line ("250-..." lines up to the last "250 ...")
+    async fn reply(&mut self, command: &str, expected: &[u16]) -> Result<(), SmtpError> {
+        let mut text = String::new();
+        loop {
+            let mut line = String::new();
+            let read = timeout(COMMAND_TIMEOUT, self.stream.read_line(&mut line)).await.map_err(|_| SmtpError::Timeout)??;
+            if read == 0 {
+                return Err(SmtpError::Reply { command: command.to_string(), reply: format!("connection closed after '{}'", text.trim()) });
+            }
+            text += &line;
+            if line.len() < 4 || line.as_bytes()[3] != b'-' {
+                break;
+            }
+        }
+        let code: u16 = text.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        if expected.contains(&code) {
+            Ok(())
+        } else {
+            Err(SmtpError::Reply { command: command.to_string(), reply: text.trim().to_string() })
+        }
+    }
+
+    // `shown` stands in for the command in errors, so credentials never reach the logs
+    async fn command(&mut self, line: &str, shown: &str, expected: &[u16]) -> Result<(), SmtpError> {
+        let stream = self.stream.get_mut();
+        timeout(COMMAND_TIMEOUT, async {
+            stream.write_all(line.as_bytes()).await?;
+            stream.write_all(b"\r\n").await?;
+            stream.flush().await
+        })
+        .await
+        .map_err(|_| SmtpError::Timeout)??;
+        self.reply(shown, expected).await
+    }
+
+    async fn ehlo(&mut self) -> Result<(), SmtpError> {
+        self.command("EHLO teeb-trade", "EHLO", &[250]).await
+    }
+
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+async fn tls(host: &str, tcp: TcpStream) -> Result<tokio_native_tls::TlsStream<TcpStream>, SmtpError> {
+    let connector = tokio_native_tls::TlsConnector::from(native_tls::TlsConnector::new()?);
+    Ok(connector.connect(host, tcp).await?)
+}
+
+// "Teeb <reports@example.com>" -> "reports@example.com"
+fn address(mailbox: &str) -> &str {
+    match (mailbox.find('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
+
+// Headers and body, CRLF line endings, leading dots doubled
+fn message(config: &SmtpConfig, to: &[String], subject: &str, html: &str) -> String {
+    let subject = if subject.is_ascii() {
+        subject.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(subject))
+    };
+    let mut data = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/html; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        config.from,
+        to.join(", "),
+        subject,
+        chrono::Utc::now().to_rfc2822()
+    );
+    for line in html.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data += line;
+        data += "\r\n";
+    }
+    data += ".";
+    data
+}
+
+async fn transaction<S: AsyncRead + AsyncWrite + Unpin>(
+    conn: &mut Connection<S>,
+    config: &SmtpConfig,
+    to: &[String],
+    subject: &str,
+    html: &str,
+) -> Result<(), SmtpError> {
+    if let (Some(username), Some(password)) = (&config.username, crate::secrets::get(PASSWORD_SECRET)) {
+        let token = base64::engine::general_purpose::STANDARD.encode(format!("\0{}\0{}", username, password.expose()));
+        conn.command(&format!("AUTH PLAIN {}", token), "AUTH PLAIN", &[235]).await?;
+    }
+    conn.command(&format!("MAIL FROM:<{}>", address(&config.from)), "MAIL FROM", &[250]).await?;
+    for recipient in to {
+        conn.command(&format!("RCPT TO:<{}>", recipient), "RCPT TO", &[250, 251]).await?;
+    }
+    conn.command("DATA", "DATA", &[354]).await?;
+    conn.command(&message(config, to, subject, html), "message", &[250]).await?;
+    // The message is accepted; a failed goodbye doesn't matter
+    let _ = conn.command("QUIT", "QUIT", &[221]).await;
+    Ok(())
+}
+
+/// Sends one HTML message to `to`.
+pub async fn send_html(config: &SmtpConfig, to: &[String], subject: &str, html: &str) -> Result<(), SmtpError> {
+    let tcp = timeout(COMMAND_TIMEOUT, TcpStream::connect((config.host.as_str(), config.port))).await.map_err(|_| SmtpError::Timeout)??;
+    match config.tls {
+        Encryption::Implicit => {
+            let mut conn = Connection::new(tls(&config.host, tcp).await?);
+            conn.reply("greeting", &[220]).await?;
+            conn.ehlo().await?;
+            transaction(&mut conn, config, to, subject, html).await
+        }
+        Encryption::StartTls => {
+            let mut plain = Connection::new(tcp);
+            plain.reply("greeting", &[220]).await?;
+            plain.ehlo().await?;
+            plain.command("STARTTLS", "STARTTLS", &[220]).await?;
+            let mut conn = Connection::new(tls(&config.host, plain.into_inner()).await?);
+            conn.ehlo().await?;
+            transaction(&mut conn, config, to, subject, html).await
+        }
+        Encryption::None => {
+            let mut conn = Connection::new(tcp);
+            conn.reply("greeting", &[220]).await?;
+            conn.ehlo().await?;
+            transaction(&mut conn, config, to, subject, html).await
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::model::{CandleBuilder, SymbolState};
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use tracing::{info, warn};
+use crate::metrics::METRICS;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("snapshot io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("snapshot (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoreSnapshot {
+    pub saved_at: i64, // ms
+    pub symbols: HashMap<String, SymbolState>,
+    // In-progress candles. Snapshots from before OHLC had a different shape here; those
+    // entries are skipped (only current-minute entries are ever restored anyway).
+    #[serde(default, rename = "candles")]
+    pub volume_cache: HashMap<String, CandleBuilder>,
+}
+
+pub fn snapshot_path() -> String {
+    std::env::var("SNAPSHOT_PATH").unwrap_or_else(|_| "state_snapshot.json".to_string())
+}
+
+fn snapshot_interval_secs() -> u64 {
+    std::env::var("SNAPSHOT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60)
+}
+
+// Past this, a window (or a whole snapshot) no longer describes the market: the symbol starts
+// cold instead of comparing new minutes against an hour-old average
+fn max_age_ms() -> i64 {
+    std::env::var("SNAPSHOT_MAX_AGE_SECS").ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(3600) * 1000
+}
+
+pub fn capture(store: &SharedState) -> StoreSnapshot {
+    StoreSnapshot {
+        saved_at: chrono::Utc::now().timestamp_millis(),
+        symbols: store.iter().map(|e| (e.key().clone(), e.value().clone())).collect(),
+        volume_cache: store.volume_cache.iter().map(|e| (e.key().clone(), *e.value())).collect(),
+    }
+}
+
+pub fn save(path: &str, store: &SharedState) {
+    if let Err(e) = write_snapshot(path, &capture(store)) {
+        METRICS.record_error("snapshot");
+        warn!(path, error = %e, "Failed to write store snapshot");
+    }
+}
+
+pub fn write_snapshot(path: &str, snapshot: &StoreSnapshot) -> Result<(), SnapshotError> {
+    let json = serde_json::to_string(snapshot)?;
+    // Write to a temp file and rename, so a crash mid-write never leaves a truncated snapshot.
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+pub fn read_snapshot(path: &str) -> Result<StoreSnapshot, SnapshotError> {
+    let data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Restores a previous snapshot into the (empty) store.
+///
+/// Windows and cooldowns are restored as-is, unless they are older than `SNAPSHOT_MAX_AGE_SECS`
+/// (default 3600): a snapshot saved longer ago is ignored, and so is a symbol whose last
+/// candle is (it had stopped trading before the snapshot), except for their hour-of-day
+/// volume baselines. Those are evicted with the symbol if it doesn't trade again. Volume cache entries are only kept
+/// if they belong to the current minute: an older start-of-minute volume would turn the whole
+/// downtime into one giant "minute" candle and fire a false spike.
+pub fn restore(path: &str, store: &SharedState) {
+    let snapshot = match read_snapshot(path) {
+        Ok(snapshot) => snapshot,
+        Err(SnapshotError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            info!(path, "No store snapshot found, starting cold");
+            return;
+        }
+        Err(e) => {
+            METRICS.record_error("snapshot");
+            warn!(path, error = %e, "Ignoring unreadable store snapshot");
+            return;
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let oldest = now - max_age_ms();
+    if snapshot.saved_at < oldest {
+        // Hour-of-day baselines take days to learn and don't go stale in an hour
+        let count = snapshot.symbols.len();
+        for (symbol, state) in snapshot.symbols {
+            store.insert(symbol, state.hourly_only());
+        }
+        info!(path, age_secs = (now - snapshot.saved_at) / 1000, symbols = count, "Store snapshot too old, starting cold with hourly baselines");
+        return;
+    }
+
+    let current_minute = now / 60000;
+    let mut symbol_count = 0;
+    let mut stale_count = 0;
+    for (symbol, mut state) in snapshot.symbols {
+        if state.window.back().is_some_and(|last| last.timestamp < oldest) {
+            stale_count += 1;
+            store.insert(symbol, state.hourly_only());
+            continue;
+        }
+        symbol_count += 1;
+        state.window.iter_mut().for_each(|c| c.fill_missing_ohlc());
+        state.rebuild_derived();
+        store.insert(symbol, state);
+    }
+
+    let mut cache_count = 0;
+    for (symbol, entry) in snapshot.volume_cache {
+        if entry.minute == current_minute {
+            store.volume_cache.insert(symbol, entry);
+            cache_count += 1;
+        }
+    }
+
+    info!(
+        path,
+        symbols = symbol_count,
+        stale = stale_count,
+        volume_cache = cache_count,
+        age_secs = (now - snapshot.saved_at) / 1000,
+        "Restored store snapshot"
+    );
+}
+
+pub async fn snapshot_task(store: SharedState) {
+    let path = snapshot_path();
+    let interval = tokio::time::Duration::from_secs(snapshot_interval_secs());
+    loop {
+        tokio::time::sleep(interval).await;
+        save(&path, &store);
+    }
+}
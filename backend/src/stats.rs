@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+// Relative accuracy of `quantile`: results are within 1% of the true value
+const SKETCH_ALPHA: f64 = 0.01;
+
+/// Statistics over a sliding window, updated in O(1) (amortized) per insertion/removal
+/// instead of rescanning the window on every tick.
+///
+/// - mean/variance: Welford's algorithm, with the inverse update for removals
+/// - min/max: monotonic deques
+/// - quantiles: a log-bucketed sketch (DDSketch style) that also supports removals
+///
+/// Values must be removed in insertion order (`remove_oldest`), as a sliding window does.
+#[derive(Debug, Clone, Default)]
+pub struct RollingStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    pushed: u64,
+    popped: u64,
+    min_queue: VecDeque<(u64, f64)>, // (sequence, value), increasing values
+    max_queue: VecDeque<(u64, f64)>, // decreasing values
+    sketch: BTreeMap<i32, u32>,
+    zeros: u32, // Values <= 0 (no log bucket)
+}
+
+impl RollingStats {
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+
+        let seq = self.pushed;
+        self.pushed += 1;
+        while self.min_queue.back().is_some_and(|(_, v)| *v >= x) {
+            self.min_queue.pop_back();
+        }
+        self.min_queue.push_back((seq, x));
+        while self.max_queue.back().is_some_and(|(_, v)| *v <= x) {
+            self.max_queue.pop_back();
+        }
+        self.max_queue.push_back((seq, x));
+
+        match bucket(x) {
+            Some(b) => *self.sketch.entry(b).or_insert(0) += 1,
+            None => self.zeros += 1,
+        }
+    }
+
+    /// Removes the oldest value still in the window; `x` must be that value.
+    pub fn remove_oldest(&mut self, x: f64) {
+        if self.count == 0 {
+            return;
+        }
+        self.count -= 1;
+        if self.count == 0 {
+            self.mean = 0.0;
+            self.m2 = 0.0;
+        } else {
+            let delta = x - self.mean;
+            self.mean -= delta / self.count as f64;
+            self.m2 = (self.m2 - delta * (x - self.mean)).max(0.0);
+        }
+
+        let seq = self.popped;
+        self.popped += 1;
+        if self.min_queue.front().is_some_and(|(s, _)| *s == seq) {
+            self.min_queue.pop_front();
+        }
+        if self.max_queue.front().is_some_and(|(s, _)| *s == seq) {
+            self.max_queue.pop_front();
+        }
+
+        match bucket(x) {
+            Some(b) => {
+                if let Some(n) = self.sketch.get_mut(&b) {
+                    *n -= 1;
+                    if *n == 0 {
+                        self.sketch.remove(&b);
+                    }
+                }
+            }
+            None => self.zeros = self.zeros.saturating_sub(1),
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    // Population variance
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+        self.m2 / self.count as f64
+    }
+
+    pub fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min_queue.front().map(|(_, v)| *v)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max_queue.front().map(|(_, v)| *v)
+    }
+
+    /// How many standard deviations `x` is above the mean. None while the window is flat.
+    pub fn zscore(&self, x: f64) -> Option<f64> {
+        let sd = self.stddev();
+        (sd > 0.0).then(|| (x - self.mean) / sd)
+    }
+
+    /// Approximate q-quantile (0.0..=1.0), within `SKETCH_ALPHA` relative error.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64).round() as u64;
+        if rank < self.zeros as u64 {
+            return Some(0.0);
+        }
+        let mut seen = self.zeros as u64;
+        for (b, n) in &self.sketch {
+            seen += *n as u64;
+            if seen > rank {
+                return Some(bucket_value(*b));
+            }
+        }
+        self.max()
+    }
+}
+
+fn gamma() -> f64 {
+    (1.0 + SKETCH_ALPHA) / (1.0 - SKETCH_ALPHA)
+}
+
+fn bucket(x: f64) -> Option<i32> {
+    (x > 0.0).then(|| (x.ln() / gamma().ln()).ceil() as i32)
+}
+
+// Midpoint of the bucket, so the relative error is at most alpha either way
+fn bucket_value(b: i32) -> f64 {
+    2.0 * gamma().powi(b) / (1.0 + gamma())
+}
+
+/// Exponentially weighted mean and variance: a baseline over hours kept in O(1) state, where
+/// old values fade out instead of falling off the end of a window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ewma {
+    mean: f64,
+    variance: f64,
+    samples: u64,
+}
+
+impl Ewma {
+    /// Weight of a new value; `half_life` is in values (minutes, for a per-minute series).
+    pub fn alpha(half_life: f64) -> f64 {
+        1.0 - 0.5f64.powf(1.0 / half_life)
+    }
+
+    pub fn push(&mut self, x: f64, alpha: f64) {
+        if self.samples == 0 {
+            self.mean = x;
+        } else {
+            let delta = x - self.mean;
+            self.mean += alpha * delta;
+            self.variance = (1.0 - alpha) * (self.variance + alpha * delta * delta);
+        }
+        self.samples += 1;
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn stddev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// How many standard deviations `x` is above the mean. None while the series is flat.
+    pub fn zscore(&self, x: f64) -> Option<f64> {
+        let sd = self.stddev();
+        (sd > 0.0).then(|| (x - self.mean) / sd)
+    }
+}
+
+/// One `Ewma` per UTC hour of the day, for series whose level follows the clock (volume is a
+/// fraction at 03:00 of what it is in the US session). Each hour only learns from its own
+/// values, so it takes days to fill in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HourlyBaseline {
+    hours: [Ewma; 24],
+}
+
+impl HourlyBaseline {
+    fn hour(timestamp: i64) -> usize {
+        timestamp.div_euclid(60 * 60 * 1000).rem_euclid(24) as usize
+    }
+
+    pub fn push(&mut self, timestamp: i64, x: f64, alpha: f64) {
+        self.hours[Self::hour(timestamp)].push(x, alpha);
+    }
+
+    /// The hour of `timestamp`'s mean, once it has `min_samples` values.
+    pub fn expected(&self, timestamp: i64, min_samples: u64) -> Option<f64> {
+        let hour = &self.hours[Self::hour(timestamp)];
+        (hour.samples() >= min_samples).then(|| hour.mean())
+    }
+}
@@ -1,9 +1,133 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use dashmap::DashMap;
-use crate::model::SymbolState;
+use tracing::info;
+use crate::metrics::METRICS;
+use crate::control::Control;
+use crate::strategy::Registry;
+use crate::model::{CandleBuilder, SymbolState};
 
-pub type SharedState = Arc<DashMap<String, SymbolState>>;
+/// Per-symbol state plus the strategies every symbol is scanned with, and the admin API's
+/// switches over them. Derefs to the symbol map, so `store.get(symbol)` etc. work as on a
+/// plain `DashMap`.
+pub struct Store {
+    symbols: DashMap<String, SymbolState>,
+    /// Map<Symbol, in-progress candle for the current minute>, used by the symbol actors to
+    /// turn rolling 24h ticker totals into per-minute candles. Lives here so eviction drops a
+    /// symbol's candle together with its windows.
+    pub volume_cache: DashMap<String, CandleBuilder>,
+    pub strategies: Registry,
+    pub control: Control,
+}
+
+impl std::ops::Deref for Store {
+    type Target = DashMap<String, SymbolState>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.symbols
+    }
+}
+
+pub type SharedState = Arc<Store>;
+
+pub fn init_store(strategies: Registry) -> SharedState {
+    Arc::new(Store { symbols: DashMap::new(), volume_cache: DashMap::new(), strategies, control: Control::default() })
+}
+
+impl Store {
+    // Every symbol with windows or a candle in progress; a symbol whose first minute hasn't
+    // finished yet only has the latter
+    fn tracked(&self) -> HashSet<String> {
+        self.symbols.iter().map(|e| e.key().clone()).chain(self.volume_cache.iter().map(|e| e.key().clone())).collect()
+    }
+
+    // When the symbol last ticked: its candle in progress is restarted by every new minute's
+    // first tick, the finalized window only covers the minutes before it
+    fn last_tick(&self, symbol: &str) -> Option<i64> {
+        let building = self.volume_cache.get(symbol).map(|b| b.minute * 60_000);
+        let finalized = self.symbols.get(symbol).and_then(|s| s.last_active());
+        building.max(finalized)
+    }
+
+    fn estimated_bytes(&self, symbol: &str) -> usize {
+        let windows = self.symbols.get(symbol).map_or(0, |s| s.estimated_bytes());
+        let building = self.volume_cache.get(symbol).map_or(0, |_| std::mem::size_of::<CandleBuilder>() + symbol.len());
+        windows + building
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+pub(crate) fn remove_symbol(store: &SharedState, symbol: &str) {
+    store.remove(symbol);
+    store.volume_cache.remove(symbol);
+    crate::metrics::SYMBOL_COUNTERS.remove(symbol);
+}
+
+/// Keeps the store bounded for long-running instances.
+///
+/// 1. Symbols with no tick for `STORE_IDLE_EVICT_SECS` (default 6h) are dropped (delisted
+///    contracts, dead pairs), windows and candle in progress alike. They are recreated on
+///    their next tick.
+/// 2. If the estimated footprint still exceeds `STORE_MAX_BYTES` (default 64MB),
+///    the least recently active symbols are evicted until we're back under budget.
+pub fn evict(store: &SharedState, now_ms: i64, idle_ms: i64, max_bytes: usize) {
+    let idle: Vec<String> =
+        store.tracked().into_iter().filter(|s| store.last_tick(s).is_none_or(|t| now_ms - t > idle_ms)).collect();
+    for symbol in &idle {
+        remove_symbol(store, symbol);
+    }
+    METRICS.evicted_idle.fetch_add(idle.len() as u64, Ordering::Relaxed);
+
+    // (last_active, bytes, symbol)
+    let mut entries: Vec<(i64, usize, String)> = store
+        .tracked()
+        .into_iter()
+        .map(|s| (store.last_tick(&s).unwrap_or(0), store.estimated_bytes(&s), s))
+        .collect();
+    let mut total: usize = entries.iter().map(|e| e.1).sum();
+
+    let mut over_budget = 0;
+    if total > max_bytes {
+        entries.sort_by_key(|e| e.0);
+        for (_, bytes, symbol) in &entries {
+            if total <= max_bytes {
+                break;
+            }
+            remove_symbol(store, symbol);
+            total -= bytes;
+            over_budget += 1;
+        }
+        METRICS.evicted_budget.fetch_add(over_budget as u64, Ordering::Relaxed);
+    }
+
+    METRICS.store_symbols.store(store.len() as u64, Ordering::Relaxed);
+    METRICS.store_candle_builders.store(store.volume_cache.len() as u64, Ordering::Relaxed);
+    METRICS.store_bytes_estimate.store(total as u64, Ordering::Relaxed);
+
+    if !idle.is_empty() || over_budget > 0 {
+        info!(idle = idle.len(), over_budget, symbols = store.len(), bytes = total, "Evicted symbols from store");
+    }
+}
 
-pub fn init_store() -> SharedState {
-    Arc::new(DashMap::new())
+/// Runs `evict` once a minute. A replay keeps the recorded event times, so there "now" is
+/// the latest tick seen rather than the wall clock, which would find every symbol idle.
+pub async fn eviction_task(store: SharedState, replaying: bool) {
+    let idle_ms = env_or("STORE_IDLE_EVICT_SECS", 6 * 60 * 60i64) * 1000;
+    let max_bytes = env_or("STORE_MAX_BYTES", 64 * 1024 * 1024usize);
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+        let now_ms = if replaying {
+            match store.tracked().iter().filter_map(|s| store.last_tick(s)).max() {
+                Some(latest) => latest,
+                None => continue,
+            }
+        } else {
+            chrono::Utc::now().timestamp_millis()
+        };
+        evict(&store, now_ms, idle_ms, max_bytes);
+    }
 }
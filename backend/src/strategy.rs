@@ -0,0 +1,257 @@
+use crate::config::{
+    config_path, BreakoutConfig, ConfigError, LiquidationConfig, ScannerConfig, ShortPressureConfig, StrategiesConfig, SymbolOverride,
+};
+use crate::metrics::METRICS;
+use crate::model::{MarketData, SymbolState};
+use crate::scanner::{check_breakout, check_for_signals, check_liquidation_cascade, check_short_pressure, Signal};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{error, info};
+
+/// One way of turning a symbol's candles into signals. Runs in the symbol's actor on every
+/// tick, so `evaluate` must be cheap and must not block.
+pub trait Strategy: Send + Sync {
+    /// Stable identifier, stored with every signal (`strategyName`) and used in config.
+    fn name(&self) -> &'static str;
+
+    /// A symbol signals at most once per this, per strategy. `symbol` is the symbol's
+    /// `[overrides]` table, if it has one; each strategy applies its own section of it.
+    fn cooldown_ms(&self, symbol: Option<&SymbolOverride>) -> i64;
+
+    /// Cooldowns are checked by the registry; implementations only decide whether `data`
+    /// (the 1m candle in progress) is a signal. `boost` is set for a watchlisted symbol: the
+    /// factor to make the thresholds (overrides included) more sensitive by.
+    fn evaluate(&self, state: &SymbolState, data: &MarketData, symbol: Option<&SymbolOverride>, boost: Option<f64>) -> Option<Signal>;
+}
+
+pub const SILENT_WATCHER: &str = "silent_watcher";
+pub const LIQUIDATION_CASCADE: &str = "liquidation_cascade";
+pub const SHORT_PRESSURE: &str = "short_pressure";
+pub const BREAKOUT: &str = "breakout";
+
+// Without a cooldown a watchlisted symbol would signal on every tick of its spike minute
+const WATCHED_COOLDOWN_MS: i64 = 60 * 1000;
+
+/// Volume spike with a quiet price, configured by the `[scanner]` table. The thresholds are
+/// shared with the registry so the admin API can change them while it runs.
+pub struct SilentWatcher {
+    pub config: Arc<RwLock<ScannerConfig>>,
+}
+
+impl Strategy for SilentWatcher {
+    fn name(&self) -> &'static str {
+        SILENT_WATCHER
+    }
+
+    fn cooldown_ms(&self, symbol: Option<&SymbolOverride>) -> i64 {
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner());
+        symbol.and_then(|o| o.scanner.cooldown_secs).map_or_else(|| config.cooldown_ms(), |secs| secs * 1000)
+    }
+
+    fn evaluate(&self, state: &SymbolState, data: &MarketData, symbol: Option<&SymbolOverride>, boost: Option<f64>) -> Option<Signal> {
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner());
+        // A symbol whose signals keep failing needs a bigger spike (see `tuner`); its own
+        // overrides still win
+        let tightened = crate::tuner::symbol_factor_for(&data.symbol).map(|factor| config.tightened(factor));
+        let config = tightened.as_ref().unwrap_or(&config);
+        match (symbol, boost) {
+            (Some(o), Some(factor)) => check_for_signals(state, data, &o.scanner.apply(config).boosted(factor)),
+            (Some(o), None) => check_for_signals(state, data, &o.scanner.apply(config)),
+            (None, Some(factor)) => check_for_signals(state, data, &config.boosted(factor)),
+            (None, None) => check_for_signals(state, data, config),
+        }
+    }
+}
+
+/// Liquidation spikes from `liquidation_client`, configured by `[liquidation_cascade]`.
+pub struct LiquidationCascade {
+    pub config: LiquidationConfig,
+}
+
+impl Strategy for LiquidationCascade {
+    fn name(&self) -> &'static str {
+        LIQUIDATION_CASCADE
+    }
+
+    fn cooldown_ms(&self, symbol: Option<&SymbolOverride>) -> i64 {
+        symbol.and_then(|o| o.liquidation_cascade.cooldown_secs).map_or_else(|| self.config.cooldown_ms(), |secs| secs * 1000)
+    }
+
+    fn evaluate(&self, state: &SymbolState, data: &MarketData, symbol: Option<&SymbolOverride>, boost: Option<f64>) -> Option<Signal> {
+        match (symbol, boost) {
+            (Some(o), Some(factor)) => check_liquidation_cascade(state, data, &o.liquidation_cascade.apply(&self.config).boosted(factor)),
+            (Some(o), None) => check_liquidation_cascade(state, data, &o.liquidation_cascade.apply(&self.config)),
+            (None, Some(factor)) => check_liquidation_cascade(state, data, &self.config.boosted(factor)),
+            (None, None) => check_liquidation_cascade(state, data, &self.config),
+        }
+    }
+}
+
+/// Shorts confirmed by sellers opening positions, configured by `[short_pressure]`.
+pub struct ShortPressure {
+    pub config: ShortPressureConfig,
+}
+
+impl Strategy for ShortPressure {
+    fn name(&self) -> &'static str {
+        SHORT_PRESSURE
+    }
+
+    fn cooldown_ms(&self, symbol: Option<&SymbolOverride>) -> i64 {
+        symbol.and_then(|o| o.short_pressure.cooldown_secs).map_or_else(|| self.config.cooldown_ms(), |secs| secs * 1000)
+    }
+
+    fn evaluate(&self, state: &SymbolState, data: &MarketData, symbol: Option<&SymbolOverride>, boost: Option<f64>) -> Option<Signal> {
+        match (symbol, boost) {
+            (Some(o), Some(factor)) => check_short_pressure(state, data, &o.short_pressure.apply(&self.config).boosted(factor)),
+            (Some(o), None) => check_short_pressure(state, data, &o.short_pressure.apply(&self.config)),
+            (None, Some(factor)) => check_short_pressure(state, data, &self.config.boosted(factor)),
+            (None, None) => check_short_pressure(state, data, &self.config),
+        }
+    }
+}
+
+/// Price velocity breakouts, configured by `[breakout]`.
+pub struct Breakout {
+    pub config: BreakoutConfig,
+}
+
+impl Strategy for Breakout {
+    fn name(&self) -> &'static str {
+        BREAKOUT
+    }
+
+    fn cooldown_ms(&self, symbol: Option<&SymbolOverride>) -> i64 {
+        symbol.and_then(|o| o.breakout.cooldown_secs).map_or_else(|| self.config.cooldown_ms(), |secs| secs * 1000)
+    }
+
+    fn evaluate(&self, state: &SymbolState, data: &MarketData, symbol: Option<&SymbolOverride>, boost: Option<f64>) -> Option<Signal> {
+        match (symbol, boost) {
+            (Some(o), Some(factor)) => check_breakout(state, data, &o.breakout.apply(&self.config).boosted(factor)),
+            (Some(o), None) => check_breakout(state, data, &o.breakout.apply(&self.config)),
+            (None, Some(factor)) => check_breakout(state, data, &self.config.boosted(factor)),
+            (None, None) => check_breakout(state, data, &self.config),
+        }
+    }
+}
+
+/// The strategies that run, in the order listed in `[strategies] enabled`, and the per-symbol
+/// `[overrides]` they consult.
+pub struct Registry {
+    strategies: Vec<Box<dyn Strategy>>,
+    scanner: Option<Arc<RwLock<ScannerConfig>>>, // The Silent Watcher's, if it runs
+    overrides: HashMap<String, SymbolOverride>,
+}
+
+impl Registry {
+    pub fn new(strategies: Vec<Box<dyn Strategy>>) -> Self {
+        Self { strategies, scanner: None, overrides: HashMap::new() }
+    }
+
+    fn with_silent_watcher(mut self, config: ScannerConfig) -> Self {
+        let config = Arc::new(RwLock::new(config));
+        self.strategies.push(Box::new(SilentWatcher { config: config.clone() }));
+        self.scanner = Some(config);
+        self
+    }
+
+    /// Strategies named in the `[strategies]` table (`STRATEGIES`), each built from its own
+    /// section of the same file.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let enabled = StrategiesConfig::load(path)?.enabled;
+        let mut registry = Self::new(Vec::new());
+        for name in &enabled {
+            match name.as_str() {
+                SILENT_WATCHER => registry = registry.with_silent_watcher(ScannerConfig::load(path)?),
+                LIQUIDATION_CASCADE => {
+                    registry.strategies.push(Box::new(LiquidationCascade { config: LiquidationConfig::load(path)? }))
+                }
+                SHORT_PRESSURE => registry.strategies.push(Box::new(ShortPressure { config: ShortPressureConfig::load(path)? })),
+                BREAKOUT => registry.strategies.push(Box::new(Breakout { config: BreakoutConfig::load(path)? })),
+                _ => return Err(ConfigError::UnknownStrategy(name.clone())),
+            }
+        }
+        registry.overrides = crate::config::load_overrides(path)?;
+        Ok(registry)
+    }
+
+    /// Loads the registry at startup. A broken config is logged and the Silent Watcher runs
+    /// alone with its defaults (plus env overrides); `--check` reports it as a failure.
+    pub fn init() -> Self {
+        let path = config_path();
+        match Self::load(&path) {
+            Ok(registry) => {
+                info!(path = %path, strategies = ?registry.names(), overrides = registry.overrides.len(), "Strategies loaded");
+                registry
+            }
+            Err(e) => {
+                METRICS.record_error("config");
+                error!(path = %path, error = %e, "Invalid strategy config, running the Silent Watcher only");
+                Self::new(Vec::new()).with_silent_watcher(crate::config::init())
+            }
+        }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|s| s.name()).collect()
+    }
+
+    /// The Silent Watcher's current thresholds; None if it isn't enabled.
+    pub fn scanner_config(&self) -> Option<ScannerConfig> {
+        self.scanner.as_ref().map(|c| c.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    /// Replaces the Silent Watcher's thresholds from the next tick on. False if it isn't enabled.
+    pub fn set_scanner_config(&self, config: ScannerConfig) -> bool {
+        let Some(current) = &self.scanner else {
+            return false;
+        };
+        *current.write().unwrap_or_else(|e| e.into_inner()) = config;
+        true
+    }
+
+    // The strategies that run on `symbol`, with its overrides if it has any
+    fn for_symbol<'a>(&'a self, symbol: &str) -> impl Iterator<Item = (&'a dyn Strategy, Option<&'a SymbolOverride>)> {
+        let overrides = self.overrides.get(symbol);
+        self.strategies
+            .iter()
+            .filter(move |s| overrides.is_none_or(|o| o.runs(s.name())))
+            .map(move |s| (s.as_ref(), overrides))
+    }
+
+    // A strategy's cooldown on a symbol; a watchlisted one only waits out the minute
+    fn cooldown_ms(strategy: &dyn Strategy, overrides: Option<&SymbolOverride>, watched: bool) -> i64 {
+        match watched {
+            true => WATCHED_COOLDOWN_MS,
+            false => strategy.cooldown_ms(overrides),
+        }
+    }
+
+    /// Every strategy past its cooldown that fires on `data`, tagged with its name. `watched`
+    /// symbols (on the watchlist) get boosted thresholds and no cooldown past the minute.
+    pub fn evaluate(&self, state: &SymbolState, data: &MarketData, watched: bool) -> Vec<Signal> {
+        let boost = watched.then(crate::watchlist::boost);
+        let mut signals = Vec::new();
+        for (strategy, overrides) in self.for_symbol(&data.symbol) {
+            let last = state.last_signal_of(strategy.name());
+            if last.is_some_and(|t| data.timestamp - t < Self::cooldown_ms(strategy, overrides, watched)) {
+                continue;
+            }
+            if let Some(mut signal) = strategy.evaluate(state, data, overrides, boost) {
+                signal.set_strategy(strategy.name());
+                signals.push(signal);
+            }
+        }
+        signals
+    }
+
+    /// Time until the symbol can signal again (the soonest any strategy comes off cooldown).
+    pub fn cooldown_remaining_ms(&self, state: &SymbolState, now: i64, watched: bool) -> i64 {
+        self.for_symbol(&state.symbol)
+            .map(|(s, overrides)| {
+                state.last_signal_of(s.name()).map_or(0, |t| (t + Self::cooldown_ms(s, overrides, watched) - now).max(0))
+            })
+            .min()
+            .unwrap_or(0)
+    }
+}
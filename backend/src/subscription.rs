@@ -0,0 +1,107 @@
+use crate::klines::CandleStream;
+use crate::scanner::WsMessage;
+use crate::wire::SubscriptionRequest;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+// What one WebSocket client has asked to receive. A new client gets everything; `subscribe`
+// narrows it to the symbols / message types listed (adding to them on later calls), and
+// `unsubscribe` takes some away. An `unsubscribe` listing nothing goes back to everything.
+// Separately, `stream_candles` picks one symbol whose minute in progress is pushed to the
+// client (see `klines::CandleStream`); the filters don't apply to it.
+
+#[derive(Debug, Clone)]
+enum Selection {
+    All { except: HashSet<String> },
+    Only(HashSet<String>),
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection::All { except: HashSet::new() }
+    }
+}
+
+impl Selection {
+    fn subscribe(&mut self, items: Vec<String>) {
+        if items.is_empty() {
+            return;
+        }
+        match self {
+            Selection::All { .. } => *self = Selection::Only(items.into_iter().collect()),
+            Selection::Only(only) => only.extend(items),
+        }
+    }
+
+    fn unsubscribe(&mut self, items: Vec<String>) {
+        match self {
+            Selection::All { except } => except.extend(items),
+            Selection::Only(only) => items.iter().for_each(|item| {
+                only.remove(item);
+            }),
+        }
+    }
+
+    fn is_everything(&self) -> bool {
+        matches!(self, Selection::All { except } if except.is_empty())
+    }
+
+    fn contains(&self, item: &str) -> bool {
+        match self {
+            Selection::All { except } => !except.contains(item),
+            Selection::Only(only) => only.contains(item),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Subscription {
+    symbols: Selection, // Upper case, as stored
+    types: Selection,   // Lower case `type` tags, e.g. "signal"
+    pub candles: Option<CandleStream>,
+}
+
+impl Subscription {
+    pub fn apply(&mut self, request: SubscriptionRequest) {
+        match request {
+            SubscriptionRequest::Subscribe { symbols, types } => {
+                self.symbols.subscribe(symbols.iter().map(|s| s.trim().to_uppercase()).collect());
+                self.types.subscribe(types.iter().map(|t| t.trim().to_lowercase()).collect());
+            }
+            SubscriptionRequest::Unsubscribe { symbols, types } if symbols.is_empty() && types.is_empty() => {
+                *self = Self { candles: self.candles.take(), ..Self::default() };
+            }
+            SubscriptionRequest::Unsubscribe { symbols, types } => {
+                self.symbols.unsubscribe(symbols.iter().map(|s| s.trim().to_uppercase()).collect());
+                self.types.unsubscribe(types.iter().map(|t| t.trim().to_lowercase()).collect());
+            }
+            SubscriptionRequest::StreamCandles { symbol } => {
+                self.candles = symbol.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).map(CandleStream::new);
+            }
+        }
+    }
+
+    /// The part of `msg` this client wants: None to skip it, batches (`Updates`, `History`)
+    /// cut down to the subscribed symbols. Messages not about one symbol (stats, market
+    /// alerts, bias, ...) are only filtered by type.
+    pub fn filter<'a>(&self, msg: &'a WsMessage) -> Option<Cow<'a, WsMessage>> {
+        if !self.types.contains(&msg.kind().to_lowercase()) {
+            return None;
+        }
+        match msg {
+            _ if self.symbols.is_everything() => Some(Cow::Borrowed(msg)),
+            WsMessage::Updates(updates) => {
+                let updates: Vec<_> = updates.iter().filter(|u| self.symbols.contains(&u.symbol)).cloned().collect();
+                (!updates.is_empty()).then_some(Cow::Owned(WsMessage::Updates(updates)))
+            }
+            WsMessage::History(signals) => {
+                let signals: Vec<_> = signals.iter().filter(|s| self.symbols.contains(&s.symbol)).cloned().collect();
+                (!signals.is_empty()).then_some(Cow::Owned(WsMessage::History(signals)))
+            }
+            _ => match msg.symbol() {
+                Some(symbol) if !self.symbols.contains(symbol) => None,
+                _ => Some(Cow::Borrowed(msg)),
+            },
+        }
+    }
+}
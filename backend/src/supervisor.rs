@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{error, info, warn};
+use crate::shutdown::Shutdown;
+use crate::system_event::{self, EventLevel};
+
+/// What a supervised task may return. `()` for tasks that can't fail,
+/// `Result<(), E>` for tasks that propagate their error up to the supervisor.
+pub trait TaskExit {
+    fn into_error(self) -> Option<String>;
+}
+
+impl TaskExit for () {
+    fn into_error(self) -> Option<String> {
+        None
+    }
+}
+
+impl<E: std::fmt::Display> TaskExit for Result<(), E> {
+    fn into_error(self) -> Option<String> {
+        self.err().map(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS, ToSchema)]
+pub enum RestartPolicy {
+    Always,    // Long-running tasks: any exit is a failure
+    OnFailure, // Restart only after a panic, a clean exit is final
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS, ToSchema)]
+pub enum TaskState {
+    Running,
+    Restarting,
+    Stopped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatus {
+    pub state: TaskState,
+    pub policy: RestartPolicy,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_started: i64, // ms
+}
+
+/// Task states for `/health` and the systemd watchdog.
+#[derive(Debug, Clone)]
+pub struct TaskReport {
+    pub healthy: bool,
+    pub tasks: BTreeMap<String, TaskStatus>,
+}
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// A task that stayed up this long is considered stable again, so backoff resets.
+const STABLE_AFTER: Duration = Duration::from_secs(120);
+// ...and one restarted task up this long has its degraded-mode notice cleared.
+const RECOVERED_AFTER: Duration = Duration::from_secs(30);
+
+/// Spawns named tasks, watches their JoinHandles and restarts them with exponential
+/// backoff according to their policy. Status of every task is kept for `/health`, and a
+/// restart or failure is raised as a `SystemEvent` under the task's name.
+/// Also owns the shutdown signal: once `shutdown` is called nothing is restarted.
+#[derive(Clone)]
+pub struct Supervisor {
+    tasks: Arc<RwLock<BTreeMap<String, TaskStatus>>>,
+    shutdown: Arc<watch::Sender<bool>>,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self { tasks: Arc::default(), shutdown: Arc::new(watch::channel(false).0) }
+    }
+
+    /// For tasks spawned with `spawn_graceful`, to notice shutdown and return.
+    pub fn shutdown_signal(&self) -> Shutdown {
+        Shutdown::new(self.shutdown.subscribe())
+    }
+
+    /// Triggers shutdown and waits until every task has stopped. Plain tasks are aborted at
+    /// once, graceful ones get `SHUTDOWN_TIMEOUT_SECS` to finish what they are doing.
+    pub async fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+        let deadline = Instant::now() + Duration::from_secs(crate::shutdown::timeout_secs() + 1);
+        while Instant::now() < deadline
+            && self.tasks.read().unwrap().values().any(|t| matches!(t.state, TaskState::Running | TaskState::Restarting))
+        {
+            sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    pub fn spawn<F, Fut>(&self, name: &str, policy: RestartPolicy, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskExit + Send,
+    {
+        self.spawn_task(name, policy, false, factory)
+    }
+
+    /// As `spawn`, for a task that watches `shutdown_signal()` and returns by itself: on
+    /// shutdown it is given time to do so instead of being aborted.
+    pub fn spawn_graceful<F, Fut>(&self, name: &str, policy: RestartPolicy, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskExit + Send,
+    {
+        self.spawn_task(name, policy, true, factory)
+    }
+
+    fn spawn_task<F, Fut>(&self, name: &str, policy: RestartPolicy, graceful: bool, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskExit + Send,
+    {
+        let name = name.to_string();
+        let tasks = self.tasks.clone();
+        let shutdown = self.shutdown_signal();
+
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            let mut restarts = 0u32;
+
+            loop {
+                set_status(&tasks, &name, TaskState::Running, policy, restarts, None);
+                let started = Instant::now();
+                let mut handle = tokio::spawn(factory());
+                // Up long enough after a restart: clear its notice
+                let mut recovery = std::pin::pin!(sleep(RECOVERED_AFTER));
+                let mut recovered = restarts == 0;
+                let result = loop {
+                    tokio::select! {
+                        result = &mut handle => break result,
+                        _ = &mut recovery, if !recovered => {
+                            recovered = true;
+                            system_event::recover(&name, format!("{} running again", name));
+                        }
+                        _ = shutdown.wait() => {
+                            let grace = if graceful { Duration::from_secs(crate::shutdown::timeout_secs()) } else { Duration::ZERO };
+                            if tokio::time::timeout(grace, &mut handle).await.is_err() {
+                                handle.abort();
+                                if graceful {
+                                    warn!(task = %name, "Task did not stop in time, aborted");
+                                }
+                            }
+                            set_status(&tasks, &name, TaskState::Stopped, policy, restarts, None);
+                            return;
+                        }
+                    }
+                };
+                if shutdown.is_triggered() {
+                    set_status(&tasks, &name, TaskState::Stopped, policy, restarts, None);
+                    return;
+                }
+
+                let (failed, reason) = match result.map(TaskExit::into_error) {
+                    Ok(None) => (false, "task exited".to_string()),
+                    Ok(Some(error)) => (true, error),
+                    Err(e) if e.is_panic() => (true, panic_message(e.into_panic())),
+                    Err(e) => (true, e.to_string()),
+                };
+
+                let restart = match policy {
+                    RestartPolicy::Always => true,
+                    RestartPolicy::OnFailure => failed,
+                };
+
+                if !restart {
+                    let state = if failed { TaskState::Failed } else { TaskState::Stopped };
+                    if failed {
+                        error!(task = %name, reason = %reason, "Task failed, not restarting");
+                        system_event::raise(&name, EventLevel::Error, format!("{} failed: {}", name, reason));
+                    } else {
+                        info!(task = %name, "Task finished");
+                    }
+                    set_status(&tasks, &name, state, policy, restarts, failed.then_some(reason));
+                    return;
+                }
+
+                if started.elapsed() >= STABLE_AFTER {
+                    backoff = INITIAL_BACKOFF;
+                }
+                restarts += 1;
+                warn!(task = %name, reason = %reason, restarts, backoff_secs = backoff.as_secs(), "Task stopped, restarting");
+                let message = format!("{} stopped: {}; restarting in {}s", name, reason, backoff.as_secs());
+                system_event::raise(&name, if failed { EventLevel::Error } else { EventLevel::Warning }, message);
+                set_status(&tasks, &name, TaskState::Restarting, policy, restarts, Some(reason));
+
+                tokio::select! {
+                    _ = sleep(backoff) => {}
+                    _ = shutdown.wait() => {
+                        set_status(&tasks, &name, TaskState::Stopped, policy, restarts, None);
+                        return;
+                    }
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+    }
+
+    pub fn report(&self) -> TaskReport {
+        let tasks = self.tasks.read().unwrap().clone();
+        // Cleanly finished tasks are fine (one-shot work), failed or restarting ones are not
+        let healthy = tasks.values().all(|t| t.state == TaskState::Running || t.state == TaskState::Stopped);
+        TaskReport { healthy, tasks }
+    }
+}
+
+fn set_status(
+    tasks: &RwLock<BTreeMap<String, TaskStatus>>,
+    name: &str,
+    state: TaskState,
+    policy: RestartPolicy,
+    restarts: u32,
+    last_error: Option<String>,
+) {
+    let mut tasks = tasks.write().unwrap();
+    let entry = tasks.entry(name.to_string()).or_insert(TaskStatus {
+        state,
+        policy,
+        restarts,
+        last_error: None,
+        last_started: 0,
+    });
+    if state == TaskState::Running {
+        entry.last_started = chrono::Utc::now().timestamp_millis();
+    }
+    entry.state = state;
+    entry.restarts = restarts;
+    if last_error.is_some() {
+        entry.last_error = last_error;
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panic: {}", s)
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panic: {}", s)
+    } else {
+        "panic".to_string()
+    }
+}
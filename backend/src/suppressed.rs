@@ -0,0 +1,90 @@
+use crate::metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::{LazyLock, Mutex};
+use ts_rs::TS;
+use utoipa::{IntoParams, ToSchema};
+
+// Candidates a strategy found but dropped on purpose (so far only the priced-in filter:
+// a spike on a coin that already ran), kept so the filters can be audited at
+// /api/suppressed. The strategies evaluate every tick of a minute, so a candidate is logged
+// once per symbol and candle, updated while the candle lasts. In memory, local to the
+// instance (each worker of a sharded setup keeps its own), the newest SUPPRESSED_LOG_SIZE.
+
+/// Why a candidate was dropped.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(tag = "code", rename_all_fields = "camelCase")]
+pub enum SuppressionReason {
+    // The last hour already moved the signal's way by more than `max_pct`: `change_pct` from
+    // the window's first open to now, `high_pct` / `low_pct` to its high and low
+    PricedIn { change_pct: f64, high_pct: f64, low_pct: f64, max_pct: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuppressedCandidate {
+    pub symbol: String,
+    pub strategy: String,
+    pub signal_type: crate::scanner::SignalType,
+    pub candle_start: i64, // The candle it would have fired on
+    pub timestamp: i64,    // Last evaluated
+    pub price: f64,
+    pub volume_ratio: f64,
+    pub reason: SuppressionReason,
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuppressedPage {
+    pub candidates: Vec<SuppressedCandidate>, // Newest first
+    pub total: u64,                           // Logged since startup, including the evicted
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuppressedQuery {
+    /// Contract symbol, e.g. BTCUSDT (case-insensitive)
+    pub symbol: Option<String>,
+    /// Number of candidates, default 100
+    pub limit: Option<usize>,
+}
+
+fn capacity() -> usize {
+    std::env::var("SUPPRESSED_LOG_SIZE").ok().and_then(|v| v.parse().ok()).unwrap_or(500)
+}
+
+static LOG: LazyLock<Mutex<VecDeque<SuppressedCandidate>>> = LazyLock::new(Default::default);
+static CAPACITY: LazyLock<usize> = LazyLock::new(capacity);
+
+/// Logs `candidate`, or refreshes the entry for its symbol, strategy and candle.
+pub fn record(candidate: SuppressedCandidate) {
+    let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let existing = log
+        .iter_mut()
+        .rev()
+        .find(|c| c.symbol == candidate.symbol && c.strategy == candidate.strategy && c.candle_start == candidate.candle_start);
+    match existing {
+        Some(entry) => *entry = candidate,
+        None => {
+            METRICS.pipeline.signals_priced_in.fetch_add(1, Ordering::Relaxed);
+            log.push_back(candidate);
+            while log.len() > *CAPACITY {
+                log.pop_front();
+            }
+        }
+    }
+}
+
+pub fn recent(query: &SuppressedQuery) -> SuppressedPage {
+    let symbol = query.symbol.as_deref().map(str::to_ascii_uppercase);
+    let log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let candidates = log
+        .iter()
+        .rev()
+        .filter(|c| symbol.as_deref().is_none_or(|s| c.symbol == s))
+        .take(query.limit.unwrap_or(100))
+        .cloned()
+        .collect();
+    SuppressedPage { candidates, total: METRICS.pipeline.signals_priced_in.load(Ordering::Relaxed) }
+}
@@ -0,0 +1,181 @@
+use crate::depth_tracker::BookImbalance;
+use crate::exchange_info::SymbolMeta;
+use crate::footprint::Footprint;
+use crate::history::HistoryManager;
+use crate::indicators::IndicatorValues;
+use crate::model::{Interval, MarketData};
+use crate::scanner::{Invalidation, Signal};
+use crate::store::SharedState;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// A 1m candle as clients see it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Candle {
+    pub timestamp: i64, // Minute start
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trades: u64,
+    pub taker_buy_volume: Option<f64>,
+}
+
+impl From<&MarketData> for Candle {
+    fn from(d: &MarketData) -> Self {
+        Self {
+            timestamp: d.timestamp,
+            open: d.open,
+            high: d.high,
+            low: d.low,
+            close: d.close,
+            volume: d.volume,
+            quote_volume: d.quote_volume,
+            trades: d.trades,
+            taker_buy_volume: d.taker_buy_volume,
+        }
+    }
+}
+
+/// Contract metadata as clients see it.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractInfo {
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub price_precision: u32,
+    pub quantity_precision: u32,
+    pub contract_type: String,
+    pub tick_size: Option<f64>,
+    pub step_size: Option<f64>,
+    pub min_qty: Option<f64>,
+    pub min_notional: Option<f64>,
+    pub status: String,
+}
+
+impl From<&SymbolMeta> for ContractInfo {
+    fn from(m: &SymbolMeta) -> Self {
+        Self {
+            base_asset: m.base_asset.clone(),
+            quote_asset: m.quote_asset.clone(),
+            price_precision: m.price_precision,
+            quantity_precision: m.quantity_precision,
+            contract_type: m.contract_type.clone(),
+            tick_size: m.tick_size,
+            step_size: m.step_size,
+            min_qty: m.min_qty,
+            min_notional: m.min_notional,
+            status: m.status.clone(),
+        }
+    }
+}
+
+/// One tracked symbol at a glance, for `GET /api/symbols`.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolSummary {
+    pub symbol: String,
+    pub price: Option<f64>, // Close of the last finished minute
+    pub candles: usize,     // Finished 1m candles in the window
+    pub last_signal_time: Option<i64>,
+    pub signal_live: bool, // Signal within the last hour and not invalidated
+}
+
+/// Every symbol this instance tracks, by name.
+pub fn summaries(store: &SharedState) -> Vec<SymbolSummary> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut symbols: Vec<SymbolSummary> = store
+        .iter()
+        .map(|e| {
+            let state = e.value();
+            SymbolSummary {
+                symbol: e.key().clone(),
+                price: state.window.back().map(|c| c.close),
+                candles: state.window.len(),
+                last_signal_time: state.last_signal_time,
+                signal_live: state.active_signal.as_ref().is_some_and(|a| !a.invalidated)
+                    && state.last_signal_time.is_some_and(|t| now - t < 60 * 60 * 1000),
+            }
+        })
+        .collect();
+    symbols.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    symbols
+}
+
+/// Everything the scanner currently knows about one symbol, for `GET /api/symbol/{symbol}`.
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolDetail {
+    pub symbol: String,
+    pub candles: Vec<Candle>,          // Finished 1m candles, oldest first
+    pub current: Option<Candle>,       // The minute in progress
+    pub average_volume: f64,           // 1m average over `candles`
+    pub expected_volume: Option<f64>,  // Usual 1m volume at this hour of day, once learned
+    pub volume_ratio: Option<f64>,     // `current` volume vs the higher of those two, what the scanner compares
+    pub volume_zscore: Option<f64>,
+    pub vwap: Option<f64>,
+    pub session_vwap: Option<f64>,
+    pub indicators: IndicatorValues, // As of the last finished candle
+    pub day_high: Option<f64>,
+    pub day_low: Option<f64>,
+    pub day_change_pct: Option<f64>,
+    pub cooldown_remaining_ms: i64, // 0 when the symbol may signal again
+    pub watched: bool,              // On the watchlist: boosted thresholds, a one-minute cooldown
+    pub meta: Option<ContractInfo>,
+    // Latest recorded signal; its reason carries the verification notes (walls, OI)
+    pub last_signal: Option<Signal>,
+    pub last_signal_invalidation: Option<Invalidation>,
+    // Order flow by price per minute, oldest first; empty unless FOOTPRINT_ENABLED and a signal is live
+    pub footprints: Vec<Footprint>,
+    // Streamed bid/ask wall ratio over the last minute; None unless a signal is live
+    pub imbalance: Option<BookImbalance>,
+}
+
+/// None when the symbol isn't tracked (unknown, evicted, or owned by another shard).
+pub async fn detail(
+    symbol: &str,
+    store: &SharedState,
+    history: &HistoryManager,
+    watched: bool,
+) -> Option<SymbolDetail> {
+    let current = store.volume_cache.get(symbol).map(|b| Candle::from(&b.finish(symbol)));
+    // Copy out of the store entry before awaiting history, never hold a shard lock across an await
+    let mut detail = {
+        let state = store.get(symbol)?;
+        let average_volume = state.get_average_volume();
+        let now = chrono::Utc::now().timestamp_millis();
+        let expected_volume = state.expected_volume(Interval::M1, now);
+        let reference_volume = average_volume.max(expected_volume.unwrap_or(0.0));
+        SymbolDetail {
+            symbol: symbol.to_string(),
+            candles: state.window.iter().map(Candle::from).collect(),
+            volume_ratio: current.as_ref().filter(|_| reference_volume > 0.0).map(|c| c.volume / reference_volume),
+            volume_zscore: current.as_ref().and_then(|c| state.volume_stats.zscore(c.volume)),
+            current,
+            average_volume,
+            expected_volume,
+            vwap: state.vwap.window(),
+            session_vwap: state.vwap.session(),
+            indicators: state.indicators(),
+            day_high: state.day.high,
+            day_low: state.day.low,
+            day_change_pct: state.day.change_pct,
+            cooldown_remaining_ms: store.strategies.cooldown_remaining_ms(&state, now, watched),
+            watched,
+            meta: state.meta.as_ref().map(ContractInfo::from),
+            last_signal: None,
+            last_signal_invalidation: None,
+            footprints: state.footprints.iter().cloned().collect(),
+            imbalance: state.depth.summary(now),
+        }
+    };
+    if let Some(record) = history.last_signal_for(symbol).await {
+        detail.last_signal = Some(record.signal);
+        detail.last_signal_invalidation = record.invalidation;
+    }
+    Some(detail)
+}
@@ -0,0 +1,105 @@
+use crate::error::TeebError;
+use crate::scanner::WsMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tokio::sync::broadcast;
+use tracing::info;
+use ts_rs::TS;
+
+// Degraded-mode notices for the dashboard ("binance_client stopped: stream closed by server").
+// A part of the system that keeps running in a reduced way raises one under its name, and
+// clears it once it is back: the supervisor for a task it restarts or gives up on, history for
+// a database it can't use, the verifier for book lookups that fail. Raising and clearing are
+// broadcast as `SystemEvent` messages (`active` false for the all-clear); the ones still active
+// are replayed to clients that connect later. Local to each instance, never relayed.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub enum EventLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemEvent {
+    pub source: String, // Task or module name; one active event per source
+    pub level: EventLevel,
+    pub message: String,
+    pub active: bool,
+    pub timestamp: i64,
+}
+
+const REPEAT_MS: i64 = 60_000;
+
+// Source -> its current notice
+static ACTIVE: LazyLock<Mutex<BTreeMap<String, SystemEvent>>> = LazyLock::new(Default::default);
+
+// Set once the broadcast channel exists; events before that are only kept for replay
+static TX: OnceLock<broadcast::Sender<WsMessage>> = OnceLock::new();
+
+pub fn init(tx: broadcast::Sender<WsMessage>) {
+    let _ = TX.set(tx);
+}
+
+/// The notices still in force, for a client that just connected.
+pub fn active() -> Vec<SystemEvent> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).values().cloned().collect()
+}
+
+/// Whether `source` has a notice in force.
+pub fn is_active(source: &str) -> bool {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).contains_key(source)
+}
+
+/// Raises (or replaces) `source`'s notice.
+pub fn raise(source: &str, level: EventLevel, message: impl std::fmt::Display) {
+    let event = SystemEvent {
+        source: source.to_string(),
+        level,
+        message: message.to_string(),
+        active: true,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        // The same trouble again (a failing lookup per signal, say) is no news: kept for
+        // replay, broadcast again only after a while
+        let announced = active.get(source).filter(|e| e.level == event.level).map(|e| e.timestamp);
+        match announced {
+            Some(at) if event.timestamp - at < REPEAT_MS => {
+                active.insert(source.to_string(), SystemEvent { timestamp: at, ..event });
+                return;
+            }
+            _ => active.insert(source.to_string(), event.clone()),
+        };
+    }
+    broadcast(event);
+}
+
+/// `raise` for an error, at its level.
+pub fn degraded(source: &str, error: &TeebError) {
+    raise(source, error.level(), error);
+}
+
+/// Clears `source`'s notice, announcing `message` if there was one. Cheap when there wasn't.
+pub fn recover(source: &str, message: impl std::fmt::Display) {
+    if ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).remove(source).is_none() {
+        return;
+    }
+    info!(source, "Recovered");
+    broadcast(SystemEvent {
+        source: source.to_string(),
+        level: EventLevel::Info,
+        message: message.to_string(),
+        active: false,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+}
+
+fn broadcast(event: SystemEvent) {
+    if let Some(tx) = TX.get() {
+        let _ = tx.send(WsMessage::SystemEvent(event));
+    }
+}
@@ -0,0 +1,82 @@
+// Minimal sd_notify(3) support: READY=1 once the feed is live, WATCHDOG=1 while healthy.
+// Everything is a no-op when not started by systemd (NOTIFY_SOCKET unset).
+
+use std::os::unix::net::UnixDatagram;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+use crate::health::{feed_age_ms, max_feed_age_ms};
+use crate::supervisor::Supervisor;
+
+fn notify(state: &str) {
+    let Ok(path) = std::env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+    let socket = match UnixDatagram::unbound() {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!(error = ?e, "sd_notify: failed to create socket");
+            return;
+        }
+    };
+
+    let result = if let Some(name) = path.strip_prefix('@') {
+        send_abstract(&socket, name, state)
+    } else {
+        socket.send_to(state.as_bytes(), &path).map(|_| ())
+    };
+    if let Err(e) = result {
+        warn!(error = ?e, state, "sd_notify failed");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_abstract(socket: &UnixDatagram, name: &str, state: &str) -> std::io::Result<()> {
+    use std::os::linux::net::SocketAddrExt;
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+    socket.send_to_addr(state.as_bytes(), &addr).map(|_| ())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_abstract(_socket: &UnixDatagram, _name: &str, _state: &str) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "abstract sockets are Linux-only"))
+}
+
+/// Tells systemd the service is on its way down, so a slow shutdown isn't taken for a hang.
+pub fn stopping() {
+    notify("STOPPING=1");
+}
+
+/// Sends READY=1 after the first market data arrives, then pings WATCHDOG=1 at half the
+/// `WatchdogSec` interval as long as the feed is fresh and all supervised tasks are up.
+/// If the feed silently dies we stop pinging and systemd restarts the service.
+pub async fn watchdog_task(supervisor: Supervisor) {
+    if std::env::var("NOTIFY_SOCKET").is_err() {
+        return std::future::pending().await;
+    }
+
+    // Readiness: wait for the feed
+    while feed_age_ms().is_none() {
+        sleep(Duration::from_millis(500)).await;
+    }
+    notify("READY=1\nSTATUS=Receiving market data");
+    info!("Notified systemd: READY");
+
+    let Some(usec) = std::env::var("WATCHDOG_USEC").ok().and_then(|v| v.parse::<u64>().ok()) else {
+        return std::future::pending().await;
+    };
+    let interval = Duration::from_micros(usec / 2);
+    let max_age = max_feed_age_ms();
+
+    loop {
+        sleep(interval).await;
+        let age = feed_age_ms().unwrap_or(i64::MAX);
+        let tasks_healthy = supervisor.report().healthy;
+        if age <= max_age && tasks_healthy {
+            notify("WATCHDOG=1");
+        } else {
+            warn!(feed_age_ms = age, tasks_healthy, "Unhealthy, withholding systemd watchdog ping");
+            notify(&format!("STATUS=Unhealthy (feed age {}ms, tasks healthy: {})", age, tasks_healthy));
+        }
+        debug!(feed_age_ms = age, "Watchdog tick");
+    }
+}
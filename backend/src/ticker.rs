@@ -0,0 +1,106 @@
+// Zero-copy parsing for the `!ticker@arr` firehose.
+//
+// Binance pushes the whole market (~300 symbols) every second. Deserializing that into a
+// `Vec<TickerEvent>` of owned Strings meant 4 allocations per symbol plus the Vec itself.
+// Here each event borrows its fields straight from the message buffer and is handed to a
+// callback while the array is being walked, so the parse itself does not allocate.
+//
+// Kept free of other crate modules so `benches/ticker_parse.rs` can include it directly.
+
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::num::ParseFloatError;
+
+#[derive(Debug, Deserialize)]
+pub struct TickerEvent<'a> {
+    #[serde(rename = "s", borrow)]
+    pub symbol: &'a str,
+    #[serde(rename = "c", borrow)]
+    pub close: &'a str,
+    #[serde(rename = "v", borrow)]
+    pub volume: &'a str, // Total traded base asset volume
+    #[serde(rename = "q", borrow)]
+    pub quote_volume: &'a str, // Total traded quote asset volume
+    #[serde(rename = "n", default)]
+    pub trades: u64, // Total number of trades
+    #[serde(rename = "h", borrow, default)]
+    pub high: &'a str, // 24h high
+    #[serde(rename = "l", borrow, default)]
+    pub low: &'a str, // 24h low
+    #[serde(rename = "P", borrow, default)]
+    pub price_change_percent: &'a str, // 24h change, percent
+    #[serde(rename = "E")]
+    pub event_time: i64,
+}
+
+impl TickerEvent<'_> {
+    pub fn price(&self) -> Result<f64, ParseFloatError> {
+        self.close.parse()
+    }
+
+    pub fn volume_total(&self) -> Result<f64, ParseFloatError> {
+        self.volume.parse()
+    }
+
+    pub fn quote_volume_total(&self) -> Result<f64, ParseFloatError> {
+        self.quote_volume.parse()
+    }
+
+    // 24h context is informational: missing or malformed values are None, not an error
+    pub fn day_high(&self) -> Option<f64> {
+        self.high.parse().ok()
+    }
+
+    pub fn day_low(&self) -> Option<f64> {
+        self.low.parse().ok()
+    }
+
+    pub fn day_change_percent(&self) -> Option<f64> {
+        self.price_change_percent.parse().ok()
+    }
+}
+
+struct ForEach<F>(F);
+
+impl<'de, F> DeserializeSeed<'de> for ForEach<F>
+where
+    F: FnMut(TickerEvent<'de>),
+{
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for ForEach<F>
+where
+    F: FnMut(TickerEvent<'de>),
+{
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of ticker events")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(event) = seq.next_element::<TickerEvent<'de>>()? {
+            (self.0)(event);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Calls `f` for every event in a `!ticker@arr` payload, returning how many were seen.
+pub fn for_each_ticker<'a, F>(text: &'a str, f: F) -> Result<usize, serde_json::Error>
+where
+    F: FnMut(TickerEvent<'a>),
+{
+    let mut de = serde_json::Deserializer::from_str(text);
+    let count = ForEach(f).deserialize(&mut de)?;
+    de.end()?;
+    Ok(count)
+}
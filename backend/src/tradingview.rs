@@ -0,0 +1,198 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use serde::Deserialize;
+use tokio::sync::broadcast;
+use tracing::info;
+use utoipa::ToSchema;
+use crate::exchange::Exchange;
+use crate::history::HistoryManager;
+use crate::metrics::METRICS;
+use crate::model::{ActiveSignal, Interval, MarketData, SymbolState};
+use crate::scanner::{Signal, SignalType, WsMessage};
+use crate::store::SharedState;
+
+// TradingView alerts as a second signal source. A Pine strategy's alert POSTs its message to
+// /api/tradingview; the message is the JSON below, usually filled from placeholders:
+//
+//   {"passphrase": "...", "ticker": "{{exchange}}:{{ticker}}", "action": "{{strategy.order.action}}",
+//    "price": {{close}}, "interval": "{{interval}}", "strategy": "My Pine strategy"}
+//
+// The alert becomes a `Signal` (source "tradingview") on the symbol's latest candle, goes
+// through the same verification as the scanner's, and is recorded and broadcast like them.
+// TradingView can't set headers, so the shared secret rides in the body.
+
+pub const PASSPHRASE_SECRET: &str = "TRADINGVIEW_PASSPHRASE";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TradingViewError {
+    #[error("unknown action '{0}' (expected buy, sell, long or short)")]
+    Action(String),
+    #[error("invalid ticker '{0}'")]
+    Ticker(String),
+    #[error("price must be a positive number")]
+    Price,
+    #[error("invalid time '{0}' (expected RFC 3339 or epoch milliseconds)")]
+    Time(String),
+}
+
+/// The alert message a Pine script sends.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TradingViewAlert {
+    // Must match TRADINGVIEW_PASSPHRASE
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    // "BINANCE:BTCUSDT.P", "BYBIT:ETHUSDT.P", "BTCUSDT"; ".P" marks a perpetual
+    pub ticker: String,
+    // buy / long or sell / short
+    pub action: String,
+    pub price: f64,
+    // TradingView's interval ("1", "5", "15"); anything else is taken as 1m
+    #[serde(default)]
+    pub interval: Option<String>,
+    #[serde(default)]
+    pub volume: Option<f64>,
+    // When the alert fired, RFC 3339 ({{timenow}}) or epoch ms; defaults to arrival
+    #[serde(default)]
+    pub time: Option<serde_json::Value>,
+    // Pine strategy or alert name, shown as the signal's strategy
+    #[serde(default)]
+    pub strategy: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Whether the alert carries the configured passphrase. None when no passphrase is set
+/// (the endpoint is then off).
+pub fn authorized(alert: &TradingViewAlert) -> Option<bool> {
+    let expected = crate::secrets::get(PASSPHRASE_SECRET)?;
+    Some(alert.passphrase.as_deref().is_some_and(|p| expected.matches(p)))
+}
+
+/// The store key for a TradingView ticker: Binance perpetuals stay bare, Binance without
+/// ".P" is spot, Bybit gets its prefix.
+fn symbol(ticker: &str) -> Result<String, TradingViewError> {
+    let (exchange, name) = match ticker.trim().split_once(':') {
+        Some((exchange, name)) => (Some(exchange), name),
+        None => (None, ticker.trim()),
+    };
+    let (name, perpetual) = match name.strip_suffix(".P") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    let name = name.to_ascii_uppercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(TradingViewError::Ticker(ticker.to_string()));
+    }
+    let exchange = match exchange.map(str::to_ascii_uppercase).as_deref() {
+        None => Exchange::Binance,
+        Some("BINANCE") if perpetual => Exchange::Binance,
+        Some("BINANCE") => Exchange::BinanceSpot,
+        Some("BYBIT") => Exchange::Bybit,
+        Some(_) => return Err(TradingViewError::Ticker(ticker.to_string())),
+    };
+    Ok(exchange.tag(&name))
+}
+
+fn signal_type(action: &str) -> Result<SignalType, TradingViewError> {
+    match action.trim().to_ascii_lowercase().as_str() {
+        "buy" | "long" => Ok(SignalType::Long),
+        "sell" | "short" => Ok(SignalType::Short),
+        _ => Err(TradingViewError::Action(action.to_string())),
+    }
+}
+
+fn interval(interval: Option<&str>) -> Interval {
+    match interval.map(str::trim) {
+        Some("5") | Some("5m") => Interval::M5,
+        Some("15") | Some("15m") => Interval::M15,
+        _ => Interval::M1,
+    }
+}
+
+fn timestamp(time: Option<&serde_json::Value>) -> Result<i64, TradingViewError> {
+    match time {
+        None | Some(serde_json::Value::Null) => Ok(chrono::Utc::now().timestamp_millis()),
+        Some(serde_json::Value::Number(ms)) => ms.as_i64().ok_or_else(|| TradingViewError::Time(ms.to_string())),
+        Some(serde_json::Value::String(text)) => chrono::DateTime::parse_from_rfc3339(text)
+            .map(|t| t.timestamp_millis())
+            .or_else(|_| text.parse())
+            .map_err(|_| TradingViewError::Time(text.clone())),
+        Some(other) => Err(TradingViewError::Time(other.to_string())),
+    }
+}
+
+/// Maps an alert to an unverified signal. A symbol the scanner tracks lends its latest
+/// candle and context; any other gets a candle made of the alert alone.
+pub fn to_signal(alert: &TradingViewAlert, store: &SharedState) -> Result<Signal, TradingViewError> {
+    let symbol = symbol(&alert.ticker)?;
+    let signal_type = signal_type(&alert.action)?;
+    if !(alert.price.is_finite() && alert.price > 0.0) {
+        return Err(TradingViewError::Price);
+    }
+    let timestamp = timestamp(alert.time.as_ref())?;
+    let timeframe = interval(alert.interval.as_deref());
+    let strategy = alert.strategy.as_deref().map(str::trim).filter(|s| !s.is_empty()).unwrap_or("TradingView");
+    let comment = alert.comment.clone().filter(|c| !c.trim().is_empty());
+
+    let bare = MarketData {
+        symbol: symbol.clone(),
+        open: alert.price,
+        high: alert.price,
+        low: alert.price,
+        close: alert.price,
+        volume: alert.volume.unwrap_or(0.0),
+        quote_volume: alert.volume.unwrap_or(0.0) * alert.price,
+        trades: 0,
+        taker_buy_volume: None,
+        taker_buy_quote_volume: None,
+        timestamp,
+    };
+    let signal = match store.get(&symbol) {
+        Some(state) => {
+            let candle = match state.window.back() {
+                Some(last) => MarketData {
+                    high: last.high.max(alert.price),
+                    low: last.low.min(alert.price),
+                    close: alert.price,
+                    timestamp,
+                    ..last.clone()
+                },
+                None => bare,
+            };
+            crate::scanner::tradingview_signal(&state, &candle, signal_type, timeframe, strategy, comment)
+        }
+        None => {
+            let state = SymbolState::new(symbol);
+            crate::scanner::tradingview_signal(&state, &bare, signal_type, timeframe, strategy, comment)
+        }
+    };
+    Ok(signal)
+}
+
+/// Verifies `signal` and, unless strict verification drops it, records and broadcasts it and
+/// makes it the symbol's live signal (for updates and invalidation) if the scanner tracks it.
+/// Returns whether it was published.
+pub async fn publish(mut signal: Signal, store: &SharedState, history: &Arc<HistoryManager>, tx: &broadcast::Sender<WsMessage>) -> bool {
+    if !crate::verifier::verify_signal(&mut signal).await {
+        METRICS.pipeline.signals_rejected.fetch_add(1, Ordering::Relaxed);
+        info!(confidence = ?signal.confidence, "TradingView signal rejected by verification");
+        if crate::verifier::record_rejected() {
+            history.add_rejected(signal).await;
+        }
+        return false;
+    }
+    if let Some(mut state) = store.get_mut(&signal.symbol) {
+        state.active_signal = Some(ActiveSignal {
+            id: signal.id.clone(),
+            trace_id: signal.trace_id.clone(),
+            signal_type: signal.signal_type.clone(),
+            entry_price: signal.price,
+            invalidated: false,
+            score: signal.score.map(|s| s.total),
+        });
+    }
+    info!(strategy = %signal.strategy_name, "TradingView signal published");
+    crate::actor::emit_signal(history, tx, signal).await;
+    true
+}
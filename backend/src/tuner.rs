@@ -0,0 +1,246 @@
+use crate::config::SpikeTrigger;
+use crate::history::{HistoryManager, SignalRecord};
+use crate::scanner::ReasonCode;
+use crate::store::SharedState;
+use crate::system_event::EventLevel;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use tokio::time::{sleep, Duration};
+use tracing::info;
+
+// Adapts the Silent Watcher's spike ratios to how its signals turned out. Every
+// TUNER_INTERVAL_SECS (default 3600) the last TUNER_LOOKBACK_DAYS (default 14) of resolved
+// signals are read from history and split by tier, the scanner's own: dead coins (average
+// value under `dead_avg_value`, judged by `dead_spike_ratio`) and active ones (`spike_ratio`).
+// Within each tier the ratio goes up in steps of a quarter of the configured one, while the
+// signals at or above it win less than TUNER_MIN_WIN_RATE percent (default 40), no further
+// than TUNER_MAX_FACTOR (default 2) times the configured ratio and only while at least
+// TUNER_MIN_SIGNALS (default 20) signals are left to judge by. It comes back down the same
+// way once they do better. A symbol that, on its own, wins less than that over at least
+// TUNER_MIN_SIGNALS signals needs a spike TUNER_SYMBOL_FACTOR (default 1.5) times the size
+// on top. Changes are logged with the numbers behind them and raised as a `tuner` system
+// event until everything is back where it was configured. The admin API still sets the
+// configured ratios (a tuned value it overwrites becomes the new base). Local to each
+// instance, on its own history. Off unless TUNER_ENABLED=true.
+
+// Resolved: past the outcome tracking window
+const RESOLVED_MS: i64 = 60 * 60 * 1000;
+// Ratio thresholds tried, in steps of this fraction of the configured one
+const STEP: f64 = 0.25;
+
+pub fn enabled() -> bool {
+    std::env::var("TUNER_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+fn env<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn interval_secs() -> u64 {
+    env("TUNER_INTERVAL_SECS", 60 * 60u64).max(60)
+}
+
+fn lookback_ms() -> i64 {
+    env("TUNER_LOOKBACK_DAYS", 14i64).max(1) * 24 * 60 * 60 * 1000
+}
+
+fn min_signals() -> usize {
+    env("TUNER_MIN_SIGNALS", 20usize).max(1)
+}
+
+fn min_win_rate() -> f64 {
+    env("TUNER_MIN_WIN_RATE", 40.0)
+}
+
+fn max_factor() -> f64 {
+    env("TUNER_MAX_FACTOR", 2.0f64).max(1.0)
+}
+
+fn symbol_factor() -> f64 {
+    env("TUNER_SYMBOL_FACTOR", 1.5f64).clamp(1.0, max_factor())
+}
+
+// Symbol -> factor its ratios (and z-scores) are tightened by, see `ScannerConfig::tightened`
+static SYMBOLS: LazyLock<RwLock<HashMap<String, f64>>> = LazyLock::new(Default::default);
+
+/// How much bigger a spike `symbol` needs for its poor record; None for most.
+pub fn symbol_factor_for(symbol: &str) -> Option<f64> {
+    SYMBOLS.read().unwrap_or_else(|e| e.into_inner()).get(symbol).copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tier {
+    Dead,
+    Active,
+}
+
+impl Tier {
+    fn name(self) -> &'static str {
+        match self {
+            Tier::Dead => "dead_spike_ratio",
+            Tier::Active => "spike_ratio",
+        }
+    }
+}
+
+// A tier's ratio as configured and as last set here, so a change from elsewhere is noticed
+#[derive(Debug, Clone, Copy)]
+struct Tuned {
+    base: f64,
+    applied: f64,
+}
+
+#[derive(Default)]
+struct State {
+    dead: Option<Tuned>,
+    active: Option<Tuned>,
+}
+
+static STATE: LazyLock<Mutex<State>> = LazyLock::new(Default::default);
+
+// One resolved Silent Watcher signal: the ratio it fired at, its tier, whether it won
+struct Sample {
+    symbol: String,
+    ratio: f64,
+    tier: Tier,
+    success: bool,
+}
+
+fn sample(record: &SignalRecord, dead_avg_value: f64) -> Option<Sample> {
+    if record.signal.strategy_name != crate::strategy::SILENT_WATCHER {
+        return None;
+    }
+    record.signal.reasons.iter().find_map(|reason| match reason {
+        ReasonCode::VolumeSpike { ratio, avg_value, .. } => Some(Sample {
+            symbol: record.signal.symbol.clone(),
+            ratio: *ratio,
+            tier: if *avg_value < dead_avg_value { Tier::Dead } else { Tier::Active },
+            success: record.outcome.success,
+        }),
+        _ => None,
+    })
+}
+
+fn win_rate(samples: &[&Sample]) -> f64 {
+    samples.iter().filter(|s| s.success).count() as f64 / samples.len().max(1) as f64 * 100.0
+}
+
+// The tier's ratio for `samples`, and why
+fn tune(samples: &[&Sample], base: f64) -> (f64, String) {
+    let (min_signals, min_win_rate, max_factor) = (min_signals(), min_win_rate(), max_factor());
+    let mut steps = Vec::new();
+    let mut ratio = base;
+    loop {
+        let above: Vec<&Sample> = samples.iter().copied().filter(|s| s.ratio >= ratio).collect();
+        if above.len() < min_signals {
+            steps.push(format!("≥{:.2}: {} signals, too few", ratio, above.len()));
+            break;
+        }
+        let rate = win_rate(&above);
+        steps.push(format!("≥{:.2}: {:.0}% of {}", ratio, rate, above.len()));
+        let next = ratio + base * STEP;
+        if rate >= min_win_rate || next > base * max_factor + 1e-9 {
+            break;
+        }
+        ratio = next;
+    }
+    (ratio, steps.join(", "))
+}
+
+// Symbols winning less than the minimum on their own
+fn poor_symbols(samples: &[Sample]) -> BTreeMap<String, (usize, f64)> {
+    let mut by_symbol: BTreeMap<&str, Vec<&Sample>> = BTreeMap::new();
+    for sample in samples {
+        by_symbol.entry(&sample.symbol).or_default().push(sample);
+    }
+    by_symbol
+        .into_iter()
+        .filter(|(_, s)| s.len() >= min_signals())
+        .map(|(symbol, s)| (symbol.to_string(), (s.len(), win_rate(&s))))
+        .filter(|(_, (_, rate))| *rate < min_win_rate())
+        .collect()
+}
+
+// Tunes `tier` from `live` (the ratio in force); Some((new ratio, rationale)) if it changes
+fn retune(tuned: &mut Option<Tuned>, tier: Tier, live: f64, samples: &[Sample]) -> Option<(f64, String)> {
+    let current = match *tuned {
+        Some(t) if t.applied == live => t,
+        // First run, or set anew since (startup config, admin API)
+        _ => Tuned { base: live, applied: live },
+    };
+    let in_tier: Vec<&Sample> = samples.iter().filter(|s| s.tier == tier).collect();
+    let (ratio, rationale) = tune(&in_tier, current.base);
+    *tuned = Some(Tuned { base: current.base, applied: ratio });
+    (ratio != live).then(|| {
+        info!(threshold = tier.name(), from = live, to = ratio, configured = current.base, rationale = %rationale, "Tuned scanner threshold");
+        (ratio, format!("{} {:.2} → {:.2} ({})", tier.name(), live, ratio, rationale))
+    })
+}
+
+async fn run(store: &SharedState, history: &HistoryManager) {
+    let Some(mut config) = store.strategies.scanner_config() else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let records = history.between(now - lookback_ms(), now - RESOLVED_MS).await;
+    let samples: Vec<Sample> = records.iter().filter_map(|r| sample(r, config.dead_avg_value)).collect();
+
+    let mut changes = Vec::new();
+    // With the z-score trigger the ratios aren't what fires; symbols are still tightened
+    let tuned_away = config.trigger == SpikeTrigger::Ratio && {
+        let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+        let State { dead, active } = &mut *state;
+        if let Some((ratio, why)) = retune(active, Tier::Active, config.spike_ratio, &samples) {
+            config.spike_ratio = ratio;
+            changes.push(why);
+        }
+        if let Some((ratio, why)) = retune(dead, Tier::Dead, config.dead_spike_ratio, &samples) {
+            config.dead_spike_ratio = ratio;
+            changes.push(why);
+        }
+        [*active, *dead].iter().flatten().any(|t| t.applied != t.base)
+    };
+    if !changes.is_empty() {
+        store.strategies.set_scanner_config(config);
+    }
+
+    let poor = poor_symbols(&samples);
+    let factor = symbol_factor();
+    {
+        let mut symbols = SYMBOLS.write().unwrap_or_else(|e| e.into_inner());
+        let added: Vec<&String> = poor.keys().filter(|s| !symbols.contains_key(*s)).collect();
+        let removed: Vec<String> = symbols.keys().filter(|s| !poor.contains_key(*s)).cloned().collect();
+        for symbol in &added {
+            let (count, rate) = poor[*symbol];
+            info!(symbol = %symbol, signals = count, win_rate = rate, factor, "Tightened thresholds for a symbol that does poorly");
+            changes.push(format!("{} ×{:.2} ({:.0}% of {})", symbol, factor, rate, count));
+        }
+        for symbol in &removed {
+            info!(symbol = %symbol, "Symbol back to the common thresholds");
+            changes.push(format!("{} back to normal", symbol));
+        }
+        *symbols = poor.keys().map(|s| (s.clone(), factor)).collect();
+    }
+
+    if changes.is_empty() {
+        return;
+    }
+    if tuned_away || !poor.is_empty() {
+        let message = format!("Scanner thresholds tuned from {} signals: {}", samples.len(), changes.join("; "));
+        crate::system_event::raise("tuner", EventLevel::Info, message);
+    } else {
+        crate::system_event::recover("tuner", "Scanner thresholds back to the configured ones");
+    }
+}
+
+/// Retunes the scanner on a timer; idles unless TUNER_ENABLED=true.
+pub async fn tuner_task(store: SharedState, history: Arc<HistoryManager>) {
+    if !enabled() {
+        return std::future::pending().await;
+    }
+    info!(interval_secs = interval_secs(), "Threshold tuner enabled");
+    loop {
+        run(&store, &history).await;
+        sleep(Duration::from_secs(interval_secs())).await;
+    }
+}
@@ -0,0 +1,119 @@
+use crate::metrics::METRICS;
+use crate::store::SharedState;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+// The Binance futures contracts worth scanning, kept in step with exchangeInfo. Every
+// `EXCHANGE_INFO_REFRESH_SECS` (default 3600) the contract list and its metadata are reloaded
+// (`exchange_info::refresh`); only contracts with status TRADING count. One that stops
+// trading (SETTLING ahead of a delisting, or gone from the list) is purged: its state, candle
+// builder and counters go, and so do its streams (aggTrade here; depth and footprint follow
+// the store on their next rescan). The feeds skip it from then on, and the kline feed
+// resubscribes to the new set, picking up listings on the way. Spot and Bybit symbols, and
+// everything before the first load, pass unchecked; so does everything in a replay, whose
+// symbols needn't be listed today.
+
+static TRADING: LazyLock<RwLock<Option<HashSet<String>>>> = LazyLock::new(Default::default);
+// Bumped whenever the set changes, so feeds can drop what they cached about it
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static CHANGED: Notify = Notify::const_new();
+
+fn refresh_interval_secs() -> u64 {
+    std::env::var("EXCHANGE_INFO_REFRESH_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60 * 60)
+}
+
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Resolves once the set has changed since `since` (a `generation()`).
+pub async fn changed(since: u64) {
+    loop {
+        let notified = CHANGED.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if generation() != since {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Whether `symbol` (a store key) may be scanned: a Binance futures contract must be TRADING
+/// in the latest exchangeInfo.
+pub fn tradable(symbol: &str) -> bool {
+    if crate::exchange::split(symbol).0 != crate::exchange::Exchange::Binance {
+        return true;
+    }
+    TRADING.read().unwrap_or_else(|e| e.into_inner()).as_ref().is_none_or(|trading| trading.contains(symbol))
+}
+
+/// Drops everything kept about `symbol`.
+pub fn purge(store: &SharedState, symbol: &str) {
+    crate::store::remove_symbol(store, symbol);
+    crate::order_flow::forget(symbol);
+}
+
+// Installs the new set; returns the contracts that stopped trading and the new ones
+fn update(trading: HashSet<String>) -> (Vec<String>, Vec<String>) {
+    let mut current = TRADING.write().unwrap_or_else(|e| e.into_inner());
+    let (mut delisted, mut listed) = match current.as_ref() {
+        Some(previous) => (
+            previous.difference(&trading).cloned().collect::<Vec<_>>(),
+            trading.difference(previous).cloned().collect::<Vec<_>>(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+    delisted.sort();
+    listed.sort();
+    let first = current.is_none();
+    *current = Some(trading);
+    if first || !delisted.is_empty() || !listed.is_empty() {
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+        CHANGED.notify_waiters();
+    }
+    (delisted, listed)
+}
+
+// Installs the set and purges what stopped trading, plus symbols restored from a snapshot
+// that no longer trade
+fn apply(store: &SharedState, trading: HashSet<String>) {
+    let (delisted, listed) = update(trading);
+    let stale = store.iter().filter(|e| !tradable(e.key())).map(|e| e.key().clone()).collect::<Vec<_>>();
+    let purged: BTreeSet<String> = delisted.into_iter().chain(stale).collect();
+    for symbol in &purged {
+        purge(store, symbol);
+    }
+    METRICS.symbols_delisted.fetch_add(purged.len() as u64, Ordering::Relaxed);
+    if !purged.is_empty() {
+        info!(symbols = ?purged, "Contracts stopped trading, purged");
+    }
+    if !listed.is_empty() {
+        info!(symbols = ?listed, "Contracts started trading");
+    }
+}
+
+/// Reloads exchangeInfo on a timer and, unless `replay`, purges contracts that stopped trading.
+pub async fn universe_task(store: SharedState, replay: bool) {
+    loop {
+        let wait = match crate::exchange_info::refresh(&store).await {
+            Ok(count) => {
+                let trading = crate::exchange_info::trading();
+                info!(symbols = count, trading = trading.len(), "Loaded symbol metadata from exchangeInfo");
+                if !replay {
+                    apply(&store, trading);
+                }
+                refresh_interval_secs()
+            }
+            Err(e) => {
+                METRICS.record_error("exchange_info");
+                warn!(error = %e, "Failed to fetch exchangeInfo");
+                60
+            }
+        };
+        tokio::time::sleep(tokio::time::Duration::from_secs(wait)).await;
+    }
+}
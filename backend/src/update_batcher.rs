@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+use crate::scanner::{SignalUpdate, WsMessage};
+use crate::shard::{forward_updates, SHARD};
+
+// Latest pending Update per symbol. Actors overwrite, the batcher drains.
+// Coalescing by symbol means a symbol ticking 10x per cadence still costs one entry.
+pub type PendingUpdates = Arc<DashMap<String, SignalUpdate>>;
+
+pub fn init_pending_updates() -> PendingUpdates {
+    Arc::new(DashMap::new())
+}
+
+fn batch_interval_ms() -> u64 {
+    std::env::var("UPDATE_BATCH_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(2000)
+}
+
+/// Emits all pending live updates as a single `WsMessage::Updates` every `UPDATE_BATCH_MS`
+/// instead of one broadcast per symbol.
+pub async fn update_batcher_task(pending: PendingUpdates, tx: broadcast::Sender<WsMessage>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(batch_interval_ms()));
+    loop {
+        interval.tick().await;
+
+        let symbols: Vec<String> = pending.iter().map(|e| e.key().clone()).collect();
+        let batch: Vec<SignalUpdate> = symbols
+            .iter()
+            .filter_map(|symbol| pending.remove(symbol).map(|(_, update)| update))
+            .collect();
+
+        if batch.is_empty() {
+            continue;
+        }
+        match &SHARD.aggregator_url {
+            Some(url) => forward_updates(url, &batch).await,
+            None => {
+                let _ = tx.send(WsMessage::Updates(batch));
+            }
+        }
+    }
+}
@@ -1,90 +1,264 @@
-use crate::scanner::{Signal, SignalType};
-use reqwest::Client;
-use serde::Deserialize;
-use log::{info, warn};
-
-#[derive(Debug, Deserialize)]
-struct Depth {
-    bids: Vec<[String; 2]>,
-    asks: Vec<[String; 2]>,
+use crate::depth_tracker::BookImbalance;
+use crate::error::TeebError;
+use crate::exchange::ClientError;
+use crate::metrics::METRICS;
+use crate::scanner::{ReasonCode, Signal, SignalType, STRONG_WALL_RATIO};
+use crate::system_event;
+use std::time::Instant;
+use tracing::{info, warn};
+
+// Levels per side the walls are summed over
+const DEPTH_LIMIT: usize = 20;
+
+// Confidence starts neutral; each check moves it by up to its weight either way
+const BASE_CONFIDENCE: f64 = 50.0;
+const BOOK_WEIGHT: f64 = 20.0; // Full at a strong wall (or the opposite's) on the signal's side
+const OI_WEIGHT: f64 = 15.0; // Full at OI_FULL_PCT of open interest change
+const OI_FULL_PCT: f64 = 2.0;
+const FUNDING_WEIGHT: f64 = 15.0; // Extreme funding, crowded (-) or squeeze (+)
+const TAKER_WEIGHT: f64 = 10.0; // Full at POSITIONING_FULL_RATIO of taker volume the signal's way
+const TOP_TRADER_WEIGHT: f64 = 5.0; // Same for the top traders' positions
+const POSITIONING_FULL_RATIO: f64 = 1.5;
+const PREMIUM_WEIGHT: f64 = 5.0; // Full at PREMIUM_FULL_PCT of mark over index, the signal's way
+const PREMIUM_FULL_PCT: f64 = 0.1;
+
+/// `strict` drops signals below `VERIFY_MIN_CONFIDENCE`; the default (`soft`) only annotates.
+pub fn strict() -> bool {
+    std::env::var("VERIFY_MODE").is_ok_and(|v| v.eq_ignore_ascii_case("strict"))
+}
+
+pub fn min_confidence() -> f64 {
+    std::env::var("VERIFY_MIN_CONFIDENCE").ok().and_then(|v| v.parse().ok()).unwrap_or(50.0)
 }
 
-// Open Interest Response
-#[derive(Debug, Deserialize)]
-struct OpenInterest {
-    symbol: String,
-    openInterest: String,
-    time: i64,
+/// Whether signals dropped in strict mode are kept in history's `rejected` table.
+pub fn record_rejected() -> bool {
+    std::env::var("VERIFY_RECORD_REJECTED").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
 }
 
+/// Checks the order book, open interest, funding and trader positioning, annotating the signal
+/// with each and scoring them into `signal.confidence`. Returns whether to publish it: always
+/// in soft mode, in strict mode only at `VERIFY_MIN_CONFIDENCE` or above. A short pressure
+/// signal also needs its sell wall, and a breakout no sell wall over it and no falling open
+/// interest, in either mode.
 pub async fn verify_signal(signal: &mut Signal) -> bool {
-    let client = Client::new();
-    
-    // 1. Check Order Book Depth
-    // API: https://fapi.binance.com/fapi/v1/depth?symbol=BTCUSDT&limit=20
-    let depth_url = format!("https://fapi.binance.com/fapi/v1/depth?symbol={}&limit=20", signal.symbol);
-    
-    match client.get(&depth_url).send().await {
-        Ok(resp) => {
-            if let Ok(depth) = resp.json::<Depth>().await {
-                let bid_wall = calculate_wall(depth.bids);
-                let ask_wall = calculate_wall(depth.asks);
-                
-                info!("Order Book for {}: Bid Wall: {:.2}, Ask Wall: {:.2}", signal.symbol, bid_wall, ask_wall);
-                
-                match signal.signal_type {
-                    SignalType::Long => {
-                        let ratio = if ask_wall > 0.0 { bid_wall / ask_wall } else { 0.0 };
-                        if ratio > 1.2 {
-                            signal.reason += &format!(" | Strong Buy Wall (x{:.1})", ratio);
-                        } else {
-                             signal.reason += &format!(" | Moderate Wall (x{:.1})", ratio);
-                        }
-                    },
-                    SignalType::Short => {
-                         let ratio = if bid_wall > 0.0 { ask_wall / bid_wall } else { 0.0 };
-                         if ratio > 1.2 {
-                            signal.reason += &format!(" | Strong Sell Wall (x{:.1})", ratio);
-                        } else {
-                            signal.reason += &format!(" | Moderate Wall (x{:.1})", ratio);
-                        }
-                    }
+    let started = Instant::now();
+    let mut confidence = BASE_CONFIDENCE;
+    // Ask vs bid wall, for Shorts whose book could be checked; bid vs ask for the others
+    let mut sell_wall = None;
+    let mut buy_wall = None;
+
+    // Each check degrades independently: a failed fetch skips that annotation (and leaves
+    // the confidence where it was), never the signal.
+
+    // 1. Check Order Book Depth, on the signal's own exchange
+    match crate::exchange::depth(&signal.symbol, DEPTH_LIMIT).await {
+        Ok(depth) => {
+            book_checks_working();
+            let bid_wall = calculate_wall(depth.bids);
+            let ask_wall = calculate_wall(depth.asks);
+
+            info!(symbol = %signal.symbol, signal_id = %signal.id, bid_wall, ask_wall, "Order book walls");
+
+            let ratio = match signal.signal_type {
+                SignalType::Long | SignalType::NewListing | SignalType::Breakout => {
+                    let ratio = if ask_wall > 0.0 { bid_wall / ask_wall } else { 0.0 };
+                    signal.add_reason(ReasonCode::BuyWall { ratio });
+                    buy_wall = Some(ratio);
+                    ratio
+                },
+                SignalType::Short => {
+                    let ratio = if bid_wall > 0.0 { ask_wall / bid_wall } else { 0.0 };
+                    signal.add_reason(ReasonCode::SellWall { ratio });
+                    sell_wall = Some(ratio);
+                    ratio
                 }
+            };
+            // Log scale: x1.2 and x1/1.2 are equally far from an even book. An empty side
+            // (ratio 0) says nothing either way.
+            if ratio > 0.0 {
+                confidence += (ratio.ln() / STRONG_WALL_RATIO.ln()).clamp(-1.0, 1.0) * BOOK_WEIGHT;
             }
-        },
-        Err(e) => warn!("Failed to fetch depth: {:?}", e),
+        }
+        Err(e) => {
+            METRICS.record_error("verifier");
+            warn!(symbol = %signal.symbol, signal_id = %signal.id, error = %e, "Failed to fetch depth");
+            book_checks_failing(e);
+        }
     }
 
     // 2. Check Open Interest
-    let oi_url = format!("https://fapi.binance.com/fapi/v1/openInterest?symbol={}", signal.symbol);
-    match client.get(&oi_url).send().await {
-        Ok(resp) => {
-            if let Ok(oi_data) = resp.json::<OpenInterest>().await {
-                if let Ok(oi_val) = oi_data.openInterest.parse::<f64>() {
-                    let oi_in_usdt = oi_val * signal.price;
-                     signal.reason += &format!(" | OI: ${:.1}M", oi_in_usdt / 1_000_000.0);
-                     info!("Open Interest for {}: ${:.2}M", signal.symbol, oi_in_usdt / 1_000_000.0);
-                }
-            }
-        },
-        Err(e) => warn!("Failed to fetch OI: {:?}", e),
+    match crate::exchange::open_interest(&signal.symbol).await {
+        Ok(oi_val) => {
+            let oi_in_usdt = oi_val * signal.price;
+            signal.add_reason(ReasonCode::OpenInterest { value: oi_in_usdt });
+            info!(symbol = %signal.symbol, signal_id = %signal.id, oi_usdt_m = oi_in_usdt / 1_000_000.0, "Open interest");
+        }
+        // Spot has no open interest to check
+        Err(ClientError::Unsupported { .. }) => {}
+        Err(e) => {
+            METRICS.record_error("verifier");
+            warn!(symbol = %signal.symbol, signal_id = %signal.id, error = %e, "Failed to fetch OI");
+        }
     }
     
-    // 3. Net Inflow (Mock/Placeholder for now)
+    // OI direction over the longest span tracked (rising: new positions behind the move)
+    if let Some(change) = signal.oi_change {
+        let (minutes, change_pct) = match (change.m60, change.m15) {
+            (Some(m60), _) => (60, m60),
+            (None, Some(m15)) => (15, m15),
+            (None, None) => (5, change.m5),
+        };
+        signal.add_reason(ReasonCode::OiChange { minutes, change_pct });
+        confidence += (change_pct / OI_FULL_PCT).clamp(-1.0, 1.0) * OI_WEIGHT;
+    }
+
+    // 3. Funding, from the cache (Binance perps only)
+    if let Some(funding) = crate::funding::get(&signal.symbol) {
+        let rate_pct = funding.rate * 100.0;
+        let extreme = rate_pct.abs() > crate::funding::extreme_pct();
+        // Positive funding: longs pay, so a Long joins the crowded side
+        let against = match signal.signal_type {
+            SignalType::Long | SignalType::NewListing | SignalType::Breakout => rate_pct > 0.0,
+            SignalType::Short => rate_pct < 0.0,
+        };
+        signal.add_reason(ReasonCode::Funding { rate_pct, extreme, against: extreme && against });
+        if extreme {
+            confidence += if against { -FUNDING_WEIGHT } else { FUNDING_WEIGHT };
+        }
+        info!(symbol = %signal.symbol, signal_id = %signal.id, funding_rate_pct = rate_pct, extreme, "Funding");
+    }
+
+    // 4. How futures traders are positioned (Binance perps only): taker flow, top traders and
+    // the perp's premium each count for the side they lean to
+    match crate::positioning::get(&signal.symbol).await {
+        Ok(positioning) => {
+            let side = match signal.signal_type {
+                SignalType::Long | SignalType::NewListing | SignalType::Breakout => 1.0,
+                SignalType::Short => -1.0,
+            };
+            let lean = |ratio: Option<f64>| ratio.filter(|r| *r > 0.0).map_or(0.0, |r| (r.ln() / POSITIONING_FULL_RATIO.ln()).clamp(-1.0, 1.0));
+            confidence += side * lean(positioning.taker_buy_sell_ratio) * TAKER_WEIGHT;
+            confidence += side * lean(positioning.top_long_short_ratio) * TOP_TRADER_WEIGHT;
+            if let Some(premium) = positioning.premium_pct {
+                confidence += side * (premium / PREMIUM_FULL_PCT).clamp(-1.0, 1.0) * PREMIUM_WEIGHT;
+            }
+            signal.add_reason(ReasonCode::Positioning {
+                top_long_short_ratio: positioning.top_long_short_ratio,
+                taker_buy_sell_ratio: positioning.taker_buy_sell_ratio,
+                premium_pct: positioning.premium_pct,
+            });
+            info!(symbol = %signal.symbol, signal_id = %signal.id, positioning = ?positioning, "Trader positioning");
+        }
+        Err(ClientError::Unsupported { .. }) => {}
+        Err(e) => {
+            METRICS.record_error("verifier");
+            warn!(symbol = %signal.symbol, signal_id = %signal.id, error = %e, "Failed to fetch trader positioning");
+        }
+    }
+
+    // 5. Net Inflow (Mock/Placeholder for now)
     // Real implementation would check Exchange Inflow API.
     // We add a "Whale Alert" tag if conditions meet.
-    if signal.volume * signal.price > 5_000_000.0 {
-         signal.reason += " | 🐋 Whale Active";
+    let traded_value = signal.volume * signal.price;
+    if traded_value > 5_000_000.0 {
+        signal.add_reason(ReasonCode::WhaleActive { value: traded_value });
+    }
+
+    let confidence = confidence.clamp(0.0, 100.0);
+    signal.confidence = Some(confidence);
+    crate::score::rescore(signal);
+    // Short pressure is only a signal with heavy asks; no book to check is no confirmation
+    let required_wall = signal.reasons.iter().find_map(|r| match r {
+        ReasonCode::ShortPressure { min_wall_ratio, .. } => Some(*min_wall_ratio),
+        _ => None,
+    });
+    let confirmed = required_wall.is_none_or(|min| sell_wall.is_some_and(|ratio| ratio >= min)) && breakout_holds(signal, buy_wall);
+    let passed = confirmed && (!strict() || confidence >= min_confidence());
+
+    let indicators = signal.indicators.unwrap_or_default();
+    info!(
+        symbol = %signal.symbol,
+        signal_id = %signal.id,
+        rsi = ?indicators.rsi,
+        atr = ?indicators.atr,
+        confidence,
+        score = ?signal.score.map(|s| s.total),
+        confirmed,
+        passed,
+        latency_ms = started.elapsed().as_millis() as u64,
+        "Signal verified"
+    );
+
+    passed
+}
+
+// A breakout needs room to run: no strong sell wall right over it, and open interest not
+// falling over the last 5 minutes (shorts covering, which is done once they are out). Either
+// one unknown doesn't count against it.
+fn breakout_holds(signal: &Signal, buy_wall: Option<f64>) -> bool {
+    if !matches!(signal.signal_type, SignalType::Breakout) {
+        return true;
     }
+    let walled = buy_wall.is_some_and(|ratio| ratio > 0.0 && ratio < 1.0 / STRONG_WALL_RATIO);
+    let covering = signal.oi_change.is_some_and(|change| change.m5 < 0.0);
+    if walled || covering {
+        info!(symbol = %signal.symbol, signal_id = %signal.id, walled, covering, "Breakout not confirmed");
+    }
+    !walled && !covering
+}
+
+fn reverify_wall_ratio() -> f64 {
+    std::env::var("REVERIFY_WALL_RATIO").ok().and_then(|v| v.parse().ok()).unwrap_or(1.5)
+}
+
+/// Re-checks a published signal's order book. Returns why the premise no longer holds if
+/// the opposite side's wall now outweighs the signal's side by `REVERIFY_WALL_RATIO`.
+/// `tracked` is the streamed book's window (see `depth_tracker`), judged on its average so a
+/// wall flashed for the check can't decide it; without it the book is fetched once.
+/// A failed fetch is not a failed verification: the signal stands.
+pub async fn reverify(signal: &Signal, tracked: Option<BookImbalance>) -> Option<String> {
+    let bid_ask = match tracked {
+        Some(imbalance) => imbalance.avg_ratio,
+        None => match crate::exchange::depth(&signal.symbol, DEPTH_LIMIT).await {
+            Ok(depth) => {
+                book_checks_working();
+                calculate_wall(depth.bids) / calculate_wall(depth.asks)
+            }
+            Err(e) => {
+                METRICS.record_error("verifier");
+                warn!(symbol = %signal.symbol, signal_id = %signal.id, error = %e, "Failed to fetch depth for re-verification");
+                book_checks_failing(e);
+                return None;
+            }
+        },
+    };
+    let (against, side) = match signal.signal_type {
+        SignalType::Long | SignalType::NewListing | SignalType::Breakout => (1.0 / bid_ask, "Sell"),
+        SignalType::Short => (bid_ask, "Buy"),
+    };
+    // An empty book on the signal's side gives inf (flipped), an empty book NaN (not flipped)
+    (against > reverify_wall_ratio()).then(|| format!("{} wall took over on re-verification (x{:.1})", side, against))
+}
+
+// Signals still go out without the book check, so that's degraded mode rather than a failure
+fn book_checks_failing(error: ClientError) {
+    let error = TeebError::from(error);
+    system_event::raise("verifier", error.level(), format!("Order book checks failing: {}", error));
+}
 
-    true 
+fn book_checks_working() {
+    system_event::recover("verifier", "Order book checks working again");
 }
 
-fn calculate_wall(orders: Vec<[String; 2]>) -> f64 {
+// Malformed levels are skipped (and counted) rather than treated as zero size
+pub(crate) fn calculate_wall(orders: Vec<[String; 2]>) -> f64 {
     let mut sum = 0.0;
     for order in orders {
-        let qty: f64 = order[1].parse().unwrap_or(0.0);
-        sum += qty;
+        match order[1].parse::<f64>() {
+            Ok(qty) => sum += qty,
+            Err(_) => METRICS.record_error("verifier"),
+        }
     }
     sum
 }
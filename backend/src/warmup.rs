@@ -0,0 +1,54 @@
+use std::sync::LazyLock;
+use serde::Serialize;
+use ts_rs::TS;
+use utoipa::ToSchema;
+use crate::store::SharedState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmupMode {
+    Suppress, // Drop signals from half-filled windows
+    Flag,     // Emit them, marked low-confidence
+}
+
+#[derive(Debug, Clone)]
+pub struct WarmupConfig {
+    pub min_candles: usize,
+    pub mode: WarmupMode,
+    pub ready_ratio: f64,
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+// Read once; these don't change at runtime.
+pub static WARMUP: LazyLock<WarmupConfig> = LazyLock::new(|| WarmupConfig {
+    // Averages over fewer candles than this are too noisy to signal on
+    min_candles: env_or("WARMUP_MIN_CANDLES", 30),
+    mode: match std::env::var("WARMUP_MODE").as_deref() {
+        Ok("flag") => WarmupMode::Flag,
+        _ => WarmupMode::Suppress,
+    },
+    // Share of tracked symbols that must be warm before /ready reports ready
+    ready_ratio: env_or("WARMUP_READY_RATIO", 0.8),
+});
+
+pub fn is_warm(window_len: usize) -> bool {
+    window_len >= WARMUP.min_candles
+}
+
+#[derive(Debug, Clone, Serialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Readiness {
+    pub ready: bool,
+    pub warm_symbols: usize,
+    pub total_symbols: usize,
+    pub min_candles: usize,
+}
+
+pub fn readiness(store: &SharedState) -> Readiness {
+    let total_symbols = store.len();
+    let warm_symbols = store.iter().filter(|e| is_warm(e.value().window.len())).count();
+    let ready = total_symbols > 0 && warm_symbols as f64 >= total_symbols as f64 * WARMUP.ready_ratio;
+    Readiness { ready, warm_symbols, total_symbols, min_candles: WARMUP.min_candles }
+}
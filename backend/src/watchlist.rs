@@ -0,0 +1,387 @@
+use crate::metrics::METRICS;
+use crate::model::MarketData;
+use crate::scanner::WsMessage;
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::{Arc, LazyLock};
+use tokio::sync::{broadcast, Notify};
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WatchlistError {
+    #[error("watchlist io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("watchlist (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("invalid rule: {0}")]
+    Invalid(String),
+}
+
+pub fn watchlist_path() -> String {
+    std::env::var("WATCHLIST_PATH").unwrap_or_else(|_| "watchlist.json".to_string())
+}
+
+fn cooldown_ms() -> i64 {
+    std::env::var("WATCH_ALERT_COOLDOWN_SECS").ok().and_then(|v| v.parse::<i64>().ok()).unwrap_or(300) * 1000
+}
+
+fn oi_poll_secs() -> u64 {
+    std::env::var("WATCH_OI_POLL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60)
+}
+
+static BOOST: LazyLock<f64> = LazyLock::new(|| {
+    std::env::var("WATCHLIST_BOOST").ok().and_then(|v| v.parse().ok()).filter(|f: &f64| *f > 0.0 && *f <= 1.0).unwrap_or(0.6)
+});
+
+/// How much more sensitive the strategies are on watched symbols: thresholds are scaled by
+/// this (`WATCHLIST_BOOST`, 0..1, default 0.6), see `ScannerConfig::boosted`.
+pub fn boost() -> f64 {
+    *BOOST
+}
+
+/// What a watch rule fires on.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(tag = "kind", rename_all_fields = "camelCase")]
+pub enum WatchCondition {
+    // Current minute's volume vs the symbol's 1m average
+    VolumeRatioAbove { ratio: f64 },
+    // Price crosses the level in either direction
+    PriceCrosses { level: f64 },
+    // Open interest moved more than `pct` percent (either way) since the rule last fired
+    OiChangeAbove { pct: f64 },
+}
+
+impl WatchCondition {
+    fn validate(&self) -> Result<(), WatchlistError> {
+        let (name, value) = match self {
+            WatchCondition::VolumeRatioAbove { ratio } => ("ratio", *ratio),
+            WatchCondition::PriceCrosses { level } => ("level", *level),
+            WatchCondition::OiChangeAbove { pct } => ("pct", *pct),
+        };
+        if value.is_finite() && value > 0.0 {
+            Ok(())
+        } else {
+            Err(WatchlistError::Invalid(format!("{} must be a positive number", name)))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchRule {
+    pub id: String,
+    pub symbol: String,
+    pub condition: WatchCondition,
+    pub label: Option<String>,
+    pub created_at: i64,
+    pub last_triggered: Option<i64>,
+    // Open interest the next OI change is measured from
+    #[serde(default)]
+    pub oi_baseline: Option<f64>,
+    // Last price seen, for detecting crossings
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schema(ignore)]
+    last_price: Option<f64>,
+}
+
+/// Body of `POST /api/watchlist`.
+#[derive(Debug, Clone, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWatchRule {
+    pub symbol: String,
+    pub condition: WatchCondition,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A watch rule fired. Sent separately from scanner signals.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchAlert {
+    pub rule_id: String,
+    pub symbol: String,
+    pub label: Option<String>,
+    pub message: String,
+    pub price: f64,
+    pub timestamp: i64,
+}
+
+// `WATCHLIST_PATH`: the watched symbols and the rules. Files from before watched symbols
+// are a bare array of rules.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WatchlistFile {
+    #[serde(default)]
+    symbols: Vec<String>,
+    rules: Vec<WatchRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StoredWatchlist {
+    Current(WatchlistFile),
+    RulesOnly(Vec<WatchRule>),
+}
+
+/// User-defined watch rules and watched symbols, persisted to `WATCHLIST_PATH`.
+///
+/// Rules are grouped by symbol so the per-tick check is a single map miss for symbols
+/// nobody watches. Watched symbols are tracked closely while the scanner covers the rest:
+/// boosted thresholds and no cooldown (see `strategy::Registry::evaluate`), and live updates
+/// even without an active signal.
+pub struct Watchlist {
+    rules: DashMap<String, Vec<WatchRule>>,
+    symbols: DashSet<String>,
+    file_path: String,
+    dirty: Notify,
+}
+
+impl Watchlist {
+    pub fn new(file_path: &str) -> Self {
+        let file = match Self::load(file_path) {
+            Ok(file) => file,
+            Err(WatchlistError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => WatchlistFile::default(),
+            Err(e) => {
+                // Same as history: keep an unreadable file aside rather than overwrite it
+                METRICS.record_error("watchlist");
+                let backup = format!("{}.corrupt-{}", file_path, chrono::Utc::now().timestamp());
+                error!(path = file_path, backup = %backup, error = %e, "Unreadable watchlist file, starting empty");
+                if let Err(e) = fs::rename(file_path, &backup) {
+                    error!(path = file_path, error = %e, "Failed to back up unreadable watchlist file");
+                }
+                WatchlistFile::default()
+            }
+        };
+        let map: DashMap<String, Vec<WatchRule>> = DashMap::new();
+        for rule in file.rules {
+            map.entry(rule.symbol.clone()).or_default().push(rule);
+        }
+        let symbols = file.symbols.into_iter().collect();
+        Self { rules: map, symbols, file_path: file_path.to_string(), dirty: Notify::new() }
+    }
+
+    fn load(file_path: &str) -> Result<WatchlistFile, WatchlistError> {
+        let data = fs::read_to_string(file_path)?;
+        Ok(match serde_json::from_str(&data)? {
+            StoredWatchlist::Current(file) => file,
+            StoredWatchlist::RulesOnly(rules) => WatchlistFile { symbols: Vec::new(), rules },
+        })
+    }
+
+    /// The watched symbols, sorted.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.symbols.iter().map(|s| s.clone()).collect();
+        symbols.sort();
+        symbols
+    }
+
+    pub fn is_watched(&self, symbol: &str) -> bool {
+        self.symbols.contains(symbol)
+    }
+
+    /// Adds `symbol` to the watched symbols. False if it already was.
+    pub fn watch(&self, symbol: &str) -> Result<bool, WatchlistError> {
+        let symbol = symbol.trim().to_uppercase();
+        if symbol.is_empty() {
+            return Err(WatchlistError::Invalid("symbol is required".to_string()));
+        }
+        let added = self.symbols.insert(symbol.clone());
+        if added {
+            self.dirty.notify_one();
+            info!(symbol = %symbol, "Symbol watched");
+        }
+        Ok(added)
+    }
+
+    /// Takes `symbol` off the watched symbols. False if it wasn't on them.
+    pub fn unwatch(&self, symbol: &str) -> bool {
+        let symbol = symbol.trim().to_uppercase();
+        let removed = self.symbols.remove(&symbol).is_some();
+        if removed {
+            self.dirty.notify_one();
+            info!(symbol = %symbol, "Symbol unwatched");
+        }
+        removed
+    }
+
+    pub fn list(&self) -> Vec<WatchRule> {
+        let mut rules: Vec<WatchRule> = self.rules.iter().flat_map(|e| e.value().clone()).collect();
+        rules.sort_by_key(|r| r.created_at);
+        rules
+    }
+
+    pub fn add(&self, new: NewWatchRule) -> Result<WatchRule, WatchlistError> {
+        new.condition.validate()?;
+        let symbol = new.symbol.trim().to_uppercase();
+        if symbol.is_empty() {
+            return Err(WatchlistError::Invalid("symbol is required".to_string()));
+        }
+        let rule = WatchRule {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol: symbol.clone(),
+            condition: new.condition,
+            label: new.label,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            last_triggered: None,
+            last_price: None,
+            oi_baseline: None,
+        };
+        self.rules.entry(symbol).or_default().push(rule.clone());
+        self.dirty.notify_one();
+        info!(id = %rule.id, symbol = %rule.symbol, condition = ?rule.condition, "Watch rule added");
+        Ok(rule)
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        let mut removed = false;
+        self.rules.retain(|_, rules| {
+            let before = rules.len();
+            rules.retain(|r| r.id != id);
+            removed |= rules.len() != before;
+            !rules.is_empty()
+        });
+        if removed {
+            self.dirty.notify_one();
+        }
+        removed
+    }
+
+    /// Evaluates the symbol's price and volume rules against the candle so far.
+    pub fn check(&self, symbol: &str, candle: &MarketData, avg_volume: f64) -> Vec<WatchAlert> {
+        let Some(mut rules) = self.rules.get_mut(symbol) else {
+            return Vec::new();
+        };
+        let now = candle.timestamp;
+        let price = candle.close;
+        let mut alerts = Vec::new();
+        for rule in rules.iter_mut() {
+            let previous = rule.last_price.replace(price);
+            if rule.last_triggered.is_some_and(|t| now - t < cooldown_ms()) {
+                continue;
+            }
+            let message = match rule.condition {
+                WatchCondition::VolumeRatioAbove { ratio } => {
+                    let current = if avg_volume > 0.0 { candle.volume / avg_volume } else { 0.0 };
+                    (current > ratio).then(|| format!("Volume {:.1}x average (rule: > {}x)", current, ratio))
+                }
+                WatchCondition::PriceCrosses { level } => previous
+                    .filter(|prev| (*prev < level && price >= level) || (*prev > level && price <= level))
+                    .map(|prev| format!("Price crossed {} {}", if prev < level { "above" } else { "below" }, level)),
+                WatchCondition::OiChangeAbove { .. } => None, // Polled in `oi_task`
+            };
+            if let Some(message) = message {
+                rule.last_triggered = Some(now);
+                alerts.push(WatchAlert {
+                    rule_id: rule.id.clone(),
+                    symbol: symbol.to_string(),
+                    label: rule.label.clone(),
+                    message,
+                    price,
+                    timestamp: now,
+                });
+            }
+        }
+        if !alerts.is_empty() {
+            self.dirty.notify_one();
+        }
+        alerts
+    }
+
+    fn oi_symbols(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter(|e| e.value().iter().any(|r| matches!(r.condition, WatchCondition::OiChangeAbove { .. })))
+            .map(|e| e.key().clone())
+            .collect()
+    }
+
+    fn check_oi(&self, symbol: &str, open_interest: f64, now: i64) -> Vec<WatchAlert> {
+        let Some(mut rules) = self.rules.get_mut(symbol) else {
+            return Vec::new();
+        };
+        let mut alerts = Vec::new();
+        let mut changed = false;
+        for rule in rules.iter_mut() {
+            let WatchCondition::OiChangeAbove { pct } = rule.condition else {
+                continue;
+            };
+            let Some(baseline) = rule.oi_baseline.filter(|b| *b > 0.0) else {
+                rule.oi_baseline = Some(open_interest);
+                changed = true;
+                continue;
+            };
+            let change = (open_interest - baseline) / baseline * 100.0;
+            if change.abs() > pct && rule.last_triggered.is_none_or(|t| now - t >= cooldown_ms()) {
+                rule.last_triggered = Some(now);
+                rule.oi_baseline = Some(open_interest);
+                changed = true;
+                alerts.push(WatchAlert {
+                    rule_id: rule.id.clone(),
+                    symbol: symbol.to_string(),
+                    label: rule.label.clone(),
+                    message: format!("Open interest {:+.2}% (rule: > {}%)", change, pct),
+                    price: rule.last_price.unwrap_or_default(),
+                    timestamp: now,
+                });
+            }
+        }
+        if changed {
+            self.dirty.notify_one();
+        }
+        alerts
+    }
+
+    pub async fn save(&self) {
+        let file = WatchlistFile { symbols: self.symbols(), rules: self.list() };
+        let json = match serde_json::to_string_pretty(&file) {
+            Ok(json) => json,
+            Err(e) => {
+                METRICS.record_error("watchlist");
+                warn!(path = %self.file_path, error = %e, "Failed to serialize watchlist");
+                return;
+            }
+        };
+        let path = self.file_path.clone();
+        let result = tokio::task::spawn_blocking(move || fs::write(&path, json)).await;
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        METRICS.record_error("watchlist");
+        warn!(path = %self.file_path, error = %error, "Failed to save watchlist");
+    }
+}
+
+/// Persists the watchlist whenever rules or watched symbols were added, removed or fired.
+pub async fn persist_task(watchlist: Arc<Watchlist>) {
+    loop {
+        watchlist.dirty.notified().await;
+        watchlist.save().await;
+    }
+}
+
+/// Polls open interest for symbols with OI rules every `WATCH_OI_POLL_SECS`.
+pub async fn oi_task(watchlist: Arc<Watchlist>, tx: broadcast::Sender<WsMessage>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(oi_poll_secs().max(1)));
+    loop {
+        interval.tick().await;
+        for symbol in watchlist.oi_symbols() {
+            match crate::exchange::open_interest(&symbol).await {
+                Ok(oi) => {
+                    let now = chrono::Utc::now().timestamp_millis();
+                    for alert in watchlist.check_oi(&symbol, oi, now) {
+                        let _ = tx.send(WsMessage::WatchAlert(alert));
+                    }
+                }
+                Err(e) => {
+                    METRICS.record_error("watchlist");
+                    warn!(symbol = %symbol, error = %e, "Failed to fetch open interest for watch rule");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Forwards published signals, and users' price alerts marked `notify`, to user-configured HTTP
+// endpoints (n8n, custom bots), signed with HMAC-SHA256. Deliveries that fail are kept in an on-disk queue and retried with
+// exponential backoff, so a receiver that is briefly down still gets every signal.
+use crate::metrics::METRICS;
+use crate::scanner::WsMessage;
+use crate::wire::versioned;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::{broadcast, Notify};
+use tokio::time::Duration;
+use tracing::{error, info, warn};
+
+pub const SIGNING_SECRET: &str = "WEBHOOK_SIGNING_KEY";
+pub const SIGNATURE_HEADER: &str = "x-teeb-signature";
+pub const TIMESTAMP_HEADER: &str = "x-teeb-timestamp";
+const BASE_BACKOFF_MS: i64 = 5_000;
+const MAX_BACKOFF_MS: i64 = 60 * 60 * 1000;
+
+/// Receivers from the comma-separated `WEBHOOK_URLS`.
+pub fn webhook_urls() -> Vec<String> {
+    std::env::var("WEBHOOK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect()
+}
+
+pub fn webhook_queue_path() -> String {
+    std::env::var("WEBHOOK_QUEUE_PATH").unwrap_or_else(|_| "webhook_queue.json".to_string())
+}
+
+// Attempts per delivery before it is dropped; with the backoff cap that is roughly half a day
+fn max_attempts() -> u32 {
+    std::env::var("WEBHOOK_MAX_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(15)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("webhook queue io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("webhook queue (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("receiver returned {0}")]
+    Status(reqwest::StatusCode),
+}
+
+/// One message for one receiver, until it is accepted or runs out of attempts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: String,
+    pub url: String,
+    pub body: String, // Exactly what is signed and sent
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+    pub created_at: i64,
+}
+
+impl Delivery {
+    fn backoff_ms(&self) -> i64 {
+        (BASE_BACKOFF_MS << self.attempts.saturating_sub(1).min(20)).min(MAX_BACKOFF_MS)
+    }
+}
+
+/// Deliveries not yet accepted, persisted to `WEBHOOK_QUEUE_PATH`.
+pub struct WebhookQueue {
+    pending: Mutex<Vec<Delivery>>,
+    file_path: String,
+    dirty: Notify,
+}
+
+impl WebhookQueue {
+    pub fn new(file_path: &str) -> Self {
+        let pending = match Self::load(file_path) {
+            Ok(pending) => pending,
+            Err(WebhookError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                METRICS.record_error("webhook");
+                let backup = format!("{}.corrupt-{}", file_path, chrono::Utc::now().timestamp());
+                error!(path = file_path, backup = %backup, error = %e, "Unreadable webhook queue, starting empty");
+                if let Err(e) = fs::rename(file_path, &backup) {
+                    error!(path = file_path, error = %e, "Failed to back up unreadable webhook queue");
+                }
+                Vec::new()
+            }
+        };
+        if !pending.is_empty() {
+            info!(path = file_path, deliveries = pending.len(), "Restored pending webhook deliveries");
+        }
+        Self { pending: Mutex::new(pending), file_path: file_path.to_string(), dirty: Notify::new() }
+    }
+
+    fn load(file_path: &str) -> Result<Vec<Delivery>, WebhookError> {
+        let data = fs::read_to_string(file_path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or(0)
+    }
+
+    fn push(&self, delivery: Delivery) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.push(delivery);
+        }
+        self.dirty.notify_one();
+    }
+
+    // Takes out every delivery that is due; they go back in via `push` if they fail again
+    fn take_due(&self, now: i64) -> Vec<Delivery> {
+        let Ok(mut pending) = self.pending.lock() else {
+            return Vec::new();
+        };
+        let (due, waiting) = pending.drain(..).partition(|d| d.next_attempt_at <= now);
+        *pending = waiting;
+        due
+    }
+
+    pub async fn save(&self) {
+        let snapshot = self.pending.lock().map(|p| p.clone()).unwrap_or_default();
+        let json = match serde_json::to_string_pretty(&snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                METRICS.record_error("webhook");
+                warn!(path = %self.file_path, error = %e, "Failed to serialize webhook queue");
+                return;
+            }
+        };
+        let path = self.file_path.clone();
+        let result = tokio::task::spawn_blocking(move || fs::write(&path, json)).await;
+        let error = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        METRICS.record_error("webhook");
+        warn!(path = %self.file_path, error = %error, "Failed to save webhook queue");
+    }
+}
+
+/// Persists the queue whenever deliveries were added or retried.
+pub async fn persist_task(queue: Arc<WebhookQueue>) {
+    loop {
+        queue.dirty.notified().await;
+        queue.save().await;
+    }
+}
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default()
+});
+
+/// `sha256=<hex>` over `<timestamp>.<body>` with `WEBHOOK_SIGNING_KEY`. Receivers recompute it
+/// and compare, and can reject stale timestamps to stop replays.
+pub fn signature(key: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    format!("sha256={}", digest.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+async fn post(delivery: &Delivery) -> Result<(), WebhookError> {
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let mut request = CLIENT
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .body(delivery.body.clone());
+    if let Some(key) = crate::secrets::get(SIGNING_SECRET) {
+        request = request.header(SIGNATURE_HEADER, signature(key.expose(), timestamp, &delivery.body));
+    }
+    let status = request.send().await?.status();
+    if !status.is_success() {
+        return Err(WebhookError::Status(status));
+    }
+    Ok(())
+}
+
+// Client errors other than timeouts and rate limits mean the receiver rejects this body for
+// good, so retrying would only fill the queue
+fn permanent(e: &WebhookError) -> bool {
+    matches!(e, WebhookError::Status(s) if s.is_client_error()
+        && *s != reqwest::StatusCode::REQUEST_TIMEOUT
+        && *s != reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+async fn attempt(queue: &WebhookQueue, mut delivery: Delivery) {
+    delivery.attempts += 1;
+    let error = match post(&delivery).await {
+        Ok(()) => {
+            if delivery.attempts > 1 {
+                info!(url = %delivery.url, id = %delivery.id, attempts = delivery.attempts, "Webhook delivered after retry");
+            }
+            return;
+        }
+        Err(e) => e,
+    };
+    METRICS.record_error("webhook");
+    if permanent(&error) || delivery.attempts >= max_attempts() {
+        error!(url = %delivery.url, id = %delivery.id, attempts = delivery.attempts, error = %error, "Dropping webhook delivery");
+        return;
+    }
+    delivery.next_attempt_at = chrono::Utc::now().timestamp_millis() + delivery.backoff_ms();
+    warn!(url = %delivery.url, id = %delivery.id, attempts = delivery.attempts, retry_in_ms = delivery.backoff_ms(), error = %error, "Webhook delivery failed, queued for retry");
+    queue.push(delivery);
+}
+
+/// Sends each published signal and `notify` alert (the same `{"schemaVersion", "type":
+/// "Signal" | "AlertTriggered", "payload"}` JSON as the WebSocket) to every `WEBHOOK_URLS`
+/// receiver, and retries queued deliveries once a second. Like the chat notifiers it only sees what this instance broadcasts.
+pub async fn webhook_task(queue: Arc<WebhookQueue>, tx: broadcast::Sender<WsMessage>) {
+    let urls = webhook_urls();
+    if urls.is_empty() && queue.len() == 0 {
+        return std::future::pending().await;
+    }
+    info!(receivers = urls.len(), queued = queue.len(), "Webhook dispatcher started");
+    let mut rx = tx.subscribe();
+    let mut retry = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            received = rx.recv() => match received {
+                Ok(message @ (WsMessage::Signal(_) | WsMessage::AlertTriggered(_))) => {
+                    let id = match &message {
+                        WsMessage::Signal(signal) if !signal.relayed => signal.id.clone(),
+                        WsMessage::AlertTriggered(alert) if alert.notify => alert.id.clone(),
+                        _ => continue,
+                    };
+                    let body = match serde_json::to_string(&versioned(message)) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            METRICS.record_error("webhook");
+                            warn!(error = %e, "Failed to serialize message for webhooks");
+                            continue;
+                        }
+                    };
+                    let now = chrono::Utc::now().timestamp_millis();
+                    for url in &urls {
+                        let delivery = Delivery { id: id.clone(), url: url.clone(), body: body.clone(), attempts: 0, next_attempt_at: now, created_at: now };
+                        attempt(&queue, delivery).await;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    METRICS.record_error("webhook");
+                    warn!(skipped, "Webhook dispatcher lagged behind the broadcast channel");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = retry.tick() => {
+                let due = queue.take_due(chrono::Utc::now().timestamp_millis());
+                if !due.is_empty() {
+                    queue.dirty.notify_one();
+                }
+                for delivery in due {
+                    attempt(&queue, delivery).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// Outbound JSON schema (WebSocket messages and REST responses).
+//
+// Conventions, for every outbound type:
+// - Field names are camelCase (`#[serde(rename_all = "camelCase")]`). Types that are also
+//   read back (Signal, SignalUpdate: history file, shard ingest) accept the old snake_case
+//   names as aliases.
+// - Enum values (`Long`/`Short`, task states, WS message `type`) are PascalCase variant names.
+// - Every top-level message carries `schemaVersion`. Adding optional fields does not bump it;
+//   renaming/removing fields or changing their meaning does.
+// - Timestamps are Unix milliseconds, prices and volumes plain JSON numbers.
+//
+// WebSocket: `{"schemaVersion": 2, "type": ..., "payload": ...}` with type one of Signal, Updates,
+//            History, Stats, Invalidate, Leaderboard, MarketOverview, MarketAlert, WatchAlert,
+//            AlertTriggered, Alerts, Feedback, Carry, MarketBias, SignalState, SignalClosed,
+//            Positions, Portfolio, ConfigChanged, Lagged, SystemEvent, WatchedSymbols, Candle.
+//            Clients may send `{"type": "Feedback", "payload": {"signalId": ..., "kind": ...}}`,
+//            `{"type": "AddAlert", "payload": NewPriceAlert}`, `{"type": "RemoveAlert", "payload":
+//            {"id": ...}}` or `{"type": "Watch" | "Unwatch", "payload": {"symbol": ...}}` (`ClientCommand`), and `{"action": "subscribe" | "unsubscribe", "symbols": [...],
+//            "types": [...]}` (`SubscriptionRequest`) to filter what they get, or `{"action":
+//            "stream_candles", "symbol": ...}` for a symbol's live candle; anything else
+//            they send is ignored. With auth configured, `/ws?token=...` or a first message
+//            `{"action": "auth", "token": ...}` (`AuthRequest`); otherwise closed with 1008.
+//            Feedback needs an admin token, `{"type": "Admin", "payload": AdminCommand}` a
+//            configured one (not just an open server).
+// REST:      `/metrics`, `/health`, `/ready`, `/api/heatmap`, `/api/carry`, `/api/klines`, `/api/klines/{symbol}`,
+//            `/api/symbol/{symbol}`,
+//            `/api/funding/{symbol}`, `/api/portfolio`, `/api/signals`, `/api/stats`, `/api/outcomes/{id}` return their object
+//            with `schemaVersion` added; `/metrics/symbols` returns `{"schemaVersion": 2, "symbols": {SYMBOL: counters}}`,
+//            `/api/watchlist`, `/api/watchlist/symbols`, `/api/alerts`, `/api/symbols` and `/api/funding` return
+//            `{"schemaVersion": 2, "rules" | "alerts" | "symbols" | "rates": [...]}`.
+//            `/api/admin/config` and `POST /api/admin/commands` take `Authorization: Bearer <admin token>`.
+//
+// The frontend's TypeScript types are generated from these structs (ts-rs) with
+// `--export-types [dir]`; regenerate after changing any outbound type.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::{Config, ExportError, TS};
+use utoipa::ToSchema;
+
+// 2: the `PriceAlert` WebSocket message became `AlertTriggered`
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Inbound WebSocket messages.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "type", content = "payload")]
+pub enum ClientCommand {
+    Feedback(crate::history::FeedbackRequest),
+    AddAlert(crate::price_alert::NewPriceAlert),
+    RemoveAlert { id: String },
+    Watch { symbol: String }, // Onto the watchlist's symbols, see `watchlist`
+    Unwatch { symbol: String },
+    Admin(crate::control::AdminCommand), // Needs a configured admin token, see `auth`
+}
+
+/// First inbound WebSocket message of a client that didn't put its token in the URL; see `auth`.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum AuthRequest {
+    Auth { token: String },
+}
+
+/// Inbound WebSocket filter changes, e.g. `{"action": "subscribe", "symbols": ["BTCUSDT"],
+/// "types": ["signal"]}`; see `subscription`.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum SubscriptionRequest {
+    Subscribe {
+        #[serde(default)]
+        symbols: Vec<String>,
+        #[serde(default)]
+        types: Vec<String>,
+    },
+    Unsubscribe {
+        #[serde(default)]
+        symbols: Vec<String>,
+        #[serde(default)]
+        types: Vec<String>,
+    },
+    // `Candle` messages for one symbol's minute in progress, or none without a symbol
+    #[serde(rename = "stream_candles")]
+    StreamCandles {
+        #[serde(default)]
+        symbol: Option<String>,
+    },
+}
+
+/// Adds `schemaVersion` to an outbound object.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Versioned<T: Serialize> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub body: T,
+}
+
+pub fn versioned<T: Serialize>(body: T) -> Versioned<T> {
+    Versioned { schema_version: SCHEMA_VERSION, body }
+}
+
+// Where `--export-types` writes by default, relative to the backend directory
+pub const DEFAULT_TYPES_DIR: &str = "../frontend/src/lib/generated";
+
+/// Writes a `.ts` declaration file per outbound type (and everything they reference) into `dir`.
+/// 64-bit integers (timestamps, counters) are plain JSON numbers on the wire, so they map to `number`.
+pub fn export_types(dir: &str) -> Result<(), ExportError> {
+    let cfg = Config::new().with_out_dir(dir).with_large_int("number");
+    crate::scanner::WsMessage::export_all(&cfg)?;
+    crate::metrics::MetricsSnapshot::export_all(&cfg)?;
+    crate::metrics::SymbolCountersSnapshot::export_all(&cfg)?;
+    crate::health::HealthReport::export_all(&cfg)?;
+    crate::health::ReadyReport::export_all(&cfg)?;
+    crate::heatmap::Heatmap::export_all(&cfg)?;
+    crate::carry::CarryScan::export_all(&cfg)?;
+    crate::funding::FundingRate::export_all(&cfg)?;
+    crate::klines::Klines::export_all(&cfg)?;
+    crate::symbol_detail::SymbolDetail::export_all(&cfg)?;
+    crate::symbol_detail::SymbolSummary::export_all(&cfg)?;
+    crate::history::HistoryPage::export_all(&cfg)?;
+    crate::suppressed::SuppressedPage::export_all(&cfg)?;
+    crate::history::StatsBreakdown::export_all(&cfg)?;
+    crate::watchlist::WatchRule::export_all(&cfg)?;
+    crate::watchlist::NewWatchRule::export_all(&cfg)?;
+    crate::price_alert::NewPriceAlert::export_all(&cfg)?;
+    ClientCommand::export_all(&cfg)?;
+    SubscriptionRequest::export_all(&cfg)?;
+    AuthRequest::export_all(&cfg)?;
+    Ok(())
+}
@@ -1,56 +1,1379 @@
 use warp::Filter;
 use tokio::sync::broadcast;
 use futures_util::{StreamExt, SinkExt};
-use log::{info, error};
-use crate::scanner::WsMessage;
-use crate::history::HistoryManager;
+use tracing::{info, error, warn, Instrument};
+use crate::scanner::{Invalidation, Signal, SignalUpdate, WsMessage};
+use crate::history::{FeedbackRequest, SignalFeedback};
+use crate::shard::{SHARD, TOKEN_HEADER, TOKEN_SECRET};
+use crate::history::{HistoryManager, HistoryPage, HistoryQuery, SignalRecord, Stats, StatsBreakdown, StatsQuery};
+use crate::shutdown::Shutdown;
+use crate::supervisor::Supervisor;
+use crate::error::TeebError;
+use crate::store::SharedState;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use crate::metrics::METRICS;
+use crate::subscription::Subscription;
+use crate::encoding::Encoding;
+use crate::client_queue::ClientQueue;
+use crate::wire::{versioned, AuthRequest, ClientCommand, SubscriptionRequest, Versioned};
+use crate::auth::{Access, AuthError};
+use crate::config::Role;
+use crate::control::{AdminCommand, ConfigChange, ControlError, RuntimeConfig};
+use crate::metrics::MetricsSnapshot;
+use crate::health::{HealthReport, ReadyReport};
+use crate::tradingview::TradingViewAlert;
+use crate::portfolio::Portfolio;
+use crate::suppressed::{SuppressedPage, SuppressedQuery};
+use crate::heatmap::Heatmap;
+use crate::carry::CarryScan;
+use crate::funding::FundingRate;
+use crate::klines::{KlineParams, KlineQuery, Klines, KlinesError};
+use crate::lifecycle::StateTransition;
+use crate::symbol_detail::{SymbolDetail, SymbolSummary};
+use crate::watchlist::{NewWatchRule, WatchRule, Watchlist, WatchlistError};
+use crate::price_alert::{NewPriceAlert, PriceAlert, PriceAlertError, PriceAlerts};
+use warp::Reply;
 
-pub async fn start_ws_server(tx: broadcast::Sender<WsMessage>, history: Arc<HistoryManager>) {
-    let tx = warp::any().map(move || tx.clone());
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct SymbolsReply {
+    symbols: std::collections::BTreeMap<String, crate::metrics::SymbolCountersSnapshot>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct WatchlistReply {
+    rules: Vec<WatchRule>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct WatchedSymbolsReply {
+    symbols: Vec<String>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct PriceAlertsReply {
+    alerts: Vec<PriceAlert>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct TrackedSymbolsReply {
+    symbols: Vec<SymbolSummary>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct FundingReply {
+    rates: Vec<FundingRate>,
+}
+
+#[derive(serde::Deserialize)]
+struct WsQuery {
+    token: Option<String>,
+    encoding: Option<String>, // For clients that can't offer a subprotocol, see encoding.rs
+}
+
+// What the client brought to the upgrade
+struct Handshake {
+    token: Option<String>,
+    encoding: Encoding,
+}
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+// Clients past authentication, so shutdown can wait for their close frames to go out
+static CONNECTED: AtomicUsize = AtomicUsize::new(0);
+
+// WebSocket close code for clients dropped because the server is stopping
+const GOING_AWAY: u16 = 1001;
+// ...and for clients dropped because they fell too far behind
+const TRY_AGAIN_LATER: u16 = 1013;
+// How long a departing client's writer gets to flush its close frame
+const CLOSE_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(5);
+
+pub async fn start_ws_server(
+    tx: broadcast::Sender<WsMessage>,
+    history: Arc<HistoryManager>,
+    supervisor: Supervisor,
+    store: SharedState,
+    watchlist: Arc<Watchlist>,
+    price_alerts: Arc<PriceAlerts>,
+) -> Result<(), TeebError> {
+    let shutdown = supervisor.shutdown_signal();
+    let metrics_tx = tx.clone();
+    let ingest_tx = tx.clone();
+    let updates_tx = tx.clone();
+    let invalidate_tx = tx.clone();
+    let ingest_history = history.clone();
+    let invalidate_history = history.clone();
+    let state_tx = tx.clone();
+    let state_history = history.clone();
+    let tradingview_tx = tx.clone();
+    let tradingview_history = history.clone();
+    let tradingview_store = store.clone();
+    let detail_history = history.clone();
+    let feedback_history = history.clone();
+    let feedback_tx = tx.clone();
+    let detail_store = store.clone();
+    let symbols_store = store.clone();
+    let signals_history = history.clone();
+    let stats_history = history.clone();
+    let breakdown_history = history.clone();
+    let outcome_history = history.clone();
+    let klines_store = store.clone();
+    let admin_config_store = store.clone();
+    let admin_store = store.clone();
+    let admin_tx = tx.clone();
+    let list_watchlist = watchlist.clone();
+    let add_watchlist = watchlist.clone();
+    let detail_watchlist = watchlist.clone();
+    let symbols_watchlist = watchlist.clone();
+    let watch_watchlist = watchlist.clone();
+    let unwatch_watchlist = watchlist.clone();
+    let watch_tx = tx.clone();
+    let unwatch_tx = tx.clone();
+    let list_alerts = price_alerts.clone();
+    let add_alerts = price_alerts.clone();
+    let remove_alerts = price_alerts.clone();
+    let add_alerts_tx = tx.clone();
+    let remove_alerts_tx = tx.clone();
+    let probe_tx = tx.clone();
+    let history_probe = history.clone();
+    let tx_filter = warp::any().map(move || tx.clone());
     let history = warp::any().map(move || history.clone());
 
-    let routes = warp::path("ws")
+    let ws_store = store.clone();
+    let ws_lists = UserLists { price_alerts: price_alerts.clone(), watchlist: watchlist.clone() };
+    let ws_shutdown = shutdown.clone();
+    let ws_route = warp::path("ws")
         .and(warp::ws())
-        .and(tx)
+        .and(warp::query::<WsQuery>())
+        .and(warp::header::optional::<String>("sec-websocket-protocol"))
+        .and(tx_filter)
         .and(history)
-        .map(|ws: warp::ws::Ws, tx: broadcast::Sender<WsMessage>, history: Arc<HistoryManager>| {
-            ws.on_upgrade(move |socket| handle_client(socket, tx, history))
-        })
+        .map(
+            move |ws: warp::ws::Ws,
+                  query: WsQuery,
+                  offered: Option<String>,
+                  tx: broadcast::Sender<WsMessage>,
+                  history: Arc<HistoryManager>| {
+                let store = ws_store.clone();
+                let lists = ws_lists.clone();
+                let shutdown = ws_shutdown.clone();
+                let (encoding, protocol) = Encoding::negotiate(offered.as_deref(), query.encoding.as_deref());
+                let handshake = Handshake { token: query.token, encoding };
+                let reply = ws.on_upgrade(move |socket| handle_client(socket, handshake, tx, history, store, lists, shutdown));
+                match protocol {
+                    Some(protocol) => warp::reply::with_header(reply, "sec-websocket-protocol", protocol).into_response(),
+                    None => reply.into_response(),
+                }
+            },
+        );
+
+    let metrics_route = warp::path("metrics")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(move || metrics(&metrics_tx));
+
+    let symbol_metrics_route = warp::path!("metrics" / "symbols")
+        .and(warp::get())
+        .map(symbol_metrics);
+
+    let probe = Probe { store: store.clone(), history: history_probe, tx: probe_tx };
+    let ready_probe = probe.clone();
+    let ready_route = warp::path("ready")
+        .and(warp::get())
+        .map(move || ready(&ready_probe));
+
+    let health_route = warp::path("health")
+        .and(warp::get())
+        .map(move || health(&supervisor, &probe));
+
+    let portfolio_route = warp::path!("api" / "portfolio")
+        .and(warp::get())
+        .map(portfolio);
+
+    let heatmap_route = warp::path!("api" / "heatmap")
+        .and(warp::get())
+        .map(heatmap);
+
+    let carry_route = warp::path!("api" / "carry")
+        .and(warp::get())
+        .map(carry);
+
+    let funding_route = warp::path!("api" / "funding")
+        .and(warp::get())
+        .map(funding_rates);
+
+    let funding_symbol_route = warp::path!("api" / "funding" / String)
+        .and(warp::get())
+        .map(funding_rate);
+
+    let klines_route = warp::path!("api" / "klines")
+        .and(warp::get())
+        .and(warp::query::<KlineQuery>())
+        .then(move |query: KlineQuery| {
+            let store = klines_store.clone();
+            async move { klines(query, &store).await }
+        });
+
+    let klines_symbol_store = store.clone();
+    let klines_symbol_route = warp::path!("api" / "klines" / String)
+        .and(warp::get())
+        .and(warp::query::<KlineParams>())
+        .then(move |symbol: String, params: KlineParams| {
+            let store = klines_symbol_store.clone();
+            async move { klines_for_symbol(symbol, params, &store).await }
+        });
+
+    let symbol_route = warp::path!("api" / "symbol" / String)
+        .and(warp::get())
+        .then(move |symbol: String| {
+            let store = detail_store.clone();
+            let history = detail_history.clone();
+            let watchlist = detail_watchlist.clone();
+            async move { symbol_detail(symbol, &store, &history, &watchlist).await }
+        });
+
+    let symbols_route = warp::path!("api" / "symbols")
+        .and(warp::get())
+        .map(move || symbols(&symbols_store));
+
+    let signals_route = warp::path!("api" / "signals")
+        .and(warp::get())
+        .and(warp::query::<HistoryQuery>())
+        .then(move |query: HistoryQuery| {
+            let history = signals_history.clone();
+            async move { signals(query, &history).await }
+        });
+
+    let suppressed_route = warp::path!("api" / "suppressed")
+        .and(warp::get())
+        .and(warp::query::<SuppressedQuery>())
+        .map(|query: SuppressedQuery| suppressed(&query));
+
+    let stats_route = warp::path!("api" / "stats")
+        .and(warp::get())
+        .then(move || {
+            let history = stats_history.clone();
+            async move { stats(&history).await }
+        });
+
+    let breakdown_route = warp::path!("api" / "stats" / "breakdown")
+        .and(warp::get())
+        .and(warp::query::<StatsQuery>())
+        .then(move |query: StatsQuery| {
+            let history = breakdown_history.clone();
+            async move { stats_breakdown(query, &history).await }
+        });
+
+    let outcome_route = warp::path!("api" / "outcomes" / String)
+        .and(warp::get())
+        .then(move |id: String| {
+            let history = outcome_history.clone();
+            async move { outcome(id, &history).await }
+        });
+
+    let watchlist_route = warp::path!("api" / "watchlist")
+        .and(warp::get())
+        .map(move || list_watch_rules(&list_watchlist));
+
+    let watchlist_add_route = warp::path!("api" / "watchlist")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .map(move |auth: Option<String>, rule: NewWatchRule| add_watch_rule(auth, rule, &add_watchlist));
+
+    let watchlist_remove_route = warp::path!("api" / "watchlist" / String)
+        .and(warp::delete())
+        .and(warp::header::optional::<String>("authorization"))
+        .map(move |id: String, auth: Option<String>| remove_watch_rule(auth, id, &watchlist));
+
+    let watched_route = warp::path!("api" / "watchlist" / "symbols")
+        .and(warp::get())
+        .map(move || list_watched_symbols(&symbols_watchlist));
+
+    let watch_route = warp::path!("api" / "watchlist" / "symbols" / String)
+        .and(warp::put())
+        .and(warp::header::optional::<String>("authorization"))
+        .map(move |symbol: String, auth: Option<String>| watch_symbol(auth, symbol, &watch_watchlist, &watch_tx));
+
+    let unwatch_route = warp::path!("api" / "watchlist" / "symbols" / String)
+        .and(warp::delete())
+        .and(warp::header::optional::<String>("authorization"))
+        .map(move |symbol: String, auth: Option<String>| unwatch_symbol(auth, symbol, &unwatch_watchlist, &unwatch_tx));
+
+    let alerts_route = warp::path!("api" / "alerts")
+        .and(warp::get())
+        .map(move || list_price_alerts(&list_alerts));
+
+    let alerts_add_route = warp::path!("api" / "alerts")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .map(move |auth: Option<String>, alert: NewPriceAlert| add_price_alert(auth, alert, &add_alerts, &add_alerts_tx));
+
+    let alerts_remove_route = warp::path!("api" / "alerts" / String)
+        .and(warp::delete())
+        .and(warp::header::optional::<String>("authorization"))
+        .map(move |id: String, auth: Option<String>| remove_price_alert(auth, id, &remove_alerts, &remove_alerts_tx));
+
+    let feedback_route = warp::path!("api" / "feedback")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .then(move |auth: Option<String>, request: FeedbackRequest| {
+            let history = feedback_history.clone();
+            let tx = feedback_tx.clone();
+            async move { signal_feedback(auth, request, &history, &tx).await }
+        });
+
+    let admin_config_route = warp::path!("api" / "admin" / "config")
+        .and(warp::get())
+        .and(warp::header::optional::<String>("authorization"))
+        .map(move |auth: Option<String>| admin_config(auth, &admin_config_store));
+
+    let admin_command_route = warp::path!("api" / "admin" / "commands")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .map(move |auth: Option<String>, command: AdminCommand| admin_command(auth, command, &admin_store, &admin_tx));
+
+    // Aggregator side of sharding: workers POST their signals and live updates here
+    let ingest_signal_route = warp::path!("ingest" / "signal")
+        .and(warp::post())
+        .and(warp::header::optional::<String>(TOKEN_HEADER))
+        .and(warp::body::json())
+        .then(move |token: Option<String>, signal: Signal| {
+            let tx = ingest_tx.clone();
+            let history = ingest_history.clone();
+            async move { ingest_signal(token, signal, &history, &tx).await }
+        });
+
+    let ingest_updates_route = warp::path!("ingest" / "updates")
+        .and(warp::post())
+        .and(warp::header::optional::<String>(TOKEN_HEADER))
+        .and(warp::body::json())
+        .map(move |token: Option<String>, updates: Vec<SignalUpdate>| ingest_updates(token, updates, &updates_tx));
+
+    let ingest_invalidate_route = warp::path!("ingest" / "invalidate")
+        .and(warp::post())
+        .and(warp::header::optional::<String>(TOKEN_HEADER))
+        .and(warp::body::json())
+        .then(move |token: Option<String>, invalidation: Invalidation| {
+            let tx = invalidate_tx.clone();
+            let history = invalidate_history.clone();
+            async move { ingest_invalidation(token, invalidation, &history, &tx).await }
+        });
+
+    let ingest_state_route = warp::path!("ingest" / "state")
+        .and(warp::post())
+        .and(warp::header::optional::<String>(TOKEN_HEADER))
+        .and(warp::body::json())
+        .then(move |token: Option<String>, transition: StateTransition| {
+            let tx = state_tx.clone();
+            let history = state_history.clone();
+            async move { ingest_transition(token, transition, &history, &tx).await }
+        });
+
+    let tradingview_route = warp::path!("api" / "tradingview")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(16 * 1024))
+        // TradingView sends text/plain unless the message parses as JSON on its side
+        .and(warp::body::bytes())
+        .then(move |body: warp::hyper::body::Bytes| {
+            let store = tradingview_store.clone();
+            let history = tradingview_history.clone();
+            let tx = tradingview_tx.clone();
+            async move { tradingview_alert(&body, &store, &history, &tx).await }
+        });
+
+    let routes = ws_route
+        .or(metrics_route)
+        .or(symbol_metrics_route)
+        .or(health_route)
+        .or(ready_route)
+        .or(heatmap_route)
+        .or(portfolio_route)
+        .or(carry_route)
+        .or(funding_route)
+        .or(funding_symbol_route)
+        .or(symbol_route)
+        .or(klines_route)
+        .or(klines_symbol_route)
+        .or(symbols_route)
+        .or(signals_route)
+        .or(suppressed_route)
+        .or(stats_route)
+        .or(breakdown_route)
+        .or(outcome_route)
+        .or(watchlist_route)
+        .or(watchlist_add_route)
+        .or(watchlist_remove_route)
+        .or(watched_route)
+        .or(watch_route)
+        .or(unwatch_route)
+        .or(alerts_route)
+        .or(alerts_add_route)
+        .or(alerts_remove_route)
+        .or(feedback_route)
+        .or(admin_config_route)
+        .or(admin_command_route)
+        .or(ingest_signal_route)
+        .or(ingest_updates_route)
+        .or(ingest_invalidate_route)
+        .or(ingest_state_route)
+        .or(tradingview_route)
+        .or(crate::openapi::routes())
         .with(warp::cors().allow_any_origin());
 
-    info!("Starting WebSocket Signal Server on 0.0.0.0:3000");
-    warp::serve(routes).run(([0, 0, 0, 0], 3000)).await;
+    // A taken port is returned to the supervisor, which retries it with backoff
+    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    info!("Starting WebSocket Signal Server on {}", addr);
+    let (_, server) = warp::serve(routes)
+        .try_bind_with_graceful_shutdown(addr, async move { shutdown.wait().await })
+        .map_err(|source| TeebError::Bind { addr, source })?;
+    server.await;
+    // Upgraded sockets outlive the server; wait for them to say goodbye
+    while CONNECTED.load(Ordering::Relaxed) > 0 {
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    }
+    info!("WebSocket server stopped");
+    Ok(())
 }
 
-async fn handle_client(ws: warp::ws::WebSocket, tx: broadcast::Sender<WsMessage>, history: Arc<HistoryManager>) {
-    let (mut client_ws_tx, _) = ws.split();
-    let mut rx = tx.subscribe();
+/// Pipeline counters, store size, broadcast and shard stats.
+#[utoipa::path(get, path = "/metrics", tag = "monitoring",
+    responses((status = 200, description = "Current metrics", body = Versioned<MetricsSnapshot>)))]
+pub(crate) fn metrics(tx: &broadcast::Sender<WsMessage>) -> impl warp::Reply {
+    let mut snapshot = METRICS.snapshot();
+    snapshot.broadcast_subscribers = tx.receiver_count() as u64;
+    warp::reply::json(&versioned(snapshot))
+}
+
+/// Per-symbol ingest counters, to verify the whole market is being seen.
+#[utoipa::path(get, path = "/metrics/symbols", tag = "monitoring",
+    responses((status = 200, description = "Counters by symbol", body = Versioned<SymbolsReply>)))]
+pub(crate) fn symbol_metrics() -> impl warp::Reply {
+    warp::reply::json(&versioned(SymbolsReply { symbols: crate::metrics::symbol_counters_snapshot() }))
+}
 
-    info!("New Frontend Client Connected");
+// What `/health` and `/ready` inspect besides the supervisor
+#[derive(Clone)]
+pub(crate) struct Probe {
+    store: SharedState,
+    history: Arc<HistoryManager>,
+    tx: broadcast::Sender<WsMessage>,
+}
 
-    // Send Initial Stats
-    let stats = history.get_stats();
-    // Send as WsMessage::Stats
-    if let Ok(json) = serde_json::to_string(&WsMessage::Stats(stats)) {
-        let _ = client_ws_tx.send(warp::ws::Message::text(json)).await;
+impl Probe {
+    fn pipeline(&self) -> crate::health::PipelineStatus {
+        crate::health::pipeline(&self.store, &self.history, &self.tx)
     }
-    
-    // Send History (Last 60 mins)
-    let recent_signals = history.get_recent_signals();
-    if !recent_signals.is_empty() {
-        if let Ok(json) = serde_json::to_string(&WsMessage::History(recent_signals)) {
-            let _ = client_ws_tx.send(warp::ws::Message::text(json)).await;
+}
+
+fn probe_status(ok: bool) -> warp::http::StatusCode {
+    if ok {
+        warp::http::StatusCode::OK
+    } else {
+        warp::http::StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// Readiness probe: 503 while the scanner is warming up (most windows still filling after a
+/// deploy) or the feed has gone quiet.
+#[utoipa::path(get, path = "/ready", tag = "monitoring", responses(
+    (status = 200, description = "Enough symbols are warm and the feed is fresh", body = Versioned<ReadyReport>),
+    (status = 503, description = "Still warming up, or no feed data within the age limit", body = Versioned<ReadyReport>)))]
+pub(crate) fn ready(probe: &Probe) -> impl warp::Reply {
+    let report = crate::health::ready(&probe.store, probe.pipeline());
+    let status = probe_status(report.ready);
+    warp::reply::with_status(warp::reply::json(&versioned(report)), status)
+}
+
+/// Liveness probe: 200 when every supervised task is running and the feed delivered data
+/// within `WATCHDOG_MAX_FEED_AGE_SECS`, 503 otherwise.
+#[utoipa::path(get, path = "/health", tag = "monitoring", responses(
+    (status = 200, description = "All tasks running, feed fresh", body = Versioned<HealthReport>),
+    (status = 503, description = "A task is restarting or failed, or the feed is stale", body = Versioned<HealthReport>)))]
+pub(crate) fn health(supervisor: &Supervisor, probe: &Probe) -> impl warp::Reply {
+    let report = crate::health::health(supervisor, probe.pipeline());
+    let status = probe_status(report.healthy);
+    warp::reply::with_status(warp::reply::json(&versioned(report)), status)
+}
+
+/// The Binance Futures account as last polled, with fills tied to the signals they traded.
+#[utoipa::path(get, path = "/api/portfolio", tag = "portfolio", responses(
+    (status = 200, description = "Latest account snapshot", body = Versioned<Portfolio>),
+    (status = 404, description = "Portfolio tracking is off, or hasn't polled yet")))]
+pub(crate) fn portfolio() -> warp::reply::Response {
+    match crate::portfolio::current() {
+        Some(portfolio) => warp::reply::json(&versioned(portfolio)).into_response(),
+        None => warp::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Volume ratio and price change of every tracked symbol, recomputed every few seconds.
+#[utoipa::path(get, path = "/api/heatmap", tag = "market",
+    responses((status = 200, description = "Latest heatmap", body = Versioned<Heatmap>)))]
+pub(crate) fn heatmap() -> impl warp::Reply {
+    warp::reply::json(&versioned(&*crate::heatmap::current()))
+}
+
+/// Funding carry opportunities from the latest scan, highest annualized yield first.
+#[utoipa::path(get, path = "/api/carry", tag = "market",
+    responses((status = 200, description = "Latest carry scan", body = Versioned<CarryScan>)))]
+pub(crate) fn carry() -> impl warp::Reply {
+    warp::reply::json(&versioned(&*crate::carry::latest()))
+}
+
+/// Current funding of every Binance perp, as of the last refresh (`FUNDING_REFRESH_SECS`).
+#[utoipa::path(get, path = "/api/funding", tag = "market",
+    responses((status = 200, description = "Funding rates, by symbol", body = Versioned<FundingReply>)))]
+pub(crate) fn funding_rates() -> impl warp::Reply {
+    warp::reply::json(&versioned(FundingReply { rates: crate::funding::all() }))
+}
+
+/// Current funding of one perp.
+#[utoipa::path(get, path = "/api/funding/{symbol}", tag = "market",
+    params(("symbol" = String, Path, description = "Contract symbol, e.g. BTCUSDT (case-insensitive)")),
+    responses(
+        (status = 200, description = "Funding rate", body = Versioned<FundingRate>),
+        (status = 404, description = "Unknown symbol, or no refresh has succeeded yet")))]
+pub(crate) fn funding_rate(symbol: String) -> warp::reply::Response {
+    match crate::funding::get(&symbol.to_uppercase()) {
+        Some(rate) => warp::reply::json(&versioned(rate)).into_response(),
+        None => warp::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// What the scanner currently sees for one symbol: its window, the minute in progress,
+/// volume ratio, cooldown, and the last signal with its verification notes.
+#[utoipa::path(get, path = "/api/symbol/{symbol}", tag = "market",
+    params(("symbol" = String, Path, description = "Contract symbol, e.g. BTCUSDT (case-insensitive)")),
+    responses(
+        (status = 200, description = "Symbol detail", body = Versioned<SymbolDetail>),
+        (status = 404, description = "Symbol not tracked by this instance")))]
+pub(crate) async fn symbol_detail(
+    symbol: String,
+    store: &SharedState,
+    history: &HistoryManager,
+    watchlist: &Watchlist,
+) -> warp::reply::Response {
+    let symbol = symbol.to_uppercase();
+    match crate::symbol_detail::detail(&symbol, store, history, watchlist.is_watched(&symbol)).await {
+        Some(detail) => warp::reply::json(&versioned(detail)).into_response(),
+        None => warp::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Chart candles: from the scanner's windows for 1m/5m/15m when they cover `limit`,
+/// otherwise proxied from Binance and cached briefly.
+#[utoipa::path(get, path = "/api/klines", tag = "market", params(KlineQuery),
+    responses(
+        (status = 200, description = "Candles, oldest first", body = Versioned<Klines>),
+        (status = 400, description = "Bad symbol or unsupported interval"),
+        (status = 502, description = "Binance request failed")))]
+pub(crate) async fn klines(query: KlineQuery, store: &SharedState) -> warp::reply::Response {
+    match crate::klines::klines(store, query).await {
+        Ok(klines) => warp::reply::json(&versioned(klines)).into_response(),
+        Err(KlinesError::Invalid(reason)) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": reason })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )
+        .into_response(),
+        Err(_) => warp::http::StatusCode::BAD_GATEWAY.into_response(),
+    }
+}
+
+/// `GET /api/klines` for the symbol in the path. Windows that cover only the last part of
+/// the request get Binance's older candles in front (`source` Mixed).
+#[utoipa::path(get, path = "/api/klines/{symbol}", tag = "market",
+    params(("symbol" = String, Path, description = "Contract symbol, e.g. BTCUSDT (case-insensitive)"), KlineParams),
+    responses(
+        (status = 200, description = "Candles, oldest first", body = Versioned<Klines>),
+        (status = 400, description = "Bad symbol or unsupported interval"),
+        (status = 502, description = "Binance request failed")))]
+pub(crate) async fn klines_for_symbol(symbol: String, params: KlineParams, store: &SharedState) -> warp::reply::Response {
+    klines(params.for_symbol(symbol), store).await
+}
+
+/// Every symbol this instance tracks, with its last price and whether a signal is live.
+#[utoipa::path(get, path = "/api/symbols", tag = "market",
+    responses((status = 200, description = "Tracked symbols, by name", body = Versioned<TrackedSymbolsReply>)))]
+pub(crate) fn symbols(store: &SharedState) -> impl warp::Reply {
+    warp::reply::json(&versioned(TrackedSymbolsReply { symbols: crate::symbol_detail::summaries(store) }))
+}
+
+/// Candidates the scanner dropped on purpose (priced in: the coin already ran), newest first.
+/// In memory and per instance.
+#[utoipa::path(get, path = "/api/suppressed", tag = "signals", params(SuppressedQuery),
+    responses((status = 200, description = "Suppressed candidates", body = Versioned<SuppressedPage>)))]
+pub(crate) fn suppressed(query: &SuppressedQuery) -> impl warp::Reply {
+    warp::reply::json(&versioned(crate::suppressed::recent(query)))
+}
+
+/// Recorded signals with their outcomes, newest first, filtered by symbol and signal time.
+#[utoipa::path(get, path = "/api/signals", tag = "history", params(HistoryQuery),
+    responses(
+        (status = 200, description = "One page of history", body = Versioned<HistoryPage>),
+        (status = 400, description = "`from` not before `to`")))]
+pub(crate) async fn signals(mut query: HistoryQuery, history: &HistoryManager) -> warp::reply::Response {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "error": "from must be before to" })),
+                warp::http::StatusCode::BAD_REQUEST,
+            )
+            .into_response();
         }
     }
+    query.symbol = query.symbol.map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty());
+    warp::reply::json(&versioned(history.query(query).await)).into_response()
+}
 
-    while let Ok(msg) = rx.recv().await {
-        if let Ok(json) = serde_json::to_string(&msg) {
-            if let Err(e) = client_ws_tx.send(warp::ws::Message::text(json)).await {
-                error!("Failed to send signal to client: {:?}", e);
+/// Win rate, top gainer and feedback totals over all history, as in the WebSocket `Stats`.
+#[utoipa::path(get, path = "/api/stats", tag = "history",
+    responses((status = 200, description = "Current stats", body = Versioned<Stats>)))]
+pub(crate) async fn stats(history: &HistoryManager) -> impl warp::Reply {
+    warp::reply::json(&versioned(history.get_stats().await))
+}
+
+/// Win rate and average max gain per symbol, signal type, strategy, day or week, over
+/// signals in an optional time range.
+#[utoipa::path(get, path = "/api/stats/breakdown", tag = "history", params(StatsQuery),
+    responses(
+        (status = 200, description = "One entry per group", body = Versioned<StatsBreakdown>),
+        (status = 400, description = "`from` not before `to`")))]
+pub(crate) async fn stats_breakdown(query: StatsQuery, history: &HistoryManager) -> warp::reply::Response {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "error": "from must be before to" })),
+                warp::http::StatusCode::BAD_REQUEST,
+            )
+            .into_response();
+        }
+    }
+    warp::reply::json(&versioned(history.breakdown(query).await)).into_response()
+}
+
+/// One signal with its outcome, lifecycle, invalidation and feedback.
+#[utoipa::path(get, path = "/api/outcomes/{id}", tag = "history",
+    params(("id" = String, Path, description = "Signal id")),
+    responses(
+        (status = 200, description = "Signal record", body = Versioned<SignalRecord>),
+        (status = 404, description = "No such signal")))]
+pub(crate) async fn outcome(id: String, history: &HistoryManager) -> warp::reply::Response {
+    match history.get(&id).await {
+        Some(record) => warp::reply::json(&versioned(record)).into_response(),
+        None => warp::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Every watch rule, oldest first.
+#[utoipa::path(get, path = "/api/watchlist", tag = "watchlist",
+    responses((status = 200, description = "Watch rules", body = Versioned<WatchlistReply>)))]
+pub(crate) fn list_watch_rules(watchlist: &Watchlist) -> impl warp::Reply {
+    warp::reply::json(&versioned(WatchlistReply { rules: watchlist.list() }))
+}
+
+/// Adds a watch rule; its alerts arrive on the WebSocket as `WatchAlert`.
+#[utoipa::path(post, path = "/api/watchlist", tag = "watchlist", request_body = NewWatchRule,
+    params(("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 201, description = "Rule created", body = Versioned<WatchRule>),
+        (status = 400, description = "Missing symbol or non-positive threshold"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn add_watch_rule(auth: Option<String>, rule: NewWatchRule, watchlist: &Watchlist) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    match watchlist.add(rule) {
+        Ok(rule) => warp::reply::with_status(warp::reply::json(&versioned(rule)), warp::http::StatusCode::CREATED)
+            .into_response(),
+        Err(WatchlistError::Invalid(reason)) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": reason })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )
+        .into_response(),
+        Err(e) => {
+            error!(error = %e, "Failed to add watch rule");
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(delete, path = "/api/watchlist/{id}", tag = "watchlist",
+    params(("id" = String, Path, description = "Rule id"),
+        ("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 404, description = "No rule with that id"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn remove_watch_rule(auth: Option<String>, id: String, watchlist: &Watchlist) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    if watchlist.remove(&id) {
+        warp::http::StatusCode::NO_CONTENT.into_response()
+    } else {
+        warp::http::StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// The watched symbols: boosted thresholds, no cooldown past the minute, and live updates
+/// without an active signal.
+#[utoipa::path(get, path = "/api/watchlist/symbols", tag = "watchlist",
+    responses((status = 200, description = "Watched symbols, sorted", body = Versioned<WatchedSymbolsReply>)))]
+pub(crate) fn list_watched_symbols(watchlist: &Watchlist) -> impl warp::Reply {
+    warp::reply::json(&versioned(WatchedSymbolsReply { symbols: watchlist.symbols() }))
+}
+
+/// Watches a symbol; same as the WebSocket `Watch` command, and like it needs an admin token
+/// once tokens are configured, since it loosens the scanner. Clients get the new list as
+/// `WatchedSymbols`.
+#[utoipa::path(put, path = "/api/watchlist/symbols/{symbol}", tag = "watchlist",
+    params(("symbol" = String, Path, description = "Symbol as tracked, e.g. BTCUSDT or BYBIT:BTCUSDT"),
+        ("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 201, description = "Symbol watched"),
+        (status = 204, description = "Already watched"),
+        (status = 400, description = "Empty symbol"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn watch_symbol(
+    auth: Option<String>,
+    symbol: String,
+    watchlist: &Watchlist,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    match watchlist.watch(&symbol) {
+        Ok(true) => {
+            let _ = tx.send(WsMessage::WatchedSymbols(watchlist.symbols()));
+            warp::http::StatusCode::CREATED.into_response()
+        }
+        Ok(false) => warp::http::StatusCode::NO_CONTENT.into_response(),
+        Err(e) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e.to_string() })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )
+        .into_response(),
+    }
+}
+
+#[utoipa::path(delete, path = "/api/watchlist/symbols/{symbol}", tag = "watchlist",
+    params(("symbol" = String, Path, description = "Watched symbol"),
+        ("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 204, description = "Symbol unwatched"),
+        (status = 404, description = "Symbol wasn't watched"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn unwatch_symbol(
+    auth: Option<String>,
+    symbol: String,
+    watchlist: &Watchlist,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    if watchlist.unwatch(&symbol) {
+        let _ = tx.send(WsMessage::WatchedSymbols(watchlist.symbols()));
+        warp::http::StatusCode::NO_CONTENT.into_response()
+    } else {
+        warp::http::StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Every price alert, pending and fired, oldest first.
+#[utoipa::path(get, path = "/api/alerts", tag = "alerts",
+    responses((status = 200, description = "Price alerts", body = Versioned<PriceAlertsReply>)))]
+pub(crate) fn list_price_alerts(alerts: &PriceAlerts) -> impl warp::Reply {
+    warp::reply::json(&versioned(PriceAlertsReply { alerts: alerts.list() }))
+}
+
+/// Adds a one-shot price or volume alert; it arrives on the WebSocket as `AlertTriggered` when
+/// it fires. Clients get the new list as `Alerts`.
+#[utoipa::path(post, path = "/api/alerts", tag = "alerts", request_body = NewPriceAlert,
+    params(("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 201, description = "Alert created", body = Versioned<PriceAlert>),
+        (status = 400, description = "Missing symbol, non-positive level, or a volume alert below its level"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn add_price_alert(
+    auth: Option<String>,
+    alert: NewPriceAlert,
+    alerts: &PriceAlerts,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    match alerts.add(alert) {
+        Ok(alert) => {
+            let _ = tx.send(WsMessage::Alerts(alerts.list()));
+            warp::reply::with_status(warp::reply::json(&versioned(alert)), warp::http::StatusCode::CREATED).into_response()
+        }
+        Err(PriceAlertError::Invalid(reason)) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": reason })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )
+        .into_response(),
+        Err(e) => {
+            error!(error = %e, "Failed to add price alert");
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(delete, path = "/api/alerts/{id}", tag = "alerts",
+    params(("id" = String, Path, description = "Alert id"),
+        ("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 204, description = "Alert removed"),
+        (status = 404, description = "No alert with that id"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) fn remove_price_alert(
+    auth: Option<String>,
+    id: String,
+    alerts: &PriceAlerts,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    if alerts.remove(&id) {
+        let _ = tx.send(WsMessage::Alerts(alerts.list()));
+        warp::http::StatusCode::NO_CONTENT.into_response()
+    } else {
+        warp::http::StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Marks a signal taken/skipped/good/bad; same as the WebSocket `Feedback` command.
+#[utoipa::path(post, path = "/api/feedback", tag = "feedback", request_body = FeedbackRequest,
+    params(("authorization" = Option<String>, Header, description = "Bearer <admin token>; required once tokens are configured")),
+    responses(
+        (status = 200, description = "Feedback recorded", body = Versioned<SignalFeedback>),
+        (status = 404, description = "No signal with that id in history"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Read-only token")))]
+pub(crate) async fn signal_feedback(
+    auth: Option<String>,
+    request: FeedbackRequest,
+    history: &HistoryManager,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    if let Err(e) = crate::auth::commands(auth.as_deref()) {
+        return auth_status(&e).into_response();
+    }
+    match apply_feedback(&request, history, tx).await {
+        Some(feedback) => warp::reply::json(&versioned(feedback)).into_response(),
+        None => warp::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// 401 for a missing or unknown token, 403 for one that may not administer
+fn auth_status(e: &AuthError) -> warp::http::StatusCode {
+    match e {
+        AuthError::Forbidden => warp::http::StatusCode::FORBIDDEN,
+        AuthError::Missing | AuthError::Invalid => warp::http::StatusCode::UNAUTHORIZED,
+    }
+}
+
+/// What the admin API has changed: pause, blacklist and the Silent Watcher's thresholds.
+#[utoipa::path(get, path = "/api/admin/config", tag = "admin",
+    params(("authorization" = String, Header, description = "Bearer <admin token>")),
+    responses(
+        (status = 200, description = "Runtime config", body = Versioned<RuntimeConfig>),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Not an admin token, or no tokens configured")))]
+pub(crate) fn admin_config(auth: Option<String>, store: &SharedState) -> warp::reply::Response {
+    match crate::auth::admin(auth.as_deref()) {
+        Ok(_) => warp::reply::json(&versioned(crate::control::current(store))).into_response(),
+        Err(e) => auth_status(&e).into_response(),
+    }
+}
+
+/// Changes the running scanner; every client is told with a `ConfigChanged` message. Same as
+/// the WebSocket `Admin` command.
+#[utoipa::path(post, path = "/api/admin/commands", tag = "admin", request_body = AdminCommand,
+    params(("authorization" = String, Header, description = "Bearer <admin token>")),
+    responses(
+        (status = 200, description = "Applied", body = Versioned<ConfigChange>),
+        (status = 400, description = "Invalid threshold, or the Silent Watcher isn't enabled"),
+        (status = 401, description = "Missing or unknown token"),
+        (status = 403, description = "Not an admin token, or no tokens configured")))]
+pub(crate) fn admin_command(
+    auth: Option<String>,
+    command: AdminCommand,
+    store: &SharedState,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    let access = match crate::auth::admin(auth.as_deref()) {
+        Ok(access) => access,
+        Err(e) => {
+            warn!(error = %e, "Rejected admin command");
+            return auth_status(&e).into_response();
+        }
+    };
+    match apply_admin(&access, command, store, tx) {
+        Ok(change) => warp::reply::json(&versioned(change)).into_response(),
+        Err(e) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e.to_string() })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )
+        .into_response(),
+    }
+}
+
+// Applies an admin command and tells every client
+fn apply_admin(
+    access: &Access,
+    command: AdminCommand,
+    store: &SharedState,
+    tx: &broadcast::Sender<WsMessage>,
+) -> Result<ConfigChange, ControlError> {
+    let change = crate::control::apply(store, command, &access.name)?;
+    info!(token = %access.name, command = ?change.command, "Admin command applied");
+    let _ = tx.send(WsMessage::ConfigChanged(Box::new(change.clone())));
+    Ok(change)
+}
+
+// Records the feedback and tells every client, along with the stats it changed
+async fn apply_feedback(
+    request: &FeedbackRequest,
+    history: &HistoryManager,
+    tx: &broadcast::Sender<WsMessage>,
+) -> Option<SignalFeedback> {
+    let feedback = history.record_feedback(request).await?;
+    info!(signal_id = %request.signal_id, kind = ?request.kind, "Signal feedback recorded");
+    let _ = tx.send(WsMessage::Feedback(feedback.clone()));
+    let _ = tx.send(WsMessage::Stats(Box::new(history.get_stats().await)));
+    Some(feedback)
+}
+
+/// Aggregator only: a shard worker hands over a verified signal.
+#[utoipa::path(post, path = "/ingest/signal", tag = "sharding", request_body = Signal,
+    params(("x-shard-token" = Option<String>, Header, description = "The shared SHARD_INGEST_TOKEN; ingest is refused while it is unset")),
+    responses(
+        (status = 202, description = "Recorded and broadcast"),
+        (status = 401, description = "Missing or wrong token"),
+        (status = 404, description = "Not an aggregator")))]
+pub(crate) async fn ingest_signal(
+    token: Option<String>,
+    signal: Signal,
+    history: &Arc<HistoryManager>,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::http::StatusCode {
+    if let Err(status) = check_ingest(token) {
+        return status;
+    }
+    METRICS.shard_ingested.fetch_add(1, Ordering::Relaxed);
+    let mut signal = signal;
+    signal.ensure_id(); // Workers on an older build don't send one
+    if signal.trace_id.is_empty() {
+        signal.trace_id = uuid::Uuid::new_v4().to_string(); // ...nor a trace id
+    }
+    let span = signal.span();
+    info!(parent: &span, "Received signal from shard worker");
+    crate::actor::emit_signal(history, tx, signal).instrument(span).await;
+    warp::http::StatusCode::ACCEPTED
+}
+
+/// Aggregator only: a shard worker hands over a batch of live updates.
+#[utoipa::path(post, path = "/ingest/updates", tag = "sharding", request_body = Vec<SignalUpdate>,
+    params(("x-shard-token" = Option<String>, Header, description = "The shared SHARD_INGEST_TOKEN; ingest is refused while it is unset")),
+    responses(
+        (status = 202, description = "Broadcast to clients"),
+        (status = 401, description = "Missing or wrong token"),
+        (status = 404, description = "Not an aggregator")))]
+pub(crate) fn ingest_updates(
+    token: Option<String>,
+    updates: Vec<SignalUpdate>,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::http::StatusCode {
+    if let Err(status) = check_ingest(token) {
+        return status;
+    }
+    let _ = tx.send(WsMessage::Updates(updates));
+    warp::http::StatusCode::ACCEPTED
+}
+
+/// Aggregator only: a shard worker reports that one of its signals was invalidated.
+#[utoipa::path(post, path = "/ingest/invalidate", tag = "sharding", request_body = Invalidation,
+    params(("x-shard-token" = Option<String>, Header, description = "The shared SHARD_INGEST_TOKEN; ingest is refused while it is unset")),
+    responses(
+        (status = 202, description = "Recorded and broadcast (once per signal)"),
+        (status = 401, description = "Missing or wrong token"),
+        (status = 404, description = "Not an aggregator")))]
+pub(crate) async fn ingest_invalidation(
+    token: Option<String>,
+    invalidation: Invalidation,
+    history: &Arc<HistoryManager>,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::http::StatusCode {
+    if let Err(status) = check_ingest(token) {
+        return status;
+    }
+    crate::actor::emit_invalidation(history, tx, invalidation).await;
+    warp::http::StatusCode::ACCEPTED
+}
+
+/// Aggregator only: a shard worker reports a lifecycle transition only it could see (TargetHit, Expired).
+#[utoipa::path(post, path = "/ingest/state", tag = "sharding", request_body = StateTransition,
+    params(("x-shard-token" = Option<String>, Header, description = "The shared SHARD_INGEST_TOKEN; ingest is refused while it is unset")),
+    responses(
+        (status = 202, description = "Applied and broadcast, unless the signal already ended"),
+        (status = 401, description = "Missing or wrong token"),
+        (status = 404, description = "Not an aggregator")))]
+pub(crate) async fn ingest_transition(
+    token: Option<String>,
+    transition: StateTransition,
+    history: &Arc<HistoryManager>,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::http::StatusCode {
+    if let Err(status) = check_ingest(token) {
+        return status;
+    }
+    if let Some(transition) = history.apply_transition(transition).await {
+        history.announce(tx, transition).await;
+    }
+    warp::http::StatusCode::ACCEPTED
+}
+
+/// A TradingView alert (see `tradingview`): mapped to a signal, verified, then recorded and
+/// broadcast like the scanner's. Off (404) until TRADINGVIEW_PASSPHRASE is set.
+#[utoipa::path(post, path = "/api/tradingview", tag = "signals", request_body = TradingViewAlert,
+    responses(
+        (status = 202, description = "Verified and published", body = Versioned<Signal>),
+        (status = 400, description = "Not JSON, or an unknown action, ticker, price or time"),
+        (status = 401, description = "Missing or wrong passphrase"),
+        (status = 404, description = "TradingView ingestion not configured"),
+        (status = 422, description = "Dropped by strict verification", body = Versioned<Signal>)))]
+pub(crate) async fn tradingview_alert(
+    body: &[u8],
+    store: &SharedState,
+    history: &Arc<HistoryManager>,
+    tx: &broadcast::Sender<WsMessage>,
+) -> warp::reply::Response {
+    let bad_request = |error: String| {
+        warp::reply::with_status(warp::reply::json(&serde_json::json!({ "error": error })), warp::http::StatusCode::BAD_REQUEST)
+            .into_response()
+    };
+    let alert: TradingViewAlert = match serde_json::from_slice(body) {
+        Ok(alert) => alert,
+        Err(e) => return bad_request(format!("alert message is not the expected JSON: {}", e)),
+    };
+    match crate::tradingview::authorized(&alert) {
+        None => return warp::http::StatusCode::NOT_FOUND.into_response(),
+        Some(false) => {
+            warn!(ticker = %alert.ticker, "Rejected TradingView alert with missing or wrong passphrase");
+            return warp::http::StatusCode::UNAUTHORIZED.into_response();
+        }
+        Some(true) => {}
+    }
+    let signal = match crate::tradingview::to_signal(&alert, store) {
+        Ok(signal) => signal,
+        Err(e) => {
+            warn!(ticker = %alert.ticker, error = %e, "Invalid TradingView alert");
+            return bad_request(e.to_string());
+        }
+    };
+    METRICS.tradingview_alerts.fetch_add(1, Ordering::Relaxed);
+    let span = signal.span();
+    info!(parent: &span, strategy = %signal.strategy_name, "Received TradingView alert");
+    let reply = signal.clone();
+    let status = if crate::tradingview::publish(signal, store, history, tx).instrument(span).await {
+        warp::http::StatusCode::ACCEPTED
+    } else {
+        warp::http::StatusCode::UNPROCESSABLE_ENTITY
+    };
+    warp::reply::with_status(warp::reply::json(&versioned(reply)), status).into_response()
+}
+
+// Ingest is only served by the aggregator, and requires the shared token. Without one
+// configured everything is refused: ingested signals reach the notifiers and the executor.
+fn check_ingest(token: Option<String>) -> Result<(), warp::http::StatusCode> {
+    if !SHARD.is_aggregator {
+        return Err(warp::http::StatusCode::NOT_FOUND);
+    }
+    match crate::secrets::get(TOKEN_SECRET).filter(|expected| !expected.expose().is_empty()) {
+        Some(expected) if token.as_deref().is_some_and(|token| expected.matches(token)) => Ok(()),
+        Some(_) => {
+            warn!("Rejected shard ingest with missing or wrong token");
+            Err(warp::http::StatusCode::UNAUTHORIZED)
+        }
+        None => {
+            warn!("Rejected shard ingest, {} is not set", TOKEN_SECRET);
+            Err(warp::http::StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+// The token from the URL or, failing that, the client's first message
+async fn authenticate(
+    token: Option<String>,
+    client_ws_rx: &mut futures_util::stream::SplitStream<warp::ws::WebSocket>,
+) -> Result<Access, AuthError> {
+    match crate::auth::authorize(token.as_deref()) {
+        Err(AuthError::Missing) => {}
+        result => return result,
+    }
+    let timeout = tokio::time::Duration::from_secs(crate::auth::timeout_secs());
+    let Ok(Some(Ok(message))) = tokio::time::timeout(timeout, client_ws_rx.next()).await else {
+        return Err(AuthError::Missing);
+    };
+    match message.to_str().ok().and_then(|text| serde_json::from_str::<AuthRequest>(text).ok()) {
+        Some(AuthRequest::Auth { token }) => crate::auth::authorize(Some(&token)),
+        None => Err(AuthError::Missing),
+    }
+}
+
+async fn handle_client(
+    ws: warp::ws::WebSocket,
+    handshake: Handshake,
+    tx: broadcast::Sender<WsMessage>,
+    history: Arc<HistoryManager>,
+    store: SharedState,
+    lists: UserLists,
+    shutdown: Shutdown,
+) {
+    let UserLists { price_alerts, watchlist } = lists;
+    let (mut client_ws_tx, mut client_ws_rx) = ws.split();
+    let client_name = format!("ws_client_{}", NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed));
+
+    let access = match authenticate(handshake.token, &mut client_ws_rx).await {
+        Ok(access) => access,
+        Err(e) => {
+            warn!(client = %client_name, error = %e, "Rejected WebSocket client");
+            let close = warp::ws::Message::close_with(crate::auth::POLICY_VIOLATION, e.to_string());
+            let _ = client_ws_tx.send(close).await;
+            return;
+        }
+    };
+    let mut rx = tx.subscribe();
+    CONNECTED.fetch_add(1, Ordering::Relaxed);
+
+    info!(client = %client_name, token = %access.name, role = ?access.role, "New Frontend Client Connected");
+
+    // Everything goes out through the client's own queue and writer, so a slow socket only
+    // ever backs up this client
+    let queue = Arc::new(ClientQueue::new(handshake.encoding));
+    let mut writer = tokio::spawn(write_frames(client_ws_tx, queue.clone(), client_name.clone()));
+    let send = |msg: WsMessage| {
+        if let Err(e) = queue.push(&msg) {
+            METRICS.record_error("ws_server");
+            warn!(client = %client_name, error = %e, "Failed to serialize message, skipping");
+        }
+    };
+
+    // Initial Stats
+    send(WsMessage::Stats(Box::new(history.get_stats().await)));
+    // History (Last 60 mins)
+    let recent_signals = history.get_recent_signals().await;
+    if !recent_signals.is_empty() {
+        send(WsMessage::History(recent_signals));
+    }
+    // A market-wide event in progress
+    if let Some(alert) = crate::market_alert::active_alert() {
+        send(WsMessage::MarketAlert(alert));
+    }
+    // Which way the market leans right now
+    if let Some(bias) = crate::bias::current() {
+        send(WsMessage::MarketBias(bias));
+    }
+    // ...and what is moving in it
+    if let Some(overview) = crate::leaderboard::current_overview() {
+        send(WsMessage::MarketOverview(overview));
+    }
+    // Open paper positions
+    if let Some(positions) = crate::paper_trader::current() {
+        send(WsMessage::Positions(positions));
+    }
+    // The real account, if tracked
+    if let Some(portfolio) = crate::portfolio::current() {
+        send(WsMessage::Portfolio(Box::new(portfolio)));
+    }
+    // Whatever is running degraded right now
+    for event in crate::system_event::active() {
+        send(WsMessage::SystemEvent(event));
+    }
+    // ...and which of them are dead by now
+    for invalidation in history.get_recent_invalidations().await {
+        send(WsMessage::Invalidate(invalidation));
+    }
+    // ...where each of them is in its lifecycle
+    for transition in history.get_recent_transitions().await {
+        send(WsMessage::SignalState(transition));
+    }
+    // ...and what traders said about them
+    for feedback in history.get_recent_feedback().await {
+        send(WsMessage::Feedback(feedback));
+    }
+    // Users' price and volume alerts
+    send(WsMessage::Alerts(price_alerts.list()));
+    // ...and the symbols they watch
+    send(WsMessage::WatchedSymbols(watchlist.symbols()));
+    let targets = CommandTargets { history: &history, store: &store, price_alerts: &price_alerts, watchlist: &watchlist, tx: &tx };
+
+    let mut subscription = Subscription::default();
+    let mut candle_timer = tokio::time::interval(crate::klines::stream_interval());
+    candle_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        let received = tokio::select! {
+            _ = shutdown.wait() => {
+                queue.close(warp::ws::Message::close_with(GOING_AWAY, "server shutting down"));
                 break;
             }
+            _ = candle_timer.tick(), if subscription.candles.is_some() => {
+                if let Some(stream) = subscription.candles.as_mut() {
+                    stream.poll(&store).into_iter().for_each(|live| send(WsMessage::Candle(live)));
+                }
+                continue;
+            }
+            // The socket failed under the writer
+            _ = &mut writer => break,
+            received = rx.recv() => received,
+            incoming = client_ws_rx.next() => match incoming {
+                Some(Ok(message)) => {
+                    handle_command(&client_name, &access, message, &mut subscription, &targets).await;
+                    continue;
+                }
+                Some(Err(e)) => {
+                    warn!(client = %client_name, error = ?e, "Client socket error");
+                    break;
+                }
+                None => break,
+            },
+        };
+        let msg = match received {
+            Ok(msg) => msg,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!(client = %client_name, skipped, "Client lagged behind the broadcast channel");
+                METRICS.record_lag(&client_name, skipped);
+                queue.missed(skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Some(msg) = subscription.filter(&msg) else {
+            continue;
+        };
+        send(msg.into_owned());
+        if queue.too_slow() {
+            warn!(client = %client_name, "Client can't keep up, disconnecting");
+            METRICS.slow_clients_disconnected.fetch_add(1, Ordering::Relaxed);
+            queue.close(warp::ws::Message::close_with(TRY_AGAIN_LATER, "too slow to keep up"));
+            break;
+        }
+    }
+    // Let the close frame (if any) go out, but don't wait on a stuck socket
+    if !writer.is_finished() && tokio::time::timeout(CLOSE_TIMEOUT, &mut writer).await.is_err() {
+        writer.abort();
+    }
+    METRICS.forget_subscriber(&client_name);
+    CONNECTED.fetch_sub(1, Ordering::Relaxed);
+    info!(client = %client_name, "Client Disconnected");
+}
+
+// Drains a client's queue into its socket until the queue closes or the socket fails
+async fn write_frames(
+    mut sink: futures_util::stream::SplitSink<warp::ws::WebSocket, warp::ws::Message>,
+    queue: Arc<ClientQueue>,
+    client_name: String,
+) {
+    while let Some(frame) = queue.next().await {
+        METRICS.ws_bytes_sent.fetch_add(frame.as_bytes().len() as u64, Ordering::Relaxed);
+        if let Err(e) = sink.send(frame).await {
+            error!(client = %client_name, error = ?e, "Failed to send message to client");
+            return;
+        }
+    }
+}
+
+// The users' own alerts and watchlist, which clients are sent and can change
+#[derive(Clone)]
+struct UserLists {
+    price_alerts: Arc<PriceAlerts>,
+    watchlist: Arc<Watchlist>,
+}
+
+// What clients' commands act on
+struct CommandTargets<'a> {
+    history: &'a HistoryManager,
+    store: &'a SharedState,
+    price_alerts: &'a PriceAlerts,
+    watchlist: &'a Watchlist,
+    tx: &'a broadcast::Sender<WsMessage>,
+}
+
+// Commands from a client; pings, closes and anything unparseable are ignored
+async fn handle_command(
+    client_name: &str,
+    access: &Access,
+    message: warp::ws::Message,
+    subscription: &mut Subscription,
+    targets: &CommandTargets<'_>,
+) {
+    let CommandTargets { history, store, price_alerts, watchlist, tx } = *targets;
+    let Ok(text) = message.to_str() else {
+        return;
+    };
+    if let Ok(request) = serde_json::from_str::<SubscriptionRequest>(text) {
+        info!(client = %client_name, request = ?request, "Client subscription changed");
+        subscription.apply(request);
+        return;
+    }
+    match serde_json::from_str::<ClientCommand>(text) {
+        Ok(_) if access.role != Role::Admin => {
+            warn!(client = %client_name, token = %access.name, "Ignoring command from a read-only client")
+        }
+        Ok(ClientCommand::Admin(_)) if !access.can_administer() => {
+            warn!(client = %client_name, token = %access.name, "Ignoring admin command without an admin token")
+        }
+        Ok(ClientCommand::Admin(command)) => {
+            if let Err(e) = apply_admin(access, command, store, tx) {
+                warn!(client = %client_name, error = %e, "Admin command failed");
+            }
+        }
+        Ok(ClientCommand::Feedback(request)) => {
+            if apply_feedback(&request, history, tx).await.is_none() {
+                warn!(client = %client_name, signal_id = %request.signal_id, "Feedback for unknown signal");
+            }
+        }
+        Ok(ClientCommand::AddAlert(alert)) => match price_alerts.add(alert) {
+            Ok(_) => {
+                let _ = tx.send(WsMessage::Alerts(price_alerts.list()));
+            }
+            Err(e) => warn!(client = %client_name, error = %e, "Alert rejected"),
+        },
+        Ok(ClientCommand::RemoveAlert { id }) => {
+            if price_alerts.remove(&id) {
+                let _ = tx.send(WsMessage::Alerts(price_alerts.list()));
+            } else {
+                warn!(client = %client_name, alert_id = %id, "Removal of unknown alert");
+            }
+        }
+        Ok(ClientCommand::Watch { symbol }) => match watchlist.watch(&symbol) {
+            Ok(true) => {
+                let _ = tx.send(WsMessage::WatchedSymbols(watchlist.symbols()));
+            }
+            Ok(false) => {}
+            Err(e) => warn!(client = %client_name, error = %e, "Watch rejected"),
+        },
+        Ok(ClientCommand::Unwatch { symbol }) => {
+            if watchlist.unwatch(&symbol) {
+                let _ = tx.send(WsMessage::WatchedSymbols(watchlist.symbols()));
+            }
         }
+        Err(e) => warn!(client = %client_name, error = %e, "Ignoring unrecognized client message"),
     }
-    info!("Client Disconnected");
 }
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalType } from "./SignalType";
+
+/**
+ * An open futures position, with the signal it was entered on if any.
+ */
+export type AccountPosition = { symbol: string, side: SignalType, quantity: number, entryPrice: number, markPrice: number, unrealizedPnl: number, leverage: number, signalId: string | null, };
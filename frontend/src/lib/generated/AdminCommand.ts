@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ThresholdsPatch } from "./ThresholdsPatch";
+
+/**
+ * A change to the running scanner, e.g. `{"type": "Blacklist", "payload": "BTCUSDT"}`.
+ */
+export type AdminCommand = { "type": "SetThresholds", "payload": ThresholdsPatch } | { "type": "Pause" } | { "type": "Resume" } | { "type": "Blacklist", "payload": string } | { "type": "Unblacklist", "payload": string } | { "type": "ClearCooldown", "payload": string };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * First inbound WebSocket message of a client that didn't put its token in the URL; see `auth`.
+ */
+export type AuthRequest = { "action": "auth", token: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The book's bid vs ask walls (summed top 20 levels) over the tracking window.
+ */
+export type BookImbalance = { ratio: number, avgRatio: number, minRatio: number, maxRatio: number, bidHeavyPct: number, samples: number, windowSecs: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Performance of the signals sharing one key: a symbol, "Long"/"Short", a strategy, a UTC
+ * day ("2024-05-06") or a week (its Monday's date).
+ */
+export type BucketStats = { key: string, totalSignals: number, wins: number, winRate: number, avgGain: number, };
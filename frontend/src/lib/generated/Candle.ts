@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A 1m candle as clients see it.
+ */
+export type Candle = { timestamp: number, open: number, high: number, low: number, close: number, volume: number, quoteVolume: number, trades: number, takerBuyVolume: number | null, };
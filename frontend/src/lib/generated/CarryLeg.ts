@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Market } from "./Market";
+import type { Side } from "./Side";
+
+export type CarryLeg = { market: Market, side: Side, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CarryLeg } from "./CarryLeg";
+
+/**
+ * A funding carry: hold spot against the perp and collect funding. Not a momentum signal.
+ */
+export type CarryOpportunity = { symbol: string, fundingRate: number, annualizedYieldPct: number, basisPct: number, markPrice: number, spotPrice: number, nextFundingTime: number, legs: Array<CarryLeg>, timestamp: number, };
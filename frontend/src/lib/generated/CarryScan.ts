@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CarryOpportunity } from "./CarryOpportunity";
+
+/**
+ * Latest scan, for `GET /api/carry`.
+ */
+export type CarryScan = { opportunities: Array<CarryOpportunity>, timestamp: number, };
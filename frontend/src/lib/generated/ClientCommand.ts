@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AdminCommand } from "./AdminCommand";
+import type { FeedbackRequest } from "./FeedbackRequest";
+import type { NewPriceAlert } from "./NewPriceAlert";
+
+/**
+ * Inbound WebSocket messages.
+ */
+export type ClientCommand = { "type": "Feedback", "payload": FeedbackRequest } | { "type": "AddAlert", "payload": NewPriceAlert } | { "type": "RemoveAlert", "payload": { id: string, } } | { "type": "Watch", "payload": { symbol: string, } } | { "type": "Unwatch", "payload": { symbol: string, } } | { "type": "Admin", "payload": AdminCommand };
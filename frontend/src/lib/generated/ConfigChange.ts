@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AdminCommand } from "./AdminCommand";
+import type { RuntimeConfig } from "./RuntimeConfig";
+
+/**
+ * An applied admin command and the config it left, broadcast as `ConfigChanged`.
+ */
+export type ConfigChange = { command: AdminCommand, changedBy: string, config: RuntimeConfig, timestamp: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Contract metadata as clients see it.
+ */
+export type ContractInfo = { baseAsset: string, quoteAsset: string, pricePrecision: number, quantityPrecision: number, contractType: string, tickSize: number | null, stepSize: number | null, minQty: number | null, minNotional: number | null, status: string, };
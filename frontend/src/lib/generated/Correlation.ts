@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a signal relates to the others fired around it.
+ */
+export type Correlation = { minute: number, groupSize: number, leaderVolumeRatio: number | null, leaderChangePct: number | null, };
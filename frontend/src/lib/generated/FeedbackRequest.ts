@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackKind } from "./FeedbackKind";
+
+/**
+ * A trader marks a signal (WS `Feedback` command or `POST /api/feedback`).
+ */
+export type FeedbackRequest = { signalId: string, kind: FeedbackKind, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One execution on the account.
+ */
+export type Fill = { id: number, symbol: string, side: string, price: number, quantity: number, realizedPnl: number, commission: number, commissionAsset: string, time: number, signalId: string | null, };
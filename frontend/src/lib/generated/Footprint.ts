@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FootprintLevel } from "./FootprintLevel";
+
+/**
+ * One minute of order flow by price, next to the 1m candle of the same `minute`.
+ */
+export type Footprint = { minute: number, bucketSize: number, buyVolume: number, sellVolume: number, levels: Array<FootprintLevel>, };
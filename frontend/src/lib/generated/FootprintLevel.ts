@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Taker volume traded within one price bucket.
+ */
+export type FootprintLevel = { price: number, buyVolume: number, sellVolume: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A perp's funding as of the last refresh. Positive: longs pay shorts.
+ */
+export type FundingRate = { symbol: string, rate: number, markPrice: number, indexPrice: number, nextFundingTime: number, updatedAt: number, };
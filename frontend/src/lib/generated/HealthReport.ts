@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStatus } from "./PipelineStatus";
+import type { TaskStatus } from "./TaskStatus";
+
+/**
+ * `/health`: healthy while every supervised task runs and the feed is fresh.
+ */
+export type HealthReport = { healthy: boolean, tasks: { [key in string]: TaskStatus }, pipeline: PipelineStatus, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HeatmapCell } from "./HeatmapCell";
+
+/**
+ * Every tracked symbol's current volume ratio and price change, for `GET /api/heatmap`.
+ */
+export type Heatmap = { cells: Array<HeatmapCell>, timestamp: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalRecord } from "./SignalRecord";
+
+/**
+ * One page of signal history, for `GET /api/signals`.
+ */
+export type HistoryPage = { records: Array<SignalRecord>, total: number, };
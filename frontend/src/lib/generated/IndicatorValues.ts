@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Indicator values as of the last finished 1m candle, each None until it has enough candles.
+ */
+export type IndicatorValues = { rsi: number | null, ema9: number | null, ema21: number | null, vwap: number | null, atr: number | null, };
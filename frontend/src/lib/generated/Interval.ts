@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Candle intervals derived from the 1m feed.
+ */
+export type Interval = "1m" | "5m" | "15m";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A published signal whose premise broke: price moved too far against it, or the order
+ * book no longer backs it on re-verification.
+ */
+export type Invalidation = { signalId: string, symbol: string, reason: string, timestamp: number, stoppedOut: boolean, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Candle } from "./Candle";
+import type { KlineSource } from "./KlineSource";
+
+/**
+ * Candles for charts, oldest first, for `GET /api/klines`.
+ */
+export type Klines = { symbol: string, interval: string, source: KlineSource, candles: Array<Candle>, };
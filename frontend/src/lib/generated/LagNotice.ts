@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sent to a client ahead of its next message when some were dropped for it.
+ */
+export type LagNotice = { missed: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Mover } from "./Mover";
+import type { RankedSignal } from "./RankedSignal";
+
+/**
+ * Market color between signals: the most active symbols and the biggest 15m moves, and
+ * the signals still live, best score first.
+ */
+export type Leaderboard = { topVolume: Array<Mover>, gainers: Array<Mover>, losers: Array<Mover>, signals: Array<RankedSignal>, timestamp: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Candle } from "./Candle";
+
+/**
+ * The minute in progress of a symbol, pushed to WebSocket clients that asked for it
+ * (`{"action": "stream_candles", "symbol": ...}`).
+ */
+export type LiveCandle = { symbol: string, candle: Candle, closed: boolean, };
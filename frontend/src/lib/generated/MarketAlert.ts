@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Start (`active`) or end of a market-wide event.
+ */
+export type MarketAlert = { active: boolean, reason: string, spikingSymbols: number, trackedSymbols: number, volumeZscore: number | null, thresholdMultiplier: number, until: number, timestamp: number, };
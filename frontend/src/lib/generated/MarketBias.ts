@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BiasLabel } from "./BiasLabel";
+
+/**
+ * Which way the whole tape leans, -100 (everything dumping) to +100 (everything ripping).
+ */
+export type MarketBias = { score: number, label: BiasLabel, leadersChange15mPct: number | null, breadth: number | null, cvdRatio: number | null, timestamp: number, };
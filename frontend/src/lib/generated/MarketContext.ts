@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Market-wide context from outside the scanner's own windows. Each part is None when its
+ * source failed on the last refresh.
+ */
+export type MarketContext = { fearGreed: number | null, fearGreedLabel: string | null, avgFundingRate: number | null, positiveFundingShare: number | null, openInterest: number | null, openInterestChangePct: number | null, updatedAt: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Mover } from "./Mover";
+
+/**
+ * The whole tracked market at a glance, every `MARKET_OVERVIEW_INTERVAL_SECS`: volume
+ * gainers, the biggest 5m moves either way, and aggregate volume, so there is something to
+ * show when no signals fire.
+ */
+export type MarketOverview = { volumeGainers: Array<Mover>, movers5m: Array<Mover>, symbols: number, quoteVolume1m: number, volumeRatio: number, dayQuoteVolume: number, advancing5m: number, declining5m: number, timestamp: number, };
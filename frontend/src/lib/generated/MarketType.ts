@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which kind of market a symbol trades on.
+ */
+export type MarketType = "Futures" | "Spot";
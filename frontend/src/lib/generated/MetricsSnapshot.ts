@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MetricsSnapshot = { storeSymbols: number, storeCandleBuilders: number, storeBytesEstimate: number, evictedIdle: number, evictedBudget: number, symbolsDelisted: number, actors: number, actorDroppedTicks: number, broadcastSubscribers: number, broadcastDropped: number, broadcastLagged: { [key in string]: number }, slowClientsDisconnected: number, wsBytesSent: number, isLeader: boolean, shardSkipped: number, shardForwarded: number, shardIngested: number, tradingviewAlerts: number, lastFeedMessageMs: number, recorderDropped: number, ingestDropped: number, restRetries: number, restRateLimited: number, restCacheHits: number, clusterPublished: number, clusterRelayed: number, symbolsBackfilled: number, pipeline: { [key in string]: number }, pipelineRatesPerSec: { [key in string]: number }, trackedSymbols: number, errors: { [key in string]: number }, };
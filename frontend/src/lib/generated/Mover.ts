@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One symbol's recent activity, from its finished 1m candles.
+ */
+export type Mover = { symbol: string, price: number, volumeRatio: number, change5mPct: number | null, change15mPct: number | null, };
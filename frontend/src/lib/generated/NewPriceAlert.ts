@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertKind } from "./AlertKind";
+import type { Direction } from "./Direction";
+
+/**
+ * Body of `POST /api/alerts` and of the WebSocket `AddAlert` command.
+ */
+export type NewPriceAlert = { symbol: string, kind: AlertKind, level: number, direction: Direction | null, note: string | null, notify: boolean, };
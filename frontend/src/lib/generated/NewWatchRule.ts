@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WatchCondition } from "./WatchCondition";
+
+/**
+ * Body of `POST /api/watchlist`.
+ */
+export type NewWatchRule = { symbol: string, condition: WatchCondition, label: string | null, };
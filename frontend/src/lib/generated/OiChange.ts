@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Open interest change in percent up to the signal.
+ */
+export type OiChange = { m5: number, m15: number | null, m60: number | null, };
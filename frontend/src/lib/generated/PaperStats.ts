@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Paper trading totals for `Stats`: closed trades from history, open ones from the trader.
+ */
+export type PaperStats = { closedTrades: number, wins: number, realizedPnl: number, openPositions: number, unrealizedPnl: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedStatus } from "./FeedStatus";
+import type { HistoryStatus } from "./HistoryStatus";
+
+/**
+ * The data path from feed to clients.
+ */
+export type PipelineStatus = { feed: FeedStatus, trackedSymbols: number, history: HistoryStatus, subscribers: number, };
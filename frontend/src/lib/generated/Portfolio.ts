@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccountBalance } from "./AccountBalance";
+import type { AccountPosition } from "./AccountPosition";
+import type { Fill } from "./Fill";
+import type { PortfolioSummary } from "./PortfolioSummary";
+import type { TradedSignal } from "./TradedSignal";
+
+/**
+ * The account as last polled, broadcast as `WsMessage::Portfolio`.
+ */
+export type Portfolio = { balance: AccountBalance, positions: Array<AccountPosition>, fills: Array<Fill>, traded: Array<TradedSignal>, summary: PortfolioSummary, updatedAt: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CloseReason } from "./CloseReason";
+import type { SignalType } from "./SignalType";
+
+/**
+ * One simulated position, opened at the signal price. PnL is in USDT, without fees.
+ */
+export type Position = { signalId: string, symbol: string, side: SignalType, entryPrice: number, quantity: number, takeProfit: number, stopLoss: number, openedAt: number, markPrice: number, unrealizedPnl: number, closedAt: number | null, exitPrice: number | null, realizedPnl: number | null, closeReason: CloseReason | null, };
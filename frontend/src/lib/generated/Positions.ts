@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Position } from "./Position";
+
+/**
+ * Open positions, as broadcast in `WsMessage::Positions`.
+ */
+export type Positions = { open: Array<Position>, unrealizedPnl: number, updatedAt: number, };
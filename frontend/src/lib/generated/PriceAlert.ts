@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlertKind } from "./AlertKind";
+import type { Direction } from "./Direction";
+
+/**
+ * A one-shot price or volume trigger. Stays listed after it fires, with `triggeredAt` set,
+ * until deleted.
+ */
+export type PriceAlert = { id: string, symbol: string, kind: AlertKind, level: number, direction: Direction | null, note: string | null, notify: boolean, createdAt: number, triggeredAt: number | null, triggeredPrice: number | null, triggeredQuoteVolume: number | null, };
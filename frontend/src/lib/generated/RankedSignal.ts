@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalType } from "./SignalType";
+
+/**
+ * A live signal's place on the board.
+ */
+export type RankedSignal = { signalId: string, symbol: string, signalType: SignalType, score: number, entryPrice: number, price: number, firedAt: number, };
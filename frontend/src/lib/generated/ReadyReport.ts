@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PipelineStatus } from "./PipelineStatus";
+import type { Readiness } from "./Readiness";
+
+/**
+ * `/ready`: ready once enough windows are warm and the feed is fresh.
+ */
+export type ReadyReport = { ready: boolean, warmup: Readiness, pipeline: PipelineStatus, };
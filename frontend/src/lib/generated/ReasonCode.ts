@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Interval } from "./Interval";
+import type { MarketType } from "./MarketType";
+
+/**
+ * Why a signal fired, one entry per check that contributed.
+ */
+export type ReasonCode = { "code": "VolumeSpike", ratio: number, avgValue: number, priceChangePct: number, zscore: number | null, } | { "code": "BuyWall", ratio: number, } | { "code": "SellWall", ratio: number, } | { "code": "OpenInterest", value: number, } | { "code": "WhaleActive", value: number, } | { "code": "WarmingUp", candles: number, required: number, } | { "code": "AgainstBias", score: number, } | { "code": "TrendConfirmed", timeframe: Interval, changePct: number, } | { "code": "Liquidations", longValue: number, shortValue: number, zscore: number | null, } | { "code": "Funding", ratePct: number, extreme: boolean, against: boolean, } | { "code": "OiChange", minutes: number, changePct: number, } | { "code": "MarketWide", groupSize: number, leaderChangePct: number | null, } | { "code": "SpotFuturesDivergence", ledBy: MarketType, spotVolumeRatio: number, futuresVolumeRatio: number, } | { "code": "NewListing", listedAt: number, ageMinutes: number, } | { "code": "TradingView", strategy: string, comment: string | null, } | { "code": "ShortPressure", volumeRatio: number, deltaPct: number, oiChangePct: number, minWallRatio: number, } | { "code": "Breakout", candles: number, changePct: number, volumeRatio: number, } | { "code": "Positioning", topLongShortRatio: number | null, takerBuySellRatio: number | null, premiumPct: number | null, };
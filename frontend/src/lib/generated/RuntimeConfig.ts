@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Thresholds } from "./Thresholds";
+
+/**
+ * What the admin API has changed from the startup config.
+ */
+export type RuntimeConfig = { paused: boolean, blacklist: Array<string>, thresholds: Thresholds | null, };
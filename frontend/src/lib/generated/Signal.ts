@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Correlation } from "./Correlation";
+import type { Exchange } from "./Exchange";
+import type { IndicatorValues } from "./IndicatorValues";
+import type { Interval } from "./Interval";
+import type { MarketContext } from "./MarketContext";
+import type { MarketType } from "./MarketType";
+import type { OiChange } from "./OiChange";
+import type { ReasonCode } from "./ReasonCode";
+import type { SignalScore } from "./SignalScore";
+import type { SignalSource } from "./SignalSource";
+import type { SignalType } from "./SignalType";
+
+export type Signal = { id: string, traceId: string, symbol: string, exchange: Exchange, market: MarketType, signalType: SignalType, timeframe: Interval, strategyName: string, source: SignalSource, price: number, volume: number, avgVolume: number, timestamp: number, reason: string, reasons: Array<ReasonCode>, high: number, low: number, quoteVolume: number, trades: number, takerBuyVolume: number | null, vwap: number | null, sessionVwap: number | null, indicators: IndicatorValues | null, oiChange: OiChange | null, confidence: number | null, score: SignalScore | null, dayHigh: number | null, dayLow: number | null, dayChangePct: number | null, dayQuoteVolume: number | null, dayRangePosition: number | null, baseAsset: string | null, quoteAsset: string | null, pricePrecision: number | null, lowConfidence: boolean, marketBias: number | null, marketContext: MarketContext | null, marketWide: boolean, correlation: Correlation | null, };
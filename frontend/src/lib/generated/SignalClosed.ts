@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalOutcome } from "./SignalOutcome";
+import type { SignalState } from "./SignalState";
+
+/**
+ * A signal's life ended, and how it did by then. Broadcast as `WsMessage::SignalClosed`
+ * right after the terminal `SignalState`, so clients can tell live alerts from dead ones
+ * without tracking states themselves.
+ */
+export type SignalClosed = { signalId: string, symbol: string, state: SignalState, detail: string | null, outcome: SignalOutcome, closedAt: number, };
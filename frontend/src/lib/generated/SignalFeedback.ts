@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What traders said about a signal: whether it was traded, and whether it was any good.
+ * Each is the latest mark on that axis; None until someone says.
+ */
+export type SignalFeedback = { signalId: string, taken: boolean | null, good: boolean | null, updatedAt: number, };
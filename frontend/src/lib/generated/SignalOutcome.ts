@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SignalOutcome = { priceAt15m: number | null, priceAt30m: number | null, priceAt60m: number | null, success: boolean, maxGainPercent: number, maxDrawdownPercent: number, hitTp: boolean, hitSl: boolean, timeToTargetMs: number | null, takeProfitPct: number | null, stopLossPct: number | null, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Invalidation } from "./Invalidation";
+import type { Position } from "./Position";
+import type { Signal } from "./Signal";
+import type { SignalFeedback } from "./SignalFeedback";
+import type { SignalOutcome } from "./SignalOutcome";
+import type { StateTransition } from "./StateTransition";
+
+export type SignalRecord = { signal: Signal, outcome: SignalOutcome, recordedAt: number, invalidation: Invalidation | null, feedback: SignalFeedback | null, transitions: Array<StateTransition>, paperTrade: Position | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A signal's composite score and what went into it.
+ */
+export type SignalScore = { total: number, volume: number | null, book: number | null, openInterest: number | null, stability: number | null, };
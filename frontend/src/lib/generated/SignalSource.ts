@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a signal came from: the scanner's own strategies, or an external alert.
+ */
+export type SignalSource = "scanner" | "tradingview";
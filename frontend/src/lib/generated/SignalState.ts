@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a signal is in its life:
+ * Created → Verified → Active → one of TargetHit / StoppedOut / Expired / Invalidated.
+ */
+export type SignalState = "Created" | "Verified" | "Active" | "TargetHit" | "StoppedOut" | "Expired" | "Invalidated";
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BookImbalance } from "./BookImbalance";
+import type { IndicatorValues } from "./IndicatorValues";
+
+export type SignalUpdate = { signalId: string | null, symbol: string, price: number, open: number, high: number, low: number, volume: number, quoteVolume: number, trades: number, takerBuyVolume: number | null, timestamp: number, indicators: IndicatorValues | null, imbalance: BookImbalance | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What makes a reading a spike: a multiple of its average (`ratio`), or a number of standard
+ * deviations above its baseline (`zscore`), which adapts to coins whose volume is spiky anyway.
+ */
+export type SpikeTrigger = "Ratio" | "ZScore";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalState } from "./SignalState";
+
+/**
+ * One step in a signal's life, persisted with its history record and broadcast as
+ * `WsMessage::SignalState`.
+ */
+export type StateTransition = { signalId: string, symbol: string, from: SignalState | null, to: SignalState, detail: string | null, timestamp: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BucketStats } from "./BucketStats";
+import type { FeedbackStats } from "./FeedbackStats";
+import type { MarketContext } from "./MarketContext";
+import type { PaperStats } from "./PaperStats";
+import type { StrategyStats } from "./StrategyStats";
+
+export type Stats = { totalSignals: number, winRate: number, topGainer: string, feedback: FeedbackStats, marketContext: MarketContext | null, paper: PaperStats | null, byStrategy: Array<StrategyStats>, bySignalType: Array<BucketStats>, bySymbol: Array<BucketStats>, byDay: Array<BucketStats>, byWeek: Array<BucketStats>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BucketStats } from "./BucketStats";
+import type { StatsGroup } from "./StatsGroup";
+
+/**
+ * `/api/stats/breakdown` reply.
+ */
+export type StatsBreakdown = { by: StatsGroup, groups: Array<BucketStats>, };
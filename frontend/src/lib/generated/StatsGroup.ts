@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What `HistoryManager::breakdown` groups signals by.
+ */
+export type StatsGroup = "symbol" | "signal_type" | "strategy" | "day" | "week";
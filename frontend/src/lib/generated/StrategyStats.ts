@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Signal count and win rate of one strategy, for `Stats::by_strategy`.
+ */
+export type StrategyStats = { strategy: string, totalSignals: number, winRate: number, avgGain: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Inbound WebSocket filter changes, e.g. `{"action": "subscribe", "symbols": ["BTCUSDT"],
+ * "types": ["signal"]}`; see `subscription`.
+ */
+export type SubscriptionRequest = { "action": "subscribe", symbols: Array<string>, types: Array<string>, } | { "action": "unsubscribe", symbols: Array<string>, types: Array<string>, } | { "action": "stream_candles", symbol: string | null, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalType } from "./SignalType";
+import type { SuppressionReason } from "./SuppressionReason";
+
+export type SuppressedCandidate = { symbol: string, strategy: string, signalType: SignalType, candleStart: number, timestamp: number, price: number, volumeRatio: number, reason: SuppressionReason, };
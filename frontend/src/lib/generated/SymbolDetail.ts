@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BookImbalance } from "./BookImbalance";
+import type { Candle } from "./Candle";
+import type { ContractInfo } from "./ContractInfo";
+import type { Footprint } from "./Footprint";
+import type { IndicatorValues } from "./IndicatorValues";
+import type { Invalidation } from "./Invalidation";
+import type { Signal } from "./Signal";
+
+/**
+ * Everything the scanner currently knows about one symbol, for `GET /api/symbol/{symbol}`.
+ */
+export type SymbolDetail = { symbol: string, candles: Array<Candle>, current: Candle | null, averageVolume: number, expectedVolume: number | null, volumeRatio: number | null, volumeZscore: number | null, vwap: number | null, sessionVwap: number | null, indicators: IndicatorValues, dayHigh: number | null, dayLow: number | null, dayChangePct: number | null, cooldownRemainingMs: number, watched: boolean, meta: ContractInfo | null, lastSignal: Signal | null, lastSignalInvalidation: Invalidation | null, footprints: Array<Footprint>, imbalance: BookImbalance | null, };
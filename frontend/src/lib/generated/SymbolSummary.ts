@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One tracked symbol at a glance, for `GET /api/symbols`.
+ */
+export type SymbolSummary = { symbol: string, price: number | null, candles: number, lastSignalTime: number | null, signalLive: boolean, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventLevel } from "./EventLevel";
+
+export type SystemEvent = { source: string, level: EventLevel, message: string, active: boolean, timestamp: number, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RestartPolicy } from "./RestartPolicy";
+import type { TaskState } from "./TaskState";
+
+export type TaskStatus = { state: TaskState, policy: RestartPolicy, restarts: number, lastError: string | null, lastStarted: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Interval } from "./Interval";
+import type { SpikeTrigger } from "./SpikeTrigger";
+
+/**
+ * The Silent Watcher's thresholds, as `[scanner]` in `config.toml`.
+ */
+export type Thresholds = { minValue: number, minAvgValue: number, deadAvgValue: number, spikeRatio: number, deadSpikeRatio: number, trigger: SpikeTrigger, zscoreThreshold: number, deadZscoreThreshold: number, maxPriceChangePct: number, maxPriorMovePct: number, cooldownSecs: number, timeframe: Interval, trendTimeframe: Interval | null, trendCandles: number, };
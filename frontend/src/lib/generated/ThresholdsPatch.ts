@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Interval } from "./Interval";
+import type { SpikeTrigger } from "./SpikeTrigger";
+
+/**
+ * Thresholds to change; the ones left out keep their value.
+ */
+export type ThresholdsPatch = { minValue: number | null, minAvgValue: number | null, deadAvgValue: number | null, spikeRatio: number | null, deadSpikeRatio: number | null, trigger: SpikeTrigger | null, zscoreThreshold: number | null, deadZscoreThreshold: number | null, maxPriceChangePct: number | null, maxPriorMovePct: number | null, cooldownSecs: number | null, timeframe: Interval | null, trendCandles: number | null, };
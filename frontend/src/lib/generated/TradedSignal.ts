@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignalType } from "./SignalType";
+
+/**
+ * A signal that was actually traded, and how the trade went.
+ */
+export type TradedSignal = { signalId: string, symbol: string, signalType: SignalType, signalPrice: number, signalTime: number, entryPrice: number, quantity: number, realizedPnl: number, commission: number, open: boolean, };
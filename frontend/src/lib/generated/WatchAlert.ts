@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A watch rule fired. Sent separately from scanner signals.
+ */
+export type WatchAlert = { ruleId: string, symbol: string, label: string | null, message: string, price: number, timestamp: number, };
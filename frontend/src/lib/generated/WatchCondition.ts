@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a watch rule fires on.
+ */
+export type WatchCondition = { "kind": "VolumeRatioAbove", ratio: number, } | { "kind": "PriceCrosses", level: number, } | { "kind": "OiChangeAbove", pct: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WatchCondition } from "./WatchCondition";
+
+export type WatchRule = { id: string, symbol: string, condition: WatchCondition, label: string | null, createdAt: number, lastTriggered: number | null, oiBaseline: number | null, };
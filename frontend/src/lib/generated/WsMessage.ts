@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CarryOpportunity } from "./CarryOpportunity";
+import type { ConfigChange } from "./ConfigChange";
+import type { Invalidation } from "./Invalidation";
+import type { LagNotice } from "./LagNotice";
+import type { Leaderboard } from "./Leaderboard";
+import type { LiveCandle } from "./LiveCandle";
+import type { MarketAlert } from "./MarketAlert";
+import type { MarketBias } from "./MarketBias";
+import type { MarketOverview } from "./MarketOverview";
+import type { Portfolio } from "./Portfolio";
+import type { Positions } from "./Positions";
+import type { PriceAlert } from "./PriceAlert";
+import type { Signal } from "./Signal";
+import type { SignalClosed } from "./SignalClosed";
+import type { SignalFeedback } from "./SignalFeedback";
+import type { SignalUpdate } from "./SignalUpdate";
+import type { StateTransition } from "./StateTransition";
+import type { Stats } from "./Stats";
+import type { SystemEvent } from "./SystemEvent";
+import type { WatchAlert } from "./WatchAlert";
+
+export type WsMessage = { "type": "Signal", "payload": Signal } | { "type": "Updates", "payload": Array<SignalUpdate> } | { "type": "History", "payload": Array<Signal> } | { "type": "Stats", "payload": Stats } | { "type": "Invalidate", "payload": Invalidation } | { "type": "Leaderboard", "payload": Leaderboard } | { "type": "MarketOverview", "payload": MarketOverview } | { "type": "MarketAlert", "payload": MarketAlert } | { "type": "WatchAlert", "payload": WatchAlert } | { "type": "AlertTriggered", "payload": PriceAlert } | { "type": "Alerts", "payload": Array<PriceAlert> } | { "type": "WatchedSymbols", "payload": Array<string> } | { "type": "Feedback", "payload": SignalFeedback } | { "type": "Carry", "payload": CarryOpportunity } | { "type": "MarketBias", "payload": MarketBias } | { "type": "SignalState", "payload": StateTransition } | { "type": "SignalClosed", "payload": SignalClosed } | { "type": "Positions", "payload": Positions } | { "type": "Portfolio", "payload": Portfolio } | { "type": "ConfigChanged", "payload": ConfigChange } | { "type": "Lagged", "payload": LagNotice } | { "type": "SystemEvent", "payload": SystemEvent } | { "type": "Candle", "payload": LiveCandle };
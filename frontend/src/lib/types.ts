@@ -1,37 +1,101 @@
-export enum SignalType {
-    Long = "Long",
-    Short = "Short",
-}
+// Payload types are generated from the backend structs; regenerate with
+// `cargo run -- --export-types` in backend/ after changing any outbound type.
+import type { WsMessage as WsPayload } from './generated/WsMessage';
 
-export interface Signal {
-    symbol: string;
-    signal_type: SignalType;
-    price: number;
-    volume: number;
-    avg_volume: number;
-    timestamp: number;
-    reason: string;
-    // Optional proofs
-    order_book_ratio?: number;
-    oi?: number;
-    net_inflow?: number;
-}
+export type { Signal } from './generated/Signal';
+export type { SignalType } from './generated/SignalType';
+export type { Exchange } from './generated/Exchange';
+export type { MarketType } from './generated/MarketType';
+export type { Interval } from './generated/Interval';
+export type { IndicatorValues } from './generated/IndicatorValues';
+export type { OiChange } from './generated/OiChange';
+export type { BookImbalance } from './generated/BookImbalance';
+export type { Correlation } from './generated/Correlation';
+export type { ReasonCode } from './generated/ReasonCode';
+export type { SignalUpdate } from './generated/SignalUpdate';
+export type { SignalScore } from './generated/SignalScore';
+export type { SignalSource } from './generated/SignalSource';
+export type { Invalidation } from './generated/Invalidation';
+export type { SignalState } from './generated/SignalState';
+export type { StateTransition } from './generated/StateTransition';
+export type { SignalClosed } from './generated/SignalClosed';
+export type { Leaderboard } from './generated/Leaderboard';
+export type { Mover } from './generated/Mover';
+export type { RankedSignal } from './generated/RankedSignal';
+export type { MarketOverview } from './generated/MarketOverview';
+export type { MarketAlert } from './generated/MarketAlert';
+export type { SystemEvent } from './generated/SystemEvent';
+export type { EventLevel } from './generated/EventLevel';
+export type { MarketBias } from './generated/MarketBias';
+export type { MarketContext } from './generated/MarketContext';
+export type { BiasLabel } from './generated/BiasLabel';
+export type { Stats } from './generated/Stats';
+export type { StrategyStats } from './generated/StrategyStats';
+export type { BucketStats } from './generated/BucketStats';
+export type { StatsBreakdown } from './generated/StatsBreakdown';
+export type { StatsGroup } from './generated/StatsGroup';
+export type { MetricsSnapshot } from './generated/MetricsSnapshot';
+export type { SymbolCountersSnapshot } from './generated/SymbolCountersSnapshot';
+export type { HealthReport } from './generated/HealthReport';
+export type { TaskStatus } from './generated/TaskStatus';
+export type { Readiness } from './generated/Readiness';
+export type { ReadyReport } from './generated/ReadyReport';
+export type { PipelineStatus } from './generated/PipelineStatus';
+export type { FeedStatus } from './generated/FeedStatus';
+export type { HistoryStatus } from './generated/HistoryStatus';
+export type { Heatmap } from './generated/Heatmap';
+export type { HeatmapCell } from './generated/HeatmapCell';
+export type { CarryOpportunity } from './generated/CarryOpportunity';
+export type { CarryScan } from './generated/CarryScan';
+export type { CarryLeg } from './generated/CarryLeg';
+export type { FundingRate } from './generated/FundingRate';
+export type { SymbolDetail } from './generated/SymbolDetail';
+export type { SymbolSummary } from './generated/SymbolSummary';
+export type { SignalRecord } from './generated/SignalRecord';
+export type { SignalOutcome } from './generated/SignalOutcome';
+export type { HistoryPage } from './generated/HistoryPage';
+export type { SuppressedPage } from './generated/SuppressedPage';
+export type { SuppressedCandidate } from './generated/SuppressedCandidate';
+export type { SuppressionReason } from './generated/SuppressionReason';
+export type { Position } from './generated/Position';
+export type { Positions } from './generated/Positions';
+export type { PaperStats } from './generated/PaperStats';
+export type { Portfolio } from './generated/Portfolio';
+export type { AccountBalance } from './generated/AccountBalance';
+export type { AccountPosition } from './generated/AccountPosition';
+export type { Fill } from './generated/Fill';
+export type { TradedSignal } from './generated/TradedSignal';
+export type { PortfolioSummary } from './generated/PortfolioSummary';
+export type { CloseReason } from './generated/CloseReason';
+export type { Candle } from './generated/Candle';
+export type { Klines } from './generated/Klines';
+export type { KlineSource } from './generated/KlineSource';
+export type { LiveCandle } from './generated/LiveCandle';
+export type { Footprint } from './generated/Footprint';
+export type { FootprintLevel } from './generated/FootprintLevel';
+export type { WatchAlert } from './generated/WatchAlert';
+export type { WatchRule } from './generated/WatchRule';
+export type { WatchCondition } from './generated/WatchCondition';
+export type { NewWatchRule } from './generated/NewWatchRule';
+export type { PriceAlert } from './generated/PriceAlert';
+export type { Direction } from './generated/Direction';
+export type { AlertKind } from './generated/AlertKind';
+export type { NewPriceAlert } from './generated/NewPriceAlert';
+export type { SignalFeedback } from './generated/SignalFeedback';
+export type { FeedbackKind } from './generated/FeedbackKind';
+export type { FeedbackStats } from './generated/FeedbackStats';
+export type { ClientCommand } from './generated/ClientCommand';
+export type { SubscriptionRequest } from './generated/SubscriptionRequest';
+export type { AuthRequest } from './generated/AuthRequest';
+export type { AdminCommand } from './generated/AdminCommand';
+export type { ConfigChange } from './generated/ConfigChange';
+export type { RuntimeConfig } from './generated/RuntimeConfig';
+export type { LagNotice } from './generated/LagNotice';
+export type { Thresholds } from './generated/Thresholds';
+export type { ThresholdsPatch } from './generated/ThresholdsPatch';
+export type { SpikeTrigger } from './generated/SpikeTrigger';
 
-export interface SignalUpdate {
-    symbol: string;
-    price: number;
-    volume: number;
-    timestamp: number;
-}
+// Outbound schema is camelCase and versioned; see backend/src/wire.rs
+export const SCHEMA_VERSION = 2;
 
-export type WsMessage =
-    | { type: 'Signal', payload: Signal }
-    | { type: 'Update', payload: SignalUpdate }
-    | { type: 'Stats', payload: Stats }
-    | { type: 'History', payload: Signal[] };
-
-export interface Stats {
-    total_signals: number;
-    win_rate: number;
-    top_gainer: string;
-}
+export type WsMessage = { schemaVersion: number } & WsPayload;
@@ -0,0 +1,85 @@
+// Decoding of the backend's WebSocket frames. Text frames are always JSON; binary ones are
+// raw-deflated JSON or MessagePack, depending on the subprotocol agreed at connect (see
+// backend/src/encoding.rs).
+
+export type WsEncoding = 'json' | 'deflate' | 'msgpack';
+
+// Subprotocol to offer per encoding; plain JSON offers none
+export const PROTOCOLS: Record<WsEncoding, string[]> = {
+    json: [],
+    deflate: ['teeb.json.deflate'],
+    msgpack: ['teeb.msgpack']
+};
+
+export function wsEncoding(value: string | undefined): WsEncoding {
+    return value === 'deflate' || value === 'msgpack' ? value : 'json';
+}
+
+/** Decodes a frame received on a socket whose `protocol` is `protocol`. */
+export async function decodeFrame(data: string | ArrayBuffer, protocol: string): Promise<unknown> {
+    if (typeof data === 'string') return JSON.parse(data);
+    if (protocol === 'teeb.msgpack') return unpack(new Uint8Array(data));
+    const inflated = new Blob([data]).stream().pipeThrough(new DecompressionStream('deflate-raw'));
+    return JSON.parse(await new Response(inflated).text());
+}
+
+// MessagePack, as far as the backend writes it: nil, booleans, integers, float64, strings,
+// arrays and maps
+function unpack(bytes: Uint8Array): unknown {
+    const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+    const text = new TextDecoder();
+    let at = 0;
+
+    const str = (len: number) => {
+        const s = text.decode(bytes.subarray(at, at + len));
+        at += len;
+        return s;
+    };
+    const array = (len: number) => Array.from({ length: len }, () => next());
+    const map = (len: number) => {
+        const object: Record<string, unknown> = {};
+        for (let i = 0; i < len; i++) {
+            const key = String(next());
+            object[key] = next();
+        }
+        return object;
+    };
+    const read = <T>(size: number, get: (offset: number) => T) => {
+        const value = get(at);
+        at += size;
+        return value;
+    };
+
+    function next(): unknown {
+        const byte = bytes[at++];
+        if (byte <= 0x7f) return byte;
+        if (byte >= 0xe0) return byte - 0x100;
+        if ((byte & 0xf0) === 0x80) return map(byte & 0x0f);
+        if ((byte & 0xf0) === 0x90) return array(byte & 0x0f);
+        if ((byte & 0xe0) === 0xa0) return str(byte & 0x1f);
+        switch (byte) {
+            case 0xc0: return null;
+            case 0xc2: return false;
+            case 0xc3: return true;
+            case 0xcb: return read(8, (o) => view.getFloat64(o));
+            case 0xcc: return read(1, (o) => view.getUint8(o));
+            case 0xcd: return read(2, (o) => view.getUint16(o));
+            case 0xce: return read(4, (o) => view.getUint32(o));
+            case 0xcf: return Number(read(8, (o) => view.getBigUint64(o)));
+            case 0xd0: return read(1, (o) => view.getInt8(o));
+            case 0xd1: return read(2, (o) => view.getInt16(o));
+            case 0xd2: return read(4, (o) => view.getInt32(o));
+            case 0xd3: return Number(read(8, (o) => view.getBigInt64(o)));
+            case 0xd9: return str(read(1, (o) => view.getUint8(o)));
+            case 0xda: return str(read(2, (o) => view.getUint16(o)));
+            case 0xdb: return str(read(4, (o) => view.getUint32(o)));
+            case 0xdc: return array(read(2, (o) => view.getUint16(o)));
+            case 0xdd: return array(read(4, (o) => view.getUint32(o)));
+            case 0xde: return map(read(2, (o) => view.getUint16(o)));
+            case 0xdf: return map(read(4, (o) => view.getUint32(o)));
+            default: throw new Error(`Unsupported MessagePack byte 0x${byte.toString(16)}`);
+        }
+    }
+
+    return next();
+}